@@ -16,6 +16,7 @@ command-alias-desc = Change your alias
 command-area_add-desc = Adds a new build area
 command-area_list-desc = List all build areas
 command-area_remove-desc = Removes specified build area
+command-audit_log-desc = Shows the most recent commands run by moderators and admins, or by the given player
 command-aura-desc = Create an aura
 command-body-desc = Change your body to different species
 command-set_body_type-desc = Set your body type, Female or Male.
@@ -34,6 +35,8 @@ command-battlemode-desc = Set your battle mode to:
   If called without arguments will show current battle mode.
 command-battlemode_force-desc = Change your battle mode flag without any checks
 command-campfire-desc = Spawns a campfire
+command-chat_mute-desc = Mute a player for the given duration. Pass true for shadow to let them still see their own messages.
+command-chat_unmute-desc = Remove the mute of the given player.
 command-clear_persisted_terrain-desc = Clears nearby persisted terrain
 command-create_location-desc = Create a location at the current position
 command-death_effect-dest = Adds an on-death effect to the target entity
@@ -46,18 +49,21 @@ command-dismount-desc = Dismount if you are riding, or dismount anything riding
 command-dropall-desc = Drops all your items on the ground
 command-dummy-desc = Spawns a training dummy
 command-explosion-desc = Explodes the ground around you
+command-export_character-desc = Export a character to a file, so that it can be imported on another server
 command-faction-desc = Send messages to your faction
 command-give_item-desc = Give yourself some items. For an example or to auto complete use Tab.
 command-gizmos-desc = Manage gizmo subscriptions.
 command-gizmos_range-desc = Change the range of gizmo subscriptions.
 command-goto-desc = Teleport to a position
 command-goto-rand = Teleport to a random position
+command-graveyard-desc = Lists the hardcore characters that died on this server, highest level first
 command-group-desc = Send messages to your group
 command-group_invite-desc = Invite a player to join a group
 command-group_kick-desc = Remove a player from a group
 command-group_leave-desc = Leave the current group
 command-group_promote-desc = Promote a player to group leader
 command-health-desc = Set your current health
+command-import_character-desc = Create a character for a player from an exported file. Files not exported by a server sharing this server's key need --unsigned
 command-into_npc-desc = Convert yourself to an NPC. Be careful!
 command-join_faction-desc = Join/leave the specified faction
 command-jump-desc = Offset your current position
@@ -79,6 +85,7 @@ command-mount-desc = Mount an entity
 command-object-desc = Spawn an object
 command-outcome-desc = Create an outcome
 command-permit_build-desc = Grants player a bounded box they can build in
+command-physics_violations-desc = Shows the physics updates of a player rejected by the server
 command-players-desc = Lists players currently online
 command-poise-desc = Set your current poise
 command-portal-desc = Spawns a portal
@@ -90,6 +97,7 @@ command-reset_recipes-desc = Resets your recipe book
 command-respawn-desc = Teleport to your waypoint
 command-revoke_build-desc = Revokes build area permission for player
 command-revoke_build_all-desc = Revokes all build area permissions for player
+command-route-desc = Finds a route over land to a site, preferring paths
 command-safezone-desc = Creates a safezone
 command-say-desc = Send messages to everyone within shouting distance
 command-scale-desc = Scale your character
@@ -129,6 +137,14 @@ players-list-header = { $count ->
   *[other] { $count } players online
     { $player_list }
 }
+# Command: /graveyard
+graveyard-list-header = { $count ->
+  [0] No hardcore character has died yet
+  [1] { $count } hardcore character rests in the graveyard
+    { $grave_list }
+  *[other] { $count } hardcore characters rest in the graveyard
+    { $grave_list }
+}
 ## Voxygen Client Commands
 
 command-clear-desc = Clears all messages in chat. Affects all chat tabs.
@@ -293,7 +309,12 @@ command-group_invite-invited-to-group = Invited { $player } to the group.
 command-group_invite-invited-to-your-group = { $player } has been invited to your group.
 command-into_npc-warning = I hope you aren't abusing this!
 command-kick-higher-role = Cannot kick players with roles higher than your own.
+command-physics_violations-none = { $player } has no physics violations
+command-audit_log-empty = No commands were recorded
 command-respawn-no-waypoint = No waypoint set
+command-route = { $site } is { $distance } km away by land, head { $direction }
+command-route-not-found = Couldn't find a route over land to that site
+command-route-cooldown = You can search for another route in { $seconds } seconds
 command-site-not-found = Site not found
 command-sudo-higher-role = Cannot sudo players with roles higher than your own.
 command-sudo-no-permission-for-non-players = You don't have permission to sudo non-players.
@@ -302,6 +323,11 @@ command-time_scale-changed = Set time scale to { $scale }.
 command-unban-successful = { $player } was successfully unbanned.
 command-unban-ip-successful = The IP banned via user "{ $player }" was successfully unbanned (this user will remain banned)
 command-unban-already-unbanned = { $player } was already unbanned.
+command-chat-mute-added = Muted { $player } with reason: { $reason }
+command-chat-mute-role-too-low = You don't have a role high enough to mute { $player }.
+command-chat-mute-muted = You are muted until { $until }. Reason: { $reason }
+command-chat-unmute-successful = { $player } was successfully unmuted.
+command-chat-unmute-not-muted = { $player } is not muted.
 command-version-current = Server is running { $hash }[{ $date }]
 command-whitelist-added = Added to whitelist: { $username }
 command-whitelist-already-added = Already in whitelist: { $username }!
@@ -140,3 +140,11 @@ hud-diary-sections-character-title = Character
 hud-diary-sections-recipes-title = Recipes
 hud-battle-mode = Battle Mode
 hud-waypoint = Waypoint
+hud-reputation = Reputation
+hud-reputation-unknown = Unknown
+hud-reputation-standing = { $standing } in { $site }
+hud-reputation-hostile = Hostile
+hud-reputation-distrusted = Distrusted
+hud-reputation-neutral = Neutral
+hud-reputation-respected = Respected
+hud-reputation-honoured = Honoured
@@ -13,6 +13,9 @@ dialogue-question-hire =
 dialogue-question-sentiment =
     .a0 = How do you feel about...
     .a1 = What do you think about...
+dialogue-question-factions =
+    .a0 = Who are your friends and foes?
+    .a1 = How do your people get along with others?
 dialogue-cancel_hire = I want to stop hiring you.
 dialogue-me = Me
 dialogue-buy_hire_days =
@@ -40,6 +43,12 @@ dialogue-question-quest-escort-where = Where are we going?
 # Used TAIL() to strip the article
 dialogue-question-quest-slay-where = Where is the { TAIL($body) }?
 dialogue-question-quest-slay-claim = The monster has been slain!
+dialogue-question-quest-bounty-where = Where was the outlaw last seen?
+dialogue-question-quest-bounty-claim = The outlaw has paid for their crimes.
+
+dialogue-arrest-pay_fine = I'll pay the fine of { $coins } coins.
+dialogue-arrest-surrender = I'll come quietly.
+dialogue-arrest-resist = You'll never take me alive!
 
 dialogue-play_game = Let's play a game
 dialogue-game-what_game =
@@ -65,3 +74,13 @@ dialogue-accept =
 dialogue-decline =
     .a1 = No thanks.
     .a2 = Sorry, not now.
+
+## Dialogue trees, see `common.dialogue`
+
+dialogue-tree-guard-topic = Is it safe around here?
+dialogue-tree-guard-help = Can I help keep the peace?
+dialogue-tree-guard-night = Is it quiet tonight?
+dialogue-tree-guard-mock = You don't look very tough.
+dialogue-tree-merchant-topic = Any advice for the road?
+dialogue-tree-merchant-broke = I'm a little short on coin...
+dialogue-tree-merchant-thanks = Thanks for the advice.
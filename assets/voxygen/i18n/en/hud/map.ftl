@@ -29,6 +29,8 @@ hud-map-character = Character
 hud-map-character-label = { $name }'s last known location
 hud-map-creature-label = Last known location of { $body }
 hud-map-escort-label = Escort { $name } to { $place }.
+hud-map-bounty-label = Last known location of the outlaw
+hud-map-jail-label = Jail
 hud-map-difficulty_dungeon =
     Dungeon
 
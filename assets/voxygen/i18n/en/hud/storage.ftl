@@ -0,0 +1,7 @@
+hud-storage-title = Storage Chest
+hud-storage-pick_up = Pick up
+hud-storage-denied = You are not allowed to open this chest.
+hud-storage-unavailable = Storage chests can't be placed on this server.
+hud-storage-place_failed = There is no room to place the chest here.
+hud-storage-not_empty = The chest has to be empty before it can be picked up.
+hud-storage-inventory_full = Your inventory is full.
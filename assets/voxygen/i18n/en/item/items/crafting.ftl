@@ -220,6 +220,9 @@ object-collar = Collar
 object-training_dummy = Training Dummy
     .desc = His name is William. Fire at will.
 
+object-storage_chest = Storage Chest
+    .desc = A sturdy chest that keeps its contents safe, even while you are away.
+
 object-mortar_pestle = Mortar and Pestle
     .desc = Crushes and grinds things into a fine powder or paste. Needed to craft various items.
 
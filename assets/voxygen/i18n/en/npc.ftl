@@ -446,6 +446,9 @@ npc-response-decline_hire =
     .a0 = Sorry, I don't have time right now.
     .a1 = No thanks, sorry.
 npc-response-already_hired = Don't be silly, you've already hired me!
+npc-response-distrusted =
+    .a0 = I've heard what you've done. I want nothing to do with you.
+    .a1 = Folk here don't trust you, and neither do I.
 npc-dialogue-hire_cancelled = That's fine, see you later!
 npc-dialogue-hire_expired =
     .a0 = It's time for me to leave your service. See you around!
@@ -472,6 +475,9 @@ npc-response-quest-timeout =
 npc-response-quest-escort-ask =
     .a0 = Could you lead me to { $dst }? I'll pay you { $coins } coins! I need to get there in { $mins } minutes!
     .a1 = I want to go to { $dst } in the next { $mins } minutes. Could you take me there for { $coins } coins?
+npc-response-quest-caravan-ask =
+    .a0 = I'm taking goods to { $dst } and the roads aren't safe. Guard my caravan and I'll pay you { $coins } coins! We must be there in { $mins } minutes.
+    .a1 = Bandits prey on caravans like mine. Escort me to { $dst } within { $mins } minutes and { $coins } coins are yours.
 npc-response-quest-escort-start =
     .a0 = Lead on then! I've marked the place on your map.
     .a1 = Excellent. You'll find the destination on your map. You first, I'll follow along.
@@ -494,6 +500,27 @@ npc-response-quest-slay-thanks =
     .a0 = Thank you! The people here will be safe once more.
     .a1 = You have my gratitude... and my money!
     .a2 = You've done us a huge favour, many thanks.
+npc-response-quest-bounty-ask =
+    .a0 = A murderer walks free with a bounty of { $coins } coins on their head. Will you bring them to justice?
+    .a1 = There's an outlaw we want dead. { $coins } coins are yours if you hunt them down.
+npc-response-quest-bounty-start =
+    .a0 = Return to me once they've paid for their crimes. I've marked where they were last seen on your map.
+    .a1 = Come back when the deed is done and the bounty is yours.
+npc-response-quest-bounty-where = I've marked where the outlaw was last seen on your map.
+npc-response-quest-bounty-unknown = Nobody has seen the outlaw for a while. Keep your eyes open.
+npc-response-quest-bounty-unfunded =
+    .a0 = On second thought, the town can't afford that bounty right now. Sorry.
+    .a1 = I'm afraid the coffers are empty. Come back another time.
+
+npc-question-arrest =
+    .a0 = You're wanted for murder! Pay the fine of { $coins } coins, or come with me to the jail.
+    .a1 = Stop right there, criminal! There's a bounty of { $coins } coins on your head.
+npc-response-arrest-fined =
+    .a0 = Very well. Your debt to this town is paid.
+    .a1 = Consider the matter settled. Behave yourself.
+npc-response-arrest-jailed =
+    .a0 = Off to the jail with you. I've marked it on your map. Stay there for { $mins } minutes, and don't even think of running off.
+    .a1 = You'll serve { $mins } minutes in the jail, which I've marked on your map. Leave early and you'll be wanted again.
 
 npc-response-like_you =
     .a0 = I like you!
@@ -505,6 +532,33 @@ npc-response-ambivalent_you =
 npc-response-dislike_you =
     .a0 = I don't like you much.
     .a1 = You don't seem very nice.
+npc-response-reputation-hostile =
+    .a0 = Everyone here knows what you are. The guards will be after you!
+    .a1 = You've got some nerve showing your face around here.
+npc-response-reputation-distrusted =
+    .a0 = People around here don't trust you.
+    .a1 = You haven't made many friends here.
+npc-response-reputation-respected =
+    .a0 = People around here speak well of you.
+    .a1 = You've earned some respect in these parts.
+npc-response-reputation-honoured =
+    .a0 = Everyone here knows of your good deeds!
+    .a1 = You're a hero to the people here.
+npc-response-faction-none =
+    .a0 = I don't take sides.
+    .a1 = I don't belong to anybody, so I've no friends or foes to speak of.
+npc-response-faction-peace =
+    .a0 = We're at peace with everyone, for now.
+    .a1 = Nobody is troubling us at the moment.
+npc-response-faction-allied =
+    .a0 = The folk of { $site } are our allies.
+    .a1 = We can count on { $site } if trouble comes.
+npc-response-faction-rival =
+    .a0 = We don't trust the folk of { $site }.
+    .a1 = There's bad blood between us and { $site }.
+npc-response-faction-war =
+    .a0 = We're at war with { $site }!
+    .a1 = Those from { $site } are our sworn enemies.
 
 npc-question-directions =
     .a0 = Where do you want to go?
@@ -539,6 +593,9 @@ npc-speech-witness_death =
     .a1 = This is terrible!
     .a2 = Oh my goodness!
     .a3 = The world is a little darker now.
+npc-speech-arrest_resisted =
+    .a0 = Then you'll answer to my blade!
+    .a1 = Guards! Seize the criminal!
 npc-speech-welcome-aboard =
     .a0 = Welcome aboard!
     .a1 = Can I see your ticket... just kidding it's free!
@@ -590,6 +647,24 @@ npc-speech-pirate_raid =
     .a1 = Come plunder { $site } matey.
     .a2 = We're going to plunder { $site }.
 
+## Dialogue trees, see `common.dialogue`
+
+npc-tree-guard-greet =
+    .a0 = Safe enough, as long as we keep our eyes open.
+    .a1 = Mostly. The wilds are never far away, though.
+npc-tree-guard-ask = Why do you ask?
+npc-tree-guard-thanks = We can always use another sword. Let's see what needs doing.
+npc-tree-guard-night =
+    .a0 = Too quiet. That's usually when the trouble starts.
+    .a1 = Quiet enough. Keep a torch handy all the same.
+npc-tree-guard-mocked = Watch your tongue, traveller.
+npc-tree-merchant-advice =
+    .a0 = Stick to the roads, and never travel with more than you can afford to lose.
+    .a1 = Hire an escort if you're carrying anything of worth.
+npc-tree-merchant-ask = Anything else?
+npc-tree-merchant-gift = You've been good to me. Here, take a few coins.
+npc-tree-merchant-refuse = Aren't we all, friend. Aren't we all.
+
 ## Signs
 
 npc-signs-keep_out =  Keep Out!
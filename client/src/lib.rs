@@ -44,6 +44,7 @@ use common::{
     rtsim,
     shared_server_config::ServerConstants,
     spiral::Spiral2d,
+    storage::{OpenStorage, StorageAction},
     terrain::{
         BiomeKind, CoordinateConversions, SiteKindMeta, SpriteKind, TerrainChunk, TerrainChunkSize,
         TerrainGrid, block::Block, map::MapConfig, neighbors,
@@ -314,7 +315,11 @@ pub struct Client {
     pending_invites: HashSet<Uid>,
     // The pending trade the client is involved in, and it's id
     pending_trade: Option<(TradeId, PendingTrade, Option<SitePrices>)>,
+    // The storage container the client has open
+    storage: Option<OpenStorage>,
     waypoint: Option<String>,
+    // The site the player is at and their standing with it
+    reputation: Option<(String, rtsim::Standing)>,
 
     network: Option<Network>,
     participant: Option<Participant>,
@@ -1072,7 +1077,9 @@ impl Client {
             group_members: HashMap::new(),
             pending_invites: HashSet::new(),
             pending_trade: None,
+            storage: None,
             waypoint: None,
+            reputation: None,
 
             network: Some(network),
             participant: Some(participant),
@@ -1418,6 +1425,17 @@ impl Client {
                 )));
             },
             (Slot::Overflow(_), _) | (_, Slot::Overflow(_)) => {},
+            (Slot::Stash(s), Slot::Inventory(inv)) | (Slot::Inventory(inv), Slot::Stash(s)) => {
+                self.send_msg(ClientGeneral::ControlEvent(ControlEvent::InventoryEvent(
+                    InventoryEvent::StashMove(s, inv),
+                )));
+            },
+            (Slot::Stash(a), Slot::Stash(b)) => {
+                self.send_msg(ClientGeneral::ControlEvent(ControlEvent::InventoryEvent(
+                    InventoryEvent::StashSwap(a, b),
+                )));
+            },
+            (Slot::Stash(_), _) | (_, Slot::Stash(_)) => {},
             (Slot::Equip(equip), slot) | (slot, Slot::Equip(equip)) => self.control_action(
                 ControlAction::InventoryAction(InventoryAction::Swap(equip, slot)),
             ),
@@ -1440,6 +1458,8 @@ impl Client {
             Slot::Overflow(o) => self.send_msg(ClientGeneral::ControlEvent(
                 ControlEvent::InventoryEvent(InventoryEvent::OverflowDrop(o)),
             )),
+            // Items must be moved out of the stash before they can be dropped
+            Slot::Stash(_) => {},
         }
     }
 
@@ -1460,6 +1480,17 @@ impl Client {
         }
     }
 
+    /// Send an action for player-placed storage containers to the server, see
+    /// [`common::storage`].
+    pub fn perform_storage_action(&mut self, action: StorageAction) {
+        if let StorageAction::Close = action {
+            self.storage.take();
+        }
+        self.send_msg(ClientGeneral::ControlEvent(ControlEvent::StorageAction(
+            action,
+        )));
+    }
+
     pub fn is_dead(&self) -> bool { self.current::<comp::Health>().is_some_and(|h| h.is_dead) }
 
     pub fn is_gliding(&self) -> bool {
@@ -1470,6 +1501,7 @@ impl Client {
     pub fn split_swap_slots(&mut self, a: Slot, b: Slot) {
         match (a, b) {
             (Slot::Overflow(_), _) | (_, Slot::Overflow(_)) => {},
+            (Slot::Stash(_), _) | (_, Slot::Stash(_)) => {},
             (Slot::Equip(equip), slot) | (slot, Slot::Equip(equip)) => self.control_action(
                 ControlAction::InventoryAction(InventoryAction::Swap(equip, slot)),
             ),
@@ -1492,6 +1524,7 @@ impl Client {
             Slot::Overflow(o) => self.send_msg(ClientGeneral::ControlEvent(
                 ControlEvent::InventoryEvent(InventoryEvent::OverflowSplitDrop(o)),
             )),
+            Slot::Stash(_) => {},
         }
     }
 
@@ -1709,7 +1742,7 @@ impl Client {
                 if let Some(item) = match item {
                     Slot::Equip(equip_slot) => inv.equipped(equip_slot),
                     Slot::Inventory(invslot) => inv.get(invslot),
-                    Slot::Overflow(_) => None,
+                    Slot::Overflow(_) | Slot::Stash(_) => None,
                 } {
                     item.has_durability()
                 } else {
@@ -1821,6 +1854,8 @@ impl Client {
 
     pub fn is_trading(&self) -> bool { self.pending_trade.is_some() }
 
+    pub fn storage(&self) -> Option<&OpenStorage> { self.storage.as_ref() }
+
     pub fn send_invite(&mut self, invitee: Uid, kind: InviteKind) {
         self.send_msg(ClientGeneral::ControlEvent(ControlEvent::InitiateInvite(
             invitee, kind,
@@ -2278,6 +2313,10 @@ impl Client {
 
     pub fn waypoint(&self) -> &Option<String> { &self.waypoint }
 
+    /// The site the player is at and their standing with it, if they're near
+    /// one.
+    pub fn reputation(&self) -> Option<&(String, rtsim::Standing)> { self.reputation.as_ref() }
+
     pub fn set_battle_mode(&mut self, battle_mode: BattleMode) {
         self.send_msg(ClientGeneral::SetBattleMode(battle_mode));
     }
@@ -3036,6 +3075,12 @@ impl Client {
                     frontend_events.push(Event::TradeComplete { result, trade })
                 }
             },
+            ServerGeneral::UpdateStorage(storage) => {
+                self.storage = storage;
+            },
+            ServerGeneral::UpdateReputation(reputation) => {
+                self.reputation = reputation;
+            },
             ServerGeneral::SiteEconomy(economy) => {
                 if let Some(rich) = self.sites.get_mut(&economy.id) {
                     rich.economy = Some(economy);
@@ -3320,6 +3365,8 @@ impl Client {
     fn clean_state(&mut self) {
         // Clear pending trade
         self.pending_trade = None;
+        self.storage = None;
+        self.reputation = None;
 
         let client_uid = self.uid().expect("Client doesn't have a Uid!!!");
 
@@ -0,0 +1,67 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+use vek::*;
+use veloren_common::{
+    nav::{ChunkNav, NavGraph},
+    terrain::{
+        SpriteKind, TerrainChunk, TerrainChunkMeta, TerrainChunkSize,
+        block::{Block, BlockKind},
+    },
+    vol::*,
+};
+
+const GROUND: i32 = 140;
+const GRID_SIZE: i32 = 8;
+
+/// A flat chunk with a wall running across it, leaving a gap on one side.
+fn walled_chunk() -> TerrainChunk {
+    let mut chunk = TerrainChunk::new(
+        GROUND,
+        Block::new(BlockKind::Rock, Rgb::zero()),
+        Block::air(SpriteKind::Empty),
+        TerrainChunkMeta::void(),
+    );
+    for y in 4..TerrainChunk::RECT_SIZE.y as i32 {
+        for z in GROUND..GROUND + 8 {
+            chunk
+                .set(
+                    Vec3::new(16, y, z),
+                    Block::new(BlockKind::Rock, Rgb::zero()),
+                )
+                .unwrap();
+        }
+    }
+    chunk
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let chunk = walled_chunk();
+    let heights = chunk.get_min_z()..chunk.get_max_z() + 1;
+
+    let mut c = c.benchmark_group("nav");
+
+    c.bench_function("build chunk", |b| {
+        b.iter(|| ChunkNav::build(black_box(&chunk), heights.clone()))
+    });
+
+    let nav = ChunkNav::build(&chunk, heights);
+    let mut graph = NavGraph::default();
+    for y in 0..GRID_SIZE {
+        for x in 0..GRID_SIZE {
+            graph.insert(Vec2::new(x, y), nav.clone());
+        }
+    }
+    let chunk_size = TerrainChunkSize::RECT_SIZE.as_::<i32>();
+    let start = Vec3::new(2, 2, GROUND);
+    let end = (chunk_size * GRID_SIZE - 3).with_z(GROUND);
+
+    c.bench_function("find route", |b| {
+        b.iter(|| graph.find_route(black_box(start), black_box(end)))
+    });
+
+    c.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -17,6 +17,7 @@ use common::{
     resources::{BattleMode, Time, TimeOfDay, TimeScale},
     rtsim,
     shared_server_config::ServerConstants,
+    storage::OpenStorage,
     terrain::{Block, TerrainChunk, TerrainChunkMeta, TerrainChunkSize},
     trade::{PendingTrade, SitePrices, TradeId, TradeResult},
     uid::Uid,
@@ -217,8 +218,14 @@ pub enum ServerGeneral {
     Notification(Notification),
     UpdatePendingTrade(TradeId, PendingTrade, Option<SitePrices>),
     FinishedTrade(TradeResult),
+    /// The storage container the client has open changed, `None` if it was
+    /// closed.
+    UpdateStorage(Option<OpenStorage>),
     /// Economic information about sites
     SiteEconomy(EconomyInfo),
+    /// The name of the site the player is at and their standing with it, or
+    /// `None` if they aren't near any site.
+    UpdateReputation(Option<(String, rtsim::Standing)>),
     MapMarker(comp::MapMarkerUpdate),
     WeatherUpdate(SharedWeatherGrid),
     LocalWindUpdate(Vec2<f32>),
@@ -374,7 +381,9 @@ impl ServerMsg {
                         | ServerGeneral::Knockback(_)
                         | ServerGeneral::UpdatePendingTrade(_, _, _)
                         | ServerGeneral::FinishedTrade(_)
+                        | ServerGeneral::UpdateStorage(_)
                         | ServerGeneral::SiteEconomy(_)
+                        | ServerGeneral::UpdateReputation(_)
                         | ServerGeneral::MapMarker(_)
                         | ServerGeneral::WeatherUpdate(_)
                         | ServerGeneral::LocalWindUpdate(_)
@@ -42,6 +42,11 @@ impl Used {
                     self.use_item_spec(i);
                 }
             },
+            ItemSpec::CustomSeasonal(items) => {
+                for (_, i) in items {
+                    self.use_item_spec(i);
+                }
+            },
         }
     }
 
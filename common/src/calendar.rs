@@ -12,9 +12,55 @@ pub enum CalendarEvent {
     Easter = 3,
 }
 
+/// An event defined by a server, such as an anniversary, which takes place
+/// every year. Outfits can be made to depend on the event by its name (see
+/// [`Calendar::is_custom_event`]), and the event can also enable the effects of
+/// built-in events.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomCalendarEvent {
+    pub name: String,
+    /// Month and day of the first day of the event
+    pub start: (u32, u32),
+    /// Month and day of the last day of the event, which is before `start` for
+    /// events spanning the new year
+    pub end: (u32, u32),
+    /// Events whose loot, NPC outfits, sprites and decorations are enabled
+    /// during this event
+    #[serde(default)]
+    pub enables: Vec<CalendarEvent>,
+}
+
+impl CustomCalendarEvent {
+    /// Whether `start` and `end` are both dates that exist.
+    pub fn has_valid_dates(&self) -> bool {
+        let is_valid = |(month, day): (u32, u32)| {
+            let days = match month {
+                2 => 29,
+                4 | 6 | 9 | 11 => 30,
+                1..=12 => 31,
+                _ => return false,
+            };
+            (1..=days).contains(&day)
+        };
+        is_valid(self.start) && is_valid(self.end)
+    }
+
+    pub fn is_active(&self, month: u32, day: u32) -> bool {
+        let date = (month, day);
+        if self.start <= self.end {
+            (self.start..=self.end).contains(&date)
+        } else {
+            date >= self.start || date <= self.end
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Calendar {
     events: Vec<CalendarEvent>,
+    /// Names of the active custom events
+    #[serde(default)]
+    custom_events: Vec<String>,
 }
 
 impl Calendar {
@@ -24,11 +70,24 @@ impl Calendar {
         self.events.iter()
     }
 
-    pub fn from_events(events: Vec<CalendarEvent>) -> Self { Self { events } }
+    pub fn is_custom_event(&self, name: &str) -> bool {
+        self.custom_events.iter().any(|event| event == name)
+    }
 
-    pub fn from_tz(tz: Option<Tz>) -> Self {
-        let mut this = Self::default();
+    pub fn custom_events(&self) -> impl ExactSizeIterator<Item = &str> + '_ {
+        self.custom_events.iter().map(String::as_str)
+    }
+
+    pub fn from_events(events: Vec<CalendarEvent>) -> Self {
+        Self {
+            events,
+            custom_events: Vec::new(),
+        }
+    }
 
+    /// The events taking place today in the given timezone, or in the local
+    /// timezone if none is given.
+    pub fn from_tz(tz: Option<Tz>, custom_events: &[CustomCalendarEvent]) -> Self {
         let now = match tz {
             Some(tz) => {
                 let utc = Utc::now().naive_utc();
@@ -38,22 +97,96 @@ impl Calendar {
             None => Local::now().naive_local(),
         };
 
-        if now.month() == 12 && (20..=30).contains(&now.day()) {
+        Self::from_date(now.month(), now.day(), custom_events)
+    }
+
+    fn from_date(month: u32, day: u32, custom_events: &[CustomCalendarEvent]) -> Self {
+        let mut this = Self::default();
+
+        if month == 12 && (20..=30).contains(&day) {
             this.events.push(CalendarEvent::Christmas);
         }
 
-        if now.month() == 10 && (24..=31).contains(&now.day()) {
+        if month == 10 && (24..=31).contains(&day) {
             this.events.push(CalendarEvent::Halloween);
         }
 
-        if now.month() == 4 && now.day() == 1 {
+        if month == 4 && day == 1 {
             this.events.push(CalendarEvent::AprilFools);
         }
 
-        if now.month() == 3 && now.day() == 31 || now.month() == 4 && (1..=7).contains(&now.day()) {
+        if month == 3 && day == 31 || month == 4 && (1..=7).contains(&day) {
             this.events.push(CalendarEvent::Easter);
         }
 
+        for custom_event in custom_events {
+            if custom_event.is_active(month, day) {
+                this.custom_events.push(custom_event.name.clone());
+                for event in &custom_event.enables {
+                    if !this.events.contains(event) {
+                        this.events.push(*event);
+                    }
+                }
+            }
+        }
+
         this
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_events() {
+        let custom_events = [
+            CustomCalendarEvent {
+                name: "Anniversary".to_string(),
+                start: (5, 20),
+                end: (5, 27),
+                enables: vec![CalendarEvent::Halloween],
+            },
+            CustomCalendarEvent {
+                name: "Winter Festival".to_string(),
+                start: (12, 28),
+                end: (1, 3),
+                enables: vec![CalendarEvent::Christmas],
+            },
+        ];
+
+        let calendar = Calendar::from_date(5, 22, &custom_events);
+        assert!(calendar.is_custom_event("Anniversary"));
+        assert!(!calendar.is_custom_event("Winter Festival"));
+        assert_eq!(calendar.events().collect::<Vec<_>>(), [
+            &CalendarEvent::Halloween
+        ]);
+
+        let calendar = Calendar::from_date(1, 2, &custom_events);
+        assert_eq!(calendar.custom_events().collect::<Vec<_>>(), [
+            "Winter Festival"
+        ]);
+        assert_eq!(calendar.events().collect::<Vec<_>>(), [
+            &CalendarEvent::Christmas
+        ]);
+
+        // Christmas is not added twice
+        let calendar = Calendar::from_date(12, 29, &custom_events);
+        assert_eq!(calendar.events().len(), 1);
+    }
+
+    #[test]
+    fn custom_event_dates() {
+        let event = |start, end| CustomCalendarEvent {
+            name: "Event".to_string(),
+            start,
+            end,
+            enables: Vec::new(),
+        };
+        assert!(event((2, 29), (12, 31)).has_valid_dates());
+        assert!(!event((13, 40), (1, 1)).has_valid_dates());
+        assert!(!event((1, 1), (4, 31)).has_valid_dates());
+        assert!(!event((0, 1), (1, 1)).has_valid_dates());
+        assert!(!event((1, 0), (1, 1)).has_valid_dates());
+    }
+}
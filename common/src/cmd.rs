@@ -357,6 +357,7 @@ pub enum ServerChatCommand {
     AreaAdd,
     AreaList,
     AreaRemove,
+    AuditLog,
     Aura,
     Ban,
     BanIp,
@@ -367,6 +368,8 @@ pub enum ServerChatCommand {
     Buff,
     Build,
     Campfire,
+    ChatMute,
+    ChatUnmute,
     ClearPersistedTerrain,
     CreateLocation,
     DeathEffect,
@@ -379,18 +382,21 @@ pub enum ServerChatCommand {
     DropAll,
     Dummy,
     Explosion,
+    ExportCharacter,
     Faction,
     GiveItem,
     Gizmos,
     GizmosRange,
     Goto,
     GotoRand,
+    Graveyard,
     Group,
     GroupInvite,
     GroupKick,
     GroupLeave,
     GroupPromote,
     Health,
+    ImportCharacter,
     IntoNpc,
     JoinFaction,
     Jump,
@@ -411,6 +417,7 @@ pub enum ServerChatCommand {
     Object,
     Outcome,
     PermitBuild,
+    PhysicsViolations,
     Players,
     Poise,
     Portal,
@@ -422,6 +429,7 @@ pub enum ServerChatCommand {
     Respawn,
     RevokeBuild,
     RevokeBuildAll,
+    Route,
     RtsimChunk,
     RtsimInfo,
     RtsimNpc,
@@ -487,6 +495,11 @@ impl ServerChatCommand {
                 Content::localized("command-alias-desc"),
                 Some(Moderator),
             ),
+            ServerChatCommand::AuditLog => cmd(
+                vec![PlayerName(Optional), Integer("max entries", 10, Optional)],
+                Content::localized("command-audit_log-desc"),
+                Some(Admin),
+            ),
             ServerChatCommand::Aura => cmd(
                 vec![
                     Float("aura_radius", 10.0, Required),
@@ -602,6 +615,21 @@ impl ServerChatCommand {
                 Content::localized("command-campfire-desc"),
                 Some(Admin),
             ),
+            ServerChatCommand::ChatMute => cmd(
+                vec![
+                    PlayerName(Required),
+                    Any("mute duration", Required),
+                    Boolean("shadow", "true".to_string(), Optional),
+                    Message(Optional),
+                ],
+                Content::localized("command-chat_mute-desc"),
+                Some(Moderator),
+            ),
+            ServerChatCommand::ChatUnmute => cmd(
+                vec![PlayerName(Required)],
+                Content::localized("command-chat_unmute-desc"),
+                Some(Moderator),
+            ),
             ServerChatCommand::ClearPersistedTerrain => cmd(
                 vec![Integer("chunk_radius", 6, Required)],
                 Content::localized("command-clear_persisted_terrain-desc"),
@@ -653,6 +681,11 @@ impl ServerChatCommand {
                 Content::localized("command-explosion-desc"),
                 Some(Admin),
             ),
+            ServerChatCommand::ExportCharacter => cmd(
+                vec![Integer("character_id", 1, Required)],
+                Content::localized("command-export_character-desc"),
+                Some(Admin),
+            ),
             ServerChatCommand::Faction => cmd(
                 vec![Message(Optional)],
                 Content::localized("command-faction-desc"),
@@ -704,6 +737,9 @@ impl ServerChatCommand {
                 Content::localized("command-goto-rand"),
                 Some(Admin),
             ),
+            ServerChatCommand::Graveyard => {
+                cmd(vec![], Content::localized("command-graveyard-desc"), None)
+            },
             ServerChatCommand::Group => cmd(
                 vec![Message(Optional)],
                 Content::localized("command-group-desc"),
@@ -732,6 +768,15 @@ impl ServerChatCommand {
                 Content::localized("command-health-desc"),
                 Some(Admin),
             ),
+            ServerChatCommand::ImportCharacter => cmd(
+                vec![
+                    PlayerName(Required),
+                    Any("file", Required),
+                    Flag("--unsigned"),
+                ],
+                Content::localized("command-import_character-desc"),
+                Some(Admin),
+            ),
             ServerChatCommand::Respawn => cmd(
                 vec![],
                 Content::localized("command-respawn-desc"),
@@ -835,6 +880,11 @@ impl ServerChatCommand {
                 Content::localized("command-permit_build-desc"),
                 Some(Admin),
             ),
+            ServerChatCommand::PhysicsViolations => cmd(
+                vec![PlayerName(Required)],
+                Content::localized("command-physics_violations-desc"),
+                Some(Moderator),
+            ),
             ServerChatCommand::Players => {
                 cmd(vec![], Content::localized("command-players-desc"), None)
             },
@@ -879,6 +929,11 @@ impl ServerChatCommand {
                 Content::localized("command-revoke_build_all-desc"),
                 Some(Admin),
             ),
+            ServerChatCommand::Route => cmd(
+                vec![SiteName(Required)],
+                Content::localized("command-route-desc"),
+                None,
+            ),
             ServerChatCommand::Region => cmd(
                 vec![Message(Optional)],
                 Content::localized("command-region-desc"),
@@ -1159,6 +1214,7 @@ impl ServerChatCommand {
             ServerChatCommand::AreaAdd => "area_add",
             ServerChatCommand::AreaList => "area_list",
             ServerChatCommand::AreaRemove => "area_remove",
+            ServerChatCommand::AuditLog => "audit_log",
             ServerChatCommand::Aura => "aura",
             ServerChatCommand::Ban => "ban",
             ServerChatCommand::BanIp => "ban_ip",
@@ -1169,6 +1225,8 @@ impl ServerChatCommand {
             ServerChatCommand::Buff => "buff",
             ServerChatCommand::Build => "build",
             ServerChatCommand::Campfire => "campfire",
+            ServerChatCommand::ChatMute => "chat_mute",
+            ServerChatCommand::ChatUnmute => "chat_unmute",
             ServerChatCommand::ClearPersistedTerrain => "clear_persisted_terrain",
             ServerChatCommand::DeathEffect => "death_effect",
             ServerChatCommand::DebugColumn => "debug_column",
@@ -1177,18 +1235,21 @@ impl ServerChatCommand {
             ServerChatCommand::DropAll => "dropall",
             ServerChatCommand::Dummy => "dummy",
             ServerChatCommand::Explosion => "explosion",
+            ServerChatCommand::ExportCharacter => "export_character",
             ServerChatCommand::Faction => "faction",
             ServerChatCommand::GiveItem => "give_item",
             ServerChatCommand::Gizmos => "gizmos",
             ServerChatCommand::GizmosRange => "gizmos_range",
             ServerChatCommand::Goto => "goto",
             ServerChatCommand::GotoRand => "goto_rand",
+            ServerChatCommand::Graveyard => "graveyard",
             ServerChatCommand::Group => "group",
             ServerChatCommand::GroupInvite => "group_invite",
             ServerChatCommand::GroupKick => "group_kick",
             ServerChatCommand::GroupLeave => "group_leave",
             ServerChatCommand::GroupPromote => "group_promote",
             ServerChatCommand::Health => "health",
+            ServerChatCommand::ImportCharacter => "import_character",
             ServerChatCommand::IntoNpc => "into_npc",
             ServerChatCommand::JoinFaction => "join_faction",
             ServerChatCommand::Jump => "jump",
@@ -1206,6 +1267,7 @@ impl ServerChatCommand {
             ServerChatCommand::Object => "object",
             ServerChatCommand::Outcome => "outcome",
             ServerChatCommand::PermitBuild => "permit_build",
+            ServerChatCommand::PhysicsViolations => "physics_violations",
             ServerChatCommand::Players => "players",
             ServerChatCommand::Poise => "poise",
             ServerChatCommand::Portal => "portal",
@@ -1215,6 +1277,7 @@ impl ServerChatCommand {
             ServerChatCommand::RemoveLights => "remove_lights",
             ServerChatCommand::RevokeBuild => "revoke_build",
             ServerChatCommand::RevokeBuildAll => "revoke_build_all",
+            ServerChatCommand::Route => "route",
             ServerChatCommand::Safezone => "safezone",
             ServerChatCommand::Say => "say",
             ServerChatCommand::ServerPhysics => "server_physics",
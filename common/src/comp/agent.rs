@@ -5,6 +5,7 @@ use crate::{
     },
     path::Chaser,
     rtsim::{self, NpcInput, RtSimController},
+    tactic::MAX_COOLDOWNS_PER_PHASE,
     trade::{PendingTrade, ReducedInventory, SiteId, SitePrices, TradeId, TradeResult},
     uid::Uid,
 };
@@ -650,6 +651,9 @@ pub struct Agent {
     pub stay_pos: Option<Pos>,
     /// Inputs sent up to rtsim
     pub rtsim_outbox: Option<VecDeque<NpcInput>>,
+    /// Asset specifier of the data-driven tactic used in combat, see
+    /// [`crate::tactic`].
+    pub tactic: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -739,6 +743,9 @@ pub struct ActionState {
     pub conditions: [bool; ACTIONSTATE_NUMBER_OF_CONCURRENT_CONDITIONS],
     pub int_counters: [u8; ACTIONSTATE_NUMBER_OF_CONCURRENT_INT_COUNTERS],
     pub positions: [Option<Vec3<f32>>; ACTIONSTATE_NUMBER_OF_CONCURRENT_POSITIONS],
+    /// Time at which each action with a cooldown of a data-driven tactic can
+    /// be used again.
+    pub cooldowns: [f32; MAX_COOLDOWNS_PER_PHASE],
     pub initialized: bool,
 }
 
@@ -763,6 +770,7 @@ impl Agent {
             stay_pos: None,
             awareness: Awareness::new(0.0),
             rtsim_outbox: None,
+            tactic: None,
         }
     }
 
@@ -808,6 +816,12 @@ impl Agent {
         self
     }
 
+    #[must_use]
+    pub fn with_tactic(mut self, tactic: Option<String>) -> Self {
+        self.tactic = tactic;
+        self
+    }
+
     #[must_use]
     pub fn with_altitude_pid_controller(mut self, mpid: PidControllers<16>) -> Self {
         self.multi_pid_controllers = Some(mpid);
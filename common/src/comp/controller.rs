@@ -10,6 +10,7 @@ use crate::{
     },
     mounting::VolumePos,
     rtsim,
+    storage::StorageAction,
     trade::{TradeAction, TradeId},
     uid::Uid,
     util::Dir,
@@ -34,6 +35,8 @@ pub enum InventoryEvent {
     OverflowMove(usize, InvSlotId),
     OverflowDrop(usize),
     OverflowSplitDrop(usize),
+    StashMove(usize, InvSlotId),
+    StashSwap(usize, usize),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -94,6 +97,8 @@ impl From<InventoryEvent> for InventoryManip {
             },
             InventoryEvent::OverflowDrop(o) => Self::Drop(Slot::Overflow(o)),
             InventoryEvent::OverflowSplitDrop(o) => Self::SplitDrop(Slot::Overflow(o)),
+            InventoryEvent::StashMove(s, inv) => Self::Swap(Slot::Stash(s), Slot::Inventory(inv)),
+            InventoryEvent::StashSwap(a, b) => Self::Swap(Slot::Stash(a), Slot::Stash(b)),
         }
     }
 }
@@ -154,6 +159,7 @@ pub enum ControlEvent {
     InitiateInvite(Uid, InviteKind),
     InviteResponse(InviteResponse),
     PerformTradeAction(TradeId, TradeAction),
+    StorageAction(StorageAction),
     Mount(Uid),
     MountVolume(VolumePos),
     Unmount,
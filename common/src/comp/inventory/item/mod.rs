@@ -49,6 +49,8 @@ pub enum Utility {
     Coins,
     Collar,
     Key,
+    /// Placed into the world as a storage container when used
+    StorageContainer,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -51,6 +51,9 @@ pub enum ItemSpec {
     },
     Choice(Vec<(Weight, Option<ItemSpec>)>),
     Seasonal(Vec<(Option<CalendarEvent>, ItemSpec)>),
+    /// Like `Seasonal`, but for the custom calendar events of the server, by
+    /// name
+    CustomSeasonal(Vec<(Option<String>, ItemSpec)>),
 }
 
 impl ItemSpec {
@@ -97,6 +100,16 @@ impl ItemSpec {
                     (None, _) => Some(spec.try_to_item(rng, time)),
                 })
                 .unwrap_or(Ok(None)),
+            ItemSpec::CustomSeasonal(specs) => specs
+                .iter()
+                .find_map(|(event, spec)| match (event, time) {
+                    (Some(event), Some((_time, calendar))) => calendar
+                        .is_custom_event(event)
+                        .then(|| spec.try_to_item(rng, time)),
+                    (Some(_event), None) => None,
+                    (None, _) => Some(spec.try_to_item(rng, time)),
+                })
+                .unwrap_or(Ok(None)),
         }
     }
 
@@ -127,6 +140,9 @@ impl ItemSpec {
             ItemSpec::Seasonal(specs) => {
                 specs.iter().try_for_each(|(_season, spec)| spec.validate())
             },
+            ItemSpec::CustomSeasonal(specs) => {
+                specs.iter().try_for_each(|(_event, spec)| spec.validate())
+            },
         }
     }
 }
@@ -42,6 +42,8 @@ pub mod trade_pricing;
 
 pub type InvSlot = Option<Item>;
 const DEFAULT_INVENTORY_SLOTS: usize = 18;
+/// Number of slots in the account-wide stash
+pub const STASH_SLOTS: usize = 36;
 
 /// NOTE: Do not add a PartialEq instance for Inventory; that's broken!
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -57,6 +59,9 @@ pub struct Inventory {
     overflow_items: Vec<Item>,
     /// Recipes that are available for use
     recipe_book: RecipeBook,
+    /// The account-wide stash, shared between all non-hardcore characters of a
+    /// player. `None` if the entity has no access to a stash.
+    stash: Option<Vec<InvSlot>>,
 }
 
 /// Errors which the methods on `Inventory` produce
@@ -136,6 +141,7 @@ impl Inventory {
             slots: vec![None; DEFAULT_INVENTORY_SLOTS],
             overflow_items: Vec::new(),
             recipe_book: RecipeBook::default(),
+            stash: None,
         }
     }
 
@@ -145,6 +151,7 @@ impl Inventory {
             slots: vec![None; 1],
             overflow_items: Vec::new(),
             recipe_book: RecipeBook::default(),
+            stash: None,
         }
     }
 
@@ -153,6 +160,14 @@ impl Inventory {
         self
     }
 
+    /// Gives the inventory access to a stash with the given contents, padded to
+    /// [`STASH_SLOTS`] slots.
+    pub fn with_stash(mut self, mut stash: Vec<InvSlot>) -> Inventory {
+        stash.resize(STASH_SLOTS, None);
+        self.stash = Some(stash);
+        self
+    }
+
     /// Total number of slots in in the inventory.
     pub fn capacity(&self) -> usize { self.slots().count() }
 
@@ -166,6 +181,9 @@ impl Inventory {
     /// An iterator of all overflow slots in the inventory
     pub fn overflow_items(&self) -> impl Iterator<Item = &Item> { self.overflow_items.iter() }
 
+    /// The slots of the stash, if this inventory has access to one
+    pub fn stash(&self) -> Option<&[InvSlot]> { self.stash.as_deref() }
+
     /// A mutable iterator of all inventory slots
     fn slots_mut(&mut self) -> impl Iterator<Item = &mut InvSlot> {
         self.slots.iter_mut().chain(self.loadout.inv_slots_mut())
@@ -498,12 +516,21 @@ impl Inventory {
         self.overflow_items.get(overflow)
     }
 
+    /// Get content of a stash slot
+    pub fn get_stash(&self, stash_slot: usize) -> Option<&Item> {
+        self.stash
+            .as_ref()
+            .and_then(|stash| stash.get(stash_slot))
+            .and_then(Option::as_ref)
+    }
+
     /// Get content of any kind of slot
     pub fn get_slot(&self, slot: Slot) -> Option<&Item> {
         match slot {
             Slot::Inventory(inv_slot) => self.get(inv_slot),
             Slot::Equip(equip) => self.equipped(equip),
             Slot::Overflow(overflow) => self.get_overflow(overflow),
+            Slot::Stash(stash_slot) => self.get_stash(stash_slot),
         }
     }
 
@@ -580,6 +607,41 @@ impl Inventory {
         *self.slot_mut(inv_slot).unwrap() = Some(item);
     }
 
+    /// Swaps the contents of a stash slot with an inventory slot
+    pub fn swap_stash_item(&mut self, stash_slot: usize, inv_slot: InvSlotId) {
+        if self
+            .stash
+            .as_ref()
+            .is_none_or(|stash| stash_slot >= stash.len())
+        {
+            warn!("Attempted to swap with a non-existent stash slot");
+            return;
+        }
+        let Some(inv_item) = self.slot_mut(inv_slot).map(mem::take) else {
+            warn!("Attempted to swap a stash slot with a non-existent inventory slot");
+            return;
+        };
+
+        let stash_item = self
+            .stash
+            .as_mut()
+            .map(|stash| mem::replace(&mut stash[stash_slot], inv_item))
+            .expect("Stash slot existence checked above");
+        *self.slot_mut(inv_slot).unwrap() = stash_item;
+    }
+
+    /// Swaps the contents of two stash slots
+    pub fn swap_stash_slots(&mut self, a: usize, b: usize) {
+        if let Some(stash) = self.stash.as_mut()
+            && a < stash.len()
+            && b < stash.len()
+        {
+            stash.swap(a, b);
+        } else {
+            warn!("swap_stash_slots called with non-existent stash slot(s)");
+        }
+    }
+
     /// Remove an item from the slot
     pub fn remove(&mut self, inv_slot_id: InvSlotId) -> Option<Item> {
         self.slot_mut(inv_slot_id).and_then(|item| item.take())
@@ -1033,6 +1095,18 @@ impl Inventory {
             (Slot::Overflow(_), Slot::Equip(_)) | (Slot::Equip(_), Slot::Overflow(_)) => Vec::new(),
             // Items cannot be moved between overflow slots
             (Slot::Overflow(_), Slot::Overflow(_)) => Vec::new(),
+            (Slot::Stash(stash_slot), Slot::Inventory(inv_slot))
+            | (Slot::Inventory(inv_slot), Slot::Stash(stash_slot)) => {
+                self.swap_stash_item(stash_slot, inv_slot);
+                Vec::new()
+            },
+            (Slot::Stash(slot_a), Slot::Stash(slot_b)) => {
+                self.swap_stash_slots(slot_a, slot_b);
+                Vec::new()
+            },
+            // Items can only enter or leave the stash through a real inventory slot
+            (Slot::Stash(_), Slot::Equip(_) | Slot::Overflow(_))
+            | (Slot::Equip(_) | Slot::Overflow(_), Slot::Stash(_)) => Vec::new(),
         }
     }
 
@@ -1188,6 +1262,11 @@ impl Inventory {
         self.overflow_items
             .iter_mut()
             .for_each(|item| item.update_item_state(ability_map, msm));
+        self.stash
+            .iter_mut()
+            .flatten()
+            .flatten()
+            .for_each(|item| item.update_item_state(ability_map, msm));
     }
 
     /// Increments durability lost for all valid items equipped in loadout and
@@ -1234,8 +1313,9 @@ impl Inventory {
                 self.loadout
                     .repair_item_at_slot(equip_slot, ability_map, msm);
             },
-            // Items in overflow slots cannot be repaired until they are moved to a real slot
-            Slot::Overflow(_) => {},
+            // Items in overflow or stash slots cannot be repaired until they are moved to a
+            // real slot
+            Slot::Overflow(_) | Slot::Stash(_) => {},
         }
     }
 
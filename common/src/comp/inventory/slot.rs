@@ -16,6 +16,8 @@ pub enum Slot {
     Inventory(InvSlotId),
     Equip(EquipSlot),
     Overflow(usize),
+    /// A slot of the account-wide stash shared between a player's characters
+    Stash(usize),
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
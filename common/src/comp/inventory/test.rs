@@ -25,6 +25,7 @@ fn push_full() {
         loadout: LoadoutBuilder::empty().build(),
         overflow_items: vec![],
         recipe_book: RecipeBook::default(),
+        stash: None,
     };
     assert_eq!(
         inv.push(TEST_ITEMS[0].duplicate(ability_map, msm))
@@ -46,6 +47,7 @@ fn push_all_full() {
         loadout: LoadoutBuilder::empty().build(),
         overflow_items: vec![],
         recipe_book: RecipeBook::default(),
+        stash: None,
     };
     let Error::Full(leftovers) = inv
         .push_all(
@@ -77,6 +79,7 @@ fn push_unique_all_full() {
         loadout: LoadoutBuilder::empty().build(),
         overflow_items: vec![],
         recipe_book: RecipeBook::default(),
+        stash: None,
     };
     inv.push_all_unique(
         TEST_ITEMS
@@ -97,6 +100,7 @@ fn push_all_empty() {
         loadout: LoadoutBuilder::empty().build(),
         overflow_items: vec![],
         recipe_book: RecipeBook::default(),
+        stash: None,
     };
     inv.push_all(
         TEST_ITEMS
@@ -117,6 +121,7 @@ fn push_all_unique_empty() {
         loadout: LoadoutBuilder::empty().build(),
         overflow_items: vec![],
         recipe_book: RecipeBook::default(),
+        stash: None,
     };
     inv.push_all_unique(
         TEST_ITEMS
@@ -523,6 +528,63 @@ fn backpack_crash() {
     );
 }
 
+#[test]
+fn swap_inventory_item_into_stash() {
+    let mut inv = Inventory::with_empty().with_stash(Vec::new());
+
+    let boots = Item::new_from_asset_expect("common.items.testing.test_boots");
+    inv.push(boots).unwrap();
+
+    let returned_items = inv.swap(
+        Slot::Inventory(InvSlotId::new(0, 0)),
+        Slot::Stash(3),
+        Time(0.0),
+    );
+
+    assert!(returned_items.is_empty());
+    assert_eq!(STASH_SLOTS, inv.stash().unwrap().len());
+    assert!(inv.get(InvSlotId::new(0, 0)).is_none());
+    assert_eq!(
+        ItemDefinitionId::Simple(Cow::Borrowed("common.items.testing.test_boots")),
+        inv.get_stash(3).unwrap().item_definition_id()
+    );
+}
+
+#[test]
+fn swap_stash_item_with_equip_slot_does_nothing() {
+    let boots = Item::new_from_asset_expect("common.items.testing.test_boots");
+    let mut stash = vec![None; STASH_SLOTS];
+    stash[0] = Some(boots);
+    let mut inv = Inventory::with_empty().with_stash(stash);
+
+    let returned_items = inv.swap(
+        Slot::Stash(0),
+        Slot::Equip(EquipSlot::Armor(ArmorSlot::Feet)),
+        Time(0.0),
+    );
+
+    assert!(returned_items.is_empty());
+    assert!(inv.get_stash(0).is_some());
+    assert!(inv.equipped(EquipSlot::Armor(ArmorSlot::Feet)).is_none());
+}
+
+#[test]
+fn swap_with_missing_stash_does_nothing() {
+    let mut inv = Inventory::with_empty();
+
+    let boots = Item::new_from_asset_expect("common.items.testing.test_boots");
+    inv.push(boots).unwrap();
+
+    let _ = inv.swap(
+        Slot::Inventory(InvSlotId::new(0, 0)),
+        Slot::Stash(0),
+        Time(0.0),
+    );
+
+    assert!(inv.stash().is_none());
+    assert!(inv.get(InvSlotId::new(0, 0)).is_some());
+}
+
 fn fill_inv_slots(inv: &mut Inventory, items: u16) {
     let msm = &MaterialStatManifest::load().read();
     let ability_map = &AbilityMap::load().read();
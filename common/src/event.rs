@@ -18,6 +18,7 @@ use crate::{
     resources::{BattleMode, Secs},
     rtsim::{self, RtSimEntity},
     states::basic_summon::BeamPillarIndicatorSpecifier,
+    storage::StorageAction,
     terrain::SpriteKind,
     trade::{TradeAction, TradeId},
     uid::Uid,
@@ -414,6 +415,8 @@ pub struct InitiateInviteEvent(pub EcsEntity, pub Uid, pub InviteKind);
 
 pub struct ProcessTradeActionEvent(pub EcsEntity, pub TradeId, pub TradeAction);
 
+pub struct StorageActionEvent(pub EcsEntity, pub StorageAction);
+
 pub enum MountEvent {
     MountEntity(EcsEntity, EcsEntity),
     MountVolume(EcsEntity, VolumePos),
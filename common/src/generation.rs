@@ -58,6 +58,9 @@ pub struct AgentConfig {
     pub no_flee: Option<bool>,
     pub idle_wander_factor: Option<f32>,
     pub aggro_range_multiplier: Option<f32>,
+    /// Asset specifier of a [`TacticSpec`](crate::tactic::TacticSpec) to use
+    /// in combat instead of the tactic picked from the entity's weapon
+    pub tactic: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -213,6 +216,7 @@ pub struct EntityInfo {
     pub no_flee: bool,
     pub idle_wander_factor: f32,
     pub aggro_range_multiplier: f32,
+    pub tactic: Option<String>,
     // Stats
     pub body: Body,
     pub name: Option<Content>,
@@ -258,6 +262,7 @@ impl EntityInfo {
             no_flee: false,
             idle_wander_factor: 1.0,
             aggro_range_multiplier: 1.0,
+            tactic: None,
 
             body: Body::Humanoid(humanoid::Body::random()),
             name: None,
@@ -395,11 +400,13 @@ impl EntityInfo {
             no_flee,
             idle_wander_factor,
             aggro_range_multiplier,
+            tactic,
         } = agent;
         self.has_agency = has_agency.unwrap_or(self.has_agency);
         self.no_flee = no_flee.unwrap_or(self.no_flee);
         self.idle_wander_factor = idle_wander_factor.unwrap_or(self.idle_wander_factor);
         self.aggro_range_multiplier = aggro_range_multiplier.unwrap_or(self.aggro_range_multiplier);
+        self.tactic = tactic.or(self.tactic);
         self.death_effects = (!death_effects.is_empty()).then_some(DeathEffects(death_effects));
         self.rider_effects = (!rider_effects.is_empty()).then_some(RiderEffects(rider_effects));
 
@@ -605,7 +612,7 @@ impl ChunkSupplement {
 #[cfg(test)]
 pub mod tests {
     use super::*;
-    use crate::SkillSetBuilder;
+    use crate::{SkillSetBuilder, tactic::TacticSpec};
     use hashbrown::HashMap;
 
     #[derive(Debug, Eq, Hash, PartialEq)]
@@ -777,6 +784,19 @@ pub mod tests {
         }
     }
 
+    fn validate_agent(agent: AgentConfig, config_asset: &str) {
+        if let Some(tactic) = agent.tactic {
+            let spec = Ron::<TacticSpec>::load_cloned(&tactic)
+                .unwrap_or_else(|e| {
+                    panic!("Tactic asset path invalid: \"{tactic}\", in {config_asset}: {e:?}")
+                })
+                .into_inner();
+            if let Err(e) = spec.validate() {
+                panic!("Tactic {tactic} used by {config_asset} is invalid: {e}");
+            }
+        }
+    }
+
     fn validate_rider(rider: Option<String>, config_asset: &str) {
         if let Some(rider) = rider {
             Ron::<EntityConfig>::load_cloned(&rider).unwrap_or_else(|_| {
@@ -799,7 +819,7 @@ pub mod tests {
             alignment: _, // can't fail if serialized, it's a boring enum
             rider_effects: _,
             scale,
-            agent: _,
+            agent,
         } = EntityConfig::from_asset_expect_owned(config_asset);
 
         assert!(
@@ -817,6 +837,7 @@ pub mod tests {
         validate_pets(pets, config_asset);
         validate_rider(rider, config_asset);
         validate_death_effects(death_effects, config_asset);
+        validate_agent(agent, config_asset);
     }
 
     #[test]
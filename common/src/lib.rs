@@ -39,6 +39,7 @@ pub mod lod;
 pub mod lottery;
 pub mod map;
 pub mod mounting;
+pub mod nav;
 pub mod npc;
 pub mod outcome;
 pub mod path;
@@ -51,7 +52,9 @@ pub mod slowjob;
 pub mod spiral;
 pub mod spot;
 pub mod states;
+pub mod storage;
 pub mod store;
+pub mod tactic;
 pub mod terrain;
 pub mod tether;
 pub mod time;
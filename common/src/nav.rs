@@ -0,0 +1,440 @@
+//! Hierarchical navigation over loaded terrain.
+//!
+//! Searching for a path block by block gets expensive over long distances, so
+//! each loaded chunk is summarised by a [`ChunkNav`]: the walkable surfaces of
+//! the chunk are split into connected regions, and the places where a region
+//! meets the border of the chunk are recorded as portals. Long routes are
+//! first planned over the portals of the [`NavGraph`], and then refined by a
+//! local search between consecutive portals (see [`crate::path::Chaser`]).
+
+use crate::{
+    astar::Astar,
+    terrain::{Block, CoordinateConversions, TerrainChunkSize},
+    vol::{ReadVol, RectVolSize},
+};
+use fxhash::FxBuildHasher;
+use hashbrown::{HashMap, HashSet};
+use std::ops::Range;
+use vek::*;
+
+/// Maximum height difference, in blocks, between neighbouring surfaces for
+/// them to be considered connected.
+const MAX_STEP: i32 = 2;
+/// Maximum height difference, in blocks, between a position and the surface
+/// it is considered to stand on.
+const MAX_LOCATE_DIST: i32 = 4;
+/// Maximum number of portals visited when searching for a route.
+const MAX_SEARCH_ITERS: usize = 20_000;
+const UNASSIGNED: u16 = u16::MAX;
+
+fn chunk_size() -> Vec2<i32> { TerrainChunkSize::RECT_SIZE.as_() }
+
+fn column_idx(pos: Vec2<i32>) -> usize { (pos.y * chunk_size().x + pos.x) as usize }
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Side {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Side {
+    const ALL: [Side; 4] = [Side::North, Side::East, Side::South, Side::West];
+
+    fn offset(self) -> Vec2<i32> {
+        match self {
+            Side::North => Vec2::unit_y(),
+            Side::East => Vec2::unit_x(),
+            Side::South => -Vec2::unit_y(),
+            Side::West => -Vec2::unit_x(),
+        }
+    }
+
+    fn opposite(self) -> Self {
+        match self {
+            Side::North => Side::South,
+            Side::East => Side::West,
+            Side::South => Side::North,
+            Side::West => Side::East,
+        }
+    }
+
+    /// Columns along this side of a chunk, in the order of the coordinate
+    /// they don't share.
+    fn border(self) -> Vec<Vec2<i32>> {
+        let size = chunk_size();
+        match self {
+            Side::North => (0..size.x).map(|x| Vec2::new(x, size.y - 1)).collect(),
+            Side::South => (0..size.x).map(|x| Vec2::new(x, 0)).collect(),
+            Side::East => (0..size.y).map(|y| Vec2::new(size.x - 1, y)).collect(),
+            Side::West => (0..size.y).map(|y| Vec2::new(0, y)).collect(),
+        }
+    }
+}
+
+/// A stretch of walkable surfaces along the border of a chunk through which
+/// a region can be left.
+#[derive(Clone, Debug)]
+struct Portal {
+    side: Side,
+    /// Span of the portal along its side of the chunk
+    along: Range<i32>,
+    /// Heights of the surfaces making up the portal
+    heights: Range<i32>,
+    /// Walkable position in the middle of the portal, relative to the chunk
+    pos: Vec3<i32>,
+    region: u16,
+}
+
+impl Portal {
+    /// Whether this portal leads into `other`, a portal of the neighbouring
+    /// chunk on this portal's side.
+    fn leads_into(&self, other: &Portal) -> bool {
+        other.side == self.side.opposite()
+            && self.along.start < other.along.end
+            && other.along.start < self.along.end
+            && self.heights.start < other.heights.end + MAX_STEP
+            && other.heights.start < self.heights.end + MAX_STEP
+    }
+}
+
+/// Navigation data of a single chunk.
+#[derive(Clone, Debug, Default)]
+pub struct ChunkNav {
+    /// Walkable surfaces of each column as `(z, region)` pairs, from the
+    /// lowest to the highest
+    columns: Vec<Vec<(i32, u16)>>,
+    portals: Vec<Portal>,
+}
+
+impl ChunkNav {
+    /// Builds the navigation data of a chunk. `chunk` is accessed with
+    /// positions relative to the chunk, and only the surfaces within `heights`
+    /// are considered.
+    pub fn build<V: ReadVol<Vox = Block>>(chunk: &V, heights: Range<i32>) -> Self {
+        let size = chunk_size();
+        let block = |pos| chunk.get(pos).ok().copied().unwrap_or_else(Block::empty);
+
+        let mut columns = (0..size.y)
+            .flat_map(|y| (0..size.x).map(move |x| Vec2::new(x, y)))
+            .map(|col| {
+                let mut surfaces = Vec::new();
+                let mut below = block(col.with_z(heights.start - 1));
+                let mut here = block(col.with_z(heights.start));
+                for z in heights.clone() {
+                    let above = block(col.with_z(z + 1));
+                    if !here.is_solid()
+                        && !above.is_solid()
+                        && (below.is_filled() || (here.is_liquid() && !above.is_liquid()))
+                    {
+                        surfaces.push((z, UNASSIGNED));
+                    }
+                    below = here;
+                    here = above;
+                }
+                surfaces
+            })
+            .collect::<Vec<_>>();
+
+        // Flood fill the surfaces to split them into connected regions
+        let mut regions: u16 = 0;
+        for col in 0..columns.len() {
+            for i in 0..columns[col].len() {
+                if columns[col][i].1 != UNASSIGNED {
+                    continue;
+                }
+                let region = regions.min(UNASSIGNED - 1);
+                regions = regions.saturating_add(1);
+                columns[col][i].1 = region;
+                let mut stack = vec![(col, i)];
+                while let Some((col, i)) = stack.pop() {
+                    let z = columns[col][i].0;
+                    let pos = Vec2::new(col as i32 % size.x, col as i32 / size.x);
+                    for dir in Side::ALL.map(Side::offset) {
+                        let neighbor = pos + dir;
+                        if neighbor.x < 0
+                            || neighbor.y < 0
+                            || neighbor.x >= size.x
+                            || neighbor.y >= size.y
+                        {
+                            continue;
+                        }
+                        let neighbor_col = column_idx(neighbor);
+                        for (j, (neighbor_z, neighbor_region)) in
+                            columns[neighbor_col].iter_mut().enumerate()
+                        {
+                            if *neighbor_region == UNASSIGNED && (*neighbor_z - z).abs() <= MAX_STEP
+                            {
+                                *neighbor_region = region;
+                                stack.push((neighbor_col, j));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Group the surfaces along each side of the chunk into portals
+        let mut portals = Vec::new();
+        for side in Side::ALL {
+            let finish = |(region, start, cells): (u16, i32, Vec<Vec3<i32>>)| {
+                let (min_z, max_z) = cells.iter().fold((i32::MAX, i32::MIN), |(min, max), cell| {
+                    (min.min(cell.z), max.max(cell.z))
+                });
+                Portal {
+                    side,
+                    along: start..start + cells.len() as i32,
+                    heights: min_z..max_z + 1,
+                    pos: cells[cells.len() / 2],
+                    region,
+                }
+            };
+
+            // Portals that can still be extended by the next border column
+            let mut open: Vec<(u16, i32, Vec<Vec3<i32>>)> = Vec::new();
+            for (along, col) in side.border().into_iter().enumerate() {
+                let mut extended = Vec::new();
+                for &(z, region) in &columns[column_idx(col)] {
+                    let cell = col.with_z(z);
+                    if let Some(k) = open.iter().position(|(open_region, _, cells)| {
+                        *open_region == region
+                            && cells
+                                .last()
+                                .is_some_and(|last| (last.z - z).abs() <= MAX_STEP)
+                    }) {
+                        let (region, start, mut cells) = open.swap_remove(k);
+                        cells.push(cell);
+                        extended.push((region, start, cells));
+                    } else {
+                        extended.push((region, along as i32, vec![cell]));
+                    }
+                }
+                portals.extend(open.drain(..).map(finish));
+                open = extended;
+            }
+            portals.extend(open.into_iter().map(finish));
+        }
+
+        Self { columns, portals }
+    }
+
+    /// The region of the surface closest to a position relative to the chunk.
+    fn region_at(&self, pos: Vec3<i32>) -> Option<u16> {
+        self.columns
+            .get(column_idx(pos.xy()))?
+            .iter()
+            .filter(|(z, _)| (z - pos.z).abs() <= MAX_LOCATE_DIST)
+            .min_by_key(|(z, _)| (z - pos.z).abs())
+            .map(|(_, region)| *region)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum NavNode {
+    Start,
+    Portal(Vec2<i32>, u16),
+    End,
+}
+
+/// Resource holding the navigation data of every loaded chunk.
+#[derive(Debug, Default)]
+pub struct NavGraph {
+    chunks: HashMap<Vec2<i32>, ChunkNav>,
+    /// Chunks whose navigation data needs to be built or rebuilt
+    dirty: HashSet<Vec2<i32>>,
+}
+
+impl NavGraph {
+    /// Sets the navigation data of a chunk. Chunks that were invalidated again
+    /// since [`NavGraph::take_dirty`] returned them stay dirty.
+    pub fn insert(&mut self, key: Vec2<i32>, nav: ChunkNav) { self.chunks.insert(key, nav); }
+
+    pub fn remove(&mut self, key: Vec2<i32>) {
+        self.dirty.remove(&key);
+        self.chunks.remove(&key);
+    }
+
+    /// Marks a chunk as needing to be rebuilt, e.g. because it was loaded or
+    /// blocks in it changed. Its current navigation data, if any, is used
+    /// until then.
+    pub fn invalidate(&mut self, key: Vec2<i32>) { self.dirty.insert(key); }
+
+    /// Takes up to `max` of the chunks that need to be rebuilt.
+    pub fn take_dirty(&mut self, max: usize) -> Vec<Vec2<i32>> {
+        let keys = self.dirty.iter().take(max).copied().collect::<Vec<_>>();
+        for key in &keys {
+            self.dirty.remove(key);
+        }
+        keys
+    }
+
+    /// Plans a route between two walkable positions, returning the positions
+    /// of the portals to pass through on the way. The list is empty if `end`
+    /// can be reached without leaving the region `start` is in.
+    ///
+    /// Returns `None` if either position isn't on a known walkable surface or
+    /// no route could be found.
+    pub fn find_route(&self, start: Vec3<i32>, end: Vec3<i32>) -> Option<Vec<Vec3<i32>>> {
+        let locate = |wpos: Vec3<i32>| {
+            let key = wpos.xy().wpos_to_cpos();
+            let local = wpos - key.cpos_to_wpos().with_z(0);
+            Some((key, self.chunks.get(&key)?.region_at(local)?))
+        };
+        let (start_key, start_region) = locate(start)?;
+        let (end_key, end_region) = locate(end)?;
+        if start_key == end_key && start_region == end_region {
+            return Some(Vec::new());
+        }
+
+        let portal = |key: Vec2<i32>, idx: u16| &self.chunks[&key].portals[idx as usize];
+        let node_pos = |node: &NavNode| match node {
+            NavNode::Start => start,
+            NavNode::Portal(key, idx) => key.cpos_to_wpos().with_z(0) + portal(*key, *idx).pos,
+            NavNode::End => end,
+        };
+        let cost = |a: &NavNode, b: &NavNode| node_pos(a).as_::<f32>().distance(node_pos(b).as_());
+        let heuristic = |node: &NavNode| node_pos(node).as_::<f32>().distance(end.as_());
+        let region_portals = |key: Vec2<i32>, region: u16| {
+            self.chunks[&key]
+                .portals
+                .iter()
+                .enumerate()
+                .filter(move |(_, portal)| portal.region == region)
+                .map(move |(idx, _)| NavNode::Portal(key, idx as u16))
+        };
+        let neighbors = |node: &NavNode| {
+            let node = *node;
+            let mut neighbors = Vec::new();
+            match node {
+                NavNode::Start => neighbors.extend(region_portals(start_key, start_region)),
+                NavNode::Portal(key, idx) => {
+                    let from = portal(key, idx);
+                    // Leave the chunk
+                    let next_key = key + from.side.offset();
+                    if let Some(next) = self.chunks.get(&next_key) {
+                        neighbors.extend(
+                            next.portals
+                                .iter()
+                                .enumerate()
+                                .filter(|(_, to)| from.leads_into(to))
+                                .map(|(next_idx, _)| NavNode::Portal(next_key, next_idx as u16)),
+                        );
+                    }
+                    // Cross the chunk to another portal of the same region
+                    neighbors.extend(
+                        region_portals(key, from.region)
+                            .filter(|other| *other != NavNode::Portal(key, idx)),
+                    );
+                    if key == end_key && from.region == end_region {
+                        neighbors.push(NavNode::End);
+                    }
+                },
+                NavNode::End => {},
+            }
+            neighbors
+                .into_iter()
+                .map(move |neighbor| (neighbor, cost(&node, &neighbor)))
+        };
+
+        let mut astar = Astar::new(MAX_SEARCH_ITERS, NavNode::Start, FxBuildHasher::default());
+        let (path, _) = astar
+            .poll(MAX_SEARCH_ITERS, heuristic, neighbors, |node| {
+                *node == NavNode::End
+            })
+            .into_path()?;
+
+        let mut waypoints = path
+            .iter()
+            .filter(|node| matches!(node, NavNode::Portal(..)))
+            .map(node_pos)
+            .collect::<Vec<_>>();
+        // Portals on either side of a chunk border are next to each other, only one
+        // of them is needed
+        waypoints.dedup_by(|next, prev| next.as_::<f32>().distance_squared(prev.as_()) < 4.0);
+        Some(waypoints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        terrain::{BlockKind, SpriteKind, TerrainChunk, TerrainChunkMeta},
+        vol::WriteVol,
+    };
+
+    const GROUND: i32 = 10;
+
+    fn flat_chunk() -> TerrainChunk {
+        TerrainChunk::new(
+            GROUND,
+            Block::new(BlockKind::Rock, Rgb::zero()),
+            Block::air(SpriteKind::Empty),
+            TerrainChunkMeta::void(),
+        )
+    }
+
+    /// A chunk split in two by a wall too high to climb, running from south to
+    /// north in the middle of the chunk.
+    fn walled_chunk() -> TerrainChunk {
+        let mut chunk = flat_chunk();
+        for y in 0..chunk_size().y {
+            for z in GROUND..GROUND + 8 {
+                chunk
+                    .set(
+                        Vec3::new(16, y, z),
+                        Block::new(BlockKind::Rock, Rgb::zero()),
+                    )
+                    .unwrap();
+            }
+        }
+        chunk
+    }
+
+    fn build(chunk: &TerrainChunk) -> ChunkNav {
+        ChunkNav::build(chunk, chunk.get_min_z()..chunk.get_max_z() + 1)
+    }
+
+    #[test]
+    fn regions_and_portals() {
+        let nav = build(&flat_chunk());
+        assert!(nav.columns.iter().all(|col| col == &[(GROUND, 0)]));
+        assert_eq!(nav.portals.len(), 4);
+
+        let nav = build(&walled_chunk());
+        let west = nav.region_at(Vec3::new(0, 0, GROUND)).unwrap();
+        let east = nav.region_at(Vec3::new(31, 0, GROUND)).unwrap();
+        assert_ne!(west, east);
+        // The top of the wall is a region of its own, reachable from the north and
+        // south sides
+        assert_eq!(nav.portals.len(), 2 + 2 * 3);
+    }
+
+    #[test]
+    fn routes_around_walls() {
+        let mut graph = NavGraph::default();
+        // A wall across the middle of the map, with a gap in the northern chunk
+        for y in 0..3 {
+            for x in 0..3 {
+                let chunk = if x == 1 && y < 2 {
+                    walled_chunk()
+                } else {
+                    flat_chunk()
+                };
+                graph.insert(Vec2::new(x, y), build(&chunk));
+            }
+        }
+
+        let start = Vec3::new(32 + 4, 4, GROUND);
+        let end = Vec3::new(32 + 28, 4, GROUND);
+        let route = graph.find_route(start, end).unwrap();
+        assert!(route.iter().any(|waypoint| waypoint.y >= 64));
+
+        assert_eq!(
+            graph.find_route(start, start + Vec3::unit_x()),
+            Some(Vec::new())
+        );
+        assert_eq!(graph.find_route(start, Vec3::new(200, 200, GROUND)), None);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
     astar::{Astar, PathResult},
+    nav::NavGraph,
     resources::Time,
     terrain::Block,
     vol::{BaseVol, ReadVol},
@@ -83,7 +84,7 @@ impl From<Path<Vec3<i32>>> for Route {
     fn from(path: Path<Vec3<i32>>) -> Self { Self { path, next_idx: 0 } }
 }
 
-pub struct TraversalConfig {
+pub struct TraversalConfig<'a> {
     /// The distance to a node at which node is considered visited.
     pub node_tolerance: f32,
     /// The slowdown factor when following corners.
@@ -103,6 +104,9 @@ pub struct TraversalConfig {
     pub vectored_propulsion: bool,
     /// Whether chunk containing target position is currently loaded
     pub is_target_loaded: bool,
+    /// Used to plan routes to far away targets before searching for a path
+    /// block by block.
+    pub nav_graph: Option<&'a NavGraph>,
 }
 
 const DIAGONALS: [Vec2<i32>; 8] = [
@@ -137,7 +141,7 @@ impl Route {
         vol: &V,
         pos: Vec3<f32>,
         vel: Vec3<f32>,
-        traversal_cfg: &TraversalConfig,
+        traversal_cfg: &TraversalConfig<'_>,
     ) -> Result<(Vec3<f32>, f32), TraverseStop>
     where
         V: BaseVol<Vox = Block> + ReadVol,
@@ -484,6 +488,12 @@ pub struct Chaser {
 
     /// (position, requested walk dir)
     recent_states: VecDeque<(Time, Vec3<f32>, Vec3<f32>)>,
+
+    /// Portals to pass through on the way to a far away target, planned over
+    /// the [`NavGraph`].
+    ///
+    /// The `Vec3` is the target the route was planned for.
+    nav_route: Option<(VecDeque<Vec3<i32>>, Vec3<f32>)>,
 }
 
 impl Chaser {
@@ -585,6 +595,58 @@ impl Chaser {
         self.last_search_tgt = None;
         self.path_length = Default::default();
         self.flee_from = None;
+        self.nav_route = None;
+    }
+
+    /// Picks the position to search a path towards: far away targets are
+    /// planned for over the navigation graph first, so that only the way to
+    /// the next portal on the route needs to be searched block by block.
+    fn nav_waypoint(
+        &mut self,
+        pos: Vec3<f32>,
+        tgt: Vec3<f32>,
+        traversal_cfg: &TraversalConfig<'_>,
+    ) -> Vec3<f32> {
+        /// Targets closer than this are searched for directly.
+        const MIN_NAV_DIST: f32 = 48.0;
+        /// How far the target can move before the route is planned again.
+        const REPLAN_DIST: f32 = 16.0;
+
+        let Some(nav_graph) = traversal_cfg.nav_graph else {
+            return tgt;
+        };
+        if pos.distance_squared(tgt) < MIN_NAV_DIST.powi(2) {
+            self.nav_route = None;
+            return tgt;
+        }
+
+        if self
+            .nav_route
+            .as_ref()
+            .is_none_or(|(_, planned_tgt)| planned_tgt.distance_squared(tgt) > REPLAN_DIST.powi(2))
+        {
+            let waypoints = nav_graph
+                .find_route(pos.map(|e| e.floor() as i32), tgt.map(|e| e.floor() as i32))
+                .unwrap_or_default();
+            self.nav_route = Some((waypoints.into(), tgt));
+        }
+
+        let Some((waypoints, _)) = &mut self.nav_route else {
+            return tgt;
+        };
+        // Skip the waypoints that were already reached
+        while waypoints.front().is_some_and(|waypoint| {
+            let waypoint = waypoint.as_::<f32>() + Vec3::new(0.5, 0.5, 0.0);
+            pos.xy().distance_squared(waypoint.xy()) < (traversal_cfg.node_tolerance + 1.0).powi(2)
+                && (pos.z - waypoint.z).abs() < 3.0
+        }) {
+            waypoints.pop_front();
+            // The current route leads to the waypoint that was just reached
+            self.route = None;
+        }
+        waypoints.front().map_or(tgt, |waypoint| {
+            waypoint.as_::<f32>() + Vec3::new(0.5, 0.5, 0.0)
+        })
     }
 
     /// Returns bearing and speed
@@ -596,7 +658,7 @@ impl Chaser {
         pos: Vec3<f32>,
         vel: Vec3<f32>,
         tgt: Vec3<f32>,
-        traversal_cfg: TraversalConfig,
+        traversal_cfg: TraversalConfig<'_>,
         time: &Time,
     ) -> Option<(Vec3<f32>, f32, bool)>
     where
@@ -612,6 +674,7 @@ impl Chaser {
             return None;
         }
 
+        let tgt = self.nav_waypoint(pos, tgt, &traversal_cfg);
         let d = tgt.distance_squared(pos);
 
         // Check if the current route is no longer valid.
@@ -728,7 +791,8 @@ impl Chaser {
                                 self.astar = None;
                             }
                         },
-                        PathState::Pending | PathState::Path => {},
+                        // Reached waypoints are skipped by `nav_waypoint`
+                        PathState::Path | PathState::Pending => {},
                     },
                 }
             }
@@ -811,7 +875,7 @@ fn find_path<V>(
     vol: &V,
     startf: Vec3<f32>,
     endf: Vec3<f32>,
-    traversal_cfg: &TraversalConfig,
+    traversal_cfg: &TraversalConfig<'_>,
     path_length: PathLength,
     flee_from: Option<Vec3<f32>>,
 ) -> PathResult<Vec3<i32>>
@@ -1064,7 +1128,7 @@ fn find_air_path<V>(
     vol: &V,
     startf: Vec3<f32>,
     endf: Vec3<f32>,
-    traversal_cfg: &TraversalConfig,
+    traversal_cfg: &TraversalConfig<'_>,
 ) -> (Option<Path<Vec3<i32>>>, bool)
 where
     V: BaseVol<Vox = Block> + ReadVol,
@@ -493,6 +493,30 @@ pub enum Profession {
     Captain,
 }
 
+/// How a site regards a player, from the worst to the best standing.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Standing {
+    /// Guards attack the player on sight.
+    Hostile,
+    /// The player is refused quests and hirelings.
+    Distrusted,
+    Neutral,
+    Respected,
+    Honoured,
+}
+
+impl Standing {
+    pub fn localization_key(&self) -> &'static str {
+        match self {
+            Self::Hostile => "hud-reputation-hostile",
+            Self::Distrusted => "hud-reputation-distrusted",
+            Self::Neutral => "hud-reputation-neutral",
+            Self::Respected => "hud-reputation-respected",
+            Self::Honoured => "hud-reputation-honoured",
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WorldSettings {
     pub start_time: f64,
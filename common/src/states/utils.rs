@@ -1267,8 +1267,9 @@ pub fn handle_manipulate_loadout(
             let inv_manip = InventoryManip::Use(slot);
             output_events.emit_server(InventoryManipEvent(data.entity, inv_manip));
         },
-        InventoryAction::Use(Slot::Overflow(_)) => {
-            // Items in overflow slots cannot be used until moved to a real slot
+        InventoryAction::Use(Slot::Overflow(_) | Slot::Stash(_)) => {
+            // Items in overflow or stash slots cannot be used until moved to a
+            // real slot
         },
         InventoryAction::ToggleSpriteLight(pos, enable) => {
             if matches!(pos.kind, Volume::Terrain) {
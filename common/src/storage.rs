@@ -0,0 +1,53 @@
+//! Player-placed storage containers.
+//!
+//! The contents of these containers are kept by the server, keyed by the
+//! position of their sprite (see [`SpriteKind::is_storage_container`]), so
+//! that they survive chunk unloads and server restarts. Clients only ever see
+//! the contents of the container they currently have open.
+//!
+//! [`SpriteKind::is_storage_container`]: crate::terrain::SpriteKind::is_storage_container
+
+use crate::comp::inventory::{InvSlot, slot::InvSlotId};
+use serde::{Deserialize, Serialize};
+use vek::*;
+
+/// Number of slots of a storage container.
+pub const STORAGE_SLOTS: usize = 27;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StorageAction {
+    /// Place the storage container item in the given inventory slot into the
+    /// world in front of the player.
+    Place(InvSlotId),
+    /// Open the storage container at the given position.
+    Open(Vec3<i32>),
+    /// Close the currently open storage container.
+    Close,
+    /// Swap an inventory slot with a slot of the open storage container.
+    Swap {
+        inv_slot: InvSlotId,
+        storage_slot: usize,
+    },
+    /// Swap two slots of the open storage container.
+    Move(usize, usize),
+    /// Pick the open storage container back up. Only the owner can do this,
+    /// and only once the container is empty.
+    PickUp,
+}
+
+/// The storage container a client currently has open.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OpenStorage {
+    pub pos: Vec3<i32>,
+    pub slots: Vec<InvSlot>,
+    /// Whether the player viewing the container also owns it.
+    pub is_owner: bool,
+}
+
+impl OpenStorage {
+    pub fn get(&self, slot: usize) -> Option<&crate::comp::Item> {
+        self.slots.get(slot).and_then(Option::as_ref)
+    }
+
+    pub fn is_empty(&self) -> bool { self.slots.iter().all(Option::is_none) }
+}
@@ -0,0 +1,342 @@
+//! Data-driven combat tactics for agents.
+//!
+//! Instead of being hard-coded in the agent's attack handlers, the behaviour
+//! of a creature in combat can be described by a [`TacticSpec`] asset,
+//! referenced by the `agent.tactic` field of its entity config. By convention,
+//! tactic assets live under `common.tactics` mirroring the path of the entity
+//! config they belong to.
+//!
+//! A tactic is made of phases. The first phase is active when combat starts,
+//! and the agent advances to a later phase as soon as all the conditions to
+//! enter it hold, e.g. when its health drops below some threshold. Phases are
+//! never left for an earlier one.
+//!
+//! On each tick, the agent uses the first action of its phase whose conditions
+//! hold and that is not on cooldown, and then moves according to the phase's
+//! movement.
+
+use crate::comp::agent::ActionState;
+use serde::Deserialize;
+
+/// The number of actions with a cooldown that a single phase can have, see
+/// [`crate::comp::agent::ActionState::cooldowns`].
+pub const MAX_COOLDOWNS_PER_PHASE: usize = 8;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TacticSpec {
+    pub phases: Vec<TacticPhase>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TacticPhase {
+    /// Conditions that all need to hold to enter this phase, ignored for the
+    /// first phase.
+    #[serde(default)]
+    pub enter_when: Vec<TacticCondition>,
+    #[serde(default)]
+    pub movement: TacticMovement,
+    /// Actions in order of priority.
+    pub actions: Vec<TacticAction>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TacticAction {
+    pub input: TacticInput,
+    /// Conditions that all need to hold for this action to be used.
+    #[serde(default)]
+    pub when: Vec<TacticCondition>,
+    /// Seconds after this action was started before it can be started again.
+    #[serde(default)]
+    pub cooldown: f32,
+    /// Seconds for which this action keeps being used once started, regardless
+    /// of its conditions and of the other actions. Used for abilities that
+    /// need their input to be held, such as beams or charges.
+    #[serde(default)]
+    pub sustain: f32,
+    /// Whether the ability is aimed at the position of the target, for
+    /// abilities that summon something at a selected location.
+    #[serde(default)]
+    pub at_target: bool,
+    /// Whether the agent stops moving while using this action.
+    #[serde(default)]
+    pub stand_still: bool,
+}
+
+/// The input pressed for an action, abilities are indexed as in the ability
+/// set of the creature's weapon.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+pub enum TacticInput {
+    Primary,
+    Secondary,
+    Ability(usize),
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub enum TacticCondition {
+    /// The target is closer than this distance, in blocks.
+    TargetWithin(f32),
+    /// The target is further away than this distance, in blocks.
+    TargetBeyond(f32),
+    /// The target is closer than this many times the attack range of the
+    /// agent.
+    TargetWithinReach(f32),
+    /// The target is further away than this many times the attack range of the
+    /// agent.
+    TargetBeyondReach(f32),
+    /// The target is at most this many degrees away from where the agent is
+    /// looking.
+    TargetInFront(f32),
+    /// The target is more than this many blocks above the agent.
+    TargetAbove(f32),
+    /// Health fraction of the agent is below this value.
+    HealthBelow(f32),
+    /// Health fraction of the agent is above this value.
+    HealthAbove(f32),
+    /// Health fraction of the target is below this value.
+    TargetHealthBelow(f32),
+    /// The agent can see its target.
+    LineOfSight,
+    /// The combo counter of the agent is at least this value.
+    ComboAtLeast(u32),
+    /// The agent has at least this much energy.
+    EnergyAtLeast(f32),
+    /// The agent has been fighting for more than this many seconds.
+    CombatTimeAbove(f32),
+    /// The current phase was entered more than this many seconds ago.
+    PhaseTimeAbove(f32),
+    Not(Box<TacticCondition>),
+    /// At least one of the conditions holds.
+    Any(Vec<TacticCondition>),
+}
+
+#[derive(Copy, Clone, Debug, Default, Deserialize)]
+pub enum TacticMovement {
+    /// Path towards the target.
+    #[default]
+    Chase,
+    /// Path towards the target, slowing down to this fraction of the normal
+    /// speed once in attack range.
+    ChaseSlowly(f32),
+    /// Stay in place.
+    Stand,
+    /// Path towards the target until this many blocks away from it, and back
+    /// off if it gets closer.
+    KeepDistance(f32),
+}
+
+impl TacticSpec {
+    /// Checks invariants that can't be expressed by the format itself.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.phases.is_empty() {
+            return Err("A tactic needs at least one phase".to_string());
+        }
+        for (i, phase) in self.phases.iter().enumerate() {
+            if phase.actions.is_empty() {
+                return Err(format!("Phase {i} has no actions"));
+            }
+            let cooldowns = phase
+                .actions
+                .iter()
+                .filter(|action| action.cooldown > 0.0)
+                .count();
+            if cooldowns > MAX_COOLDOWNS_PER_PHASE {
+                return Err(format!(
+                    "Phase {i} has {cooldowns} actions with a cooldown, at most \
+                     {MAX_COOLDOWNS_PER_PHASE} are supported"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Advances an agent through the phases of the tactic and picks the action
+    /// it uses this tick, if any, along with its current phase.
+    ///
+    /// The progress of the agent is kept in `state`. `holds` tells whether a
+    /// condition holds, given how long the agent has been fighting and how
+    /// long ago the current phase was entered.
+    pub fn next_action(
+        &self,
+        state: &mut ActionState,
+        dt: f32,
+        holds: impl Fn(&TacticCondition, f32, f32) -> bool,
+    ) -> Option<(&TacticPhase, Option<&TacticAction>)> {
+        enum ActionStateTimers {
+            CombatTime = 0,
+        }
+
+        enum ActionStateFCounters {
+            PhaseStart = 0,
+            SustainStart = 1,
+        }
+
+        enum ActionStateICounters {
+            Phase = 0,
+            /// Index of the sustained action plus one, zero if there is none
+            SustainedAction = 1,
+        }
+
+        state.timers[ActionStateTimers::CombatTime as usize] += dt;
+        let combat_time = state.timers[ActionStateTimers::CombatTime as usize];
+
+        // Advance to the last phase that can be entered, phases are never left for
+        // an earlier one
+        let mut phase = (state.int_counters[ActionStateICounters::Phase as usize] as usize)
+            .min(self.phases.len().saturating_sub(1));
+        let phase_time = combat_time - state.counters[ActionStateFCounters::PhaseStart as usize];
+        if let Some(next_phase) = (phase + 1..self.phases.len()).rev().find(|i| {
+            self.phases[*i]
+                .enter_when
+                .iter()
+                .all(|condition| holds(condition, combat_time, phase_time))
+        }) {
+            phase = next_phase;
+            state.int_counters[ActionStateICounters::Phase as usize] = phase as u8;
+            state.counters[ActionStateFCounters::PhaseStart as usize] = combat_time;
+            state.int_counters[ActionStateICounters::SustainedAction as usize] = 0;
+            state.cooldowns = Default::default();
+        }
+        let phase_spec = self.phases.get(phase)?;
+        let phase_time = combat_time - state.counters[ActionStateFCounters::PhaseStart as usize];
+
+        let sustained = state.int_counters[ActionStateICounters::SustainedAction as usize]
+            .checked_sub(1)
+            .map(usize::from)
+            .filter(|i| {
+                phase_spec.actions.get(*i).is_some_and(|action| {
+                    combat_time - state.counters[ActionStateFCounters::SustainStart as usize]
+                        < action.sustain
+                })
+            });
+
+        if let Some(i) = sustained {
+            return Some((phase_spec, phase_spec.actions.get(i)));
+        }
+
+        state.int_counters[ActionStateICounters::SustainedAction as usize] = 0;
+        // Actions with a cooldown are given a slot in the order they appear
+        let mut cooldown_slots = 0..;
+        let chosen = phase_spec
+            .actions
+            .iter()
+            .enumerate()
+            .map(|(i, action)| {
+                let slot = (action.cooldown > 0.0)
+                    .then(|| cooldown_slots.next())
+                    .flatten();
+                (i, action, slot)
+            })
+            .find(|(_, action, slot)| {
+                slot.is_none_or(|slot| {
+                    state
+                        .cooldowns
+                        .get(slot)
+                        .is_none_or(|ready_at| combat_time >= *ready_at)
+                }) && action
+                    .when
+                    .iter()
+                    .all(|condition| holds(condition, combat_time, phase_time))
+            });
+
+        if let Some((i, action, slot)) = chosen {
+            if let Some(ready_at) = slot.and_then(|slot| state.cooldowns.get_mut(slot)) {
+                *ready_at = combat_time + action.cooldown;
+            }
+            if action.sustain > 0.0 {
+                state.int_counters[ActionStateICounters::SustainedAction as usize] = (i + 1) as u8;
+                state.counters[ActionStateFCounters::SustainStart as usize] = combat_time;
+            }
+        }
+        Some((phase_spec, chosen.map(|(_, action, _)| action)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assets::{self, AssetExt, Ron};
+
+    fn action(input: TacticInput, when: Vec<TacticCondition>) -> TacticAction {
+        TacticAction {
+            input,
+            when,
+            cooldown: 0.0,
+            sustain: 0.0,
+            at_target: false,
+            stand_still: false,
+        }
+    }
+
+    #[test]
+    fn interpreter_follows_phases_cooldowns_and_sustain() {
+        let tactic = TacticSpec {
+            phases: vec![
+                TacticPhase {
+                    enter_when: Vec::new(),
+                    movement: TacticMovement::Chase,
+                    actions: vec![
+                        TacticAction {
+                            cooldown: 5.0,
+                            sustain: 2.0,
+                            ..action(TacticInput::Ability(0), Vec::new())
+                        },
+                        action(TacticInput::Primary, vec![TacticCondition::TargetWithin(
+                            3.0,
+                        )]),
+                    ],
+                },
+                TacticPhase {
+                    enter_when: vec![TacticCondition::HealthBelow(0.5)],
+                    movement: TacticMovement::Stand,
+                    actions: vec![action(TacticInput::Secondary, Vec::new())],
+                },
+            ],
+        };
+        assert!(tactic.validate().is_ok());
+
+        let mut state = ActionState::default();
+        // Health and target distance of the fake agent, conditions are evaluated
+        // against these
+        let input = |state: &mut ActionState, health: f32, distance: f32| {
+            tactic
+                .next_action(state, 1.0, |condition, _, _| match condition {
+                    TacticCondition::TargetWithin(d) => distance < *d,
+                    TacticCondition::HealthBelow(h) => health < *h,
+                    _ => false,
+                })
+                .and_then(|(_, action)| action)
+                .map(|action| action.input)
+        };
+
+        // The ability is used first, and kept in use while sustained
+        assert_eq!(input(&mut state, 1.0, 1.0), Some(TacticInput::Ability(0)));
+        assert_eq!(input(&mut state, 1.0, 1.0), Some(TacticInput::Ability(0)));
+        // It's then on cooldown, so the next action whose conditions hold is used
+        assert_eq!(input(&mut state, 1.0, 1.0), Some(TacticInput::Primary));
+        assert_eq!(input(&mut state, 1.0, 10.0), None);
+        assert_eq!(input(&mut state, 1.0, 1.0), Some(TacticInput::Primary));
+        // Until the cooldown is over
+        assert_eq!(input(&mut state, 1.0, 1.0), Some(TacticInput::Ability(0)));
+
+        // Entering the second phase, which is never left again
+        assert_eq!(input(&mut state, 0.4, 1.0), Some(TacticInput::Secondary));
+        assert_eq!(input(&mut state, 1.0, 1.0), Some(TacticInput::Secondary));
+    }
+
+    #[test]
+    fn validate_all_tactics() {
+        let tactics = assets::load_rec_dir::<Ron<TacticSpec>>("common.tactics")
+            .expect("Failed to load tactics");
+        for id in tactics.read().ids() {
+            let tactic = Ron::<TacticSpec>::load_cloned(id)
+                .unwrap_or_else(|e| panic!("{id}: {e:?}"))
+                .into_inner();
+            if let Err(e) = tactic.validate() {
+                panic!("{id}: {e}");
+            }
+        }
+    }
+}
@@ -530,7 +530,8 @@ impl Block {
                 | SpriteKind::BoneKeyDoor
                 | SpriteKind::OneWayWall
                 | SpriteKind::KeyholeBars
-                | SpriteKind::DoorBars => None,
+                | SpriteKind::DoorBars
+                | SpriteKind::StorageChest => None,
                 SpriteKind::Anvil
                 | SpriteKind::Cauldron
                 | SpriteKind::CookingPot
@@ -149,6 +149,8 @@ sprites! {
         // Uncollectable containers
         Barrel            = 0x30,
         CrateBlock        = 0x31,
+        // Player-placed container, contents are stored by the server
+        StorageChest      = 0x32,
         // Wall
         HangingBasket     = 0x50,
         HangingSign       = 0x51,
@@ -796,6 +798,7 @@ impl SpriteKind {
             SpriteKind::HotSurface => 0.01,
             SpriteKind::Barrel => 1.0,
             SpriteKind::CrateBlock => 1.0,
+            SpriteKind::StorageChest => 1.09,
             SpriteKind::BarrelWoodWater | SpriteKind::BarrelWoodCoal => 1.545,
             SpriteKind::LanternpostWoodLantern | SpriteKind::LanternpostWoodUpper => 2.000,
             SpriteKind::LanternpostWoodBase => 3.000,
@@ -1132,6 +1135,11 @@ impl SpriteKind {
         )
     }
 
+    /// Is this a player-placed container whose contents are stored by the
+    /// server, see [`crate::storage`].
+    #[inline]
+    pub fn is_storage_container(&self) -> bool { matches!(self, SpriteKind::StorageChest) }
+
     #[inline]
     pub fn is_mountable(&self) -> bool { self.mount_offset().is_some() }
 
@@ -373,9 +373,27 @@ pub struct SiteInformation {
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct SitePrices {
     pub values: HashMap<Good, f32>,
+    /// How much better (or worse, if negative) than usual the deals are that
+    /// the site offers the trader, depending on the trader's reputation.
+    #[serde(default)]
+    pub goodwill: f32,
 }
 
 impl SitePrices {
+    /// The factor applied to the value of goods that the trader offers to the
+    /// site: the trade margin of the good, adjusted by the site's goodwill.
+    ///
+    /// Goodwill makes the trader's coins worth more and their goods fetch
+    /// better prices, but never lets goods be sold back for more than they were
+    /// bought for.
+    pub fn margin(&self, good: Good) -> f32 {
+        let goodwill = 1.0 + self.goodwill;
+        match good {
+            Good::Coin => goodwill,
+            _ => (good.trade_margin() * goodwill).min(1.0 / goodwill),
+        }
+    }
+
     pub fn balance(
         &self,
         offers: &[HashMap<InvSlotId, u32>; 2],
@@ -396,7 +414,7 @@ impl SitePrices {
                                 .map(|(amount2, material)| {
                                     self.values.get(material).copied().unwrap_or_default()
                                         * *amount2
-                                        * (if reduce { material.trade_margin() } else { 1.0 })
+                                        * (if reduce { self.margin(*material) } else { 1.0 })
                                 })
                                 .sum::<f32>()
                                 * (*amount as f32),
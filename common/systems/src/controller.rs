@@ -26,6 +26,7 @@ event_emitters! {
         initiate_invite: event::InitiateInviteEvent,
         invite_response: event::InviteResponseEvent,
         process_trade_action: event::ProcessTradeActionEvent,
+        storage_action: event::StorageActionEvent,
         inventory_manip: event::InventoryManipEvent,
         group_manip: event::GroupManipEvent,
         respawn: event::RespawnEvent,
@@ -125,6 +126,9 @@ impl<'a> System<'a> for Sys {
                         ControlEvent::PerformTradeAction(trade_id, action) => {
                             emitters.emit(event::ProcessTradeActionEvent(entity, trade_id, action));
                         },
+                        ControlEvent::StorageAction(action) => {
+                            emitters.emit(event::StorageActionEvent(entity, action));
+                        },
                         ControlEvent::InventoryEvent(event) => {
                             emitters.emit(event::InventoryManipEvent(entity, event.into()));
                         },
@@ -0,0 +1,760 @@
+//! Inspect a saved rtsim data file without starting a server.
+//!
+//! The save can be found in the server's data directory, under
+//! `rtsim/data.dat`. Each command prints a table, which can also be exported
+//! as CSV or JSON for further analysis:
+//!
+//! ```text
+//! cargo run --bin rtsim_inspect --features bin_inspect -- <FILE> --format csv npcs --query guard
+//! ```
+
+use clap::{Parser, Subcommand, ValueEnum};
+use common::{
+    npc::NPC_NAMES,
+    rtsim::{Actor, FactionId, Role, SiteId},
+};
+use hashbrown::HashMap;
+use serde_json::Value;
+use std::{
+    error::Error,
+    fs::File,
+    io::{self, BufReader, Write},
+    path::PathBuf,
+};
+use veloren_rtsim::data::{
+    Data, ReadError, ReportKind, Sentiments, architect::TrackedPopulation, npc::Job,
+    quest::QuestKind, sentiment::Target,
+};
+
+const DAY: f64 = 60.0 * 60.0 * 24.0;
+
+#[derive(Parser)]
+struct Cli {
+    /// The rtsim data file to inspect
+    file: PathBuf,
+    #[arg(short, long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+    /// Write the output to this file instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum Format {
+    Table,
+    Csv,
+    Json,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// General information about the save
+    Summary,
+    /// List NPCs
+    Npcs {
+        /// Only list NPCs living in the site with this uid
+        #[arg(long)]
+        home: Option<u64>,
+        /// Only list NPCs of this faction, e.g: `1v1`
+        #[arg(long)]
+        faction: Option<String>,
+        /// Comma-separated terms that all need to match the profession, role,
+        /// body or uid of an NPC, like `/rtsim_npc`
+        #[arg(short, long)]
+        query: Option<String>,
+        /// List at most this many NPCs
+        #[arg(short, long)]
+        limit: Option<usize>,
+    },
+    /// List sites
+    Sites {
+        /// Only list sites of this faction, e.g: `1v1`
+        #[arg(long)]
+        faction: Option<String>,
+    },
+    /// List factions
+    Factions,
+    /// List the diplomatic relations between factions
+    Relations {
+        /// Only list the relations of this faction, e.g: `1v1`
+        #[arg(long)]
+        faction: Option<String>,
+    },
+    /// List quests
+    Quests {
+        /// Only list quests that haven't been resolved yet
+        #[arg(long)]
+        unresolved: bool,
+    },
+    /// List reports
+    Reports {
+        /// Only list reports known by the site with this uid
+        #[arg(long)]
+        site: Option<u64>,
+    },
+    /// The number of living NPCs and recorded deaths of each population
+    /// tracked by the architect
+    Population,
+    /// The deaths recorded by the architect, per in-game day and population
+    Deaths {
+        /// Only list deaths of this population, e.g: `Guards`
+        #[arg(long)]
+        population: Option<String>,
+    },
+    /// List the sentiments held by NPCs and factions
+    Sentiments {
+        /// Only list sentiments held by the NPC with this uid
+        #[arg(long)]
+        npc: Option<u64>,
+        /// Only list sentiments held by this faction, e.g: `1v1`
+        #[arg(long)]
+        faction: Option<String>,
+        /// Only list sentiments at least this strong, positive or negative
+        #[arg(long, default_value_t = 0.0)]
+        min: f32,
+    },
+}
+
+struct Table {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    fn new(headers: Vec<&'static str>) -> Self {
+        Self {
+            headers,
+            rows: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, row: Vec<Value>) {
+        debug_assert_eq!(row.len(), self.headers.len());
+        self.rows.push(row);
+    }
+
+    fn write(&self, format: Format, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        match format {
+            Format::Table => {
+                let cells = self
+                    .rows
+                    .iter()
+                    .map(|row| row.iter().map(cell_text).collect::<Vec<_>>())
+                    .collect::<Vec<_>>();
+                let widths = self
+                    .headers
+                    .iter()
+                    .enumerate()
+                    .map(|(i, header)| {
+                        cells
+                            .iter()
+                            .map(|row| row[i].chars().count())
+                            .chain([header.len()])
+                            .max()
+                            .unwrap_or(0)
+                    })
+                    .collect::<Vec<_>>();
+                let separator = widths.iter().map(|width| "-".repeat(*width));
+                write_row(out, &widths, self.headers.iter().map(|h| h.to_string()))?;
+                write_row(out, &widths, separator)?;
+                for row in cells {
+                    write_row(out, &widths, row.into_iter())?;
+                }
+                writeln!(out, "({} rows)", self.rows.len())?;
+            },
+            Format::Csv => {
+                let mut wtr = csv::Writer::from_writer(out);
+                wtr.write_record(&self.headers)?;
+                for row in &self.rows {
+                    wtr.write_record(row.iter().map(cell_text))?;
+                }
+                wtr.flush()?;
+            },
+            Format::Json => {
+                let objects = self
+                    .rows
+                    .iter()
+                    .map(|row| {
+                        Value::Object(
+                            self.headers
+                                .iter()
+                                .map(|header| header.to_string())
+                                .zip(row.iter().cloned())
+                                .collect(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                serde_json::to_writer_pretty(&mut *out, &objects)?;
+                writeln!(out)?;
+            },
+        }
+        Ok(())
+    }
+}
+
+fn write_row(
+    out: &mut dyn Write,
+    widths: &[usize],
+    row: impl Iterator<Item = String>,
+) -> io::Result<()> {
+    let line = row
+        .zip(widths)
+        .map(|(cell, width)| format!("{cell:width$}"))
+        .collect::<Vec<_>>()
+        .join("  ");
+    writeln!(out, "{}", line.trim_end())
+}
+
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+fn faction_name(faction: FactionId) -> String { format!("{faction:?}") }
+
+/// Whether the faction matches the faction given on the command line, either
+/// as its full name (`FactionId(1v1)`) or just its key (`1v1`).
+fn faction_matches(faction: Option<FactionId>, filter: &Option<String>) -> bool {
+    filter.as_ref().is_none_or(|filter| {
+        faction.is_some_and(|faction| {
+            let name = faction_name(faction);
+            name == *filter || name == format!("FactionId({filter})")
+        })
+    })
+}
+
+fn site_uid(data: &Data, site: Option<SiteId>) -> Value {
+    site.and_then(|site| data.sites.get(site))
+        .map(|site| site.uid)
+        .into()
+}
+
+fn actor_name(data: &Data, actor: Actor) -> String {
+    match actor {
+        Actor::Npc(npc) => match data.npcs.get(npc) {
+            Some(npc) => format!("npc {}", npc.uid),
+            None => "npc <removed>".to_string(),
+        },
+        Actor::Character(character) => format!("character {}", character.0),
+    }
+}
+
+fn target_name(data: &Data, target: Target) -> String {
+    match target {
+        Target::Npc(npc) => actor_name(data, Actor::Npc(npc)),
+        Target::Character(character) => actor_name(data, Actor::Character(character)),
+        Target::Faction(faction) => format!("faction {}", faction_name(faction)),
+    }
+}
+
+fn role_name(role: &Role) -> &'static str {
+    match role {
+        Role::Civilised(_) => "civilised",
+        Role::Wild => "wild",
+        Role::Monster => "monster",
+        Role::Vehicle => "vehicle",
+    }
+}
+
+fn summary(data: &Data) -> Table {
+    let mut table = Table::new(vec!["key", "value"]);
+    let unresolved = data
+        .quests
+        .iter()
+        .filter(|(_, quest)| quest.resolution().is_none())
+        .count();
+    let rows: Vec<(&str, Value)> = vec![
+        ("version", data.version.into()),
+        ("tick", data.tick.into()),
+        ("time_of_day", data.time_of_day.0.into()),
+        ("day", ((data.time_of_day.0 / DAY).floor() as u64).into()),
+        ("should_purge", data.should_purge.into()),
+        ("npcs", data.npcs.len().into()),
+        (
+            "dead_npcs",
+            data.npcs
+                .values()
+                .filter(|npc| npc.is_dead())
+                .count()
+                .into(),
+        ),
+        ("sites", data.sites.len().into()),
+        ("factions", data.factions.len().into()),
+        ("faction_relations", data.factions.relations.len().into()),
+        ("quests", data.quests.iter().count().into()),
+        ("unresolved_quests", unresolved.into()),
+        ("reports", data.reports.len().into()),
+        ("recorded_deaths", data.architect.deaths.len().into()),
+    ];
+    for (key, value) in rows {
+        table.push(vec![key.into(), value]);
+    }
+    table
+}
+
+fn npcs(
+    data: &Data,
+    home: Option<u64>,
+    faction: &Option<String>,
+    query: &Option<String>,
+    limit: Option<usize>,
+) -> Table {
+    let npc_names = &NPC_NAMES.read();
+    let terms = query
+        .iter()
+        .flat_map(|query| query.split(','))
+        .filter(|s| !s.is_empty())
+        .map(|s| s.trim().to_lowercase())
+        .collect::<Vec<_>>();
+
+    let mut table = Table::new(vec![
+        "uid",
+        "name",
+        "role",
+        "profession",
+        "body",
+        "home",
+        "house",
+        "age_days",
+        "faction",
+        "x",
+        "y",
+        "z",
+        "health",
+        "job",
+        "known_reports",
+        "sentiments",
+    ]);
+    for npc in data
+        .npcs
+        .values()
+        .filter(|npc| {
+            home.is_none_or(|home| {
+                npc.home
+                    .and_then(|site| data.sites.get(site))
+                    .is_some_and(|site| site.uid == home)
+            })
+        })
+        .filter(|npc| faction_matches(npc.faction, faction))
+        .filter(|npc| {
+            let tags = [
+                npc.profession()
+                    .map(|p| format!("{:?}", p))
+                    .unwrap_or_default(),
+                role_name(&npc.role).to_string(),
+                format!("{}", npc.uid),
+                npc_names[&npc.body].keyword.clone(),
+            ];
+            terms.iter().all(|term| {
+                tags.iter()
+                    .any(|tag| tag.trim().to_lowercase().contains(term.as_str()))
+            })
+        })
+        .take(limit.unwrap_or(usize::MAX))
+    {
+        table.push(vec![
+            npc.uid.into(),
+            npc.get_name().into(),
+            role_name(&npc.role).into(),
+            npc.profession().map(|p| format!("{:?}", p)).into(),
+            npc_names[&npc.body].keyword.clone().into(),
+            site_uid(data, npc.home),
+            npc.house.into(),
+            npc.age(data.time_of_day)
+                .map(|age| age / (60.0 * 60.0 * 24.0))
+                .into(),
+            npc.faction.map(faction_name).into(),
+            npc.wpos.x.into(),
+            npc.wpos.y.into(),
+            npc.wpos.z.into(),
+            npc.health_fraction.into(),
+            npc.job
+                .as_ref()
+                .map(|job| match job {
+                    Job::Hired(actor, _) => format!("hired by {}", actor_name(data, *actor)),
+                    Job::Quest(quest) => format!("quest {}", quest.0),
+                    Job::Caravan(caravan) => format!(
+                        "caravan from {} to {}",
+                        cell_text(&site_uid(data, Some(caravan.from))),
+                        cell_text(&site_uid(data, Some(caravan.to))),
+                    ),
+                })
+                .into(),
+            npc.known_reports.len().into(),
+            npc.sentiments.iter().count().into(),
+        ]);
+    }
+    table
+}
+
+fn sites(data: &Data, faction: &Option<String>) -> Table {
+    let mut population = HashMap::<SiteId, usize>::new();
+    for npc in data.npcs.values().filter(|npc| !npc.is_dead()) {
+        if let Some(home) = npc.home {
+            *population.entry(home).or_default() += 1;
+        }
+    }
+
+    let mut table = Table::new(vec![
+        "uid",
+        "x",
+        "y",
+        "faction",
+        "population",
+        "prosperity",
+        "danger",
+        "neglected_since_day",
+        "constructions",
+        "built_plots",
+        "known_reports",
+    ]);
+    for (site_id, site) in data
+        .sites
+        .iter()
+        .filter(|(_, site)| faction_matches(site.faction, faction))
+    {
+        table.push(vec![
+            site.uid.into(),
+            site.wpos.x.into(),
+            site.wpos.y.into(),
+            site.faction.map(faction_name).into(),
+            population.get(&site_id).copied().unwrap_or(0).into(),
+            site.prosperity.into(),
+            site.danger.into(),
+            site.neglected_since
+                .map(|time| (time.0 / DAY).floor() as u64)
+                .into(),
+            site.constructions.len().into(),
+            site.built_plots().count().into(),
+            site.known_reports.len().into(),
+        ]);
+    }
+    table
+}
+
+fn factions(data: &Data) -> Table {
+    let mut table = Table::new(vec![
+        "faction",
+        "seed",
+        "leader",
+        "members",
+        "sites",
+        "relations",
+        "sentiments",
+    ]);
+    for (faction_id, faction) in data.factions.iter() {
+        table.push(vec![
+            faction_name(faction_id).into(),
+            faction.seed.into(),
+            faction.leader.map(|leader| actor_name(data, leader)).into(),
+            data.npcs
+                .values()
+                .filter(|npc| npc.faction == Some(faction_id) && !npc.is_dead())
+                .count()
+                .into(),
+            data.sites
+                .values()
+                .filter(|site| site.faction == Some(faction_id))
+                .count()
+                .into(),
+            data.factions.relations_of(faction_id).count().into(),
+            faction.sentiments.iter().count().into(),
+        ]);
+    }
+    table
+}
+
+fn relations(data: &Data, faction: &Option<String>) -> Table {
+    let mut table = Table::new(vec![
+        "faction_a",
+        "faction_b",
+        "relation",
+        "since_day",
+        "last_conflict_day",
+    ]);
+    for ((a, b), diplomacy) in data.factions.relations.iter().filter(|((a, b), _)| {
+        faction_matches(Some(*a), faction) || faction_matches(Some(*b), faction)
+    }) {
+        table.push(vec![
+            faction_name(*a).into(),
+            faction_name(*b).into(),
+            format!("{:?}", diplomacy.relation).into(),
+            ((diplomacy.since.0 / DAY).floor() as u64).into(),
+            diplomacy
+                .last_conflict
+                .map(|time| (time.0 / DAY).floor() as u64)
+                .into(),
+        ]);
+    }
+    table
+}
+
+fn quests(data: &Data, unresolved: bool) -> Table {
+    let mut table = Table::new(vec![
+        "id",
+        "kind",
+        "arbiter",
+        "actor",
+        "target",
+        "timeout",
+        "resolution",
+    ]);
+    let mut quests = data
+        .quests
+        .iter()
+        .filter(|(_, quest)| !unresolved || quest.resolution().is_none())
+        .collect::<Vec<_>>();
+    quests.sort_by_key(|(id, _)| id.0);
+    for (id, quest) in quests {
+        let (kind, actor, target) = match &quest.kind {
+            QuestKind::Escort {
+                escortee,
+                escorter,
+                to,
+            } => (
+                "escort",
+                actor_name(data, *escorter),
+                format!(
+                    "{} to site {}",
+                    actor_name(data, *escortee),
+                    cell_text(&site_uid(data, Some(*to)))
+                ),
+            ),
+            QuestKind::Slay { target, slayer } => {
+                ("slay", actor_name(data, *slayer), actor_name(data, *target))
+            },
+        };
+        table.push(vec![
+            id.0.into(),
+            kind.into(),
+            actor_name(data, quest.arbiter).into(),
+            actor.into(),
+            target.into(),
+            quest.timeout.map(|time| time.0).into(),
+            quest
+                .resolution()
+                .map(|res| if res { "succeeded" } else { "failed" })
+                .into(),
+        ]);
+    }
+    table
+}
+
+fn reports(data: &Data, site: Option<u64>) -> Table {
+    let mut table = Table::new(vec![
+        "report",
+        "kind",
+        "day",
+        "time_of_day",
+        "actor",
+        "by",
+        "site",
+        "sprite",
+        "known_by_sites",
+        "known_by_npcs",
+    ]);
+    for (report_id, report) in data.reports.iter().filter(|(report_id, _)| {
+        site.is_none_or(|uid| {
+            data.sites
+                .values()
+                .any(|site| site.uid == uid && site.known_reports.contains(report_id))
+        })
+    }) {
+        let (kind, actor, by, site, sprite) = match report.kind {
+            ReportKind::Death { actor, killer } => (
+                "death",
+                actor_name(data, actor),
+                killer.map(|killer| actor_name(data, killer)),
+                Value::Null,
+                None,
+            ),
+            ReportKind::Theft {
+                thief,
+                site,
+                sprite,
+            } => (
+                "theft",
+                actor_name(data, thief),
+                None,
+                site_uid(data, site),
+                Some(format!("{sprite:?}")),
+            ),
+        };
+        table.push(vec![
+            format!("{report_id:?}").into(),
+            kind.into(),
+            ((report.at_tod.0 / DAY).floor() as u64).into(),
+            report.at_tod.0.into(),
+            actor.into(),
+            by.into(),
+            site,
+            sprite.into(),
+            data.sites
+                .values()
+                .filter(|site| site.known_reports.contains(&report_id))
+                .count()
+                .into(),
+            data.npcs
+                .values()
+                .filter(|npc| npc.known_reports.contains(&report_id))
+                .count()
+                .into(),
+        ]);
+    }
+    table
+}
+
+fn population(data: &Data) -> Table {
+    let mut alive = HashMap::<String, usize>::new();
+    for npc in data.npcs.values().filter(|npc| !npc.is_dead()) {
+        *alive
+            .entry(format!(
+                "{:?}",
+                TrackedPopulation::from_body_and_role(&npc.body, &npc.role)
+            ))
+            .or_default() += 1;
+    }
+    let mut deaths = HashMap::<String, usize>::new();
+    for death in &data.architect.deaths {
+        *deaths
+            .entry(format!(
+                "{:?}",
+                TrackedPopulation::from_body_and_role(&death.body, &death.role)
+            ))
+            .or_default() += 1;
+    }
+
+    let mut names = alive
+        .keys()
+        .chain(deaths.keys())
+        .cloned()
+        .collect::<Vec<_>>();
+    names.sort();
+    names.dedup();
+
+    let mut table = Table::new(vec!["population", "alive", "recorded_deaths"]);
+    for name in names {
+        let alive = alive.get(&name).copied().unwrap_or(0);
+        let deaths = deaths.get(&name).copied().unwrap_or(0);
+        table.push(vec![name.into(), alive.into(), deaths.into()]);
+    }
+    table
+}
+
+fn deaths(data: &Data, population: &Option<String>) -> Table {
+    let mut deaths = HashMap::<(u64, String), usize>::new();
+    for death in &data.architect.deaths {
+        let name = format!(
+            "{:?}",
+            TrackedPopulation::from_body_and_role(&death.body, &death.role)
+        );
+        if population
+            .as_ref()
+            .is_none_or(|population| population.eq_ignore_ascii_case(&name))
+        {
+            let day = (death.time.0 / DAY).floor() as u64;
+            *deaths.entry((day, name)).or_default() += 1;
+        }
+    }
+    let mut deaths = deaths.into_iter().collect::<Vec<_>>();
+    deaths.sort();
+
+    let mut table = Table::new(vec!["day", "population", "deaths"]);
+    for ((day, name), count) in deaths {
+        table.push(vec![day.into(), name.into(), count.into()]);
+    }
+    table
+}
+
+fn sentiments(data: &Data, npc: Option<u64>, faction: &Option<String>, min: f32) -> Table {
+    let mut table = Table::new(vec!["holder", "target", "value"]);
+    let push_sentiments = |table: &mut Table, holder: String, sentiments: &Sentiments| {
+        let mut sentiments = sentiments
+            .iter()
+            .filter(|(_, sentiment)| sentiment.value().abs() >= min)
+            .collect::<Vec<_>>();
+        sentiments.sort_by_key(|(target, _)| *target);
+        for (target, sentiment) in sentiments {
+            table.push(vec![
+                holder.clone().into(),
+                target_name(data, target).into(),
+                sentiment.value().into(),
+            ]);
+        }
+    };
+
+    // When filtering by faction only, don't list the sentiments of every NPC
+    if faction.is_none() {
+        for holder in data
+            .npcs
+            .values()
+            .filter(|holder| npc.is_none_or(|uid| holder.uid == uid))
+        {
+            push_sentiments(
+                &mut table,
+                format!("npc {}", holder.uid),
+                &holder.sentiments,
+            );
+        }
+    }
+    if npc.is_none() {
+        for (faction_id, holder) in data
+            .factions
+            .iter()
+            .filter(|(faction_id, _)| faction_matches(Some(*faction_id), faction))
+        {
+            push_sentiments(
+                &mut table,
+                format!("faction {}", faction_name(faction_id)),
+                &holder.sentiments,
+            );
+        }
+    }
+    table
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+
+    let data = match Data::from_reader(BufReader::new(File::open(&cli.file)?)) {
+        Ok(data) => data,
+        Err(ReadError::VersionMismatch(data)) => {
+            eprintln!(
+                "Warning: the data has version {}, which a server would purge. Inspecting it \
+                 anyway.",
+                data.version
+            );
+            data
+        },
+        Err(ReadError::Load(e)) => return Err(format!("Failed to load rtsim data: {e}").into()),
+    };
+
+    let table = match &cli.command {
+        Command::Summary => summary(&data),
+        Command::Npcs {
+            home,
+            faction,
+            query,
+            limit,
+        } => npcs(&data, *home, faction, query, *limit),
+        Command::Sites { faction } => sites(&data, faction),
+        Command::Factions => factions(&data),
+        Command::Relations { faction } => relations(&data, faction),
+        Command::Quests { unresolved } => quests(&data, *unresolved),
+        Command::Reports { site } => reports(&data, *site),
+        Command::Population => population(&data),
+        Command::Deaths { population } => deaths(&data, population),
+        Command::Sentiments { npc, faction, min } => sentiments(&data, *npc, faction, *min),
+    };
+
+    match &cli.output {
+        Some(path) => table.write(cli.format, &mut File::create(path)?)?,
+        None => table.write(cli.format, &mut io::stdout().lock())?,
+    }
+    Ok(())
+}
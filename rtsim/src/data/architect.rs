@@ -18,7 +18,7 @@ pub struct Death {
     pub faction: Option<FactionId>,
 }
 
-#[derive(enum_map::Enum)]
+#[derive(Copy, Clone, Debug, enum_map::Enum)]
 pub enum TrackedPopulation {
     Adventurers,
     Merchants,
@@ -123,6 +123,10 @@ impl Population {
     }
 
     pub fn add(&mut self, pop: TrackedPopulation, amount: u32) { self.populations[pop] += amount; }
+
+    pub fn remove(&mut self, pop: TrackedPopulation, amount: u32) {
+        self.populations[pop] = self.populations[pop].saturating_sub(amount);
+    }
 }
 
 /// The architect has the responsibility of making sure the game keeps working.
@@ -0,0 +1,205 @@
+use common::{
+    character::CharacterId,
+    rtsim::{QuestId, SiteId},
+    trade::Good,
+};
+use hashbrown::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+/// The bounties that sites have placed on players who murdered their residents.
+///
+/// Guards pursue wanted players and give them the choice between paying the
+/// bounty as a fine, giving themselves up to serve a sentence in the site's
+/// jail, or being fought. Guards also hand out bounties to other players as
+/// slay quests, which can be claimed once the wanted player has been slain
+/// (see [`crate::rule::justice`]).
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Bounties {
+    pub bounties: HashMap<(SiteId, CharacterId), Bounty>,
+    /// Bounty quests whose target has been slain by the bounty hunter, and that
+    /// the hunter can now claim the reward of.
+    pub claims: HashSet<QuestId>,
+    /// Coins reserved for the rewards of bounty quests accepted since the last
+    /// tick, by site. Guards hand out bounties in parallel and the coins are
+    /// only taken from the stock of their site at the start of the next tick
+    /// (see [`crate::rule::simulate_npcs`]), so this keeps them from promising
+    /// the same coins twice.
+    #[serde(skip)]
+    pub reserved: Reserved,
+}
+
+#[derive(Default)]
+pub struct Reserved(Mutex<HashMap<SiteId, u32>>);
+
+impl Clone for Reserved {
+    // Reservations only last until the next tick, so they aren't worth cloning
+    fn clone(&self) -> Self { Self::default() }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Bounty {
+    /// The reward, in coins, for bringing the player to justice. This is also
+    /// the fine that the player must pay to clear their name.
+    pub reward: u32,
+    /// The jail sentence being served by the player, if they gave themselves
+    /// up. Players serving a sentence aren't wanted.
+    pub sentence: Option<Sentence>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Sentence {
+    /// The time, in seconds, spent in jail so far.
+    pub served: f32,
+    /// The time, in seconds, that must be spent in jail.
+    pub length: f32,
+    /// The time, in seconds, spent away from the jail since the player was
+    /// last there.
+    pub away: f32,
+}
+
+/// What a guard decided to do with a wanted player.
+#[derive(Copy, Clone, Debug)]
+pub enum Verdict {
+    /// The player paid the bounty as a fine.
+    Fined,
+    /// The player gave themselves up and must serve a sentence in jail.
+    Jailed,
+}
+
+impl Bounties {
+    /// How long, in seconds, the sentence for a bounty lasts per coin of its
+    /// reward.
+    pub const SENTENCE_PER_COIN: f32 = 1.0;
+
+    pub fn get(&self, site: SiteId, character: CharacterId) -> Option<&Bounty> {
+        self.bounties.get(&(site, character))
+    }
+
+    /// Whether the character has a bounty on them at the site and isn't
+    /// serving a sentence for it.
+    pub fn is_wanted(&self, site: SiteId, character: CharacterId) -> bool {
+        self.get(site, character)
+            .is_some_and(|bounty| bounty.sentence.is_none())
+    }
+
+    /// The characters that are wanted at the site, along with their bounty.
+    pub fn wanted_at(&self, site: SiteId) -> impl Iterator<Item = (CharacterId, &Bounty)> + '_ {
+        self.bounties
+            .iter()
+            .filter(move |((s, _), bounty)| *s == site && bounty.sentence.is_none())
+            .map(|((_, character), bounty)| (*character, bounty))
+    }
+
+    /// Raise the bounty on a character. Characters that commit crimes while
+    /// serving a sentence are wanted again.
+    pub fn raise(&mut self, site: SiteId, character: CharacterId, reward: u32) {
+        let bounty = self.bounties.entry((site, character)).or_insert(Bounty {
+            reward: 0,
+            sentence: None,
+        });
+        bounty.reward = bounty.reward.saturating_add(reward);
+        bounty.sentence = None;
+    }
+
+    /// Whether a site with the given stock (see [`crate::data::Site::stock`])
+    /// can pay for the reward of a bounty quest, on top of the rewards already
+    /// reserved.
+    pub fn can_fund(&self, site: SiteId, stock: &HashMap<Good, f32>, reward: u32) -> bool {
+        let reserved = self.reserved.0.lock().unwrap().get(&site).copied();
+        Self::covers(stock, reserved.unwrap_or(0), reward)
+    }
+
+    /// Reserves the reward of a bounty quest out of the stock of a site, if it
+    /// can pay for it (see [`Bounties::can_fund`]).
+    pub fn reserve_reward(&self, site: SiteId, stock: &HashMap<Good, f32>, reward: u32) -> bool {
+        let mut reserved = self.reserved.0.lock().unwrap();
+        let reserved = reserved.entry(site).or_default();
+        let covered = Self::covers(stock, *reserved, reward);
+        if covered {
+            *reserved += reward;
+        }
+        covered
+    }
+
+    fn covers(stock: &HashMap<Good, f32>, reserved: u32, reward: u32) -> bool {
+        stock.get(&Good::Coin).copied().unwrap_or(0.0) >= reserved.saturating_add(reward) as f32
+    }
+
+    /// Forgets the reserved rewards, once they have been taken from the stock
+    /// of their site.
+    pub fn clear_reserved(&mut self) { self.reserved.0.get_mut().unwrap().clear(); }
+
+    pub fn clear(&mut self, site: SiteId, character: CharacterId) {
+        self.bounties.remove(&(site, character));
+    }
+
+    pub fn pass_verdict(&mut self, site: SiteId, character: CharacterId, verdict: Verdict) {
+        match verdict {
+            Verdict::Fined => self.clear(site, character),
+            Verdict::Jailed => {
+                if let Some(bounty) = self.bounties.get_mut(&(site, character))
+                    && bounty.sentence.is_none()
+                {
+                    bounty.sentence = Some(Sentence {
+                        served: 0.0,
+                        length: bounty.reward as f32 * Self::SENTENCE_PER_COIN,
+                        away: 0.0,
+                    });
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jailed_players_are_wanted_again_for_new_crimes() {
+        let mut bounties = Bounties::default();
+        let (site, character) = (SiteId::default(), CharacterId(1));
+
+        bounties.raise(site, character, 100);
+        bounties.raise(site, character, 50);
+        assert!(bounties.is_wanted(site, character));
+
+        bounties.pass_verdict(site, character, Verdict::Jailed);
+        assert!(!bounties.is_wanted(site, character));
+        let sentence = bounties.get(site, character).unwrap().sentence.as_ref();
+        assert_eq!(
+            sentence.map(|s| s.length),
+            Some(150.0 * Bounties::SENTENCE_PER_COIN)
+        );
+
+        bounties.raise(site, character, 100);
+        assert!(bounties.is_wanted(site, character));
+        assert_eq!(bounties.get(site, character).map(|b| b.reward), Some(250));
+
+        bounties.pass_verdict(site, character, Verdict::Fined);
+        assert!(bounties.get(site, character).is_none());
+    }
+
+    #[test]
+    fn rewards_are_funded_from_site_stock() {
+        let mut bounties = Bounties::default();
+        let site = SiteId::default();
+        let stock = HashMap::from([(Good::Coin, 150.0)]);
+
+        assert!(!bounties.can_fund(site, &HashMap::new(), 1));
+        assert!(!bounties.can_fund(site, &stock, 200));
+        assert!(bounties.can_fund(site, &stock, 100));
+
+        // Two bounties accepted at the same time can't both be paid for
+        assert!(bounties.reserve_reward(site, &stock, 100));
+        assert!(!bounties.can_fund(site, &stock, 100));
+        assert!(!bounties.reserve_reward(site, &stock, 100));
+        assert!(bounties.reserve_reward(site, &stock, 50));
+
+        // Once the rewards are taken from the stock, the reservations are lifted
+        bounties.clear_reserved();
+        let stock = HashMap::from([(Good::Coin, 0.0)]);
+        assert!(!bounties.reserve_reward(site, &stock, 100));
+    }
+}
@@ -1,6 +1,7 @@
-use crate::data::Sentiments;
-use common::rtsim::Actor;
+use crate::data::{Sentiment, Sentiments};
 pub use common::rtsim::FactionId;
+use common::{resources::TimeOfDay, rtsim::Actor};
+use hashbrown::HashMap;
 use serde::{Deserialize, Serialize};
 use slotmap::HopSlotMap;
 use std::ops::{Deref, DerefMut};
@@ -10,7 +11,6 @@ use vek::*;
 pub struct Faction {
     pub seed: u32,
     pub leader: Option<Actor>,
-    pub good_or_evil: bool, // TODO: Very stupid, get rid of this
 
     #[serde(default)]
     pub sentiments: Sentiments,
@@ -23,13 +23,144 @@ impl Faction {
     }
 }
 
+/// The diplomatic stance between two factions, ordered from the friendliest to
+/// the most hostile.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Relation {
+    Allied,
+    Neutral,
+    Rival,
+    War,
+}
+
+impl Relation {
+    /// The relation implied by the sentiments of two factions toward one
+    /// another. Alliances need both factions to like each other, while either
+    /// faction is enough to start hostilities.
+    pub fn from_sentiments(a: &Sentiment, b: &Sentiment) -> Self {
+        let one_way = |s: &Sentiment| {
+            if s.is(Sentiment::VILLAIN) {
+                Self::War
+            } else if s.is(Sentiment::RIVAL) {
+                Self::Rival
+            } else if s.is(Sentiment::ALLY) {
+                Self::Allied
+            } else {
+                Self::Neutral
+            }
+        };
+        one_way(a).max(one_way(b))
+    }
+
+    pub fn is_friendly(&self) -> bool { matches!(self, Self::Allied) }
+
+    pub fn is_hostile(&self) -> bool { matches!(self, Self::Rival | Self::War) }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Diplomacy {
+    pub relation: Relation,
+    /// When the relation last changed.
+    pub since: TimeOfDay,
+    /// When a member of one faction last killed a member of the other.
+    pub last_conflict: Option<TimeOfDay>,
+}
+
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct Factions {
     pub factions: HopSlotMap<FactionId, Faction>,
+    /// Relations between pairs of factions, keyed by the ordered pair. Pairs
+    /// without an entry are neutral.
+    #[serde(default)]
+    pub relations: HashMap<(FactionId, FactionId), Diplomacy>,
 }
 
 impl Factions {
     pub fn create(&mut self, faction: Faction) -> FactionId { self.factions.insert(faction) }
+
+    fn key(a: FactionId, b: FactionId) -> (FactionId, FactionId) {
+        if a < b { (a, b) } else { (b, a) }
+    }
+
+    /// The relation between two factions. A faction is always allied with
+    /// itself.
+    pub fn relation(&self, a: FactionId, b: FactionId) -> Relation {
+        if a == b {
+            Relation::Allied
+        } else {
+            self.relations
+                .get(&Self::key(a, b))
+                .map_or(Relation::Neutral, |d| d.relation)
+        }
+    }
+
+    /// Like [`Factions::relation`], but treats the lack of a faction as
+    /// neutral.
+    pub fn relation_between(&self, a: Option<FactionId>, b: Option<FactionId>) -> Relation {
+        match (a, b) {
+            (Some(a), Some(b)) => self.relation(a, b),
+            _ => Relation::Neutral,
+        }
+    }
+
+    pub fn diplomacy(&self, a: FactionId, b: FactionId) -> Option<&Diplomacy> {
+        self.relations.get(&Self::key(a, b))
+    }
+
+    /// The diplomacy between two factions, starting out neutral at `now` if
+    /// they have none yet.
+    pub fn diplomacy_mut(&mut self, a: FactionId, b: FactionId, now: TimeOfDay) -> &mut Diplomacy {
+        self.relations
+            .entry(Self::key(a, b))
+            .or_insert_with(|| Diplomacy {
+                relation: Relation::Neutral,
+                since: now,
+                last_conflict: None,
+            })
+    }
+
+    /// Sets the relation between two factions, along with sentiments that
+    /// support it so that it doesn't immediately change back.
+    pub fn set_relation(&mut self, a: FactionId, b: FactionId, relation: Relation, now: TimeOfDay) {
+        // Comfortably past the thresholds of the relation, see
+        // [`Relation::from_sentiments`]
+        let sentiment = match relation {
+            Relation::Allied => 0.7,
+            Relation::Neutral => 0.0,
+            Relation::Rival => -0.45,
+            Relation::War => -0.9,
+        };
+        for (from, to) in [(a, b), (b, a)] {
+            if let Some(faction) = self.factions.get_mut(from) {
+                let s = faction.sentiments.toward_mut(to);
+                *s = Sentiment::default();
+                s.change_by(sentiment, 1.0);
+            }
+        }
+        let diplomacy = self.diplomacy_mut(a, b, now);
+        diplomacy.relation = relation;
+        diplomacy.since = now;
+    }
+
+    /// Every other faction that the given faction has a non-neutral relation
+    /// with.
+    pub fn relations_of(
+        &self,
+        faction: FactionId,
+    ) -> impl Iterator<Item = (FactionId, &Diplomacy)> + '_ {
+        self.relations
+            .iter()
+            .filter(|(_, d)| d.relation != Relation::Neutral)
+            .filter_map(move |((a, b), d)| {
+                if *a == faction {
+                    Some((*b, d))
+                } else if *b == faction {
+                    Some((*a, d))
+                } else {
+                    None
+                }
+            })
+    }
 }
 
 impl Deref for Factions {
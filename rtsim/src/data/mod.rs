@@ -1,21 +1,25 @@
 pub mod airship;
 pub mod architect;
+pub mod bounty;
 pub mod faction;
 pub mod nature;
 pub mod npc;
 pub mod quest;
 pub mod report;
+pub mod reputation;
 pub mod sentiment;
 pub mod site;
 
 pub use self::{
-    faction::{Faction, FactionId, Factions},
+    bounty::Bounties,
+    faction::{Faction, FactionId, Factions, Relation},
     nature::Nature,
     npc::{Npc, NpcId, Npcs},
     quest::Quests,
     report::{Report, ReportId, ReportKind, Reports},
+    reputation::Reputations,
     sentiment::{Sentiment, Sentiments},
-    site::{Site, SiteId, Sites},
+    site::{Construction, ConstructionKind, Site, SiteId, Sites},
 };
 use airship::AirshipSim;
 use architect::Architect;
@@ -55,6 +59,8 @@ pub struct Data {
     pub architect: Architect,
     #[serde(default)]
     pub quests: Quests,
+    #[serde(default)]
+    pub bounties: Bounties,
 
     #[serde(default)]
     pub tick: u64,
@@ -67,6 +73,10 @@ pub struct Data {
 
     #[serde(skip)]
     pub airship_sim: AirshipSim,
+    /// Derived from sentiments and reports, see
+    /// [`crate::rule::reputation::UpdateReputations`].
+    #[serde(skip)]
+    pub reputations: Reputations,
 }
 
 pub enum ReadError {
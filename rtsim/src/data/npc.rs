@@ -1,6 +1,6 @@
 use crate::{
     ai::Action,
-    data::{Reports, Sentiments, quest::Quest},
+    data::{Reports, Sentiments, bounty::Verdict, quest::Quest},
     generate::name,
 };
 pub use common::rtsim::{NpcId, Profession};
@@ -9,13 +9,14 @@ use common::{
     comp::{self, agent::FlightMode, item::ItemDef},
     grid::Grid,
     map::Marker,
-    resources::Time,
+    resources::{Time, TimeOfDay},
     rtsim::{
         Actor, Dialogue, DialogueId, DialogueKind, FactionId, NpcAction, NpcActivity, NpcInput,
         Personality, QuestId, ReportId, Response, Role, SiteId, TerrainResource,
     },
     store::Id,
     terrain::CoordinateConversions,
+    trade::Good,
     util::Dir,
 };
 use hashbrown::{HashMap, HashSet};
@@ -69,6 +70,11 @@ pub struct Controller {
     pub look_dir: Option<Dir>,
     pub job: Option<Job>,
     pub quests_to_create: Vec<(QuestId, Quest)>,
+    /// Verdicts on wanted players, passed on the bounties of the NPC's home.
+    pub verdicts: Vec<(CharacterId, Verdict)>,
+    /// Goods given to (positive) or taken from (negative) the stock of the
+    /// NPC's home, such as to pay for the rewards of bounty quests.
+    pub home_goods: Vec<(Good, f32)>,
 }
 
 impl Controller {
@@ -148,6 +154,14 @@ impl Controller {
         self.new_home = Some(new_home.into());
     }
 
+    pub fn pass_verdict(&mut self, criminal: CharacterId, verdict: Verdict) {
+        self.verdicts.push((criminal, verdict));
+    }
+
+    pub fn change_home_goods(&mut self, good: Good, amount: f32) {
+        self.home_goods.push((good, amount));
+    }
+
     pub fn set_newly_hired(&mut self, actor: Actor, expires: Time) {
         self.job = Some(Job::Hired(actor, expires));
     }
@@ -158,6 +172,12 @@ impl Controller {
         }
     }
 
+    pub fn set_caravan_escort(&mut self, quest_id: QuestId) {
+        if let Some(Job::Caravan(caravan)) = &mut self.job {
+            caravan.escort = Some(quest_id);
+        }
+    }
+
     pub fn end_quest(&mut self) {
         if matches!(self.job, Some(Job::Quest(..))) {
             self.job = None;
@@ -306,6 +326,15 @@ pub struct Npc {
     #[serde(default)]
     pub job: Option<Job>,
 
+    /// When the NPC was born. This is only known for NPCs that have been given
+    /// an age, see [`crate::rule::household`].
+    #[serde(default)]
+    pub born: Option<TimeOfDay>,
+    /// The id of the house plot of their home site that the NPC lives in, which
+    /// they share with the rest of their household.
+    #[serde(default)]
+    pub house: Option<u64>,
+
     // Unpersisted state
     #[serde(skip)]
     pub chunk_pos: Option<Vec2<i32>>,
@@ -341,6 +370,22 @@ pub enum Job {
     Hired(Actor, Time),
     /// NPC is helping to perform a quest
     Quest(QuestId),
+    /// NPC is leading a caravan carrying goods to another site.
+    Caravan(Caravan),
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct Caravan {
+    pub from: SiteId,
+    pub to: SiteId,
+    /// The goods carried, taken from the stock of `from` and added to the
+    /// stock of `to` on arrival.
+    pub cargo: Vec<(Good, f32)>,
+    /// The animal carrying the cargo, steered by the NPC leading the caravan.
+    pub pack_animal: Option<NpcId>,
+    /// The quest of the player escorting the caravan, if any.
+    #[serde(default)]
+    pub escort: Option<QuestId>,
 }
 
 impl Clone for Npc {
@@ -359,6 +404,8 @@ impl Clone for Npc {
             personality: self.personality,
             sentiments: self.sentiments.clone(),
             job: self.job.clone(),
+            born: self.born,
+            house: self.house,
             // Not persisted
             chunk_pos: None,
             current_site: Default::default(),
@@ -372,8 +419,10 @@ impl Clone for Npc {
 }
 
 impl Npc {
+    pub const PERM_AGE: u32 = 3;
     pub const PERM_ENTITY_CONFIG: u32 = 1;
     const PERM_NAME: u32 = 0;
+    pub const PERM_SCHEDULE: u32 = 2;
 
     pub fn new(seed: u32, wpos: Vec3<f32>, body: comp::Body, role: Role) -> Self {
         Self {
@@ -386,6 +435,8 @@ impl Npc {
             personality: Default::default(),
             sentiments: Default::default(),
             job: None,
+            born: None,
+            house: None,
             role,
             home: None,
             faction: None,
@@ -403,6 +454,11 @@ impl Npc {
 
     pub fn is_dead(&self) -> bool { self.health_fraction <= 0.0 }
 
+    /// How old the NPC is, in seconds of in-game time, if known.
+    pub fn age(&self, time_of_day: TimeOfDay) -> Option<f64> {
+        self.born.map(|born| time_of_day.0 - born.0)
+    }
+
     // TODO: have a dedicated `NpcBuilder` type for this.
     pub fn with_personality(mut self, personality: Personality) -> Self {
         self.personality = personality;
@@ -66,6 +66,11 @@ impl Quests {
 
     pub fn get(&self, id: QuestId) -> Option<&Quest> { self.quests.get(&id) }
 
+    /// Iterate over all quests, both active and resolved.
+    pub fn iter(&self) -> impl Iterator<Item = (QuestId, &Quest)> + '_ {
+        self.quests.iter().map(|(id, quest)| (*id, quest))
+    }
+
     pub fn related_to(&self, actor: impl Into<Actor>) -> impl Iterator<Item = QuestId> + '_ {
         match self.related_quests.get(&actor.into()) {
             Some(quests) => Either::Left(
@@ -0,0 +1,84 @@
+use crate::data::Npc;
+use common::{
+    character::CharacterId,
+    rtsim::{FactionId, SiteId, Standing},
+};
+use hashbrown::HashMap;
+
+/// The reputation of players with sites and factions.
+///
+/// A reputation is a score between -1 and 1 that sums up what a site or
+/// faction as a whole thinks of a player, as opposed to the sentiments of
+/// individual NPCs. It is derived from sentiments and reports (see
+/// [`crate::rule::reputation`]) rather than being persisted, and decides how
+/// the NPCs of a site treat players they have never met: how they price their
+/// goods, whether guards attack them on sight, and whether they offer them
+/// quests or work for them.
+#[derive(Clone, Default)]
+pub struct Reputations {
+    pub sites: HashMap<SiteId, HashMap<CharacterId, f32>>,
+    pub factions: HashMap<FactionId, HashMap<CharacterId, f32>>,
+}
+
+impl Reputations {
+    /// Players with this reputation or worse are refused quests and hirelings.
+    pub const DISTRUSTED: f32 = -0.15;
+    pub const HONOURED: f32 = 0.6;
+    /// Guards attack players with this reputation or worse on sight.
+    pub const HOSTILE: f32 = -0.5;
+    /// How much better or worse than usual the deals that sites offer
+    /// characters get with the best or worst reputation, see
+    /// [`common::trade::SitePrices::goodwill`].
+    pub const MAX_GOODWILL: f32 = 0.15;
+    /// Players with this reputation or better may always hire adventurers.
+    pub const RESPECTED: f32 = 0.25;
+
+    /// The reputation of a character with a site.
+    pub fn site(&self, site: SiteId, character: CharacterId) -> f32 {
+        self.sites
+            .get(&site)
+            .and_then(|reps| reps.get(&character))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// The reputation of a character with a faction.
+    pub fn faction(&self, faction: FactionId, character: CharacterId) -> f32 {
+        self.factions
+            .get(&faction)
+            .and_then(|reps| reps.get(&character))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// The goodwill that a site shows a character when trading with them.
+    pub fn goodwill(&self, site: SiteId, character: CharacterId) -> f32 {
+        self.site(site, character) * Self::MAX_GOODWILL
+    }
+
+    /// The reputation that an NPC goes by when dealing with a character: that
+    /// of the NPC's home site, or of their faction if they have no home.
+    pub fn seen_by(&self, npc: &Npc, character: CharacterId) -> f32 {
+        if let Some(home) = npc.home {
+            self.site(home, character)
+        } else if let Some(faction) = npc.faction {
+            self.faction(faction, character)
+        } else {
+            0.0
+        }
+    }
+
+    pub fn standing(reputation: f32) -> Standing {
+        if reputation <= Self::HOSTILE {
+            Standing::Hostile
+        } else if reputation <= Self::DISTRUSTED {
+            Standing::Distrusted
+        } else if reputation >= Self::HONOURED {
+            Standing::Honoured
+        } else if reputation >= Self::RESPECTED {
+            Standing::Respected
+        } else {
+            Standing::Neutral
+        }
+    }
+}
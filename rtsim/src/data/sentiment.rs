@@ -23,7 +23,7 @@ const DECAY_TIME_FACTOR: f32 = 2500.0;
 // - Occupations (hatred of hunters or chefs?)
 // - Ideologies (dislikes democracy, likes monarchy?)
 // - etc.
-#[derive(Copy, Clone, Hash, PartialEq, Eq, Serialize, Deserialize, PartialOrd, Ord)]
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize, PartialOrd, Ord)]
 pub enum Target {
     Character(CharacterId),
     Npc(NpcId),
@@ -65,6 +65,13 @@ impl Sentiments {
         self.map.entry(target.into()).or_default()
     }
 
+    /// Iterate over all of the targets that a sentiment is felt toward.
+    pub fn iter(&self) -> impl Iterator<Item = (Target, &Sentiment)> + '_ {
+        self.map
+            .iter()
+            .map(|(target, sentiment)| (*target, sentiment))
+    }
+
     /// Progressively decay the sentiment back to a neutral sentiment.
     ///
     /// Note that sentiment get decay gets slower the harsher the sentiment is.
@@ -151,7 +158,8 @@ impl Sentiment {
     /// generally try to harm the actor in any way they can.
     pub const VILLAIN: f32 = -0.8;
 
-    fn value(&self) -> f32 { self.positivity as f32 * (1.0 / 126.0) }
+    /// How positive the sentiment is, between -1 and 1.
+    pub fn value(&self) -> f32 { self.positivity as f32 * (1.0 / 126.0) }
 
     /// Change the sentiment toward the given target by the given amount,
     /// capping out at the given value.
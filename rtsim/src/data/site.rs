@@ -1,15 +1,19 @@
 use crate::data::{ReportId, Reports};
 pub use common::rtsim::SiteId;
 use common::{
+    resources::TimeOfDay,
     rtsim::{FactionId, NpcId},
     store::Id,
+    trade::Good,
 };
 use hashbrown::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
 use slotmap::HopSlotMap;
 use std::ops::{Deref, DerefMut};
 use vek::*;
-use world::site::Site as WorldSite;
+use world::site::{PlotPlan, Site as WorldSite};
+
+fn default_prosperity() -> f32 { 0.5 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Site {
@@ -22,6 +26,35 @@ pub struct Site {
     /// being on a noticeboard or something).
     pub known_reports: HashSet<ReportId>,
 
+    /// Goods brought to (positive) or taken away from (negative) the site by
+    /// caravans or spent on bounties, on top of the stock produced by the
+    /// site's economy. These
+    /// decay over time as the goods are used up or replaced (see
+    /// [`crate::rule::replenish_resources`]).
+    #[serde(default)]
+    pub goods: HashMap<Good, f32>,
+
+    /// How well the site is doing, between 0 and 1, based on its population,
+    /// trade and safety. Thriving sites grow while neglected ones decay.
+    #[serde(default = "default_prosperity")]
+    pub prosperity: f32,
+    /// How dangerous the site has recently been for its residents, increases
+    /// with each death and decays over time.
+    #[serde(default)]
+    pub danger: f32,
+    /// Since when the site has been neglected, i.e: had too little prosperity
+    /// to maintain its buildings.
+    #[serde(default)]
+    pub neglected_since: Option<TimeOfDay>,
+    /// Changes to the layout of the site made by the architect since world
+    /// generation, in the order that they were made.
+    #[serde(default)]
+    pub constructions: Vec<Construction>,
+    /// The plot of the world site that serves as the site's jail, a building
+    /// that nobody lives in (see [`crate::rule::justice`]).
+    #[serde(default)]
+    pub jail: Option<u64>,
+
     /// How many chunks this site is loaded in.
     #[serde(skip)]
     pub count_loaded_chunks: usize,
@@ -68,6 +101,68 @@ impl Site {
     }
 
     pub fn is_loaded(&self) -> bool { self.count_loaded_chunks > 0 }
+
+    /// The plots built by the architect that haven't fallen into ruin since.
+    pub fn built_plots(&self) -> impl Iterator<Item = &PlotPlan> + '_ {
+        self.constructions
+            .iter()
+            .filter_map(|c| match &c.kind {
+                ConstructionKind::Build(plan) => Some(plan),
+                _ => None,
+            })
+            .filter(|plan| {
+                !self.constructions.iter().any(|c| {
+                    matches!(&c.kind, ConstructionKind::RuinBuilt(ruin) if ruin.seed == plan.seed)
+                })
+            })
+    }
+
+    /// The time at which the last construction was started, if any.
+    pub fn last_construction(&self) -> Option<TimeOfDay> {
+        self.constructions.last().map(|c| c.started)
+    }
+
+    /// Applies the goods brought or taken away by caravans to the stock of the
+    /// site's economy.
+    pub fn adjust_stock(&self, stock: &mut HashMap<Good, f32>) {
+        for (good, amount) in &self.goods {
+            let stock = stock.entry(*good).or_default();
+            *stock = (*stock + amount).max(0.0);
+        }
+    }
+
+    /// The stock of the site's economy, including the goods brought or taken
+    /// away by caravans.
+    pub fn stock(&self, world_site: &WorldSite) -> HashMap<Good, f32> {
+        let mut stock = world_site
+            .economy
+            .as_ref()
+            .map(|economy| economy.get_available_stock())
+            .unwrap_or_default();
+        self.adjust_stock(&mut stock);
+        stock
+    }
+}
+
+/// A change to the layout of a site, see [`crate::rule::architect`].
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Construction {
+    pub kind: ConstructionKind,
+    pub started: TimeOfDay,
+    /// Whether the server has applied the blocks of the construction to the
+    /// terrain yet.
+    pub applied: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub enum ConstructionKind {
+    /// A new plot built on free tiles of the site.
+    Build(PlotPlan),
+    /// A plot of the generated site falling into ruin, identified by its id in
+    /// the world site.
+    Ruin(u64),
+    /// A plot built by the architect falling into ruin.
+    RuinBuilt(PlotPlan),
 }
 
 #[derive(Clone, Default, Serialize, Deserialize)]
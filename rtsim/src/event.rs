@@ -48,6 +48,12 @@ impl Event for OnDeath {
     type SystemData<'a> = ();
 }
 
+/// Deaths caused by rules (such as dying of old age) rather than by the game.
+/// Rules can't emit events themselves, so these are emitted as [`OnDeath`] at
+/// the end of each tick.
+#[derive(Default)]
+pub struct PendingDeaths(pub Vec<OnDeath>);
+
 #[derive(Clone)]
 pub struct OnHelped {
     pub actor: Actor,
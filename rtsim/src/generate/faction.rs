@@ -1,4 +1,5 @@
-use crate::data::Faction;
+use crate::data::{Faction, FactionId, Factions, Relation};
+use common::resources::TimeOfDay;
 use rand::prelude::*;
 use world::{IndexRef, World};
 
@@ -7,8 +8,32 @@ impl Faction {
         Self {
             seed: rng.random(),
             leader: None,
-            good_or_evil: rng.random(),
             sentiments: Default::default(),
         }
     }
 }
+
+impl Factions {
+    /// Sets up the initial relations between factions, given whether each of
+    /// them is civilised. Civilised and hostile factions start out at war,
+    /// while factions of the same kind are either neutral or allied.
+    pub fn generate_relations(
+        &mut self,
+        civilised: &[(FactionId, bool)],
+        now: TimeOfDay,
+        rng: &mut impl Rng,
+    ) {
+        for (i, (a, a_civilised)) in civilised.iter().enumerate() {
+            for (b, b_civilised) in &civilised[i + 1..] {
+                let relation = if a_civilised != b_civilised {
+                    Relation::War
+                } else if rng.random_bool(0.5) {
+                    Relation::Allied
+                } else {
+                    continue;
+                };
+                self.set_relation(*a, *b, relation, now);
+            }
+        }
+    }
+}
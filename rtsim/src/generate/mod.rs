@@ -97,8 +97,10 @@ impl Data {
             factions: Default::default(),
             reports: Default::default(),
             airship_sim: Default::default(),
+            reputations: Default::default(),
             architect: Default::default(),
             quests: Default::default(),
+            bounties: Default::default(),
 
             tick: 0,
             time_of_day: TimeOfDay(settings.start_time),
@@ -114,21 +116,23 @@ impl Data {
                     .map2(TerrainChunkSize::RECT_SIZE, |e, sz| {
                         rng.random_range(0..(e * sz) as i32)
                     });
-                (wpos, this.factions.create(faction))
+                let civilised = rng.random();
+                (wpos, this.factions.create(faction), civilised)
             })
             .collect::<Vec<_>>();
+        this.factions.generate_relations(
+            &initial_factions
+                .iter()
+                .map(|(_, faction, civilised)| (*faction, *civilised))
+                .collect::<Vec<_>>(),
+            this.time_of_day,
+            &mut rng,
+        );
         info!("Generated {} rtsim factions.", this.factions.len());
 
         // Register sites with rtsim
         for (world_site_id, _) in index.sites.iter() {
-            let site = Site::generate(
-                world_site_id,
-                world,
-                index,
-                &initial_factions,
-                &this.factions,
-                &mut rng,
-            );
+            let site = Site::generate(world_site_id, world, index, &initial_factions, &mut rng);
             this.sites.create(site);
         }
         info!(
@@ -1,4 +1,7 @@
-use crate::data::{FactionId, Factions, Site};
+use crate::{
+    data::{FactionId, Site},
+    rule::justice,
+};
 use common::store::Id;
 use rand::prelude::*;
 use vek::*;
@@ -12,47 +15,13 @@ impl Site {
         world_site_id: Id<WorldSite>,
         _world: &World,
         index: IndexRef,
-        nearby_factions: &[(Vec2<i32>, FactionId)],
-        factions: &Factions,
+        nearby_factions: &[(Vec2<i32>, FactionId, bool)],
         rng: &mut impl Rng,
     ) -> Self {
         let world_site = index.sites.get(world_site_id);
         let wpos = world_site.origin;
 
-        // TODO: This is stupid, do better
-        let good_or_evil = match &world_site.kind {
-            // Good
-            Some(
-                SiteKind::Refactor
-                | SiteKind::CliffTown
-                | SiteKind::DesertCity
-                | SiteKind::SavannahTown
-                | SiteKind::CoastalTown
-                | SiteKind::Citadel,
-            ) => Some(true),
-            // Evil
-            Some(
-                SiteKind::Myrmidon
-                | SiteKind::ChapelSite
-                | SiteKind::Terracotta
-                | SiteKind::Gnarling
-                | SiteKind::Cultist
-                | SiteKind::Sahagin
-                | SiteKind::PirateHideout
-                | SiteKind::JungleRuin
-                | SiteKind::RockCircle
-                | SiteKind::TrollCave
-                | SiteKind::Camp
-                | SiteKind::Haniwa
-                | SiteKind::Adlet
-                | SiteKind::VampireCastle
-                | SiteKind::DwarvenMine,
-            ) => Some(false),
-            // Neutral
-            Some(SiteKind::GiantTree | SiteKind::GliderCourse | SiteKind::Bridge(..)) | None => {
-                None
-            },
-        };
+        let civilised = is_civilised(world_site);
 
         Self {
             // This is assigned later
@@ -60,25 +29,65 @@ impl Site {
             seed: rng.random(),
             wpos,
             world_site: Some(world_site_id),
-            faction: good_or_evil.and_then(|good_or_evil| {
+            faction: civilised.and_then(|civilised| {
                 nearby_factions
                     .iter()
-                    .filter(|(_, faction)| {
-                        factions
-                            .get(*faction)
-                            .is_some_and(|f| f.good_or_evil == good_or_evil)
-                    })
-                    .min_by_key(|(faction_wpos, _)| {
+                    .filter(|(_, _, faction_civilised)| *faction_civilised == civilised)
+                    .min_by_key(|(faction_wpos, _, _)| {
                         faction_wpos
                             .as_::<i64>()
                             .distance_squared(wpos.as_::<i64>())
                     })
-                    .map(|(_, faction)| *faction)
+                    .map(|(_, faction, _)| *faction)
             }),
             count_loaded_chunks: 0,
             population: Default::default(),
             known_reports: Default::default(),
+            goods: Default::default(),
+            prosperity: 0.5,
+            danger: 0.0,
+            neglected_since: None,
+            constructions: Vec::new(),
+            jail: justice::choose_jail(world_site),
             nearby_sites_by_size: Vec::new(),
         }
     }
 }
+
+/// Whether the site is inhabited by civilised folk (`Some(true)`), by hostile
+/// ones (`Some(false)`), or by no faction at all (`None`). Factions start out
+/// at war with factions of the other kind.
+// TODO: This is stupid, do better
+pub fn is_civilised(world_site: &WorldSite) -> Option<bool> {
+    match &world_site.kind {
+        // Civilised
+        Some(
+            SiteKind::Refactor
+            | SiteKind::CliffTown
+            | SiteKind::DesertCity
+            | SiteKind::SavannahTown
+            | SiteKind::CoastalTown
+            | SiteKind::Citadel,
+        ) => Some(true),
+        // Hostile
+        Some(
+            SiteKind::Myrmidon
+            | SiteKind::ChapelSite
+            | SiteKind::Terracotta
+            | SiteKind::Gnarling
+            | SiteKind::Cultist
+            | SiteKind::Sahagin
+            | SiteKind::PirateHideout
+            | SiteKind::JungleRuin
+            | SiteKind::RockCircle
+            | SiteKind::TrollCave
+            | SiteKind::Camp
+            | SiteKind::Haniwa
+            | SiteKind::Adlet
+            | SiteKind::VampireCastle
+            | SiteKind::DwarvenMine,
+        ) => Some(false),
+        // Neutral
+        Some(SiteKind::GiantTree | SiteKind::GliderCourse | SiteKind::Bridge(..)) | None => None,
+    }
+}
@@ -184,7 +184,10 @@ impl RtState {
             rules: SendSyncAnyMap::new(),
             event_handlers: SendSyncAnyMap::new(),
         }
-        .with_resource(data);
+        .with_resource(data)
+        .with_resource(rule::npc_ai::movement::Routes::default())
+        .with_resource(rule::architect::SiteGrowth(true))
+        .with_resource(event::PendingDeaths::default());
 
         this.start_default_rules();
 
@@ -200,11 +203,16 @@ impl RtState {
         info!("Starting default rtsim rules...");
         self.start_rule::<rule::migrate::Migrate>();
         self.start_rule::<rule::architect::Architect>();
+        self.start_rule::<rule::household::Households>();
         self.start_rule::<rule::replenish_resources::ReplenishResources>();
         self.start_rule::<rule::report::ReportEvents>();
+        self.start_rule::<rule::diplomacy::Diplomacy>();
+        self.start_rule::<rule::reputation::UpdateReputations>();
+        self.start_rule::<rule::justice::Justice>();
         self.start_rule::<rule::sync_npcs::SyncNpcs>();
         self.start_rule::<rule::simulate_npcs::SimulateNpcs>();
         self.start_rule::<rule::npc_ai::NpcAi>();
+        self.start_rule::<rule::caravan::Caravans>();
         self.start_rule::<rule::cleanup::CleanUp>();
     }
 
@@ -333,5 +341,10 @@ impl RtState {
             dt,
         };
         self.emit(event, system_data, world, index);
+
+        let deaths = std::mem::take(&mut self.resource_mut::<event::PendingDeaths>().0);
+        for death in deaths {
+            self.emit(death, &mut (), world, index);
+        }
     }
 }
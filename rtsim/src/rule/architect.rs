@@ -8,12 +8,16 @@ use rand::{
     Rng, rng,
     seq::{IndexedRandom, IteratorRandom},
 };
-use world::{CONFIG, IndexRef, World, sim::SimChunk, site::SiteKind};
+use world::{
+    CONFIG, IndexRef, World,
+    sim::SimChunk,
+    site::{GrowthKind, SiteKind, plot::PlotKindMeta},
+};
 
 use crate::{
     Data, EventCtx, OnTick, RtState,
     data::{
-        Npc,
+        Construction, ConstructionKind, Npc,
         architect::{Death, TrackedPopulation},
     },
     event::OnDeath,
@@ -31,12 +35,34 @@ const MIN_SPAWN_DELAY: f64 = 60.0 * 60.0 * 24.0;
 /// respawn.
 const RESPAWN_ATTEMPTS: usize = 30;
 
+/// How many ticks to skip between updates of site growth.
+const GROWTH_TICK_SKIP: u64 = 960;
+/// Prosperity above which a site builds new plots.
+const THRIVING_PROSPERITY: f32 = 0.7;
+/// Prosperity below which a site is neglected and starts decaying.
+const NEGLECTED_PROSPERITY: f32 = 0.2;
+/// Min delay between two constructions at the same site, in ingame time.
+const MIN_CONSTRUCTION_DELAY: f64 = 60.0 * 60.0 * 24.0 * 7.0;
+/// How long a site has to be neglected before it starts decaying, in ingame
+/// time.
+const MIN_NEGLECT_DURATION: f64 = 60.0 * 60.0 * 24.0 * 14.0;
+/// The most plots that the architect builds at a single site.
+const MAX_BUILT_PLOTS: usize = 8;
+/// How many residents a house is expected to have.
+const RESIDENTS_PER_HOUSE: f32 = 2.0;
+
 pub struct Architect;
 
+/// Whether the architect may change the layout of sites. The server can only
+/// keep the changes when terrain is persisted, so they would otherwise be lost
+/// as soon as the chunks are generated again.
+pub struct SiteGrowth(pub bool);
+
 impl Rule for Architect {
     fn start(rtstate: &mut RtState) -> Result<Self, RuleError> {
         rtstate.bind(on_death);
         rtstate.bind(architect_tick);
+        rtstate.bind(site_growth);
 
         Ok(Self)
     }
@@ -49,6 +75,13 @@ fn on_death(ctx: EventCtx<Architect, OnDeath>) {
         && let Some(npc) = data.npcs.get(npc_id)
     {
         data.architect.on_death(npc, data.time_of_day);
+
+        // Deaths without a killer, such as of old age, don't make a site dangerous
+        if ctx.event.killer.is_some()
+            && let Some(home) = npc.home.and_then(|home| data.sites.get_mut(home))
+        {
+            home.danger += 1.0;
+        }
     }
 }
 
@@ -91,14 +124,7 @@ fn architect_tick(ctx: EventCtx<Architect, OnTick>) {
                 ),
                 TrackedPopulation::OtherTownNpcs => (
                     Body::Humanoid(comp::humanoid::Body::random()),
-                    Role::Civilised(Some(match rng.random_range(0..10) {
-                        0 => Profession::Hunter,
-                        1 => Profession::Blacksmith,
-                        2 => Profession::Chef,
-                        3 => Profession::Alchemist,
-                        4..=5 => Profession::Herbalist,
-                        _ => Profession::Farmer,
-                    })),
+                    Role::Civilised(Some(town_profession(&mut rng))),
                 ),
                 TrackedPopulation::Pirates => (
                     Body::Humanoid(comp::humanoid::Body::random()),
@@ -251,6 +277,141 @@ fn architect_tick(ctx: EventCtx<Architect, OnTick>) {
     }
 }
 
+/// Let thriving sites build new plots and neglected ones fall into ruin.
+///
+/// The architect only records the changes in
+/// [`crate::data::Site::constructions`], it's up to the server to apply them to
+/// the terrain. Sites aren't changed while they are loaded to avoid building on
+/// top of players.
+fn site_growth(ctx: EventCtx<Architect, OnTick>) {
+    if !ctx.event.tick.is_multiple_of(GROWTH_TICK_SKIP) {
+        return;
+    }
+
+    let tod = ctx.event.time_of_day;
+    let can_construct = ctx.state.resource::<SiteGrowth>().0;
+    let data = &mut *ctx.state.data_mut();
+    let mut rng = rng();
+
+    let is_house =
+        |plot: &world::site::Plot| matches!(plot.kind().meta(), Some(PlotKindMeta::House { .. }));
+
+    for site in data.sites.values_mut() {
+        let Some(world_site) = site.world_site.map(|ws| ctx.index.sites.get(ws)) else {
+            continue;
+        };
+        if !world_site.can_grow() {
+            continue;
+        }
+
+        // Measure prosperity by how crowded the site's houses are, how much
+        // trade reaches it and how safe it is for its residents
+        let ruined = site
+            .constructions
+            .iter()
+            .filter(|c| matches!(c.kind, ConstructionKind::Ruin(_)))
+            .count();
+        let houses = (world_site.plots().filter(|plot| is_house(plot)).count()
+            + site
+                .built_plots()
+                .filter(|plan| plan.kind == GrowthKind::House)
+                .count())
+        .saturating_sub(ruined)
+        .max(1);
+        let crowding =
+            (site.population.len() as f32 / (houses as f32 * RESIDENTS_PER_HOUSE)).min(1.0);
+        let trade = 1.0 - (-site.goods.values().map(|g| g.max(0.0)).sum::<f32>() / 50.0).exp();
+        let safety = 1.0 / (1.0 + site.danger);
+        let target = crowding * 0.4 + trade * 0.3 + safety * 0.3;
+        site.prosperity += (target - site.prosperity) * 0.1;
+        site.danger *= 0.95;
+
+        if !can_construct
+            || site.is_loaded()
+            || site
+                .last_construction()
+                .is_some_and(|last| tod.0 - last.0 < MIN_CONSTRUCTION_DELAY)
+        {
+            continue;
+        }
+
+        if site.prosperity > THRIVING_PROSPERITY {
+            site.neglected_since = None;
+
+            let planned = site.built_plots().cloned().collect::<Vec<_>>();
+            if planned.len() < MAX_BUILT_PLOTS {
+                let kind = if rng.random_bool(0.75) {
+                    GrowthKind::House
+                } else {
+                    GrowthKind::Workshop
+                };
+                if let Some(plan) = world_site.plan_growth(&mut rng, kind, &planned) {
+                    site.constructions.push(Construction {
+                        kind: ConstructionKind::Build(plan),
+                        started: tod,
+                        applied: false,
+                    });
+                    // New plots attract new residents
+                    data.architect
+                        .wanted_population
+                        .add(TrackedPopulation::OtherTownNpcs, 1);
+                }
+            }
+        } else if site.prosperity < NEGLECTED_PROSPERITY {
+            let neglected_since = *site.neglected_since.get_or_insert(tod);
+            if tod.0 - neglected_since.0 < MIN_NEGLECT_DURATION {
+                continue;
+            }
+
+            // Plots built by the architect are the first to go, and at least half
+            // of the houses of the generated site are left standing
+            let kind = if let Some(plan) = site.built_plots().last() {
+                ConstructionKind::RuinBuilt(plan.clone())
+            } else if ruined < houses / 2
+                && let Some((plot_id, _)) = world_site
+                    .plots
+                    .iter()
+                    .filter(|(plot_id, plot)| {
+                        is_house(plot)
+                            && !site.constructions.iter().any(|c| {
+                                matches!(c.kind, ConstructionKind::Ruin(id) if id == plot_id.id())
+                            })
+                    })
+                    .choose(&mut rng)
+            {
+                ConstructionKind::Ruin(plot_id.id())
+            } else {
+                continue;
+            };
+            // Ruined plots built by the architect no longer attract residents
+            if matches!(kind, ConstructionKind::RuinBuilt(_)) {
+                data.architect
+                    .wanted_population
+                    .remove(TrackedPopulation::OtherTownNpcs, 1);
+            }
+            site.constructions.push(Construction {
+                kind,
+                started: tod,
+                applied: false,
+            });
+        } else {
+            site.neglected_since = None;
+        }
+    }
+}
+
+/// A random profession among those of the NPCs that make up most of a town.
+pub(crate) fn town_profession(rng: &mut impl Rng) -> Profession {
+    match rng.random_range(0..10) {
+        0 => Profession::Hunter,
+        1 => Profession::Blacksmith,
+        2 => Profession::Chef,
+        3 => Profession::Alchemist,
+        4..=5 => Profession::Herbalist,
+        _ => Profession::Farmer,
+    }
+}
+
 fn randomize_body(body: Body, rng: &mut impl Rng) -> Body {
     let mut random_humanoid = || {
         let species = comp::humanoid::ALL_SPECIES.choose(rng).unwrap();
@@ -0,0 +1,318 @@
+use crate::{
+    Data, RtState, Rule, RuleError,
+    data::{
+        Npc, Sites,
+        architect::TrackedPopulation,
+        npc::{Caravan, Job},
+    },
+    event::{EventCtx, OnDeath, OnTick},
+};
+use common::{
+    comp::{Body, quadruped_medium},
+    rtsim::{Actor, NpcId, Profession, Role, SiteId},
+    trade::Good,
+};
+use hashbrown::HashSet;
+use rand::prelude::*;
+use rand_chacha::ChaChaRng;
+use tracing::debug;
+use world::{IndexRef, World, site::SiteKind};
+
+/// Prevent checking every merchant every tick
+const CARAVAN_TICK_SKIP: u64 = 60;
+/// Chance that a merchant at home sets off with a caravan each time they're
+/// checked.
+const DEPART_CHANCE: f64 = 0.02;
+/// How much of the stock of each good a caravan takes with it.
+const CARGO_SHARE: f32 = 0.1;
+/// How many different goods a caravan carries.
+const CARGO_GOODS: usize = 3;
+/// How far away a pack animal can be for a merchant to take it with them.
+const PACK_ANIMAL_RANGE: f32 = 64.0;
+/// How many pack animals a site keeps at most. Merchants that can't find one
+/// nearby when the site already has this many set off on foot.
+const MAX_PACK_ANIMALS: usize = 2;
+
+/// A rule that sends merchants with pack animals along the tracks between
+/// neighbouring sites, carrying goods from the stock of one site to the other.
+///
+/// Caravans that are ambushed on the way lose their cargo, so their
+/// destination never receives it.
+pub struct Caravans;
+
+impl Rule for Caravans {
+    fn start(rtstate: &mut RtState) -> Result<Self, RuleError> {
+        rtstate.bind::<Self, OnDeath>(on_death);
+        rtstate.bind::<Self, OnTick>(on_tick);
+
+        Ok(Self)
+    }
+}
+
+fn on_death(ctx: EventCtx<Caravans, OnDeath>) {
+    let data = &mut *ctx.state.data_mut();
+
+    // The cargo is lost along with the pack animal carrying it
+    if let Actor::Npc(animal) = ctx.event.actor
+        && let Some((leader, caravan)) = data.npcs.iter().find_map(|(id, npc)| match &npc.job {
+            Some(Job::Caravan(caravan)) if caravan.pack_animal == Some(animal) => {
+                Some((id, caravan.clone()))
+            },
+            _ => None,
+        })
+    {
+        debug!(?leader, ?caravan.to, "Caravan lost its cargo");
+        set_job(
+            data.npcs.get_mut(leader),
+            Some(Job::Caravan(Caravan {
+                cargo: Vec::new(),
+                pack_animal: None,
+                ..caravan
+            })),
+        );
+    }
+}
+
+fn on_tick(ctx: EventCtx<Caravans, OnTick>) {
+    if !ctx.event.tick.is_multiple_of(CARAVAN_TICK_SKIP) {
+        return;
+    }
+
+    let data = &mut *ctx.state.data_mut();
+    let mut rng = ChaChaRng::from_seed(rand::rng().random::<[u8; 32]>());
+
+    // Caravans that reached their destination hand over their cargo
+    let arrived = data
+        .npcs
+        .iter()
+        .filter_map(|(id, npc)| match &npc.job {
+            Some(Job::Caravan(caravan))
+                if !npc.is_dead()
+                    && npc.current_site == Some(caravan.to)
+                    // Let the escort finish first
+                    && caravan
+                        .escort
+                        .and_then(|quest_id| data.quests.get(quest_id))
+                        .is_none_or(|quest| quest.resolution().is_some()) =>
+            {
+                Some((id, npc.home, caravan.clone()))
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    for (npc_id, home, caravan) in arrived {
+        if let Some(site) = data.sites.get_mut(caravan.to) {
+            for (good, amount) in &caravan.cargo {
+                *site.goods.entry(*good).or_default() += amount;
+            }
+        }
+        debug!(?npc_id, ?caravan.to, ?caravan.cargo, "Caravan arrived");
+
+        // Head back home with goods from here, or end the trip if we're home
+        let job = match home {
+            Some(home) if home != caravan.to && data.sites.contains_key(home) => {
+                Some(Job::Caravan(Caravan {
+                    from: caravan.to,
+                    to: home,
+                    cargo: load_cargo(&mut data.sites, caravan.to, ctx.index),
+                    pack_animal: caravan.pack_animal,
+                    escort: None,
+                }))
+            },
+            _ => {
+                data.npcs.mounts.dismount(npc_id);
+                None
+            },
+        };
+        set_job(data.npcs.get_mut(npc_id), job);
+    }
+
+    // Only one caravan leaves each site at a time
+    let mut busy_sites = data
+        .npcs
+        .values()
+        .filter(|npc| matches!(npc.job, Some(Job::Caravan(_))))
+        .filter_map(|npc| npc.home)
+        .collect::<HashSet<_>>();
+
+    let departing = data
+        .npcs
+        .iter()
+        .filter(|(id, npc)| {
+            !npc.is_dead()
+                && npc.job.is_none()
+                && matches!(npc.profession(), Some(Profession::Merchant))
+                && npc.home.is_some()
+                && npc.current_site == npc.home
+                && data.npcs.mounts.get_mount_link(*id).is_none()
+        })
+        .filter_map(|(id, npc)| Some((id, npc.home?, npc.faction)))
+        .filter(|_| rng.random_bool(DEPART_CHANCE))
+        .collect::<Vec<_>>();
+    for (npc_id, home, faction) in departing {
+        if busy_sites.contains(&home) {
+            continue;
+        }
+        let Some(to) = choose_destination(ctx.world, ctx.index, &data.sites, home, &mut rng)
+            .filter(|to| {
+                !data
+                    .factions
+                    .relation_between(faction, data.sites.get(*to).and_then(|s| s.faction))
+                    .is_hostile()
+            })
+        else {
+            continue;
+        };
+        let cargo = load_cargo(&mut data.sites, home, ctx.index);
+        if cargo.is_empty() {
+            continue;
+        }
+
+        let pack_animal = find_or_spawn_pack_animal(data, ctx.index, npc_id, home, &mut rng)
+            .filter(|animal| data.npcs.mounts.steer(*animal, npc_id).is_ok());
+        debug!(?npc_id, ?home, ?to, ?cargo, "Caravan departed");
+        set_job(
+            data.npcs.get_mut(npc_id),
+            Some(Job::Caravan(Caravan {
+                from: home,
+                to,
+                cargo,
+                pack_animal,
+                escort: None,
+            })),
+        );
+        busy_sites.insert(home);
+    }
+}
+
+/// Jobs are usually changed by the NPC's own controller, so it needs to be
+/// kept up to date too.
+fn set_job(npc: Option<&mut Npc>, job: Option<Job>) {
+    if let Some(npc) = npc {
+        npc.controller.job = job.clone();
+        npc.job = job;
+    }
+}
+
+/// Choose one of the sites connected to the given one by a track.
+fn choose_destination(
+    world: &World,
+    index: IndexRef,
+    sites: &Sites,
+    from: SiteId,
+    rng: &mut impl Rng,
+) -> Option<SiteId> {
+    let world_site = sites.get(from)?.world_site?;
+    let civ_site = world.civs().sites.recreate_id(world_site.id())?;
+    world
+        .civs()
+        .neighbors(civ_site)
+        .filter_map(|neighbor| index.sites.recreate_id(neighbor.id()))
+        .filter(|neighbor| index.sites.get(*neighbor).economy.is_some())
+        .filter_map(|neighbor| sites.world_site_map.get(&neighbor).copied())
+        .choose(rng)
+}
+
+/// Take a share of the most plentiful goods from the stock of the site.
+fn load_cargo(sites: &mut Sites, from: SiteId, index: IndexRef) -> Vec<(Good, f32)> {
+    let Some(site) = sites.get_mut(from) else {
+        return Vec::new();
+    };
+    let Some(world_site) = site.world_site else {
+        return Vec::new();
+    };
+    let mut stock = site
+        .stock(index.sites.get(world_site))
+        .into_iter()
+        .filter(|(good, amount)| {
+            // Only goods that can be carried
+            matches!(
+                good,
+                Good::Flour
+                    | Good::Meat
+                    | Good::Food
+                    | Good::Wood
+                    | Good::Stone
+                    | Good::Tools
+                    | Good::Armor
+                    | Good::Ingredients
+                    | Good::Potions
+            ) && *amount > 0.0
+        })
+        .collect::<Vec<_>>();
+    stock.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    let cargo = stock
+        .into_iter()
+        .take(CARGO_GOODS)
+        .map(|(good, amount)| (good, amount * CARGO_SHARE))
+        .collect::<Vec<_>>();
+    for (good, amount) in &cargo {
+        *site.goods.entry(*good).or_default() -= amount;
+    }
+    cargo
+}
+
+fn is_pack_animal(npc: &Npc) -> bool {
+    matches!(npc.role, Role::Wild)
+        && matches!(
+            npc.body,
+            Body::QuadrupedMedium(body)
+                if matches!(body.species, quadruped_medium::Species::Donkey | quadruped_medium::Species::Camel)
+        )
+}
+
+/// Find an idle pack animal of the site close to the merchant, or buy a new
+/// one if the site doesn't have too many already.
+fn find_or_spawn_pack_animal(
+    data: &mut Data,
+    index: IndexRef,
+    merchant: NpcId,
+    home: SiteId,
+    rng: &mut impl Rng,
+) -> Option<NpcId> {
+    let wpos = data.npcs.get(merchant)?.wpos;
+    if let Some(animal) = data
+        .npcs
+        .iter()
+        .filter(|(id, npc)| {
+            npc.home == Some(home)
+                && is_pack_animal(npc)
+                && !npc.is_dead()
+                && data.npcs.mounts.get_steerer_link(*id).is_none()
+                && npc.wpos.xy().distance_squared(wpos.xy()) < PACK_ANIMAL_RANGE.powi(2)
+        })
+        .map(|(id, _)| id)
+        .next()
+    {
+        return Some(animal);
+    }
+
+    let pack_animals = data
+        .npcs
+        .values()
+        .filter(|npc| npc.home == Some(home) && is_pack_animal(npc) && !npc.is_dead())
+        .count();
+    if pack_animals >= MAX_PACK_ANIMALS {
+        return None;
+    }
+
+    let species = match data
+        .sites
+        .get(home)
+        .and_then(|site| site.world_site)
+        .and_then(|ws| index.sites.get(ws).kind.as_ref())
+    {
+        Some(SiteKind::DesertCity) => quadruped_medium::Species::Camel,
+        _ => quadruped_medium::Species::Donkey,
+    };
+    let body = Body::QuadrupedMedium(quadruped_medium::Body::random_with(rng, &species));
+    let role = Role::Wild;
+    data.architect
+        .population
+        .add(TrackedPopulation::from_body_and_role(&body, &role), 1);
+    Some(
+        data.npcs
+            .create_npc(Npc::new(rng.random(), wpos, body, role).with_home(home)),
+    )
+}
@@ -0,0 +1,188 @@
+use crate::{
+    RtState, Rule, RuleError,
+    data::{FactionId, Relation, Sentiment},
+    event::{EventCtx, OnDeath, OnTick},
+};
+use common::rtsim::Actor;
+use rand::prelude::*;
+use rand_chacha::ChaChaRng;
+use tracing::debug;
+
+/// Prevent updating relations every tick
+const DIPLOMACY_TICK_SKIP: u64 = 50;
+/// How long a war must last before the factions will consider peace, in
+/// seconds of in-game time.
+const MIN_WAR_DURATION: f64 = 3.0 * 24.0 * 3600.0;
+/// How long the factions at war must go without killing one another to sign a
+/// peace treaty, in seconds of in-game time.
+const CEASEFIRE_DURATION: f64 = 24.0 * 3600.0;
+
+/// A rule that lets the relations between factions evolve: killings sour the
+/// sentiments of factions toward one another, which eventually leads them to
+/// war, while factions sharing an enemy grow closer and wars end in peace
+/// treaties after a while.
+pub struct Diplomacy;
+
+impl Rule for Diplomacy {
+    fn start(rtstate: &mut RtState) -> Result<Self, RuleError> {
+        rtstate.bind::<Self, OnDeath>(on_death);
+        rtstate.bind::<Self, OnTick>(on_tick);
+
+        Ok(Self)
+    }
+}
+
+fn on_death(ctx: EventCtx<Diplomacy, OnDeath>) {
+    let data = &mut *ctx.state.data_mut();
+
+    let Some(victim_faction) = ctx
+        .event
+        .actor
+        .npc()
+        .and_then(|npc| data.npcs.get(npc))
+        .and_then(|npc| npc.faction)
+    else {
+        return;
+    };
+    let Some(killer) = ctx.event.killer else {
+        return;
+    };
+
+    // The faction of the victim, and its allies, remember the killer
+    // TODO: Don't hard-code sentiment changes
+    let allies = data
+        .factions
+        .relations_of(victim_faction)
+        .filter(|(_, d)| d.relation.is_friendly())
+        .map(|(ally, _)| ally)
+        .collect::<Vec<_>>();
+    for (faction, change) in
+        std::iter::once((victim_faction, -0.3)).chain(allies.iter().map(|ally| (*ally, -0.1)))
+    {
+        if let Some(faction) = data.factions.get_mut(faction) {
+            faction
+                .sentiments
+                .toward_mut(killer)
+                .change_by(change, Sentiment::VILLAIN);
+        }
+    }
+
+    // Killings between members of different factions sour their relations
+    if let Actor::Npc(killer) = killer
+        && let Some(killer_faction) = data.npcs.get(killer).and_then(|npc| npc.faction)
+        && killer_faction != victim_faction
+    {
+        for (from, to, change) in [
+            (victim_faction, killer_faction, -0.1),
+            (killer_faction, victim_faction, -0.05),
+        ] {
+            if let Some(faction) = data.factions.get_mut(from) {
+                faction
+                    .sentiments
+                    .toward_mut(to)
+                    .change_by(change, Sentiment::VILLAIN);
+            }
+        }
+        let now = data.time_of_day;
+        data.factions
+            .diplomacy_mut(victim_faction, killer_faction, now)
+            .last_conflict = Some(now);
+    }
+}
+
+fn on_tick(ctx: EventCtx<Diplomacy, OnTick>) {
+    if !ctx.event.tick.is_multiple_of(DIPLOMACY_TICK_SKIP) {
+        return;
+    }
+
+    let data = &mut *ctx.state.data_mut();
+    let mut rng = ChaChaRng::from_seed(rand::rng().random::<[u8; 32]>());
+    let now = data.time_of_day;
+
+    // Factions slowly forget grudges and friendships
+    for faction in data.factions.values_mut() {
+        faction
+            .sentiments
+            .decay(&mut rng, ctx.event.dt * DIPLOMACY_TICK_SKIP as f32);
+    }
+
+    let faction_ids = data.factions.keys().collect::<Vec<_>>();
+
+    // The enemy of my enemy is my friend
+    let mut wars = Vec::new();
+    for (i, a) in faction_ids.iter().enumerate() {
+        for b in &faction_ids[i + 1..] {
+            if data.factions.relation(*a, *b) == Relation::War {
+                wars.push((*a, *b));
+            }
+        }
+    }
+    for (i, (a, enemy_a)) in wars.iter().enumerate() {
+        for (b, enemy_b) in &wars[i + 1..] {
+            if let Some((a, b)) = shared_enemy((*a, *enemy_a), (*b, *enemy_b)) {
+                for (from, to) in [(a, b), (b, a)] {
+                    if let Some(faction) = data.factions.get_mut(from) {
+                        faction
+                            .sentiments
+                            .toward_mut(to)
+                            .change_by(0.01, Sentiment::FRIEND);
+                    }
+                }
+            }
+        }
+    }
+
+    for (i, a) in faction_ids.iter().enumerate() {
+        for b in &faction_ids[i + 1..] {
+            let (a, b) = (*a, *b);
+            let current = data.factions.relation(a, b);
+
+            // Wars only end with a peace treaty
+            if current == Relation::War {
+                let diplomacy = data.factions.diplomacy(a, b);
+                let weary = diplomacy.is_some_and(|d| now.0 - d.since.0 > MIN_WAR_DURATION);
+                let ceasefire = diplomacy
+                    .and_then(|d| d.last_conflict)
+                    .is_none_or(|t| now.0 - t.0 > CEASEFIRE_DURATION);
+                if weary && ceasefire {
+                    debug!(?a, ?b, "Factions signed a peace treaty");
+                    data.factions.set_relation(a, b, Relation::Rival, now);
+                }
+                continue;
+            }
+
+            let relation = Relation::from_sentiments(
+                data.factions[a].sentiments.toward(b),
+                data.factions[b].sentiments.toward(a),
+            );
+            if relation != current {
+                debug!(?a, ?b, ?current, ?relation, "Faction relation changed");
+                let diplomacy = data.factions.diplomacy_mut(a, b, now);
+                diplomacy.relation = relation;
+                diplomacy.since = now;
+            }
+        }
+    }
+
+    // Neutral relations without a recent conflict don't need to be remembered
+    data.factions.relations.retain(|(a, b), d| {
+        data.factions.factions.contains_key(*a)
+            && data.factions.factions.contains_key(*b)
+            && (d.relation != Relation::Neutral
+                || d.last_conflict
+                    .is_some_and(|t| now.0 - t.0 < CEASEFIRE_DURATION))
+    });
+}
+
+/// If two wars share a side, returns the two factions fighting that side.
+fn shared_enemy(
+    a: (FactionId, FactionId),
+    b: (FactionId, FactionId),
+) -> Option<(FactionId, FactionId)> {
+    [(a.0, a.1), (a.1, a.0)]
+        .into_iter()
+        .flat_map(|(x, enemy_x)| [(x, enemy_x, b.0, b.1), (x, enemy_x, b.1, b.0)])
+        .find(|(_, enemy_x, _, enemy_y)| enemy_x == enemy_y)
+        .map(|(x, _, y, _)| (x, y))
+        .filter(|(x, y)| x != y)
+}
@@ -0,0 +1,401 @@
+use crate::{
+    Data, RtState, Rule, RuleError,
+    data::{
+        ConstructionKind, Npc, Site,
+        architect::TrackedPopulation,
+        npc::{Profession, SimulationMode},
+    },
+    event::{EventCtx, OnDeath, OnTick, PendingDeaths},
+    rule::architect::town_profession,
+};
+use common::{
+    comp::{self, Body},
+    resources::TimeOfDay,
+    rtsim::{Actor, NpcId, Personality, Role, SiteId},
+    trade::Good,
+};
+use hashbrown::HashMap;
+use rand::prelude::*;
+use vek::*;
+use world::{
+    World,
+    site::{Site as WorldSite, plot::PlotKindMeta},
+};
+
+/// How many ticks to skip between updates of households.
+const HOUSEHOLD_TICK_SKIP: u64 = 480;
+const DAY: f64 = 60.0 * 60.0 * 24.0;
+/// The age, in in-game time, at which NPCs are old enough to work.
+pub const ADULTHOOD: f64 = DAY * 8.0;
+/// The age after which NPCs may die of old age.
+const OLD_AGE: f64 = DAY * 60.0;
+/// The age that no NPC lives beyond.
+const MAX_AGE: f64 = DAY * 80.0;
+/// The most NPCs that live in a single house.
+const MAX_HOUSEHOLD: usize = 4;
+/// How many children a household has per in-game day, if it has room for them.
+const BIRTHS_PER_DAY: f64 = 0.1;
+/// How much food a site needs to have in stock per resident for its households
+/// to have children.
+const FOOD_PER_RESIDENT: f32 = 0.5;
+
+/// A rule that gives the civilised NPCs of a site households, in which they are
+/// born, grow up, grow old and die.
+///
+/// Each household lives in one of the house plots of its site, see
+/// [`Npc::house`]. Households with two adults and room to spare have children
+/// when their site has enough food. Children take up the profession of one of
+/// their parents and keep the house once their parents are gone, or found a
+/// household of their own in an empty house. Residents who can't find room in
+/// any house look for a home elsewhere (see the villager AI).
+///
+/// Births count toward the population that the architect maintains, so the
+/// NPCs that die in a site that raises its own children aren't replaced from
+/// scratch. Deaths of old age are queued as [`PendingDeaths`], so that they
+/// are handled like any other death.
+pub struct Households;
+
+impl Rule for Households {
+    fn start(rtstate: &mut RtState) -> Result<Self, RuleError> {
+        rtstate.bind(on_tick);
+
+        Ok(Self)
+    }
+}
+
+/// Whether the NPC is too young to work.
+pub fn is_child(npc: &Npc, time_of_day: TimeOfDay) -> bool {
+    npc.age(time_of_day).is_some_and(|age| age < ADULTHOOD)
+}
+
+/// Whether the NPC ages and lives in a household.
+fn has_household(npc: &Npc) -> bool {
+    matches!(npc.body, Body::Humanoid(_)) && matches!(npc.role, Role::Civilised(_))
+}
+
+struct Birth {
+    site: SiteId,
+    house: u64,
+    wpos: Vec2<i32>,
+    body: Body,
+    profession: Profession,
+}
+
+fn on_tick(ctx: EventCtx<Households, OnTick>) {
+    if !ctx.event.tick.is_multiple_of(HOUSEHOLD_TICK_SKIP) {
+        return;
+    }
+
+    let now = ctx.event.time_of_day;
+    // The in-game time that passed since the last update
+    let elapsed = ctx.event.dt as f64
+        * HOUSEHOLD_TICK_SKIP as f64
+        * ctx.system_data.server_constants.day_cycle_coefficient;
+    let data = &mut *ctx.state.data_mut();
+    let mut rng = rand::rng();
+
+    // NPCs from before households existed, or spawned by the architect, arrive
+    // as adults of some age
+    for npc in data
+        .npcs
+        .values_mut()
+        .filter(|npc| npc.born.is_none() && has_household(npc))
+    {
+        let age = ADULTHOOD + (OLD_AGE - ADULTHOOD) * npc.rng(Npc::PERM_AGE).random::<f64>();
+        npc.born = Some(TimeOfDay(now.0 - age));
+    }
+
+    // Old NPCs die, unless they're loaded or busy with a job
+    let mut pending_deaths = ctx.state.resource_mut::<PendingDeaths>();
+    for (npc_id, npc) in data.npcs.iter().filter(|(_, npc)| {
+        !npc.is_dead()
+            && matches!(npc.mode, SimulationMode::Simulated)
+            && npc.job.is_none()
+            && has_household(npc)
+    }) {
+        let chance = npc
+            .age(now)
+            .map_or(0.0, |age| old_age_death_chance(age, elapsed));
+        if rng.random_bool(chance) {
+            pending_deaths.0.push(OnDeath {
+                actor: Actor::Npc(npc_id),
+                wpos: Some(npc.wpos),
+                killer: None,
+            });
+        }
+    }
+    drop(pending_deaths);
+
+    let mut moves = Vec::new();
+    let mut births = Vec::new();
+    for (site_id, site) in data.sites.iter() {
+        let Some(world_site) = site.world_site.map(|ws| ctx.index.sites.get(ws)) else {
+            continue;
+        };
+        let houses = houses(site, world_site);
+
+        // Gather the households of the site
+        let mut households = houses
+            .keys()
+            .map(|house| (*house, Vec::new()))
+            .collect::<HashMap<_, Vec<NpcId>>>();
+        let mut homeless = Vec::new();
+        for (npc_id, npc) in site.population.iter().filter_map(|npc_id| {
+            let npc = data.npcs.get(*npc_id)?;
+            (!npc.is_dead() && has_household(npc) && npc.home == Some(site_id))
+                .then_some((*npc_id, npc))
+        }) {
+            match npc.house.and_then(|house| households.get_mut(&house)) {
+                Some(members) => members.push(npc_id),
+                None => homeless.push(npc_id),
+            }
+        }
+
+        moves.extend(assign_houses(&mut households, homeless, |members| {
+            let born = |npc_id: &NpcId| data.npcs[*npc_id].born.map_or(f64::MIN, |born| born.0);
+            members
+                .iter()
+                .filter(|npc_id| !is_child(&data.npcs[**npc_id], now))
+                .max_by(|a, b| born(a).total_cmp(&born(b)))
+                .copied()
+        }));
+
+        // Households have children if there's enough food to go around. New NPCs
+        // don't appear in front of players.
+        let food = site
+            .stock(world_site)
+            .get(&Good::Food)
+            .copied()
+            .unwrap_or(0.0);
+        if site.is_loaded() || food < site.population.len() as f32 * FOOD_PER_RESIDENT {
+            continue;
+        }
+        let birth_chance = (BIRTHS_PER_DAY * elapsed / DAY).min(1.0);
+        for (house, members) in &households {
+            let adults = members
+                .iter()
+                .map(|npc_id| &data.npcs[*npc_id])
+                .filter(|npc| !is_child(npc, now))
+                .collect::<Vec<_>>();
+            if !can_have_child(adults.len(), members.len()) || !rng.random_bool(birth_chance) {
+                continue;
+            }
+            let Some(parent) = adults.choose(&mut rng) else {
+                continue;
+            };
+            let body = match parent.body {
+                Body::Humanoid(body) => {
+                    Body::Humanoid(comp::humanoid::Body::random_with(&mut rng, &body.species))
+                },
+                body => body,
+            };
+            births.push(Birth {
+                site: site_id,
+                house: *house,
+                wpos: houses[house],
+                body,
+                profession: child_profession(parent.profession(), &mut rng),
+            });
+        }
+    }
+
+    for (npc_id, house) in moves {
+        if let Some(npc) = data.npcs.get_mut(npc_id) {
+            npc.house = house;
+        }
+    }
+    for birth in births {
+        spawn_child(data, ctx.world, birth, now, &mut rng);
+    }
+}
+
+/// The chance that an NPC of the given age dies of old age within `elapsed`
+/// in-game time.
+fn old_age_death_chance(age: f64, elapsed: f64) -> f64 {
+    if age <= OLD_AGE {
+        0.0
+    } else if age >= MAX_AGE {
+        1.0
+    } else {
+        (elapsed / (MAX_AGE - OLD_AGE)).min(1.0)
+    }
+}
+
+/// The youngest adult of each full household founds their own in an empty
+/// house, then residents without a house move into the least crowded house with
+/// room, if there is one. Returns the residents that moved along with their new
+/// house.
+fn assign_houses(
+    households: &mut HashMap<u64, Vec<NpcId>>,
+    homeless: Vec<NpcId>,
+    youngest_adult: impl Fn(&[NpcId]) -> Option<NpcId>,
+) -> Vec<(NpcId, Option<u64>)> {
+    let mut moves = Vec::new();
+    let full = households
+        .values()
+        .filter(|members| members.len() >= MAX_HOUSEHOLD)
+        .filter_map(|members| youngest_adult(members))
+        .collect::<Vec<_>>();
+    for npc_id in full {
+        if let Some(house) = households
+            .iter()
+            .find(|(_, members)| members.is_empty())
+            .map(|(house, _)| *house)
+        {
+            households
+                .values_mut()
+                .for_each(|members| members.retain(|member| *member != npc_id));
+            households.entry(house).or_default().push(npc_id);
+            moves.push((npc_id, Some(house)));
+        }
+    }
+
+    for npc_id in homeless {
+        let house = households
+            .iter_mut()
+            .filter(|(_, members)| members.len() < MAX_HOUSEHOLD)
+            .min_by_key(|(_, members)| members.len())
+            .map(|(house, members)| {
+                members.push(npc_id);
+                *house
+            });
+        moves.push((npc_id, house));
+    }
+    moves
+}
+
+/// Whether a household with the given number of adults and members may have
+/// children.
+fn can_have_child(adults: usize, members: usize) -> bool { adults >= 2 && members < MAX_HOUSEHOLD }
+
+/// Children take up the trade of their parent, unless it's one that isn't
+/// passed down.
+fn child_profession(parent: Option<Profession>, rng: &mut impl Rng) -> Profession {
+    match parent {
+        Some(
+            profession @ (Profession::Farmer
+            | Profession::Hunter
+            | Profession::Merchant
+            | Profession::Guard
+            | Profession::Blacksmith
+            | Profession::Chef
+            | Profession::Alchemist
+            | Profession::Herbalist),
+        ) => profession,
+        _ => town_profession(rng),
+    }
+}
+
+/// The house plots of a site that haven't fallen into ruin, by id, along with
+/// their position.
+pub fn houses(site: &Site, world_site: &WorldSite) -> HashMap<u64, Vec2<i32>> {
+    world_site
+        .plots
+        .iter()
+        .filter(|(_, plot)| matches!(plot.meta(), Some(PlotKindMeta::House { .. })))
+        .filter(|(plot_id, _)| {
+            !site
+                .constructions
+                .iter()
+                .any(|c| matches!(c.kind, ConstructionKind::Ruin(id) if id == plot_id.id()))
+        })
+        .map(|(plot_id, plot)| (plot_id.id(), world_site.tile_center_wpos(plot.root_tile())))
+        .collect()
+}
+
+fn spawn_child(data: &mut Data, world: &World, birth: Birth, now: TimeOfDay, rng: &mut impl Rng) {
+    let role = Role::Civilised(Some(birth.profession));
+    data.architect
+        .population
+        .add(TrackedPopulation::from_body_and_role(&birth.body, &role), 1);
+
+    let wpos = birth
+        .wpos
+        .as_()
+        .with_z(world.sim().get_alt_approx(birth.wpos).unwrap_or(0.0));
+    let mut npc = Npc::new(rng.random(), wpos, birth.body, role)
+        .with_personality(Personality::random(rng))
+        .with_home(birth.site);
+    if let Some(faction) = data.sites[birth.site].faction {
+        npc = npc.with_faction(faction);
+    }
+    npc.born = Some(now);
+    npc.house = Some(birth.house);
+    data.spawn_npc(npc);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slotmap::SlotMap;
+
+    fn npc_ids(n: usize) -> Vec<NpcId> {
+        let mut ids = SlotMap::<NpcId, ()>::with_key();
+        (0..n).map(|_| ids.insert(())).collect()
+    }
+
+    #[test]
+    fn npcs_grow_up_and_die_of_old_age() {
+        let mut npc = Npc::new(
+            0,
+            Vec3::zero(),
+            Body::Humanoid(comp::humanoid::Body::random()),
+            Role::Civilised(None),
+        );
+        npc.born = Some(TimeOfDay(0.0));
+        assert!(is_child(&npc, TimeOfDay(ADULTHOOD - 1.0)));
+        assert!(!is_child(&npc, TimeOfDay(ADULTHOOD)));
+
+        assert_eq!(old_age_death_chance(OLD_AGE, DAY), 0.0);
+        let chance = old_age_death_chance(OLD_AGE + DAY, DAY);
+        assert!(chance > 0.0 && chance < 1.0);
+        assert_eq!(old_age_death_chance(MAX_AGE, DAY), 1.0);
+    }
+
+    #[test]
+    fn only_households_with_two_adults_and_room_have_children() {
+        assert!(can_have_child(2, 2));
+        assert!(can_have_child(2, MAX_HOUSEHOLD - 1));
+        assert!(!can_have_child(1, 1));
+        assert!(!can_have_child(2, MAX_HOUSEHOLD));
+
+        let mut rng = rand::rng();
+        assert!(matches!(
+            child_profession(Some(Profession::Guard), &mut rng),
+            Profession::Guard
+        ));
+        assert!(!matches!(
+            child_profession(Some(Profession::Captain), &mut rng),
+            Profession::Captain
+        ));
+    }
+
+    #[test]
+    fn homeless_and_grown_up_residents_are_assigned_houses() {
+        let ids = npc_ids(MAX_HOUSEHOLD + 2);
+        let (full, rest) = ids.split_at(MAX_HOUSEHOLD);
+        let mut households = HashMap::from([(0, full.to_vec()), (1, Vec::new())]);
+
+        // The youngest adult of the full household moves out to the empty house
+        // first, then the homeless resident joins the least crowded household
+        let moves = assign_houses(&mut households, vec![rest[0]], |members| {
+            members.last().copied()
+        });
+        assert_eq!(moves, vec![
+            (full[MAX_HOUSEHOLD - 1], Some(1)),
+            (rest[0], Some(1)),
+        ]);
+        assert!(
+            households
+                .values()
+                .all(|members| members.len() <= MAX_HOUSEHOLD)
+        );
+        assert_eq!(households[&1], vec![full[MAX_HOUSEHOLD - 1], rest[0]]);
+
+        // Residents stay homeless when every house is full
+        let mut households = HashMap::from([(0, full.to_vec())]);
+        let moves = assign_houses(&mut households, vec![rest[1]], |_| None);
+        assert_eq!(moves, vec![(rest[1], None)]);
+        assert_eq!(households[&0], full.to_vec());
+    }
+}
@@ -0,0 +1,305 @@
+use crate::{
+    Data, RtState, Rule, RuleError,
+    data::{
+        Site,
+        quest::{QuestKind, Quests},
+    },
+    event::{EventCtx, OnDeath, OnTick},
+};
+use common::{
+    character::CharacterId,
+    rtsim::{Actor, ItemResource, NpcId, Profession, QuestId, Role, SiteId},
+    trade::Good,
+};
+use hashbrown::{HashMap, HashSet};
+use vek::*;
+use world::site::{Site as WorldSite, plot::PlotKindMeta};
+
+/// How many ticks to skip between updates of jail sentences.
+const JUSTICE_TICK_SKIP: u64 = 30;
+/// The bounty placed on a player for each resident of a site that they murder.
+const MURDER_BOUNTY: u32 = 100;
+/// The bounty placed on a player for each guard of a site that they murder.
+const GUARD_MURDER_BOUNTY: u32 = 250;
+/// How close to a murder NPCs must be to witness it.
+const WITNESS_RADIUS: f32 = 32.0;
+/// How close to the jail of a site a player must stay to serve their sentence.
+const JAIL_RADIUS: f32 = 24.0;
+/// How long, in seconds, a player serving a sentence may stay away from the
+/// jail before they are considered to have escaped.
+const ESCAPE_TIME: f32 = 60.0;
+
+/// A rule that places bounties on players who murder the residents of a site,
+/// and that keeps track of bounties being served out in jail or claimed by
+/// bounty hunters.
+///
+/// Murders that nobody witnesses go unpunished. Players that give themselves up
+/// to a guard (see [`crate::rule::npc_ai`]) must stay in the site's jail until
+/// they have served their sentence, or they are wanted once more. Bounties are
+/// also cleared when the wanted player is slain by a guard of the site, or by a
+/// player that took on the bounty as a quest.
+///
+/// The rewards of bounty quests are paid for out of the site's stock, and are
+/// returned to it when the quest is called off because the wanted player paid
+/// their fine, gave themselves up or was otherwise brought to justice.
+pub struct Justice;
+
+impl Rule for Justice {
+    fn start(rtstate: &mut RtState) -> Result<Self, RuleError> {
+        rtstate.bind::<Self, OnDeath>(on_death);
+        rtstate.bind::<Self, OnTick>(on_tick);
+
+        Ok(Self)
+    }
+}
+
+/// Chooses a plot of the world site that nobody lives in, such as a workshop or
+/// a tavern, to serve as the jail of the site.
+pub fn choose_jail(world_site: &WorldSite) -> Option<u64> {
+    world_site
+        .plots
+        .iter()
+        .filter(|(_, plot)| {
+            matches!(
+                plot.meta(),
+                Some(PlotKindMeta::Workshop { .. } | PlotKindMeta::Other { .. })
+            )
+        })
+        .map(|(plot_id, _)| plot_id.id())
+        .min()
+}
+
+/// The plot of a site that serves as its jail (see [`Site::jail`]), by id,
+/// along with its position.
+pub fn jail(site: &Site, world_site: &WorldSite) -> Option<(u64, Vec2<i32>)> {
+    let jail = site.jail?;
+    world_site
+        .plots
+        .iter()
+        .find(|(plot_id, _)| plot_id.id() == jail)
+        .map(|(plot_id, plot)| (plot_id.id(), world_site.tile_center_wpos(plot.root_tile())))
+}
+
+/// Calls off the open bounty quests on a character that were handed out by the
+/// guards of a site, now that the character is no longer wanted there, and
+/// returns their reward to the site's stock. Quests that a bounty hunter
+/// already has a claim on are left to be claimed.
+pub fn call_off_bounty_quests(data: &mut Data, site_id: SiteId, criminal: CharacterId) {
+    let npcs = &data.npcs;
+    let refund = call_off_quests(&data.quests, &data.bounties.claims, criminal, |arbiter| {
+        npcs.get(arbiter)
+            .is_some_and(|arbiter| arbiter.home == Some(site_id))
+    });
+    if refund > 0.0
+        && let Some(site) = data.sites.get_mut(site_id)
+    {
+        *site.goods.entry(Good::Coin).or_default() += refund;
+    }
+}
+
+/// Fails the open, unclaimed bounty quests on a character whose arbiter is
+/// accepted by `is_arbiter`, returning the sum of their rewards.
+fn call_off_quests(
+    quests: &Quests,
+    claims: &HashSet<QuestId>,
+    criminal: CharacterId,
+    is_arbiter: impl Fn(NpcId) -> bool,
+) -> f32 {
+    let mut refund = 0.0;
+    for quest_id in quests.related_to(criminal) {
+        if !claims.contains(&quest_id)
+            && let Some(quest) = quests.get(quest_id)
+            && let QuestKind::Slay { target, .. } = quest.kind
+            && target == Actor::Character(criminal)
+            && let Some(arbiter) = quest.arbiter.npc()
+            && is_arbiter(arbiter)
+            && let Some(outcome) = quest.resolve(arbiter, false)
+            && let Some((ItemResource::Coin, amount)) = outcome.deposit
+        {
+            refund += amount;
+        }
+    }
+    refund
+}
+
+fn on_death(ctx: EventCtx<Justice, OnDeath>) {
+    let data = &mut *ctx.state.data_mut();
+
+    match (ctx.event.actor, ctx.event.killer) {
+        // The murder of a resident by a player
+        (Actor::Npc(victim_id), Some(Actor::Character(murderer))) => {
+            let Some(victim) = data.npcs.get(victim_id) else {
+                return;
+            };
+            let reward = match &victim.role {
+                Role::Civilised(Some(Profession::Pirate(_) | Profession::Cultist)) => return,
+                Role::Civilised(Some(Profession::Guard)) => GUARD_MURDER_BOUNTY,
+                Role::Civilised(_) => MURDER_BOUNTY,
+                _ => return,
+            };
+            let Some(home) = victim.home else {
+                return;
+            };
+            let witnessed = ctx.event.wpos.is_some_and(|wpos| {
+                data.npcs
+                    .nearby(Some(victim_id), wpos, WITNESS_RADIUS)
+                    .filter_map(|actor| data.npcs.get(actor.npc()?))
+                    .any(|npc| matches!(npc.role, Role::Civilised(_)))
+            });
+            if witnessed {
+                data.bounties.raise(home, murderer, reward);
+            }
+        },
+        // A wanted player brought to justice
+        (Actor::Character(criminal), Some(killer)) => {
+            let mut served_at = Vec::new();
+            match killer {
+                Actor::Npc(guard) => {
+                    if let Some(guard) = data.npcs.get(guard)
+                        && matches!(guard.profession(), Some(Profession::Guard))
+                        && let Some(home) = guard.home
+                    {
+                        served_at.push(home);
+                    }
+                },
+                Actor::Character(hunter) => {
+                    for quest_id in data.quests.related_to(criminal).collect::<Vec<_>>() {
+                        if let Some(quest) = data.quests.get(quest_id)
+                            && let QuestKind::Slay { target, slayer } = quest.kind
+                            && target == Actor::Character(criminal)
+                            && slayer == Actor::Character(hunter)
+                            && let Some(home) = quest
+                                .arbiter
+                                .npc()
+                                .and_then(|arbiter| data.npcs.get(arbiter))
+                                .and_then(|arbiter| arbiter.home)
+                        {
+                            data.bounties.claims.insert(quest_id);
+                            served_at.push(home);
+                        }
+                    }
+                },
+            }
+            for site in served_at {
+                data.bounties.clear(site, criminal);
+                call_off_bounty_quests(data, site, criminal);
+            }
+        },
+        _ => {},
+    }
+}
+
+fn on_tick(ctx: EventCtx<Justice, OnTick>) {
+    if !ctx.event.tick.is_multiple_of(JUSTICE_TICK_SKIP) {
+        return;
+    }
+
+    let dt = ctx.event.dt * JUSTICE_TICK_SKIP as f32;
+    let data = &mut *ctx.state.data_mut();
+
+    // Sites that don't have a jail yet pick one
+    for site in data.sites.values_mut().filter(|site| site.jail.is_none()) {
+        if let Some(world_site) = site.world_site.map(|ws| ctx.index.sites.get(ws)) {
+            site.jail = choose_jail(world_site);
+        }
+    }
+
+    // Claims on quests that are over have been paid out
+    let quests = &data.quests;
+    data.bounties.claims.retain(|quest_id| {
+        quests
+            .get(*quest_id)
+            .is_some_and(|quest| quest.resolution().is_none())
+    });
+
+    let players = data
+        .npcs
+        .character_map
+        .values()
+        .flatten()
+        .copied()
+        .collect::<HashMap<CharacterId, Vec3<f32>>>();
+
+    let mut finished = Vec::new();
+    for ((site_id, character), bounty) in data.bounties.bounties.iter_mut() {
+        let Some(sentence) = &mut bounty.sentence else {
+            continue;
+        };
+        // Time only counts while the player is around
+        let Some(wpos) = players.get(character) else {
+            continue;
+        };
+        let Some(jail_wpos) = data.sites.get(*site_id).and_then(|site| {
+            let world_site = ctx.index.sites.get(site.world_site?);
+            jail(site, world_site).map(|(_, wpos)| wpos)
+        }) else {
+            // Sites without a jail let their prisoners go
+            finished.push((*site_id, *character));
+            continue;
+        };
+
+        if wpos.xy().distance_squared(jail_wpos.as_()) < JAIL_RADIUS.powi(2) {
+            sentence.served += dt;
+            sentence.away = 0.0;
+            if sentence.served >= sentence.length {
+                finished.push((*site_id, *character));
+            }
+        } else {
+            sentence.away += dt;
+            if sentence.away > ESCAPE_TIME {
+                bounty.sentence = None;
+            }
+        }
+    }
+    for (site_id, character) in finished {
+        data.bounties.clear(site_id, character);
+        call_off_bounty_quests(data, site_id, character);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::quest::Quest;
+    use slotmap::SlotMap;
+
+    #[test]
+    fn bounty_rewards_are_returned_to_the_site() {
+        let mut npcs = SlotMap::<NpcId, ()>::with_key();
+        let (guard, other_guard) = (npcs.insert(()), npcs.insert(()));
+        let criminal = CharacterId(1);
+        let mut quests = Quests::default();
+        let mut bounty_quest = |arbiter: NpcId, hunter: i64, reward: f32| {
+            let quest_id = quests.register();
+            quests.create(
+                quest_id,
+                Quest::slay(
+                    Actor::Npc(arbiter),
+                    Actor::Character(criminal),
+                    Actor::Character(CharacterId(hunter)),
+                )
+                .with_deposit(ItemResource::Coin, reward),
+            );
+            quest_id
+        };
+        let open = bounty_quest(guard, 2, 100.0);
+        let claimed = bounty_quest(guard, 3, 50.0);
+        let elsewhere = bounty_quest(other_guard, 4, 25.0);
+        let claims = HashSet::from([claimed]);
+
+        // Only the open quest handed out by the site's guards is called off
+        assert_eq!(
+            call_off_quests(&quests, &claims, criminal, |npc| npc == guard),
+            100.0
+        );
+        assert_eq!(quests.get(open).unwrap().resolution(), Some(false));
+        assert_eq!(quests.get(claimed).unwrap().resolution(), None);
+        assert_eq!(quests.get(elsewhere).unwrap().resolution(), None);
+
+        // Quests can't be called off twice
+        assert_eq!(
+            call_off_quests(&quests, &claims, criminal, |npc| npc == guard),
+            0.0
+        );
+    }
+}
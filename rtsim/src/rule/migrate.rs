@@ -6,7 +6,7 @@ use crate::{
         npc::Profession,
     },
     event::OnSetup,
-    generate::wanted_population,
+    generate::{site::is_civilised, wanted_population},
 };
 use rand::prelude::*;
 use rand_chacha::ChaChaRng;
@@ -64,12 +64,39 @@ impl Rule for Migrate {
                         ctx.world,
                         ctx.index,
                         &[],
-                        &data.factions,
                         &mut rng,
                     ));
                 }
             }
 
+            // Data from before faction relations existed has none, so derive them from
+            // the kind of sites that each faction holds
+            if data.factions.relations.is_empty() {
+                let civilised = data
+                    .factions
+                    .keys()
+                    .filter_map(|faction_id| {
+                        let (civilised, hostile) = data
+                            .sites
+                            .values()
+                            .filter(|site| site.faction == Some(faction_id))
+                            .filter_map(|site| {
+                                site.world_site
+                                    .and_then(|ws| is_civilised(ctx.index.sites.get(ws)))
+                            })
+                            .fold(
+                                (0, 0),
+                                |(c, h), civilised| {
+                                    if civilised { (c + 1, h) } else { (c, h + 1) }
+                                },
+                            );
+                        (civilised + hostile > 0).then_some((faction_id, civilised >= hostile))
+                    })
+                    .collect::<Vec<_>>();
+                let now = data.time_of_day;
+                data.factions.generate_relations(&civilised, now, &mut rng);
+            }
+
             // Reassign NPCs to sites if their old one was deleted. If they were already
             // homeless, no need to do anything.
             // Keep track of airship captains separately, as they need to be handled
@@ -88,17 +115,10 @@ impl Rule for Migrate {
                         .sites
                         .iter()
                         .filter(|(_, site)| {
-                            let ally_faction = match (
-                                npc.faction.and_then(|f| data.factions.get(f)),
-                                site.faction.and_then(|f| data.factions.get(f)),
-                            ) {
-                                (None, None) => true,
-                                (None, Some(_)) => true,
-                                (Some(_), None) => true,
-                                (Some(npc_faction), Some(site_faction)) => {
-                                    npc_faction.good_or_evil == site_faction.good_or_evil
-                                },
-                            };
+                            let ally_faction = !data
+                                .factions
+                                .relation_between(npc.faction, site.faction)
+                                .is_hostile();
 
                             // See if there is at least one house in this site.
                             let has_house = site.world_site.is_some_and(|ws| {
@@ -186,6 +206,15 @@ impl Rule for Migrate {
 
             // Calculate architect populations
             data.architect.wanted_population = wanted_population(ctx.world, ctx.index);
+            // Plots built by the architect attract residents too
+            let built_plots = data
+                .sites
+                .values()
+                .map(|site| site.built_plots().count() as u32)
+                .sum();
+            data.architect
+                .wanted_population
+                .add(TrackedPopulation::OtherTownNpcs, built_plots);
 
             data.architect.population = Population::default();
 
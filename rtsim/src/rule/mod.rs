@@ -1,9 +1,14 @@
 pub mod architect;
+pub mod caravan;
 pub mod cleanup;
+pub mod diplomacy;
+pub mod household;
+pub mod justice;
 pub mod migrate;
 pub mod npc_ai;
 pub mod replenish_resources;
 pub mod report;
+pub mod reputation;
 pub mod simulate_npcs;
 pub mod sync_npcs;
 
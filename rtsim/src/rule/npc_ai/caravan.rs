@@ -0,0 +1,35 @@
+use super::*;
+use crate::data::npc::Caravan;
+
+/// Lead a caravan to its destination, following the player escorting it if
+/// there is one. The cargo is handed over by [`crate::rule::caravan`] once the
+/// caravan arrives.
+pub fn lead<S: State>(caravan: Caravan) -> impl Action<S> {
+    let (from, to) = (caravan.from, caravan.to);
+    now(move |ctx, _| {
+        let data = ctx.state.data();
+        let escorter = caravan
+            .escort
+            .and_then(|quest_id| data.quests.get(quest_id))
+            .filter(|quest| quest.resolution().is_none())
+            .and_then(|quest| match &quest.kind {
+                QuestKind::Escort { escorter, .. } => Some(*escorter),
+                QuestKind::Slay { .. } => None,
+            });
+        drop(data);
+
+        if let Some(quest_id) = caravan.escort
+            && let Some(escorter) = escorter
+            && util::actor_exists(ctx, escorter)
+        {
+            quest::escorted(quest_id, escorter, caravan.to).boxed()
+        } else {
+            travel_to_site(caravan.to, 0.5)
+                // Wait for the cargo to be unloaded
+                .then(idle().repeat().stop_if(timeout(10.0)))
+                .map(|_, _| ())
+                .boxed()
+        }
+    })
+    .debug(move || format!("lead caravan from {:?} to {:?}", from, to))
+}
@@ -16,11 +16,17 @@ pub fn general<S: State>(tgt: Actor, session: DialogueSession) -> impl Action<S>
                         .boxed(),
                 ));
             },
+            Some(Job::Caravan(caravan)) if caravan.escort.is_none() => {
+                responses.push((
+                    Response::from(Content::localized("dialogue-question-quest_req")),
+                    dialogue::ask_for_quest(tgt, session).boxed(),
+                ));
+            },
             Some(_) => {},
             None => {
                 responses.push((
                     Response::from(Content::localized("dialogue-question-quest_req")),
-                    quest::quest_request(session).boxed(),
+                    dialogue::ask_for_quest(tgt, session).boxed(),
                 ));
 
                 let can_be_hired = matches!(ctx.npc.profession(), Some(Profession::Adventurer(_)));
@@ -77,10 +83,48 @@ pub fn general<S: State>(tgt: Actor, session: DialogueSession) -> impl Action<S>
                             .boxed(),
                     ));
                 },
+                // Bounties on players can be claimed once the bounty hunter has slain them
+                QuestKind::Slay {
+                    target: target @ Actor::Character(_),
+                    slayer,
+                } if quest.arbiter == Actor::Npc(ctx.npc_id) && *slayer == tgt => {
+                    if data.bounties.claims.contains(&quest_id) {
+                        responses.push(claim_slay_reward(
+                            quest_id,
+                            session,
+                            Content::localized("dialogue-question-quest-bounty-claim"),
+                        ));
+                    } else {
+                        let target = *target;
+                        responses.push((
+                            Response::from(Content::localized(
+                                "dialogue-question-quest-bounty-where",
+                            )),
+                            if let Some(wpos) = util::locate_actor(ctx, target) {
+                                session
+                                    .give_marker(
+                                        Marker::at(wpos.xy())
+                                            .with_id(target)
+                                            .with_label(Content::localized("hud-map-bounty-label"))
+                                            .with_quest_flag(true),
+                                    )
+                                    .then(session.say_statement(Content::localized(
+                                        "npc-response-quest-bounty-where",
+                                    )))
+                                    .boxed()
+                            } else {
+                                session
+                                    .say_statement(Content::localized(
+                                        "npc-response-quest-bounty-unknown",
+                                    ))
+                                    .boxed()
+                            },
+                        ));
+                    }
+                },
                 QuestKind::Slay { target, slayer }
                     if quest.arbiter == Actor::Npc(ctx.npc_id) && *slayer == tgt =>
                 {
-                    // TODO: Work for non-NPCs?
                     let Actor::Npc(target_npc_id) = target else {
                         continue;
                     };
@@ -110,27 +154,10 @@ pub fn general<S: State>(tgt: Actor, session: DialogueSession) -> impl Action<S>
                                 .boxed(),
                         ));
                     } else {
-                        responses.push((
-                            Response::from(Content::localized(
-                                "dialogue-question-quest-slay-claim",
-                            )),
-                            session
-                                .say_statement(Content::localized("npc-response-quest-slay-thanks"))
-                                .then(now(move |ctx, _| {
-                                    if let Ok(deposit) =
-                                        quest::resolve_take_deposit(ctx, quest_id, true)
-                                    {
-                                        session
-                                            .say_statement_with_gift(
-                                                Content::localized("npc-response-quest-reward"),
-                                                deposit,
-                                            )
-                                            .boxed()
-                                    } else {
-                                        finish().boxed()
-                                    }
-                                }))
-                                .boxed(),
+                        responses.push(claim_slay_reward(
+                            quest_id,
+                            session,
+                            Content::localized("dialogue-question-quest-slay-claim"),
                         ));
                     }
                 },
@@ -138,6 +165,17 @@ pub fn general<S: State>(tgt: Actor, session: DialogueSession) -> impl Action<S>
             }
         }
 
+        // Topics described by dialogue tree assets
+        for tree in dialogue_tree::load_all() {
+            let tree_data = tree.read();
+            if tree_data.0.when.iter().all(|cond| cond.holds(ctx, tgt)) {
+                responses.push((
+                    Response::from(Content::localized(&tree_data.0.topic)),
+                    dialogue_tree::run(tree, tgt, session).boxed(),
+                ));
+            }
+        }
+
         // General informational questions
         responses.push((
             Response::from(Content::localized("dialogue-question-site")),
@@ -151,6 +189,10 @@ pub fn general<S: State>(tgt: Actor, session: DialogueSession) -> impl Action<S>
             Response::from(Content::localized("dialogue-question-sentiment")),
             dialogue::sentiments(tgt, session).boxed(),
         ));
+        responses.push((
+            Response::from(Content::localized("dialogue-question-factions")),
+            dialogue::faction_relations(session).boxed(),
+        ));
         responses.push((
             Response::from(Content::localized("dialogue-question-directions")),
             dialogue::directions(session).boxed(),
@@ -174,6 +216,32 @@ pub fn general<S: State>(tgt: Actor, session: DialogueSession) -> impl Action<S>
     })
 }
 
+/// The response with which the slayer of a slay quest claims its reward.
+fn claim_slay_reward<S: State>(
+    quest_id: QuestId,
+    session: DialogueSession,
+    claim: Content,
+) -> (Response, Box<dyn Action<S>>) {
+    (
+        Response::from(claim),
+        session
+            .say_statement(Content::localized("npc-response-quest-slay-thanks"))
+            .then(now(move |ctx, _| {
+                if let Ok(deposit) = quest::resolve_take_deposit(ctx, quest_id, true) {
+                    session
+                        .say_statement_with_gift(
+                            Content::localized("npc-response-quest-reward"),
+                            deposit,
+                        )
+                        .boxed()
+                } else {
+                    finish().boxed()
+                }
+            }))
+            .boxed(),
+    )
+}
+
 fn about_site<S: State>(session: DialogueSession) -> impl Action<S> {
     now(move |ctx, _| {
         if let Some(site_name) = util::site_name(ctx, ctx.npc.current_site) {
@@ -248,24 +316,226 @@ fn about_self<S: State>(session: DialogueSession) -> impl Action<S> {
     })
 }
 
+/// The standing of the target with the NPC's home site or faction, see
+/// [`Reputations::seen_by`].
+fn standing(ctx: &NpcCtx, tgt: Actor) -> Standing {
+    match tgt {
+        Actor::Character(character) => {
+            Reputations::standing(ctx.state.data().reputations.seen_by(ctx.npc, character))
+        },
+        Actor::Npc(_) => Standing::Neutral,
+    }
+}
+
 fn sentiments<S: State>(tgt: Actor, session: DialogueSession) -> impl Action<S> {
     session.ask_question(Content::Plain("...".to_string()), [(
         Content::localized("dialogue-me"),
         now(move |ctx, _| {
-            if ctx.sentiments.toward(tgt).is(Sentiment::ALLY) {
+            let action = if ctx.sentiments.toward(tgt).is(Sentiment::ALLY) {
                 session.say_statement(Content::localized("npc-response-like_you"))
             } else if ctx.sentiments.toward(tgt).is(Sentiment::RIVAL) {
                 session.say_statement(Content::localized("npc-response-dislike_you"))
             } else {
                 session.say_statement(Content::localized("npc-response-ambivalent_you"))
+            };
+            // Also tell what others make of the target
+            let reputation = match standing(ctx, tgt) {
+                Standing::Hostile => Some("npc-response-reputation-hostile"),
+                Standing::Distrusted => Some("npc-response-reputation-distrusted"),
+                Standing::Neutral => None,
+                Standing::Respected => Some("npc-response-reputation-respected"),
+                Standing::Honoured => Some("npc-response-reputation-honoured"),
+            };
+            match reputation {
+                Some(key) => action
+                    .then(session.say_statement(Content::localized(key)))
+                    .boxed(),
+                None => action.boxed(),
             }
         }),
     )])
 }
 
+fn faction_relations<S: State>(session: DialogueSession) -> impl Action<S> {
+    now(move |ctx, _| {
+        let Some(faction) = ctx.npc.faction else {
+            return session
+                .say_statement(Content::localized("npc-response-faction-none"))
+                .boxed();
+        };
+
+        let data = ctx.state.data();
+        // Factions have no names, so refer to them by the nearest site they hold
+        let faction_site = |other: FactionId| {
+            data.sites
+                .iter()
+                .filter(|(_, site)| site.faction == Some(other))
+                .min_by_key(|(_, site)| {
+                    site.wpos.as_::<f32>().distance_squared(ctx.npc.wpos.xy()) as i64
+                })
+                .and_then(|(site_id, _)| util::site_name(ctx, site_id))
+        };
+        let mut statements = data
+            .factions
+            .relations_of(faction)
+            .filter_map(|(other, diplomacy)| {
+                let key = match diplomacy.relation {
+                    Relation::Allied => "npc-response-faction-allied",
+                    Relation::Rival => "npc-response-faction-rival",
+                    Relation::War => "npc-response-faction-war",
+                    Relation::Neutral => return None,
+                };
+                Some((diplomacy.relation, key, faction_site(other)?))
+            })
+            .collect::<Vec<_>>();
+        drop(data);
+        // Talk about the most pressing matters first
+        statements.sort_by_key(|(relation, _, _)| std::cmp::Reverse(*relation));
+
+        if statements.is_empty() {
+            session
+                .say_statement(Content::localized("npc-response-faction-peace"))
+                .boxed()
+        } else {
+            let mut action = finish().boxed();
+            for (_, key, site) in statements.into_iter().take(3) {
+                action = action
+                    .then(session.say_statement(Content::localized_with_args(key, [(
+                        "site",
+                        Content::Plain(site),
+                    )])))
+                    .boxed();
+            }
+            action
+        }
+    })
+}
+
+/// Ask the NPC for a quest, which they refuse to players that their site
+/// distrusts.
+fn ask_for_quest<S: State>(tgt: Actor, session: DialogueSession) -> impl Action<S> {
+    now(move |ctx, _| {
+        if standing(ctx, tgt) <= Standing::Distrusted {
+            session
+                .say_statement(Content::localized("npc-response-distrusted"))
+                .boxed()
+        } else {
+            quest::quest_request(session).boxed()
+        }
+    })
+}
+
+/// Confront a player that is wanted by the NPC's site, who may pay their bounty
+/// as a fine or give themselves up to serve a sentence in the site's jail.
+/// Players that refuse, that don't answer or that run away are fought.
+pub fn arrest<S: State>(criminal: CharacterId) -> impl Action<S> {
+    let tgt = Actor::Character(criminal);
+    goto_actor(tgt, 2.0)
+        .stop_if(timeout(30.0))
+        .and_then(move |caught: Option<()>| {
+            // Players that outrun the guard are resisting arrest
+            if caught.is_none() {
+                return just(|_, _| false).boxed();
+            }
+            do_dialogue(tgt, move |session| {
+                now(move |ctx, _| {
+                    let data = ctx.state.data();
+                    let Some((home, bounty)) = ctx.npc.home.and_then(|home| {
+                        data.bounties
+                            .get(home, criminal)
+                            .map(|bounty| (home, bounty.clone()))
+                    }) else {
+                        return just(|_, _| true).boxed();
+                    };
+                    let jail = data.sites.get(home).and_then(|site| {
+                        justice::jail(site, ctx.index.sites.get(site.world_site?))
+                    });
+                    let sentence_mins =
+                        (bounty.reward as f32 * Bounties::SENTENCE_PER_COIN / 60.0).ceil() as u64;
+
+                    let mut responses = vec![(
+                        Response {
+                            msg: Content::localized("dialogue-arrest-pay_fine")
+                                .with_arg("coins", bounty.reward as u64),
+                            given_item: Some((
+                                Arc::<ItemDef>::load_cloned("common.items.utility.coins").unwrap(),
+                                bounty.reward,
+                            )),
+                        },
+                        just(move |ctx, _| ctx.controller.pass_verdict(criminal, Verdict::Fined))
+                            .then(
+                                session
+                                    .say_statement(Content::localized("npc-response-arrest-fined")),
+                            )
+                            .map(|_, _| true)
+                            .boxed(),
+                    )];
+                    if let Some((_, jail_wpos)) = jail {
+                        responses.push((
+                            Response::from(Content::localized("dialogue-arrest-surrender")),
+                            just(move |ctx, _| {
+                                ctx.controller.pass_verdict(criminal, Verdict::Jailed)
+                            })
+                            .then(
+                                session.give_marker(
+                                    Marker::at(jail_wpos.as_())
+                                        .with_id(jail_wpos)
+                                        .with_label(Content::localized("hud-map-jail-label"))
+                                        .with_quest_flag(true),
+                                ),
+                            )
+                            .then(
+                                session.say_statement(
+                                    Content::localized("npc-response-arrest-jailed")
+                                        .with_arg("mins", sentence_mins),
+                                ),
+                            )
+                            .map(|_, _| true)
+                            .boxed(),
+                        ));
+                    }
+                    responses.push((
+                        Response::from(Content::localized("dialogue-arrest-resist")),
+                        just(|_, _| false).boxed(),
+                    ));
+
+                    session
+                        .ask_question(
+                            Content::localized("npc-question-arrest")
+                                .with_arg("coins", bounty.reward as u64),
+                            responses,
+                        )
+                        .boxed()
+                })
+            })
+            .boxed()
+        })
+        .and_then(move |complied: bool| {
+            just(move |ctx, _| {
+                if !complied {
+                    ctx.controller
+                        .say(tgt, Content::localized("npc-speech-arrest_resisted"));
+                    ctx.sentiments
+                        .toward_mut(tgt)
+                        .change_by(-1.0, Sentiment::ENEMY);
+                    ctx.controller.attack(tgt);
+                }
+            })
+        })
+}
+
 fn hire<S: State>(tgt: Actor, session: DialogueSession) -> impl Action<S> {
     now(move |ctx, _| {
-        if ctx.npc.job.is_none() && ctx.npc.rng(38792).random_bool(0.5) {
+        // Adventurers won't work for players that are distrusted where they live,
+        // but never turn down those that are respected
+        let standing = standing(ctx, tgt);
+        if standing <= Standing::Distrusted {
+            session
+                .say_statement(Content::localized("npc-response-distrusted"))
+                .boxed()
+        } else if ctx.npc.job.is_none()
+            && (standing >= Standing::Respected || ctx.npc.rng(38792).random_bool(0.5))
+        {
             let hire_level = match ctx.npc.profession() {
                 Some(Profession::Adventurer(l)) => l,
                 _ => 0,
@@ -0,0 +1,383 @@
+//! Data-driven dialogue trees for rtsim NPCs.
+//!
+//! Besides the hard-coded dialogue in [`super::dialogue`], NPCs can hold
+//! conversations described by [`DialogueTree`] assets living under
+//! `common.dialogue`. Every tree whose conditions hold for the NPC and the
+//! player talking to them is offered as a topic among the general questions.
+//!
+//! A tree is a set of named nodes. When a node is visited, the NPC says each of
+//! its lines, applies its effects and then, if the node has a question, asks it
+//! and continues with the node that the chosen response leads to. The
+//! conversation ends when a node has no question or the chosen response has
+//! nowhere to go. All text is given as i18n keys so that trees are localised
+//! like the rest of the dialogue.
+
+use super::*;
+use common::assets::{self, AssetHandle, Ron};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The asset directory that dialogue trees are loaded from.
+pub const DIALOGUE_TREES: &str = "common.dialogue";
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DialogueTree {
+    /// Conditions that all need to hold for the tree to be offered.
+    #[serde(default)]
+    pub when: Vec<DialogueCondition>,
+    /// The i18n key of the response that the player picks to start the tree.
+    pub topic: String,
+    /// The node that the conversation starts at.
+    pub start: String,
+    pub nodes: HashMap<String, DialogueNode>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DialogueNode {
+    /// The i18n keys of the lines said by the NPC, in order.
+    #[serde(default)]
+    pub say: Vec<String>,
+    /// Effects applied after the lines have been said.
+    #[serde(default)]
+    pub effects: Vec<DialogueEffect>,
+    #[serde(default)]
+    pub question: Option<DialogueQuestion>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DialogueQuestion {
+    /// The i18n key of the question asked by the NPC.
+    pub text: String,
+    pub responses: Vec<DialogueResponse>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DialogueResponse {
+    /// The i18n key of the response given by the player.
+    pub text: String,
+    /// Conditions that all need to hold for this response to be offered.
+    #[serde(default)]
+    pub when: Vec<DialogueCondition>,
+    /// The node that the conversation continues at, it ends if there is none.
+    #[serde(default)]
+    pub goto: Option<String>,
+}
+
+/// Professions without their associated data, see [`Profession`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum ProfessionKind {
+    Farmer,
+    Hunter,
+    Merchant,
+    Guard,
+    Adventurer,
+    Blacksmith,
+    Chef,
+    Alchemist,
+    Pirate,
+    Cultist,
+    Herbalist,
+    Captain,
+}
+
+impl From<&Profession> for ProfessionKind {
+    fn from(profession: &Profession) -> Self {
+        match profession {
+            Profession::Farmer => Self::Farmer,
+            Profession::Hunter => Self::Hunter,
+            Profession::Merchant => Self::Merchant,
+            Profession::Guard => Self::Guard,
+            Profession::Adventurer(_) => Self::Adventurer,
+            Profession::Blacksmith => Self::Blacksmith,
+            Profession::Chef => Self::Chef,
+            Profession::Alchemist => Self::Alchemist,
+            Profession::Pirate(_) => Self::Pirate,
+            Profession::Cultist => Self::Cultist,
+            Profession::Herbalist => Self::Herbalist,
+            Profession::Captain => Self::Captain,
+        }
+    }
+}
+
+impl ProfessionKind {
+    fn matches(&self, profession: &Profession) -> bool { *self == Self::from(profession) }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub enum DialogueCondition {
+    /// The NPC has one of these professions.
+    Profession(Vec<ProfessionKind>),
+    /// The NPC's sentiment toward the player is at least this positive, or at
+    /// least this negative for negative values, see [`Sentiment::is`].
+    Sentiment(f32),
+    /// It is currently one of these periods of the day.
+    DayPeriod(Vec<DayPeriod>),
+    /// The NPC and the player are both involved in an unresolved quest.
+    SharedQuest,
+    /// The inner condition does not hold.
+    Not(Box<DialogueCondition>),
+    /// At least one of the inner conditions holds.
+    Any(Vec<DialogueCondition>),
+}
+
+impl DialogueCondition {
+    pub fn holds(&self, ctx: &NpcCtx, tgt: Actor) -> bool {
+        match self {
+            Self::Profession(kinds) => ctx
+                .npc
+                .profession()
+                .is_some_and(|p| kinds.iter().any(|kind| kind.matches(&p))),
+            Self::Sentiment(value) => ctx.sentiments.toward(tgt).is(*value),
+            Self::DayPeriod(periods) => periods.contains(&DayPeriod::from(ctx.time_of_day.0)),
+            Self::SharedQuest => {
+                let data = ctx.state.data();
+                data.quests.related_to(ctx.npc_id).any(|quest_id| {
+                    data.quests
+                        .get(quest_id)
+                        .is_some_and(|quest| quest.get_related_actors().contains(&tgt))
+                })
+            },
+            Self::Not(cond) => !cond.holds(ctx, tgt),
+            Self::Any(conds) => conds.iter().any(|cond| cond.holds(ctx, tgt)),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Sentiment(value) if !(-1.0..=1.0).contains(value) => {
+                Err(format!("Sentiment {value} is outside of [-1, 1]"))
+            },
+            Self::Not(cond) => cond.validate(),
+            Self::Any(conds) => conds.iter().try_for_each(|cond| cond.validate()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub enum DialogueEffect {
+    /// Change the NPC's sentiment toward the player by this amount. Dialogue
+    /// alone can't turn an NPC further than [`Sentiment::FRIEND`] or
+    /// [`Sentiment::RIVAL`].
+    ChangeSentiment(f32),
+    /// Give the player an item from the NPC's inventory along with a line. The
+    /// effect is skipped if the NPC doesn't carry enough of the item.
+    GiveItem {
+        /// The i18n key of the line said with the gift.
+        say: String,
+        /// The asset specifier of the item.
+        item: String,
+        #[serde(default = "default_amount")]
+        amount: u32,
+    },
+    /// Offer the player one of the quests that the NPC has available, as if
+    /// they had asked for work.
+    OfferQuest,
+}
+
+fn default_amount() -> u32 { 1 }
+
+impl DialogueEffect {
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::ChangeSentiment(change) if !(-1.0..=1.0).contains(change) => {
+                Err(format!("Sentiment change {change} is outside of [-1, 1]"))
+            },
+            Self::GiveItem { item, amount, .. } => {
+                if *amount == 0 {
+                    Err(format!("Gift of {item} has an amount of 0"))
+                } else if let Err(e) = Arc::<ItemDef>::load(item) {
+                    Err(format!("Gift of {item} is not a valid item: {e:?}"))
+                } else {
+                    Ok(())
+                }
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+impl DialogueTree {
+    /// Check that the tree is well-formed, e.g. that it only refers to nodes
+    /// that exist.
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.nodes.contains_key(&self.start) {
+            return Err(format!("Start node '{}' does not exist", self.start));
+        }
+        for cond in &self.when {
+            cond.validate()?;
+        }
+        for (name, node) in &self.nodes {
+            for effect in &node.effects {
+                effect
+                    .validate()
+                    .map_err(|e| format!("Node '{name}': {e}"))?;
+            }
+            let Some(question) = &node.question else {
+                continue;
+            };
+            if question.responses.is_empty() {
+                return Err(format!("Node '{name}' has a question without responses"));
+            }
+            for response in &question.responses {
+                for cond in &response.when {
+                    cond.validate().map_err(|e| format!("Node '{name}': {e}"))?;
+                }
+                if let Some(goto) = &response.goto
+                    && !self.nodes.contains_key(goto)
+                {
+                    return Err(format!(
+                        "Node '{name}' has a response leading to '{goto}', which does not exist"
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Load all dialogue trees. Trees are cached by the asset system and only
+/// handles to them are returned, so this is cheap enough to do whenever a
+/// conversation starts.
+pub fn load_all() -> Vec<AssetHandle<Ron<DialogueTree>>> {
+    let trees = match assets::load_rec_dir::<Ron<DialogueTree>>(DIALOGUE_TREES) {
+        Ok(trees) => trees,
+        Err(e) => {
+            tracing::warn!(?e, "Failed to load dialogue trees");
+            return Vec::new();
+        },
+    };
+    trees
+        .read()
+        .ids()
+        .filter_map(|id| match Ron::<DialogueTree>::load(id) {
+            Ok(tree) => Some(tree),
+            Err(e) => {
+                tracing::warn!(?e, ?id, "Failed to load dialogue tree");
+                None
+            },
+        })
+        .collect()
+}
+
+/// Hold the conversation described by the tree, starting at its start node.
+pub fn run<S: State>(
+    tree: AssetHandle<Ron<DialogueTree>>,
+    tgt: Actor,
+    session: DialogueSession,
+) -> impl Action<S> {
+    let start = tree.read().0.start.clone();
+    visit(tree, start, tgt, session)
+}
+
+fn visit<S: State>(
+    tree: AssetHandle<Ron<DialogueTree>>,
+    node_name: String,
+    tgt: Actor,
+    session: DialogueSession,
+) -> impl Action<S> {
+    now(move |ctx, _| {
+        let tree_data = tree.read();
+        let Some(node) = tree_data.0.nodes.get(&node_name) else {
+            return finish().boxed();
+        };
+
+        let mut action = finish().boxed();
+        for line in &node.say {
+            action = action
+                .then(session.say_statement(Content::localized(line)))
+                .boxed();
+        }
+        for effect in &node.effects {
+            action = action
+                .then(apply_effect(effect.clone(), tgt, session))
+                .boxed();
+        }
+        if let Some(question) = &node.question {
+            let responses = question
+                .responses
+                .iter()
+                .filter(|response| response.when.iter().all(|cond| cond.holds(ctx, tgt)))
+                .map(|response| {
+                    let next = match &response.goto {
+                        Some(goto) => visit(tree, goto.clone(), tgt, session).boxed(),
+                        None => finish().boxed(),
+                    };
+                    (Response::from(Content::localized(&response.text)), next)
+                })
+                .collect::<Vec<_>>();
+            action = action
+                .then(session.ask_question(Content::localized(&question.text), responses))
+                .boxed();
+        }
+        action
+    })
+}
+
+fn apply_effect<S: State>(
+    effect: DialogueEffect,
+    tgt: Actor,
+    session: DialogueSession,
+) -> impl Action<S> {
+    now(move |ctx, _| match effect {
+        DialogueEffect::ChangeSentiment(change) => {
+            let cap = if change > 0.0 {
+                Sentiment::FRIEND
+            } else {
+                Sentiment::RIVAL
+            };
+            ctx.sentiments.toward_mut(tgt).change_by(change, cap);
+            finish().boxed()
+        },
+        DialogueEffect::GiveItem { say, item, amount } => {
+            let Ok(item) = Arc::<ItemDef>::load_cloned(&item) else {
+                return finish().boxed();
+            };
+            // Items given in dialogue are taken from the NPC's inventory
+            let has_item = ctx
+                .system_data
+                .id_maps
+                .rtsim_entity(ctx.npc_id)
+                .and_then(|npc_entity| {
+                    ctx.system_data
+                        .inventories
+                        .lock()
+                        .unwrap()
+                        .get(npc_entity)
+                        .map(|inv| inv.item_count(&item) >= amount as u64)
+                })
+                .unwrap_or(false);
+            if has_item {
+                session
+                    .say_statement_with_gift(Content::localized(say), Some((item, amount)))
+                    .boxed()
+            } else {
+                finish().boxed()
+            }
+        },
+        DialogueEffect::OfferQuest => quest::quest_request(session).boxed(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_all_dialogue_trees() {
+        let trees = assets::load_rec_dir::<Ron<DialogueTree>>(DIALOGUE_TREES)
+            .expect("Failed to load dialogue trees");
+        for id in trees.read().ids() {
+            let tree = Ron::<DialogueTree>::load_cloned(id)
+                .unwrap_or_else(|e| panic!("{id}: {e:?}"))
+                .into_inner();
+            if let Err(e) = tree.validate() {
+                panic!("{id}: {e}");
+            }
+        }
+    }
+}
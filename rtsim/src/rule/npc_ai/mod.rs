@@ -26,12 +26,19 @@
 mod airship_ai;
 #[cfg(feature = "airship_log")]
 mod airship_logger;
+pub mod caravan;
 pub mod dialogue;
+pub mod dialogue_tree;
 pub mod movement;
 pub mod quest;
+pub mod schedule;
 pub mod util;
 
-use std::{collections::VecDeque, hash::BuildHasherDefault, sync::Arc};
+use std::{
+    collections::VecDeque,
+    hash::BuildHasherDefault,
+    sync::{Arc, Mutex},
+};
 
 use crate::{
     RtState, Rule, RuleError,
@@ -41,15 +48,18 @@ use crate::{
         seq, until,
     },
     data::{
-        ReportKind, Sentiment, Sites,
+        Bounties, FactionId, Relation, ReportKind, Reputations, Sentiment, Sites,
+        bounty::Verdict,
         npc::{Brain, DialogueSession, Job, PathData, SimulationMode},
         quest::{Quest, QuestKind},
     },
     event::OnTick,
+    rule::{household, justice},
 };
 use common::{
     assets::AssetExt,
     astar::{Astar, PathResult},
+    character::CharacterId,
     comp::{
         self, Content, bird_large,
         compass::{Direction, Distance},
@@ -70,6 +80,7 @@ use common::{
 };
 use core::ops::ControlFlow;
 use fxhash::FxHasher64;
+use hashbrown::HashMap;
 use itertools::{Either, Itertools};
 use rand::{prelude::*, seq::IndexedRandom};
 use rand_chacha::ChaChaRng;
@@ -78,6 +89,7 @@ use vek::*;
 use world::{
     IndexRef, World,
     civ::{self, Track},
+    pathfinding::Searcher,
     site::{
         self, PlotKind, Site as WorldSite, SiteKind, TileKind,
         plot::{PlotKindMeta, tavern},
@@ -89,6 +101,7 @@ use self::{
     movement::{
         follow_actor, goto, goto_2d, goto_2d_flying, goto_actor, travel_to_point, travel_to_site,
     },
+    schedule::Activity,
     util::do_dialogue,
 };
 
@@ -119,6 +132,7 @@ impl Rule for NpcAi {
             if last_ticks.len() >= SIMULATED_TICK_SKIP as usize {
                 last_ticks.pop_back();
             }
+            ctx.state.resource::<movement::Routes>().new_tick();
             // Temporarily take the brains of NPCs out of their heads to appease the borrow
             // checker
             let mut npc_data = {
@@ -390,6 +404,11 @@ fn pirate(is_leader: bool) -> impl Action<DefaultState> {
                         // Don't go further than 10km
                         site.wpos.as_::<f32>().distance_squared(ctx.npc.wpos.xy())
                             < 10000.0f32.powi(2)
+                            // Don't raid our allies
+                            && !data
+                                .factions
+                                .relation_between(Some(faction), site.faction)
+                                .is_friendly()
                     })
                 })
                 .choose(&mut ctx.rng)
@@ -745,13 +764,17 @@ fn hunt_animals<S: State>() -> impl Action<S> {
 }
 
 fn find_forest(ctx: &mut NpcCtx) -> Option<Vec2<f32>> {
-    let chunk_pos = ctx.npc.wpos.xy().as_().wpos_to_cpos();
+    forest_near(ctx.world, ctx.npc.wpos.xy(), &mut ctx.rng)
+}
+
+fn forest_near(world: &World, wpos: Vec2<f32>, rng: &mut impl Rng) -> Option<Vec2<f32>> {
+    let chunk_pos = wpos.as_().wpos_to_cpos();
     Spiral2d::new()
-        .skip(ctx.rng.random_range(1..=64))
+        .skip(rng.random_range(1..=64))
         .take(24)
         .map(|rpos| chunk_pos + rpos)
         .find(|cpos| {
-            ctx.world
+            world
                 .sim()
                 .get(*cpos)
                 .is_some_and(|c| c.tree_density > 0.75 && c.surface_veg > 0.5)
@@ -784,6 +807,167 @@ fn choose_plaza(ctx: &mut NpcCtx, site: SiteId) -> Option<Vec2<f32>> {
 
 const WALKING_SPEED: f32 = 0.35;
 
+fn sell_wares<S: State>() -> impl Action<S> {
+    just(|ctx, _| {
+        // Try to direct our speech at nearby actors, if there are any
+        let (target, phrase) = if ctx.rng.random_bool(0.3)
+            && let Some(other) = ctx
+                .state
+                .data()
+                .npcs
+                .nearby(Some(ctx.npc_id), ctx.npc.wpos, 8.0)
+                .choose(&mut ctx.rng)
+        {
+            (Some(other), "npc-speech-merchant_sell_directed")
+        } else {
+            // Otherwise, resort to generic expressions
+            (None, "npc-speech-merchant_sell_undirected")
+        };
+
+        ctx.controller.say(target, Content::localized(phrase));
+    })
+    .then(idle().repeat().stop_if(timeout(8.0)))
+    .map(|_, _| ())
+}
+
+fn cook_food<S: State>(
+    tavern: &tavern::Tavern,
+    rng: &mut ChaChaRng,
+) -> Option<impl Action<S> + use<S>> {
+    let (bar_pos, room_center) = tavern
+        .rooms
+        .values()
+        .flat_map(|room| {
+            room.details.iter().filter_map(|detail| {
+                match_some!(detail,
+                    tavern::Detail::Bar { aabr } => {
+                        let center = aabr.center();
+                        (center.with_z(room.bounds.min.z), room.bounds.center().xy())
+                    },
+                )
+            })
+        })
+        .choose(rng)?;
+
+    let face_dir = Dir::from_unnormalized((room_center - bar_pos).as_::<f32>().with_z(0.0))
+        .unwrap_or_else(|| Dir::random_2d(rng));
+
+    Some(
+        travel_to_point(tavern.door_wpos.xy().as_(), 0.5)
+            .then(goto(bar_pos.as_() + Vec2::new(0.5, 0.5), WALKING_SPEED, 2.0))
+            // TODO: Just dance there for now, in the future do other stuff.
+            .then(
+                just(move |ctx, _| ctx.controller.do_dance(Some(face_dir)))
+                    .repeat()
+                    .stop_if(timeout(60.0)),
+            )
+            .debug(|| "cook food")
+            .map(|_, _| ()),
+    )
+}
+
+/// Spend the time of a schedule entry on its activity, see [`schedule`].
+fn follow_schedule(site: SiteId, activity: Activity) -> impl Action<DefaultState> {
+    now(move |ctx, _| {
+        let data = ctx.state.data();
+        let world_site = data
+            .sites
+            .get(site)
+            .and_then(|site| Some(ctx.index.sites.get(site.world_site?)));
+        let Some(wpos) = schedule::place(ctx.world, ctx.index, &data, ctx.npc, site, activity)
+        else {
+            return finish().boxed();
+        };
+
+        // Chefs work at the bar of their tavern
+        if activity == Activity::Work
+            && matches!(ctx.npc.profession(), Some(Profession::Chef))
+            && let Some(ws) = world_site
+            && let Some(plot) = schedule::tavern(ws, ctx.npc)
+            && let PlotKind::Tavern(tavern) = ws.plots[plot].kind()
+            && let Some(action) = cook_food(tavern, &mut ctx.rng)
+        {
+            return action.repeat().map(|_, _| ()).boxed();
+        }
+
+        match activity {
+            Activity::Sleep => just(|ctx, _| {
+                if DayPeriod::from(ctx.time_of_day.0).is_dark() {
+                    ctx.controller
+                        .say(None, Content::localized("npc-speech-night_time"))
+                }
+            })
+            .then(travel_to_point(wpos, 0.65))
+            .then(idle().repeat())
+            .debug(|| "sleep at home")
+            .map(|_, _| ())
+            .boxed(),
+            Activity::Work => match ctx.npc.profession() {
+                Some(Profession::Farmer | Profession::Herbalist) => travel_to_point(wpos, 0.5)
+                    .then(gather_ingredients().repeat())
+                    .debug(|| "gather ingredients at work")
+                    .map(|_, _| ())
+                    .boxed(),
+                Some(Profession::Hunter) => just(|ctx, _| {
+                    ctx.controller
+                        .say(None, Content::localized("npc-speech-start_hunting"))
+                })
+                .then(travel_to_point(wpos, 0.75))
+                .then(hunt_animals().repeat())
+                .debug(|| "hunt at work")
+                .map(|_, _| ())
+                .boxed(),
+                Some(Profession::Merchant) => travel_to_point(wpos, 0.5)
+                    .then(sell_wares().repeat())
+                    .debug(|| "sell wares at stall")
+                    .map(|_, _| ())
+                    .boxed(),
+                // Patrol around the plaza that we keep watch over
+                Some(Profession::Guard) => now(move |ctx, _| {
+                    let offset = Vec2::new(
+                        ctx.rng.random_range(-16.0..16.0),
+                        ctx.rng.random_range(-16.0..16.0),
+                    );
+                    travel_to_point(wpos + offset, 0.4).then(idle().repeat().stop_if(timeout(10.0)))
+                })
+                .repeat()
+                .interrupt_with(move |ctx, _| {
+                    if ctx.rng.random_bool(0.0003) {
+                        Some(just(move |ctx, _| {
+                            ctx.controller
+                                .say(None, Content::localized("npc-speech-guard_thought"))
+                        }))
+                    } else {
+                        None
+                    }
+                })
+                .debug(|| "keep watch")
+                .map(|_, _| ())
+                .boxed(),
+                _ => travel_to_point(wpos, 0.5)
+                    .then(idle().repeat())
+                    .debug(|| "work at workshop")
+                    .map(|_, _| ())
+                    .boxed(),
+            },
+            Activity::Eat => match world_site.and_then(|ws| schedule::tavern(ws, ctx.npc)) {
+                Some(tavern) => go_to_tavern(site, tavern).boxed(),
+                None => finish().boxed(),
+            },
+            Activity::Socialize => travel_to_point(wpos, 0.5)
+                .debug(|| "walk to plaza")
+                .then(
+                    socialize()
+                        .repeat()
+                        .map_state(|state: &mut DefaultState| &mut state.socialize_timer),
+                )
+                .debug(|| "socialize at plaza")
+                .map(|_, _| ())
+                .boxed(),
+        }
+    })
+}
+
 fn villager(visiting_site: SiteId) -> impl Action<DefaultState> {
     choose(move |ctx, state: &mut DefaultState| {
         // Consider moving home if the home site gets too full
@@ -793,8 +977,10 @@ fn villager(visiting_site: SiteId) -> impl Action<DefaultState> {
             && let Some(home_pop_ratio) = ctx.state.data().sites.get(home)
                 .and_then(|site| Some((site, ctx.index.sites.get(site.world_site?))))
                 .and_then(|(site, world_site)| { let houses = world_site.filter_plots(|p| matches!(p.meta(), Some(PlotKindMeta::House { .. }))).count(); if houses == 0 { return None } Some(site.population.len() as f32 / houses as f32) } )
-                // Only consider moving if the population is more than 1.5x the number of homes
-                .filter(|pop_ratio| *pop_ratio > 1.5)
+                // Only consider moving if the population is more than 1.5x the number of homes, or if
+                // there's no room for us in any house
+                .filter(|pop_ratio| *pop_ratio > 1.5
+                    || (ctx.npc.house.is_none() && ctx.npc.born.is_some() && !household::is_child(ctx.npc, ctx.time_of_day)))
             && let Some(new_home) = ctx
                 .state
                 .data()
@@ -814,8 +1000,8 @@ fn villager(visiting_site: SiteId) -> impl Action<DefaultState> {
                 })
                 // Only select sites that are less densely populated than our own
                 .filter(|(_, site, houses)| (site.population.len() as f32 / *houses as f32) < home_pop_ratio)
-                // Find the closest of the candidate sites
-                .min_by_key(|(_, site, _)| site.wpos.as_().distance(ctx.npc.wpos.xy()) as i32)
+                // Find the closest of the candidate sites, favouring those that are prospering
+                .min_by_key(|(_, site, _)| (site.wpos.as_().distance(ctx.npc.wpos.xy()) / (0.5 + site.prosperity)) as i32)
                 .map(|(site_id, _, _)| site_id)
         {
             let site_name = util::site_name(ctx, new_home);
@@ -834,6 +1020,39 @@ fn villager(visiting_site: SiteId) -> impl Action<DefaultState> {
         let is_free_time = is_weekend || is_evening;
 
         let is_raining = ctx.system_data.weather_grid.is_raining(ctx.npc.wpos.xy());
+        let is_guard = matches!(ctx.npc.profession(), Some(Profession::Guard));
+
+        // Residents follow their daily schedule, but seek shelter from the rain
+        // rather than working or socialising outdoors. Travelling merchants set
+        // up their stall in whichever site they stop at.
+        if let Some((activity, until)) = schedule::current(ctx.npc, ctx.time_of_day)
+            && (ctx.npc.home == Some(visiting_site)
+                || (activity == Activity::Work
+                    && matches!(ctx.npc.profession(), Some(Profession::Merchant))))
+            // Villagers with nowhere to go for the activity do as they please
+            && schedule::place(
+                ctx.world,
+                ctx.index,
+                &ctx.state.data(),
+                ctx.npc,
+                visiting_site,
+                activity,
+            )
+            .is_some()
+            && (matches!(activity, Activity::Sleep | Activity::Eat) || !is_raining || is_guard)
+        {
+            return important(
+                follow_schedule(visiting_site, activity)
+                    .stop_if(move |ctx: &mut NpcCtx| {
+                        ctx.time_of_day.0 >= until.0
+                            || (!matches!(activity, Activity::Sleep | Activity::Eat)
+                                && !matches!(ctx.npc.profession(), Some(Profession::Guard))
+                                && ctx.system_data.weather_grid.is_raining(ctx.npc.wpos.xy()))
+                    })
+                    .map(|_, _| ())
+                    .debug(move || format!("scheduled {activity:?}")),
+            );
+        }
 
         // Go to a house if it's dark
         if day_period.is_dark()
@@ -1042,51 +1261,18 @@ fn villager(visiting_site: SiteId) -> impl Action<DefaultState> {
         } else if matches!(ctx.npc.profession(), Some(Profession::Merchant)) && ctx.rng.random_bool(0.8)
         {
             return casual(
-                just(|ctx, _| {
-                    // Try to direct our speech at nearby actors, if there are any
-                    let (target, phrase) = if ctx.rng.random_bool(0.3) && let Some(other) = ctx
-                        .state
-                        .data()
-                        .npcs
-                        .nearby(Some(ctx.npc_id), ctx.npc.wpos, 8.0)
-                        .choose(&mut ctx.rng)
-                    {
-                        (Some(other), "npc-speech-merchant_sell_directed")
-                    } else {
-                        // Otherwise, resort to generic expressions
-                        (None, "npc-speech-merchant_sell_undirected")
-                    };
-
-                    ctx.controller.say(target, Content::localized(phrase));
-                })
-                .then(idle().repeat().stop_if(timeout(8.0)))
-                .repeat()
-                .stop_if(timeout(60.0))
-                .debug(|| "sell wares")
-                .map(|_, _| ()),
+                sell_wares()
+                    .repeat()
+                    .stop_if(timeout(60.0))
+                    .debug(|| "sell wares")
+                    .map(|_, _| ()),
             );
         } else if matches!(ctx.npc.profession(), Some(Profession::Chef))
             && ctx.rng.random_bool(0.8)
             && let Some(ws_id) = ctx.state.data().sites[visiting_site].world_site
             && let Some(tavern) = ctx.index.sites.get(ws_id).plots().filter_map(|p| match_some!(p.kind(), PlotKind::Tavern(a) => a)).choose(&mut ctx.rng)
-            && let Some((bar_pos, room_center)) = tavern.rooms.values().flat_map(|room|
-                room.details.iter().filter_map(|detail| match_some!(detail,
-                    tavern::Detail::Bar { aabr } => {
-                        let center = aabr.center();
-                        (center.with_z(room.bounds.min.z), room.bounds.center().xy())
-                    },
-                ))
-            ).choose(&mut ctx.rng) {
-
-            let face_dir = Dir::from_unnormalized((room_center - bar_pos).as_::<f32>().with_z(0.0)).unwrap_or_else(|| Dir::random_2d(&mut ctx.rng));
-
-            return casual(
-                travel_to_point(tavern.door_wpos.xy().as_(), 0.5)
-                    .then(goto(bar_pos.as_() + Vec2::new(0.5, 0.5), WALKING_SPEED, 2.0))
-                    // TODO: Just dance there for now, in the future do other stuff.
-                    .then(just(move |ctx, _| ctx.controller.do_dance(Some(face_dir))).repeat().stop_if(timeout(60.0)))
-                    .debug(|| "cook food").map(|_, _| ())
-            )
+            && let Some(action) = cook_food(tavern, &mut ctx.rng) {
+            return casual(action)
         }
 
         // If nothing else needs doing, walk between plazas and socialize
@@ -1393,7 +1579,19 @@ fn check_inbox<S: State>(ctx: &mut NpcCtx) -> Option<impl Action<S> + use<S>> {
                                 false
                             };
 
+                            // Members of factions we're at war with are enemies too
+                            let is_victim_at_war = if let Actor::Npc(victim) = actor {
+                                data.npcs.get(victim).is_some_and(|victim| {
+                                    data.factions
+                                        .relation_between(ctx.npc.faction, victim.faction)
+                                        == Relation::War
+                                })
+                            } else {
+                                false
+                            };
+
                             let is_victim_enemy = is_victim_inherent_enemy
+                                || is_victim_at_war
                                 || ctx.sentiments.toward(actor).is(Sentiment::ENEMY);
 
                             if can_damage_killer {
@@ -1499,18 +1697,79 @@ fn check_for_enemies<S: State>(ctx: &mut NpcCtx) -> Option<impl Action<S> + use<
     // implementing this means accounting for changes in sentiment (that could
     // suddenly make a nearby actor an enemy) as well as variable NPC tick
     // rates!
-    ctx.state
-        .data()
-        .npcs
+    let data = ctx.state.data();
+
+    // Guards also fight members of factions at war with their own, those that
+    // their faction or its allies hold as enemies, and players with a hostile
+    // reputation.
+    let is_guard = matches!(ctx.npc.profession(), Some(Profession::Guard));
+    let (enemy_factions, friendly_factions) = match ctx.npc.faction {
+        Some(faction) if is_guard => {
+            let relations = data.factions.relations_of(faction);
+            let (friendly, enemy): (Vec<_>, Vec<_>) = relations
+                .filter(|(_, d)| matches!(d.relation, Relation::War | Relation::Allied))
+                .partition(|(_, d)| d.relation.is_friendly());
+            (
+                enemy.into_iter().map(|(f, _)| f).collect::<Vec<_>>(),
+                friendly
+                    .into_iter()
+                    .map(|(f, _)| f)
+                    .chain([faction])
+                    .collect::<Vec<_>>(),
+            )
+        },
+        _ => (Vec::new(), Vec::new()),
+    };
+    // Bandits ambush passing caravans
+    let is_bandit = matches!(ctx.npc.profession(), Some(Profession::Pirate(_)));
+
+    data.npcs
         .nearby(Some(ctx.npc_id), ctx.npc.wpos, 24.0)
-        .find(|actor| ctx.sentiments.toward(*actor).is(Sentiment::ENEMY))
+        .find(|actor| {
+            let other = actor.npc().and_then(|npc| data.npcs.get(npc));
+            ctx.sentiments.toward(*actor).is(Sentiment::ENEMY)
+                || other
+                    .and_then(|npc| npc.faction)
+                    .is_some_and(|f| enemy_factions.contains(&f))
+                || (is_bandit && other.is_some_and(|npc| matches!(npc.job, Some(Job::Caravan(_)))))
+                || (is_guard
+                    && matches!(actor, Actor::Character(character)
+                        if data.reputations.seen_by(ctx.npc, *character) <= Reputations::HOSTILE))
+                || friendly_factions.iter().any(|f| {
+                    data.factions
+                        .get(*f)
+                        .is_some_and(|f| f.sentiments.toward(*actor).is(Sentiment::ENEMY))
+                })
+        })
         .map(|enemy| just(move |ctx, _| ctx.controller.attack(enemy)))
 }
 
+/// Guards go after players that are wanted by their site to arrest them.
+fn check_for_criminals<S: State>(ctx: &mut NpcCtx) -> Option<impl Action<S> + use<S>> {
+    const PURSUIT_RADIUS: f32 = 48.0;
+
+    if !matches!(ctx.npc.profession(), Some(Profession::Guard)) {
+        return None;
+    }
+    let home = ctx.npc.home?;
+    let data = ctx.state.data();
+
+    data.npcs
+        .nearby(Some(ctx.npc_id), ctx.npc.wpos, PURSUIT_RADIUS)
+        .find_map(|actor| match actor {
+            Actor::Character(character) if data.bounties.is_wanted(home, character) => {
+                Some(character)
+            },
+            _ => None,
+        })
+        .map(dialogue::arrest)
+}
+
 fn react_to_events<S: State>(ctx: &mut NpcCtx, _: &mut S) -> Option<impl Action<S> + use<S>> {
     check_inbox::<S>(ctx)
         .map(Action::boxed)
         .or_else(|| check_for_enemies(ctx).map(Action::boxed))
+        .or_else(|| check_for_criminals(ctx).map(Action::boxed))
         .or_else(|| quest::check_for_timeouts(ctx).map(Action::boxed))
 }
 
@@ -1529,7 +1788,13 @@ fn humanoid() -> impl Action<DefaultState> {
                         comp::Body::Ship(
                             comp::ship::Body::SailBoat | comp::ship::Body::Galleon,
                         ) => important(captain()),
-                        _ => casual(idle()),
+                        _ => match &ctx.npc.job {
+                            // Pack animals are steered by the caravan leader
+                            Some(Job::Caravan(caravan)) => important(
+                                caravan::lead(caravan.clone()).interrupt_with(react_to_events),
+                            ),
+                            _ => casual(idle()),
+                        },
                     }
                 } else {
                     casual(finish())
@@ -1564,6 +1829,7 @@ fn humanoid() -> impl Action<DefaultState> {
                             _ => just(|ctx, _| ctx.controller.end_quest()).boxed(),
                         }
                     },
+                    Job::Caravan(caravan) => caravan::lead(caravan.clone()).boxed(),
                 }
                 .interrupt_with(react_to_events),
             )
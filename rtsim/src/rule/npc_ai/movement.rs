@@ -428,6 +428,66 @@ pub fn travel_to_point<S: State>(wpos: Vec2<f32>, speed_factor: f32) -> impl Act
     .debug(move || format!("travel to point {}, {}", wpos.x, wpos.y))
 }
 
+/// Routes across the land found for NPCs travelling to sites that aren't
+/// connected to their current site by tracks, see [`travel_to_site`].
+///
+/// Searching for a route is expensive, so routes are shared between NPCs that
+/// set off from the same chunk towards the same site, and only a few searches
+/// are run each tick. NPCs that can't search for a route walk towards their
+/// destination in a straight line instead.
+#[derive(Default)]
+pub struct Routes(Mutex<RoutesInner>);
+
+#[derive(Default)]
+struct RoutesInner {
+    routes: HashMap<(Vec2<i32>, SiteId), Option<Arc<[Vec2<i32>]>>>,
+    searches_this_tick: usize,
+}
+
+impl Routes {
+    const MAX_CACHED_ROUTES: usize = 4096;
+    const MAX_SEARCHES_PER_TICK: usize = 4;
+
+    /// Allow new searches to run, called at the start of each tick.
+    pub fn new_tick(&self) {
+        if let Ok(mut inner) = self.0.lock() {
+            inner.searches_this_tick = 0;
+        }
+    }
+
+    /// The chunks along a route from the given chunk to a site.
+    fn find(
+        &self,
+        world: &World,
+        from: Vec2<i32>,
+        site_id: SiteId,
+        site_wpos: Vec2<i32>,
+    ) -> Option<Arc<[Vec2<i32>]>> {
+        {
+            let mut inner = self.0.lock().ok()?;
+            if let Some(route) = inner.routes.get(&(from, site_id)) {
+                return route.clone();
+            }
+            if inner.searches_this_tick >= Self::MAX_SEARCHES_PER_TICK {
+                return None;
+            }
+            inner.searches_this_tick += 1;
+        }
+
+        // Don't hold the lock during the search, other NPCs may still use cached routes
+        let route = Searcher::new(world.sim())
+            .search(from, site_wpos.wpos_to_cpos())
+            .map(|route| route.into_iter().collect::<Arc<[_]>>());
+
+        let mut inner = self.0.lock().ok()?;
+        if inner.routes.len() >= Self::MAX_CACHED_ROUTES {
+            inner.routes.clear();
+        }
+        inner.routes.insert((from, site_id), route.clone());
+        route
+    }
+}
+
 /// Try to travel to a site. Where practical, paths will be taken.
 pub fn travel_to_site<S: State>(tgt_site: SiteId, speed_factor: f32) -> impl Action<S> {
     now(move |ctx, _| {
@@ -471,6 +531,25 @@ pub fn travel_to_site<S: State>(tgt_site: SiteId, speed_factor: f32) -> impl Act
                 }
             }, speed_factor)
                 .boxed()
+        } else if let Some(site) = sites.get(tgt_site)
+            && let Some(route) = ctx.state.resource::<Routes>().find(
+                ctx.world,
+                ctx.npc.wpos.xy().as_::<i32>().wpos_to_cpos(),
+                tgt_site,
+                site.wpos,
+            )
+        {
+            // Otherwise, find a route across the land that makes use of nearby paths
+            let mut nodes = (0..route.len()).map(move |i| route[i]);
+            traverse_points(move |ctx| {
+                let node_chunk_wpos = TerrainChunkSize::center_wpos(nodes.next()?);
+                Some(ctx.world.sim()
+                    .get_nearest_path(node_chunk_wpos)
+                    .filter(|(dist, ..)| *dist < TerrainChunkSize::RECT_SIZE.x as f32)
+                    .map_or(node_chunk_wpos, |(_, wpos, _, _)| wpos.as_())
+                    .as_::<f32>())
+            }, speed_factor)
+                .boxed()
         } else if let Some(site) = sites.get(tgt_site) {
             // If all else fails, just walk toward the target site in a straight line
             travel_to_point(site.wpos.map(|e| e as f32 + 0.5), speed_factor).debug(|| "travel to point fallback").boxed()
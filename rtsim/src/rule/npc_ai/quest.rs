@@ -1,5 +1,8 @@
 use super::*;
-use common::comp::{Item, item::ItemBase};
+use common::{
+    comp::{Item, item::ItemBase},
+    trade::Good,
+};
 
 /// Perform a deposit check, ensuring that the NPC has the given item and amount
 /// in their inventory. If they do, the provided action is performed to
@@ -100,6 +103,26 @@ pub fn resolve_take_deposit(
     }
 }
 
+/// Resolve a quest as failed, returning its deposit to the stock of the NPC's
+/// home rather than to their inventory. Used for quests that were paid for by
+/// the site, such as bounties.
+pub fn resolve_refund_deposit(ctx: &mut NpcCtx, quest_id: QuestId) -> bool {
+    if let Some(outcome) = ctx
+        .state
+        .data()
+        .quests
+        .get(quest_id)
+        .and_then(|q| q.resolve(ctx.npc_id, false))
+    {
+        if let Some((ItemResource::Coin, amount)) = outcome.deposit {
+            ctx.controller.change_home_goods(Good::Coin, amount);
+        }
+        true
+    } else {
+        false
+    }
+}
+
 /// Register and create a new quest, producing its ID.
 ///
 /// This is an action because quest creation can only happen at the end of an
@@ -120,6 +143,78 @@ pub fn quest_request<S: State>(session: DialogueSession) -> impl Action<S> {
 
         // Escort quest.
         const ESCORT_REWARD_ITEM: ItemResource = ItemResource::Coin;
+
+        // Caravans on the road pay to be escorted to their destination
+        if let Some(dst_site_id) = ctx.npc.job.as_ref().and_then(|job| match job {
+            Job::Caravan(caravan) if caravan.escort.is_none() => Some(caravan.to),
+            _ => None,
+        }) && let Some(dst_site) = ctx.state.data().sites.get(dst_site_id)
+            && let dist = dst_site.wpos.as_().distance(ctx.npc.wpos.xy())
+            && let escort_reward_amount = dist / 25.0
+            && let Some(dst_site_name) = util::site_name(ctx, dst_site_id)
+            && let time_limit = 1.0 + dist as f64 / 80.0
+            && let Some(accept_quest) = create_deposit(
+                ctx,
+                ESCORT_REWARD_ITEM,
+                escort_reward_amount,
+                session.ask_yes_no_question(
+                    Content::localized("npc-response-quest-caravan-ask")
+                        .with_arg("dst", dst_site_name.clone())
+                        .with_arg("coins", escort_reward_amount as u64)
+                        .with_arg("mins", time_limit as u64),
+                ),
+            )
+        {
+            let dst_wpos = dst_site.wpos.as_();
+            quests.push(
+                accept_quest
+                    .and_then(move |yes| {
+                        now(move |ctx, _| {
+                            if yes {
+                                let quest =
+                                    Quest::escort(ctx.npc_id.into(), session.target, dst_site_id)
+                                        .with_deposit(ESCORT_REWARD_ITEM, escort_reward_amount)
+                                        .with_timeout(ctx.time.add_minutes(time_limit));
+                                create_quest(quest.clone())
+                                    .and_then(move |quest_id| {
+                                        now(move |ctx, _| {
+                                            ctx.controller.set_caravan_escort(quest_id);
+                                            session.give_marker(
+                                                Marker::at(dst_wpos)
+                                                    .with_id(quest_id)
+                                                    .with_label(
+                                                        Content::localized("hud-map-escort-label")
+                                                            .with_arg(
+                                                                "name",
+                                                                ctx.npc.get_name().unwrap_or_else(
+                                                                    || "<unknown>".to_string(),
+                                                                ),
+                                                            )
+                                                            .with_arg(
+                                                                "place",
+                                                                dst_site_name.clone(),
+                                                            ),
+                                                    )
+                                                    .with_quest_flag(true),
+                                            )
+                                        })
+                                    })
+                                    .then(session.say_statement(Content::localized(
+                                        "npc-response-quest-escort-start",
+                                    )))
+                                    .boxed()
+                            } else {
+                                session
+                                    .say_statement(Content::localized(
+                                        "npc-response-quest-rejected",
+                                    ))
+                                    .boxed()
+                            }
+                        })
+                    })
+                    .boxed(),
+            );
+        }
         // Escortable NPCs must have no existing job
         if ctx.npc.job.is_none()
             // They must be a merchant
@@ -271,6 +366,91 @@ pub fn quest_request<S: State>(session: DialogueSession) -> impl Action<S> {
             );
         }
 
+        // Bounty quest
+        const BOUNTY_REWARD_ITEM: ItemResource = ItemResource::Coin;
+        if matches!(ctx.npc.profession(), Some(Profession::Guard))
+            && let Some(home) = ctx.npc.home
+            && let Some((criminal, reward)) = ctx
+                .state
+                .data()
+                .bounties
+                .wanted_at(home)
+                // Players can't claim the bounty on their own head
+                .filter(|(criminal, _)| Actor::Character(*criminal) != session.target)
+                // Try to filter out players that are already being hunted (imperfect: race conditions)
+                .filter(|(criminal, _)| ctx.state.data().quests.related_to(*criminal).count() == 0)
+                .max_by_key(|(_, bounty)| bounty.reward)
+                .map(|(criminal, bounty)| (criminal, bounty.reward))
+            // Bounties are paid for by the site rather than by the guard
+            && let Some(stock) = ctx.state.data().sites.get(home).and_then(|site| {
+                Some(site.stock(ctx.index.sites.get(site.world_site?)))
+            })
+            && ctx.state.data().bounties.can_fund(home, &stock, reward)
+        {
+            quests.push(
+                session
+                    .ask_yes_no_question(
+                        Content::localized("npc-response-quest-bounty-ask")
+                            .with_arg("coins", reward as u64),
+                    )
+                    .and_then(move |yes| {
+                        now(move |ctx, _| {
+                            // The site may have spent its coins since the question was asked
+                            let funded = yes
+                                && ctx.state.data().sites.get(home).is_some_and(|site| {
+                                    site.world_site.is_some_and(|world_site| {
+                                        let stock = site.stock(ctx.index.sites.get(world_site));
+                                        ctx.state
+                                            .data()
+                                            .bounties
+                                            .reserve_reward(home, &stock, reward)
+                                    })
+                                });
+                            if funded {
+                                ctx.controller
+                                    .change_home_goods(Good::Coin, -(reward as f32));
+                                let quest =
+                                    Quest::slay(ctx.npc_id.into(), criminal.into(), session.target)
+                                        .with_deposit(BOUNTY_REWARD_ITEM, reward as f32)
+                                        .with_timeout(ctx.time.add_minutes(60.0));
+                                let marker = match util::locate_actor(ctx, criminal.into()) {
+                                    Some(wpos) => session
+                                        .give_marker(
+                                            Marker::at(wpos.xy())
+                                                .with_id(Actor::from(criminal))
+                                                .with_label(Content::localized(
+                                                    "hud-map-bounty-label",
+                                                ))
+                                                .with_quest_flag(true),
+                                        )
+                                        .boxed(),
+                                    None => finish().boxed(),
+                                };
+                                create_quest(quest)
+                                    .then(marker)
+                                    .then(session.say_statement(Content::localized(
+                                        "npc-response-quest-bounty-start",
+                                    )))
+                                    .boxed()
+                            } else if yes {
+                                session
+                                    .say_statement(Content::localized(
+                                        "npc-response-quest-bounty-unfunded",
+                                    ))
+                                    .boxed()
+                            } else {
+                                session
+                                    .say_statement(Content::localized(
+                                        "npc-response-quest-rejected",
+                                    ))
+                                    .boxed()
+                            }
+                        })
+                    })
+                    .boxed(),
+            );
+        }
+
         if quests.is_empty() {
             session
                 .say_statement(Content::localized("npc-response-quest-nothing"))
@@ -290,8 +470,12 @@ pub fn check_for_timeouts<S: State>(ctx: &mut NpcCtx) -> Option<impl Action<S> +
         if let Some(timeout) = quest.timeout
             // The quest has timed out...
             && ctx.time > timeout
-            // ...so resolve it
-            && let Ok(Some(_)) = resolve_take_deposit(ctx, quest_id, false)
+            // ...so resolve it, returning the reward of bounties to the site that paid for it
+            && if matches!(quest.kind, QuestKind::Slay { target: Actor::Character(_), .. }) {
+                resolve_refund_deposit(ctx, quest_id)
+            } else {
+                matches!(resolve_take_deposit(ctx, quest_id, false), Ok(Some(_)))
+            }
         {
             // Stop any job related to the quest
             if ctx.npc.job == Some(Job::Quest(quest_id)) {
@@ -0,0 +1,296 @@
+//! Daily schedules for villagers.
+//!
+//! Villagers living in a site follow the [`Schedule`] of their profession, as
+//! described by the [`SCHEDULES`] asset: they sleep at home, work at a place
+//! that suits their profession, eat at a tavern and socialise at given hours of
+//! the day. Hours that no entry covers are free time, which villagers spend
+//! however they please.
+//!
+//! The places that a villager goes to are picked from their seed, so a villager
+//! can always be found in the same house, workshop, field or tavern at the same
+//! time of day. [`whereabouts`] tells where that is, for quests and anything
+//! else that needs to find a villager.
+
+use super::{dialogue_tree::ProfessionKind, *};
+use crate::data::{Data, Npc, Site};
+use common::{assets::Ron, resources::TimeOfDay};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The asset that schedules are loaded from.
+pub const SCHEDULES: &str = "common.schedules";
+
+const HOUR: f64 = 60.0 * 60.0;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Schedules {
+    /// The schedule of villagers whose profession has none of its own.
+    pub default: Schedule,
+    #[serde(default)]
+    pub professions: HashMap<ProfessionKind, Schedule>,
+}
+
+impl Schedules {
+    pub fn get(&self, profession: Option<&Profession>) -> &Schedule {
+        profession
+            .and_then(|p| self.professions.get(&ProfessionKind::from(p)))
+            .unwrap_or(&self.default)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.default
+            .validate()
+            .map_err(|e| format!("default: {e}"))?;
+        for (kind, schedule) in &self.professions {
+            schedule.validate().map_err(|e| format!("{kind:?}: {e}"))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Schedule {
+    /// The number of shifts that villagers are split into. Every shift follows
+    /// the same entries, each one starting later than the previous by an
+    /// equal part of the day.
+    #[serde(default = "default_shifts")]
+    pub shifts: u32,
+    pub entries: Vec<ScheduleEntry>,
+}
+
+fn default_shifts() -> u32 { 1 }
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleEntry {
+    /// The hour of the day at which the entry starts.
+    pub from: f32,
+    /// The hour of the day at which the entry ends. Entries spanning midnight
+    /// end at an earlier hour than they start.
+    pub to: f32,
+    pub activity: Activity,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+pub enum Activity {
+    /// Stay in a house of the site.
+    Sleep,
+    /// Work at the place that suits the villager's profession.
+    Work,
+    /// Eat at a tavern.
+    Eat,
+    /// Spend time with others at a plaza.
+    Socialize,
+}
+
+impl ScheduleEntry {
+    /// The length of the entry, in hours.
+    fn hours(&self) -> f32 {
+        let hours = (self.to - self.from).rem_euclid(24.0);
+        if hours == 0.0 && self.to != self.from {
+            24.0
+        } else {
+            hours
+        }
+    }
+
+    /// How many hours of the entry have passed at the given hour of the day,
+    /// if it is within the entry.
+    fn elapsed(&self, hour: f32) -> Option<f32> {
+        Some((hour - self.from).rem_euclid(24.0)).filter(|elapsed| *elapsed < self.hours())
+    }
+}
+
+impl Schedule {
+    /// The entry followed at the given hour of the day by villagers in the
+    /// given shift, along with the number of hours left until it ends.
+    pub fn entry_at(&self, hour: f32, shift: u32) -> Option<(&ScheduleEntry, f32)> {
+        let shifts = self.shifts.max(1);
+        let hour = hour - 24.0 * (shift % shifts) as f32 / shifts as f32;
+        self.entries.iter().find_map(|entry| {
+            let elapsed = entry.elapsed(hour)?;
+            Some((entry, entry.hours() - elapsed))
+        })
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.shifts == 0 {
+            return Err("there must be at least one shift".to_string());
+        }
+        for (i, entry) in self.entries.iter().enumerate() {
+            if !(0.0..24.0).contains(&entry.from) || !(0.0..=24.0).contains(&entry.to) {
+                return Err(format!("entry {i} has hours outside of the day"));
+            } else if entry.from == entry.to {
+                return Err(format!("entry {i} is empty"));
+            }
+            for (j, other) in self.entries.iter().enumerate().skip(i + 1) {
+                if entry.elapsed(other.from).is_some() || other.elapsed(entry.from).is_some() {
+                    return Err(format!("entries {i} and {j} overlap"));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The activity that the NPC's schedule has them do at the given time, if any,
+/// along with the time at which it ends.
+pub fn current(npc: &Npc, time_of_day: TimeOfDay) -> Option<(Activity, TimeOfDay)> {
+    let schedules = Ron::<Schedules>::load_expect(SCHEDULES).read();
+    let hour = (time_of_day.day() / HOUR) as f32;
+    let (entry, hours_left) = schedules
+        .0
+        .get(npc.profession().as_ref())
+        .entry_at(hour, npc.seed)?;
+    // Children play while their elders work
+    let activity = match entry.activity {
+        Activity::Work if household::is_child(npc, time_of_day) => Activity::Socialize,
+        activity => activity,
+    };
+    Some((
+        activity,
+        TimeOfDay(time_of_day.0 + hours_left as f64 * HOUR),
+    ))
+}
+
+/// Pick one of the plots of a site, always the same one for a given NPC.
+fn pick_plot(
+    site: &WorldSite,
+    npc: &Npc,
+    mut filter: impl FnMut(&site::Plot) -> bool,
+) -> Option<Id<site::Plot>> {
+    let plots = site
+        .plots
+        .iter()
+        .filter(|(_, plot)| filter(plot))
+        .map(|(id, _)| id)
+        .collect::<Vec<_>>();
+    plots.get(npc.seed as usize % plots.len().max(1)).copied()
+}
+
+/// The house that the NPC sleeps in, which is the house of their household if
+/// they have one in the site.
+pub fn house(site: &WorldSite, npc: &Npc) -> Option<Id<site::Plot>> {
+    let is_house = |plot: &site::Plot| matches!(plot.meta(), Some(PlotKindMeta::House { .. }));
+    npc.house
+        .and_then(|house| {
+            site.plots
+                .iter()
+                .find(|(id, plot)| id.id() == house && is_house(plot))
+        })
+        .map(|(id, _)| id)
+        .or_else(|| pick_plot(site, npc, is_house))
+}
+
+/// The tavern that the NPC eats at, or works at for chefs.
+pub fn tavern(site: &WorldSite, npc: &Npc) -> Option<Id<site::Plot>> {
+    pick_plot(site, npc, |plot| matches!(plot.kind(), PlotKind::Tavern(_)))
+}
+
+/// The plaza that the NPC socialises at, or works at for merchants and guards.
+pub fn plaza(site: &WorldSite, npc: &Npc) -> Option<Id<site::Plot>> {
+    let plazas = site.plazas().collect::<Vec<_>>();
+    plazas.get(npc.seed as usize % plazas.len().max(1)).copied()
+}
+
+/// Where the NPC works, if there is somewhere in or near the site that suits
+/// their profession.
+pub fn workplace(
+    world: &World,
+    site: &Site,
+    world_site: &WorldSite,
+    npc: &Npc,
+) -> Option<Vec2<f32>> {
+    let plot = match npc.profession()? {
+        Profession::Blacksmith | Profession::Alchemist => pick_plot(world_site, npc, |plot| {
+            matches!(plot.meta(), Some(PlotKindMeta::Workshop { .. }))
+        }),
+        Profession::Farmer => pick_plot(world_site, npc, |plot| {
+            matches!(plot.kind(), PlotKind::FarmField(_))
+        }),
+        Profession::Merchant | Profession::Guard => plaza(world_site, npc),
+        Profession::Chef => tavern(world_site, npc),
+        Profession::Herbalist | Profession::Hunter => {
+            return forest_near(world, site.wpos.as_(), &mut npc.rng(Npc::PERM_SCHEDULE));
+        },
+        _ => None,
+    }?;
+    Some(plot_wpos(world_site, plot))
+}
+
+fn plot_wpos(site: &WorldSite, plot: Id<site::Plot>) -> Vec2<f32> {
+    site.tile_center_wpos(site.plots[plot].root_tile()).as_()
+}
+
+/// Where the NPC goes for the given activity in the given site, if the site has
+/// somewhere suitable.
+pub fn place(
+    world: &World,
+    index: IndexRef,
+    data: &Data,
+    npc: &Npc,
+    site: SiteId,
+    activity: Activity,
+) -> Option<Vec2<f32>> {
+    let site = data.sites.get(site)?;
+    let world_site = index.sites.get(site.world_site?);
+    match activity {
+        Activity::Sleep => house(world_site, npc).map(|plot| plot_wpos(world_site, plot)),
+        Activity::Work => workplace(world, site, world_site, npc),
+        Activity::Eat => tavern(world_site, npc).map(|plot| plot_wpos(world_site, plot)),
+        Activity::Socialize => plaza(world_site, npc).map(|plot| plot_wpos(world_site, plot)),
+    }
+}
+
+/// What a villager is scheduled to be doing at the given time, and where.
+///
+/// This is only known for NPCs with a home, and tells where they are expected
+/// to be rather than where they actually are: an NPC may be delayed on the way
+/// or busy with something more important.
+pub fn whereabouts(
+    world: &World,
+    index: IndexRef,
+    data: &Data,
+    npc: &Npc,
+    time_of_day: TimeOfDay,
+) -> Option<(Activity, Vec2<f32>)> {
+    let (activity, _) = current(npc, time_of_day)?;
+    let wpos = place(world, index, data, npc, npc.home?, activity)?;
+    Some((activity, wpos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_schedules() {
+        let schedules = Ron::<Schedules>::load_cloned(SCHEDULES)
+            .expect("Failed to load schedules")
+            .into_inner();
+        if let Err(e) = schedules.validate() {
+            panic!("{SCHEDULES}: {e}");
+        }
+    }
+
+    #[test]
+    fn entries_wrap_around_midnight() {
+        let schedule = Schedule {
+            shifts: 2,
+            entries: vec![ScheduleEntry {
+                from: 22.0,
+                to: 6.0,
+                activity: Activity::Sleep,
+            }],
+        };
+        let (entry, hours_left) = schedule.entry_at(2.0, 0).unwrap();
+        assert_eq!(entry.activity, Activity::Sleep);
+        assert_eq!(hours_left, 4.0);
+        assert!(schedule.entry_at(12.0, 0).is_none());
+        // The second shift sleeps 12 hours later
+        assert!(schedule.entry_at(2.0, 1).is_none());
+        assert!(schedule.entry_at(12.0, 1).is_some());
+    }
+}
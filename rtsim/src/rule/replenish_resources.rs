@@ -13,6 +13,9 @@ pub const REPLENISH_TIME: f32 = 60.0 * 60.0;
 // closed-form solution to the replenishment to calculate resources in a lazy
 // manner.
 pub const REPLENISH_PER_TICK: usize = 8192;
+/// Take 1 hour for the goods brought to or taken away from sites by caravans to
+/// mostly go back to the stock produced by the site's economy.
+pub const GOODS_DECAY_TIME: f32 = 60.0 * 60.0;
 
 impl Rule for ReplenishResources {
     fn start(rtstate: &mut RtState) -> Result<Self, RuleError> {
@@ -38,6 +41,14 @@ impl Rule for ReplenishResources {
                     *res = (*res + replenish_amount).clamp(0.0, 1.0);
                 }
             }
+
+            let goods_decay = (-ctx.event.dt / GOODS_DECAY_TIME).exp();
+            for site in data.sites.values_mut() {
+                site.goods.retain(|_, amount| {
+                    *amount *= goods_decay;
+                    amount.abs() > 0.01
+                });
+            }
         });
 
         Ok(Self)
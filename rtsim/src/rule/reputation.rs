@@ -0,0 +1,135 @@
+use crate::{
+    RtState, Rule, RuleError,
+    data::{ReportKind, Reputations, Sentiments, sentiment::Target},
+    event::{EventCtx, OnTick},
+};
+use common::{character::CharacterId, rtsim::Actor};
+use hashbrown::HashMap;
+
+/// How many ticks to skip between updates of reputations.
+const REPUTATION_TICK_SKIP: u64 = 60;
+/// Residents whose opinion of a character weighs in on the reputation of their
+/// site only count for much once word has spread to a few of them.
+const WORD_OF_MOUTH: f32 = 2.0;
+/// How much the sentiment of a site's faction weighs in on its reputations.
+const FACTION_WEIGHT: f32 = 0.5;
+/// How much each murder known to a site lowers the reputation of the killer.
+const MURDER_PENALTY: f32 = 0.25;
+/// How much each theft known to a site lowers the reputation of the thief.
+const THEFT_PENALTY: f32 = 0.1;
+
+/// A rule that keeps [`Reputations`] up to date with the sentiments of NPCs
+/// and factions and with the reports known to sites.
+///
+/// The reputation of a character with a site is the opinion of its residents,
+/// along with that of its faction, lowered by the murders and thefts that the
+/// site knows the character to have committed. The reputation with a faction
+/// is the sentiment of the faction along with the reputations of its sites.
+pub struct UpdateReputations;
+
+impl Rule for UpdateReputations {
+    fn start(rtstate: &mut RtState) -> Result<Self, RuleError> {
+        rtstate.bind(on_tick);
+
+        Ok(Self)
+    }
+}
+
+/// The sentiments felt toward characters.
+fn toward_characters(sentiments: &Sentiments) -> impl Iterator<Item = (CharacterId, f32)> + '_ {
+    sentiments
+        .iter()
+        .filter_map(|(target, sentiment)| match target {
+            Target::Character(character) => Some((character, sentiment.value())),
+            _ => None,
+        })
+}
+
+fn on_tick(ctx: EventCtx<UpdateReputations, OnTick>) {
+    if !ctx.event.tick.is_multiple_of(REPUTATION_TICK_SKIP) {
+        return;
+    }
+
+    let data = &mut *ctx.state.data_mut();
+    let mut reputations = Reputations::default();
+
+    for (site_id, site) in data.sites.iter() {
+        let mut reps = HashMap::<CharacterId, f32>::new();
+
+        // The opinion of the residents who know the character
+        let mut opinions = HashMap::<CharacterId, (f32, f32)>::new();
+        for npc in site
+            .population
+            .iter()
+            .filter_map(|npc_id| data.npcs.get(*npc_id))
+            .filter(|npc| !npc.is_dead())
+        {
+            for (character, value) in toward_characters(&npc.sentiments) {
+                let (sum, count) = opinions.entry(character).or_default();
+                *sum += value;
+                *count += 1.0;
+            }
+        }
+        for (character, (sum, count)) in opinions {
+            *reps.entry(character).or_default() += sum / (count + WORD_OF_MOUTH);
+        }
+
+        if let Some(faction) = site.faction.and_then(|faction| data.factions.get(faction)) {
+            for (character, value) in toward_characters(&faction.sentiments) {
+                *reps.entry(character).or_default() += value * FACTION_WEIGHT;
+            }
+        }
+
+        for report in site
+            .known_reports
+            .iter()
+            .filter_map(|report| data.reports.get(*report))
+        {
+            match report.kind {
+                ReportKind::Death {
+                    killer: Some(Actor::Character(character)),
+                    ..
+                } => *reps.entry(character).or_default() -= MURDER_PENALTY,
+                ReportKind::Theft {
+                    thief: Actor::Character(character),
+                    ..
+                } => *reps.entry(character).or_default() -= THEFT_PENALTY,
+                _ => {},
+            }
+        }
+
+        reps.values_mut()
+            .for_each(|rep| *rep = rep.clamp(-1.0, 1.0));
+        reputations.sites.insert(site_id, reps);
+    }
+
+    for (faction_id, faction) in data.factions.iter() {
+        let mut site_reps = HashMap::<CharacterId, (f32, f32)>::new();
+        for reps in data
+            .sites
+            .iter()
+            .filter(|(_, site)| site.faction == Some(faction_id))
+            .filter_map(|(site_id, _)| reputations.sites.get(&site_id))
+        {
+            for (character, rep) in reps {
+                let (sum, count) = site_reps.entry(*character).or_default();
+                *sum += rep;
+                *count += 1.0;
+            }
+        }
+
+        let mut reps = site_reps
+            .into_iter()
+            .map(|(character, (sum, count))| (character, sum / count * 0.5))
+            .collect::<HashMap<_, _>>();
+        for (character, value) in toward_characters(&faction.sentiments) {
+            *reps.entry(character).or_default() += value * 0.5;
+        }
+
+        reps.values_mut()
+            .for_each(|rep| *rep = rep.clamp(-1.0, 1.0));
+        reputations.factions.insert(faction_id, reps);
+    }
+
+    data.reputations = reputations;
+}
@@ -2,6 +2,7 @@ use crate::{
     RtState, Rule, RuleError,
     data::{Sentiment, npc::SimulationMode},
     event::{EventCtx, OnHealthChange, OnHelped, OnMountVolume, OnTick},
+    rule::justice,
 };
 use common::{
     comp::{self, Body},
@@ -115,6 +116,7 @@ fn on_tick(ctx: EventCtx<SimulateNpcs, OnTick>) {
         }
     }
 
+    let mut verdicts = Vec::new();
     for (npc_id, npc) in data.npcs.npcs.iter_mut().filter(|(_, npc)| !npc.is_dead()) {
         if matches!(npc.mode, SimulationMode::Simulated) {
             // Consume NPC actions
@@ -286,6 +288,8 @@ fn on_tick(ctx: EventCtx<SimulateNpcs, OnTick>) {
                 new_home.population.insert(npc_id);
             }
             npc.home = new_home;
+            // The NPC's house was in their old home
+            npc.house = None;
         }
 
         // Create registered quests
@@ -293,7 +297,30 @@ fn on_tick(ctx: EventCtx<SimulateNpcs, OnTick>) {
             data.quests.create(id, quest);
         }
 
+        // Change the stock of the NPC's home
+        for (good, amount) in core::mem::take(&mut npc.controller.home_goods) {
+            if let Some(home) = npc.home.and_then(|home| data.sites.get_mut(home)) {
+                *home.goods.entry(good).or_default() += amount;
+            }
+        }
+
+        // Pass verdicts on wanted players
+        for (criminal, verdict) in core::mem::take(&mut npc.controller.verdicts) {
+            if let Some(home) = npc.home {
+                data.bounties.pass_verdict(home, criminal, verdict);
+                verdicts.push((home, criminal));
+            }
+        }
+
         // Set job status
         npc.job = npc.controller.job.clone();
     }
+
+    // Rewards reserved for bounty quests have now been taken from their site
+    data.bounties.clear_reserved();
+
+    // Players that paid their fine or gave themselves up are no longer hunted
+    for (home, criminal) in verdicts {
+        justice::call_off_bounty_quests(data, home, criminal);
+    }
 }
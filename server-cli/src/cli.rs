@@ -4,8 +4,10 @@
 
 use clap::{Parser, builder::ValueParser};
 use common::comp;
-use server::persistence::SqlLogMode;
-use std::{str::FromStr, sync::mpsc::Sender};
+use server::{
+    audit_log::AuditRecord, graveyard::Grave, persistence::SqlLogMode, pregen::PregenProgress,
+};
+use std::{path::PathBuf, str::FromStr, sync::mpsc::Sender};
 use tracing::error;
 
 // Custom value parser for case-insensitive parsing of AdminRole
@@ -29,6 +31,52 @@ pub enum Admin {
         /// Name of the admin from whom to remove any existing roles
         username: String,
     },
+    /// Adds a player to a permission group defined in the permission groups
+    /// settings file
+    AddGroup {
+        /// Name of the player to add to the group
+        username: String,
+        /// Name of the permission group
+        group: String,
+    },
+    /// Removes a player from a permission group
+    RemoveGroup {
+        /// Name of the player to remove from the group
+        username: String,
+        /// Name of the permission group
+        group: String,
+    },
+}
+
+#[derive(Clone, Debug, Parser)]
+pub enum Backup {
+    /// Lists the snapshots of the persisted state, newest first
+    List,
+    /// Replaces the persisted state with that of a snapshot. The server must
+    /// not be running while doing this.
+    Restore {
+        /// Name of the snapshot, as shown by `backup list`
+        name: String,
+    },
+}
+
+#[derive(Clone, Debug, Parser)]
+pub enum Pregen {
+    /// Pre-generates every chunk of the world
+    World,
+    /// Pre-generates the chunks in a rectangle, given in chunk coordinates
+    Area {
+        min_x: i32,
+        min_y: i32,
+        /// Inclusive
+        max_x: i32,
+        /// Inclusive
+        max_y: i32,
+    },
+    /// Cancels the ongoing pre-generation
+    Cancel,
+    /// Shows the progress of the ongoing pre-generation
+    Status,
 }
 
 #[derive(Clone, Debug, Parser)]
@@ -72,16 +120,52 @@ pub enum Message {
         /// View distance of the loaded area
         view_distance: u32,
     },
+    /// Generates chunks in the background so that they are stored in the
+    /// chunk cache before players get there
+    Pregen {
+        #[command(subcommand)]
+        command: Pregen,
+    },
     /// Enable or disable sql logging
     SqlLogMode {
         #[arg(default_value_t, value_parser = clap::value_parser!(SqlLogMode))]
         mode: SqlLogMode,
     },
+    /// Takes a snapshot of the character database, rtsim data and persisted
+    /// terrain
+    Snapshot,
+    /// Exports a character to the character transfer directory, so that it can
+    /// be imported on another server
+    ExportCharacter {
+        /// Database ID of the character
+        character_id: i64,
+    },
+    /// Creates a character for a player from a file in the character transfer
+    /// directory
+    ImportCharacter {
+        /// Name of the player the character is created for
+        username: String,
+        /// Name of the file in the character transfer directory
+        file: String,
+        #[arg(long)]
+        /// Accept files that were not signed with this server's key
+        allow_unsigned: bool,
+    },
     /// Disconnects all connected clients
     DisconnectAllClients,
     /// returns active player names
     ListPlayers,
     ListLogs,
+    /// returns the leaderboard of dead hardcore characters
+    ListGraves,
+    /// returns the most recent commands run by moderators and admins
+    ListAuditLog {
+        /// Only list the commands run by the player with this alias or UUID
+        #[arg(long)]
+        actor: Option<String>,
+        #[arg(long, default_value_t = 20)]
+        max: usize,
+    },
     /// sends a msg to everyone on the server
     SendGlobalMsg {
         msg: String,
@@ -92,6 +176,9 @@ pub enum Message {
 pub enum MessageReturn {
     Players(Vec<String>),
     Logs(Vec<String>),
+    Graves(Vec<Grave>),
+    Pregen(Option<PregenProgress>),
+    AuditLog(Vec<AuditRecord>),
 }
 
 #[derive(Parser)]
@@ -117,6 +204,22 @@ pub struct BenchParams {
     pub duration: u32,
 }
 
+#[derive(Debug, Clone, Parser)]
+pub struct RtsimParams {
+    /// Number of in-game days to simulate
+    #[arg(long, default_value_t = 7.0)]
+    pub days: f64,
+    /// Report statistics every this many in-game days
+    #[arg(long, default_value_t = 1.0)]
+    pub report_every: f64,
+    /// Length of each tick (in seconds), longer ticks are faster but coarser
+    #[arg(long, default_value_t = 1.0 / 30.0)]
+    pub dt: f32,
+    /// Save the resulting rtsim data to this file instead of discarding it
+    #[arg(long)]
+    pub save_to: Option<PathBuf>,
+}
+
 #[derive(Parser)]
 pub enum ArgvCommand {
     #[command(flatten)]
@@ -124,6 +227,15 @@ pub enum ArgvCommand {
     /// Load an area, run the server for some time, and then exit (useful for
     /// profiling).
     Bench(BenchParams),
+    /// Load the world and rtsim data, and run rtsim without clients as fast as
+    /// possible, reporting statistics along the way (useful for balancing).
+    #[cfg(feature = "worldgen")]
+    Rtsim(RtsimParams),
+    /// Restore snapshots of the persisted state
+    Backup {
+        #[command(subcommand)]
+        command: Backup,
+    },
 }
 
 #[derive(Parser)]
@@ -19,7 +19,8 @@ mod tuilog;
 mod web;
 use crate::{
     cli::{
-        Admin, ArgvApp, ArgvCommand, BenchParams, Message, MessageReturn, SharedCommand, Shutdown,
+        Admin, ArgvApp, ArgvCommand, Backup, BenchParams, Message, MessageReturn, Pregen,
+        SharedCommand, Shutdown,
     },
     settings::Settings,
     shutdown_coordinator::ShutdownCoordinator,
@@ -27,6 +28,7 @@ use crate::{
     tuilog::TuiLog,
 };
 use common::{
+    character::CharacterId,
     clock::Clock,
     comp::{ChatType, Player},
     consts::MIN_RECOMMENDED_TOKIO_THREADS,
@@ -42,6 +44,7 @@ use std::{
 };
 use tokio::sync::Notify;
 use tracing::{info, trace};
+use vek::*;
 
 lazy_static::lazy_static! {
     pub static ref LOG: TuiLog<'static> = TuiLog::default();
@@ -178,8 +181,68 @@ fn main() -> io::Result<()> {
                         );
                         Ok(())
                     },
+                    Admin::AddGroup { username, group } => {
+                        let _ = server::add_to_group(
+                            &username,
+                            &group,
+                            &login_provider,
+                            &mut editable_settings,
+                            &server_data_dir,
+                        );
+                        Ok(())
+                    },
+                    Admin::RemoveGroup { username, group } => {
+                        let _ = server::remove_from_group(
+                            &username,
+                            &group,
+                            &login_provider,
+                            &mut editable_settings,
+                            &server_data_dir,
+                        );
+                        Ok(())
+                    },
+                };
+            },
+            ArgvCommand::Backup { command } => {
+                return match command {
+                    Backup::List => {
+                        for (name, manifest) in
+                            server::backup::list(&server_settings.backup, &server_data_dir)?
+                        {
+                            println!(
+                                "{} (tick {}, {}{}{})",
+                                name,
+                                manifest.tick,
+                                manifest.game_version,
+                                if manifest.rtsim { ", rtsim" } else { "" },
+                                if manifest.terrain { ", terrain" } else { "" },
+                            );
+                        }
+                        Ok(())
+                    },
+                    Backup::Restore { name } => server::backup::restore(
+                        &server_settings.backup,
+                        &server_data_dir,
+                        &database_settings.db_dir,
+                        &name,
+                    )
+                    .map_err(io::Error::other),
                 };
             },
+            #[cfg(feature = "worldgen")]
+            ArgvCommand::Rtsim(params) => {
+                return server::rtsim::simulate::simulate(
+                    &server_settings,
+                    &server_data_dir,
+                    server::rtsim::simulate::SimulateOpts {
+                        days: params.days,
+                        report_every: params.report_every,
+                        dt: params.dt,
+                        save_to: params.save_to,
+                    },
+                )
+                .map_err(|e| io::Error::other(e.to_string()));
+            },
             ArgvCommand::Bench(params) => {
                 bench = Some(params);
                 // If we are trying to benchmark, don't limit the server view distance.
@@ -369,13 +432,52 @@ fn server_loop(
                 }) => {
                     server.remove_admin(&username);
                 },
+                Message::Shared(SharedCommand::Admin {
+                    command: Admin::AddGroup { username, group },
+                }) => {
+                    server.add_to_group(&username, &group);
+                },
+                Message::Shared(SharedCommand::Admin {
+                    command: Admin::RemoveGroup { username, group },
+                }) => {
+                    server.remove_from_group(&username, &group);
+                },
                 #[cfg(feature = "worldgen")]
                 Message::LoadArea { view_distance } => {
                     server.create_centered_persister(view_distance);
                 },
+                Message::Pregen { command } => match command {
+                    Pregen::World => server.start_pregen(None),
+                    Pregen::Area {
+                        min_x,
+                        min_y,
+                        max_x,
+                        max_y,
+                    } => server.start_pregen(Some(Aabr {
+                        min: Vec2::new(min_x, min_y),
+                        max: Vec2::new(max_x, max_y) + 1,
+                    })),
+                    Pregen::Cancel => server.cancel_pregen(),
+                    Pregen::Status => {
+                        let _ = response.send(MessageReturn::Pregen(server.pregen_progress()));
+                    },
+                },
                 Message::SqlLogMode { mode } => {
                     server.set_sql_log_mode(mode);
                 },
+                Message::Snapshot => {
+                    server.take_snapshot();
+                },
+                Message::ExportCharacter { character_id } => {
+                    server.export_character(None, CharacterId(character_id));
+                },
+                Message::ImportCharacter {
+                    username,
+                    file,
+                    allow_unsigned,
+                } => {
+                    server.import_character(None, &username, file, allow_unsigned);
+                },
                 Message::DisconnectAllClients => {
                     server.disconnect_all_clients();
                 },
@@ -400,6 +502,19 @@ fn server_loop(
                         .collect();
                     let _ = response.send(MessageReturn::Logs(lines));
                 },
+                Message::ListGraves => {
+                    let graves = server
+                        .state()
+                        .ecs()
+                        .read_resource::<server::graveyard::Graveyard>()
+                        .leaderboard()
+                        .to_vec();
+                    let _ = response.send(MessageReturn::Graves(graves));
+                },
+                Message::ListAuditLog { actor, max } => {
+                    let records = server.audit_log(actor.as_deref(), max);
+                    let _ = response.send(MessageReturn::AuditLog(records));
+                },
                 Message::SendGlobalMsg { msg } => {
                     use server::state_ext::StateExt;
                     let msg = ChatType::Meta.into_plain_msg(msg);
@@ -420,6 +535,14 @@ fn server_loop(
                     match msg_answ {
                         MessageReturn::Players(players) => info!("Players: {:?}", players),
                         MessageReturn::Logs(_) => info!("skipp sending logs to tui"),
+                        MessageReturn::Graves(graves) => info!("Graves: {:?}", graves),
+                        MessageReturn::Pregen(Some(progress)) => info!("{}", progress),
+                        MessageReturn::Pregen(None) => info!("No pre-generation is running"),
+                        MessageReturn::AuditLog(records) => {
+                            for record in records.iter().rev() {
+                                info!(?record, "Audit log");
+                            }
+                        },
                     };
                 }
             }
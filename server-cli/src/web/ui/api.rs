@@ -1,7 +1,7 @@
-use crate::cli::{Message, MessageReturn};
+use crate::cli::{Message, MessageReturn, Pregen};
 use axum::{
     Json, Router,
-    extract::{ConnectInfo, Request, State},
+    extract::{ConnectInfo, Query, Request, State},
     http::header::COOKIE,
     middleware::Next,
     response::{IntoResponse, Response},
@@ -72,6 +72,9 @@ pub fn router(web_ui_request_s: UiRequestSender, secret_token: String) -> Router
     Router::new()
         .route("/players", get(players))
         .route("/logs", get(logs))
+        .route("/graves", get(graves))
+        .route("/pregen", get(pregen))
+        .route("/audit_log", get(audit_log))
         .route("/send_global_msg", post(send_global_msg))
         .layer(axum::middleware::from_fn_with_state(ip_addrs, log_users))
         .layer(axum::middleware::from_fn_with_state(token, validate_secret))
@@ -106,6 +109,70 @@ async fn logs(
     }
 }
 
+async fn graves(
+    State(web_ui_request_s): State<UiRequestSender>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    let _ = web_ui_request_s.send((Message::ListGraves, sender)).await;
+    match receiver
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    {
+        MessageReturn::Graves(graves) => Ok(Json(graves)),
+        _ => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn pregen(
+    State(web_ui_request_s): State<UiRequestSender>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    let _ = web_ui_request_s
+        .send((
+            Message::Pregen {
+                command: Pregen::Status,
+            },
+            sender,
+        ))
+        .await;
+    match receiver
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    {
+        MessageReturn::Pregen(progress) => Ok(Json(progress)),
+        _ => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+#[derive(Deserialize)]
+struct AuditLogParams {
+    actor: Option<String>,
+    max: Option<usize>,
+}
+
+async fn audit_log(
+    State(web_ui_request_s): State<UiRequestSender>,
+    Query(params): Query<AuditLogParams>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let (sender, receiver) = tokio::sync::oneshot::channel();
+    let _ = web_ui_request_s
+        .send((
+            Message::ListAuditLog {
+                actor: params.actor,
+                max: params.max.unwrap_or(20),
+            },
+            sender,
+        ))
+        .await;
+    match receiver
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    {
+        MessageReturn::AuditLog(records) => Ok(Json(records)),
+        _ => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
 #[derive(Deserialize)]
 struct SendWorldMsgBody {
     msg: String,
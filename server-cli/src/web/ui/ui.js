@@ -91,9 +91,26 @@ async function update_logs() {
     }
 }
 
+async function update_pregen() {
+    const pregen_response = await fetch("/ui_api/v1/pregen");
+    const pregen = await pregen_response.json();
+
+    var pregen_status = document.getElementById("pregen_status");
+    var pregen_progress = document.getElementById("pregen_progress");
+    if (pregen == null) {
+      pregen_status.innerText = "No pre-generation is running";
+      pregen_progress.value = 0;
+    } else {
+      pregen_status.innerText = "Pre-generated " + pregen.generated + "/" + pregen.total
+        + " chunks in " + pregen.elapsed.secs + "s";
+      pregen_progress.value = pregen.generated / Math.max(pregen.total, 1);
+    }
+}
+
 async function loop() {
     await update_players();
     await update_logs();
+    await update_pregen();
 }
 
 var loopId = window.setInterval(loop, 1000);
\ No newline at end of file
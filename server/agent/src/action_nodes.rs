@@ -11,6 +11,7 @@ use crate::{
     },
 };
 use common::{
+    assets::{AssetExt, AssetHandle, Ron},
     combat::perception_dist_multiplier_from_stealth,
     comp::{
         self, Agent, Alignment, Body, CharacterState, Content, ControlAction, ControlEvent,
@@ -35,6 +36,7 @@ use common::{
     path::TraversalConfig,
     rtsim::NpcActivity,
     states::basic_beam,
+    tactic::TacticSpec,
     terrain::Block,
     time::DayPeriod,
     util::Dir,
@@ -43,11 +45,33 @@ use common::{
 use itertools::Itertools;
 use rand::{Rng, rng};
 use specs::Entity as EcsEntity;
+use std::{
+    collections::HashSet,
+    sync::{LazyLock, Mutex},
+};
+use tracing::error;
 use vek::*;
 
 #[cfg(feature = "use-dyn-lib")]
 use {crate::LIB, std::ffi::CStr};
 
+/// Loads a data-driven tactic. Tactics that fail to load are reported once,
+/// agents using them fall back to the tactic of their weapon.
+fn load_tactic(spec: &str) -> Option<AssetHandle<Ron<TacticSpec>>> {
+    static FAILED: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(Default::default);
+
+    Ron::<TacticSpec>::load(spec)
+        .inspect_err(|err| {
+            if FAILED
+                .lock()
+                .is_ok_and(|mut failed| failed.insert(spec.to_owned()))
+            {
+                error!(?err, "Failed to load tactic {spec}");
+            }
+        })
+        .ok()
+}
+
 impl AgentData<'_> {
     ////////////////////////////////////////
     // Action Nodes
@@ -1562,6 +1586,19 @@ impl AgentData<'_> {
             angle_xy,
         };
 
+        // Data-driven tactics take precedence over the hard-coded ones
+        if let Some(tactic) = agent.tactic.as_deref().and_then(load_tactic) {
+            self.handle_data_driven_attack(
+                agent,
+                controller,
+                &attack_data,
+                tgt_data,
+                read_data,
+                &tactic.read().0,
+            );
+            return;
+        }
+
         // Match on tactic. Each tactic has different controls depending on the distance
         // from the agent to the target.
         match tactic {
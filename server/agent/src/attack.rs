@@ -21,6 +21,7 @@ use common::{
         sprite_summon::{self, SpriteSummonAnchor},
         utils::StageSection,
     },
+    tactic::{TacticCondition, TacticInput, TacticMovement, TacticSpec},
     terrain::Block,
     util::Dir,
     vol::ReadVol,
@@ -8577,4 +8578,152 @@ impl AgentData<'_> {
             None,
         );
     }
+
+    /// Uses a data-driven tactic, see [`common::tactic`].
+    pub fn handle_data_driven_attack(
+        &self,
+        agent: &mut Agent,
+        controller: &mut Controller,
+        attack_data: &AttackData,
+        tgt_data: &TargetData,
+        read_data: &ReadData,
+        tactic: &TacticSpec,
+    ) {
+        let holds = |condition: &TacticCondition, combat_time: f32, phase_time: f32| {
+            self.tactic_condition_holds(
+                condition,
+                attack_data,
+                tgt_data,
+                read_data,
+                combat_time,
+                phase_time,
+            )
+        };
+        let Some((phase_spec, action)) =
+            tactic.next_action(&mut agent.combat_state, read_data.dt.0, holds)
+        else {
+            return;
+        };
+
+        if let Some(action) = action {
+            let input = match action.input {
+                TacticInput::Primary => InputKind::Primary,
+                TacticInput::Secondary => InputKind::Secondary,
+                TacticInput::Ability(i) => InputKind::Ability(i),
+            };
+            if action.at_target {
+                controller.push_action(ControlAction::StartInput {
+                    input,
+                    target_entity: None,
+                    select_pos: Some(tgt_data.pos.0),
+                });
+            } else {
+                controller.push_basic_input(input);
+            }
+        }
+
+        if action.is_some_and(|action| action.stand_still) {
+            controller.inputs.move_dir = Vec2::zero();
+            return;
+        }
+        match phase_spec.movement {
+            TacticMovement::Chase => {
+                self.path_toward_target(
+                    agent,
+                    controller,
+                    tgt_data.pos.0,
+                    read_data,
+                    Path::AtTarget,
+                    None,
+                );
+            },
+            TacticMovement::ChaseSlowly(speed) => {
+                self.path_toward_target(
+                    agent,
+                    controller,
+                    tgt_data.pos.0,
+                    read_data,
+                    Path::AtTarget,
+                    attack_data.in_min_range().then_some(speed),
+                );
+            },
+            TacticMovement::Stand => controller.inputs.move_dir = Vec2::zero(),
+            TacticMovement::KeepDistance(distance) => {
+                if attack_data.dist_sqrd < distance.powi(2) {
+                    controller.inputs.move_dir = (self.pos.0 - tgt_data.pos.0)
+                        .xy()
+                        .try_normalized()
+                        .unwrap_or_else(Vec2::zero);
+                } else {
+                    self.path_toward_target(
+                        agent,
+                        controller,
+                        tgt_data.pos.0,
+                        read_data,
+                        Path::AtTarget,
+                        None,
+                    );
+                }
+            },
+        }
+    }
+
+    fn tactic_condition_holds(
+        &self,
+        condition: &TacticCondition,
+        attack_data: &AttackData,
+        tgt_data: &TargetData,
+        read_data: &ReadData,
+        combat_time: f32,
+        phase_time: f32,
+    ) -> bool {
+        let holds = |condition| {
+            self.tactic_condition_holds(
+                condition,
+                attack_data,
+                tgt_data,
+                read_data,
+                combat_time,
+                phase_time,
+            )
+        };
+        match condition {
+            TacticCondition::TargetWithin(distance) => attack_data.dist_sqrd < distance.powi(2),
+            TacticCondition::TargetBeyond(distance) => attack_data.dist_sqrd > distance.powi(2),
+            TacticCondition::TargetWithinReach(factor) => {
+                attack_data.dist_sqrd < (factor * attack_data.min_attack_dist).powi(2)
+            },
+            TacticCondition::TargetBeyondReach(factor) => {
+                attack_data.dist_sqrd > (factor * attack_data.min_attack_dist).powi(2)
+            },
+            TacticCondition::TargetInFront(angle) => attack_data.angle < *angle,
+            TacticCondition::TargetAbove(height) => tgt_data.pos.0.z > self.pos.0.z + height,
+            TacticCondition::HealthBelow(fraction) => self
+                .health
+                .is_some_and(|health| health.fraction() < *fraction),
+            TacticCondition::HealthAbove(fraction) => self
+                .health
+                .is_some_and(|health| health.fraction() > *fraction),
+            TacticCondition::TargetHealthBelow(fraction) => tgt_data
+                .health
+                .is_some_and(|health| health.fraction() < *fraction),
+            TacticCondition::LineOfSight => entities_have_line_of_sight(
+                self.pos,
+                self.body,
+                self.scale,
+                tgt_data.pos,
+                tgt_data.body,
+                tgt_data.scale,
+                read_data,
+            ),
+            TacticCondition::ComboAtLeast(combo) => {
+                self.combo.is_some_and(|c| c.counter() >= *combo)
+            },
+            TacticCondition::EnergyAtLeast(energy) => self.energy.current() >= *energy,
+            TacticCondition::CombatTimeAbove(time) => combat_time > *time,
+            TacticCondition::PhaseTimeAbove(time) => phase_time > *time,
+            TacticCondition::Not(condition) => !holds(condition),
+            TacticCondition::Any(conditions) => conditions.iter().any(holds),
+        }
+    }
 }
@@ -22,6 +22,7 @@ use common::{
     interaction::Interactors,
     link::Is,
     mounting::{Mount, Rider, VolumeRider},
+    nav::NavGraph,
     path::TraversalConfig,
     resources::{DeltaTime, Time, TimeOfDay},
     rtsim::RtSimEntity,
@@ -56,7 +57,7 @@ pub struct AgentData<'a> {
     pub skill_set: &'a SkillSet,
     pub physics_state: &'a PhysicsState,
     pub alignment: Option<&'a Alignment>,
-    pub traversal_config: TraversalConfig,
+    pub traversal_config: TraversalConfig<'a>,
     pub scale: f32,
     pub damage: f32,
     pub light_emitter: Option<&'a LightEmitter>,
@@ -426,6 +427,7 @@ pub struct ReadData<'a> {
     pub uids: ReadStorage<'a, Uid>,
     pub groups: ReadStorage<'a, group::Group>,
     pub terrain: ReadExpect<'a, TerrainGrid>,
+    pub nav_graph: Read<'a, NavGraph>,
     pub alignments: ReadStorage<'a, Alignment>,
     pub bodies: ReadStorage<'a, Body>,
     pub is_mounts: ReadStorage<'a, Is<Mount>>,
@@ -0,0 +1,176 @@
+//! Records of the client physics updates the server rejected, and escalation
+//! of repeated violations, see [`AntiCheatSettings`].
+
+use crate::settings::AntiCheatSettings;
+use authc::Uuid;
+use chrono::{DateTime, Utc};
+use hashbrown::HashMap;
+use specs::Entity as EcsEntity;
+use std::{
+    collections::VecDeque,
+    fmt,
+    time::{Duration, Instant},
+};
+use vek::*;
+
+/// Number of violations kept per player for inspection.
+const MAX_HISTORY: usize = 32;
+
+#[derive(Clone, Copy, Debug)]
+pub enum ViolationKind {
+    TooFar { old: Vec3<f32>, new: Vec3<f32> },
+    TooFast { vel: Vec3<f32> },
+    InsideTerrain,
+}
+
+impl fmt::Display for ViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooFar { old, new } => write!(
+                f,
+                "moved {:.1} blocks, from {:.0?} to {:.0?}",
+                old.distance(*new),
+                old,
+                new
+            ),
+            Self::TooFast { vel } => {
+                write!(f, "moved at {:.1} blocks/s ({:.1?})", vel.magnitude(), vel)
+            },
+            Self::InsideTerrain => write!(f, "moved inside terrain"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Violation {
+    pub kind: ViolationKind,
+    pub time: DateTime<Utc>,
+}
+
+#[derive(Default)]
+pub struct PlayerViolations {
+    pub alias: String,
+    pub total: u64,
+    /// The most recent violations, oldest first
+    pub history: VecDeque<Violation>,
+    /// Times of the violations within the violation window
+    recent: VecDeque<Instant>,
+}
+
+impl PlayerViolations {
+    /// Number of violations within the last `window`.
+    pub fn recent(&self, window: Duration) -> usize {
+        self.recent
+            .iter()
+            .filter(|at| at.elapsed() <= window)
+            .count()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Escalation {
+    ForceServerPhysics,
+    Kick,
+}
+
+/// Resource holding the physics violations of every player since the server
+/// started.
+#[derive(Default)]
+pub struct PhysicsViolations {
+    players: HashMap<Uuid, PlayerViolations>,
+    /// Escalations decided this tick, which are applied by the server
+    /// afterwards
+    escalations: HashMap<Uuid, (EcsEntity, Escalation)>,
+}
+
+impl PhysicsViolations {
+    pub fn get(&self, uuid: &Uuid) -> Option<&PlayerViolations> { self.players.get(uuid) }
+
+    /// Records a violation and decides whether it calls for an escalation.
+    pub fn record(
+        &mut self,
+        entity: EcsEntity,
+        uuid: Uuid,
+        alias: &str,
+        kind: ViolationKind,
+        settings: &AntiCheatSettings,
+    ) {
+        let player = self.players.entry(uuid).or_default();
+        player.alias = alias.to_owned();
+        player.total += 1;
+        if player.history.len() >= MAX_HISTORY {
+            player.history.pop_front();
+        }
+        player.history.push_back(Violation {
+            kind,
+            time: Utc::now(),
+        });
+
+        let now = Instant::now();
+        while player
+            .recent
+            .front()
+            .is_some_and(|at| now.duration_since(*at) > settings.violation_window)
+        {
+            player.recent.pop_front();
+        }
+        player.recent.push_back(now);
+
+        let recent = player.recent.len();
+        let escalation = if settings.kick_after.is_some_and(|n| recent >= n) {
+            Escalation::Kick
+        } else if settings
+            .force_server_physics_after
+            .is_some_and(|n| recent >= n)
+        {
+            Escalation::ForceServerPhysics
+        } else {
+            return;
+        };
+        let entry = self.escalations.entry(uuid).or_insert((entity, escalation));
+        entry.1 = entry.1.max(escalation);
+    }
+
+    pub fn take_escalations(&mut self) -> Vec<(EcsEntity, Uuid, Escalation)> {
+        self.escalations
+            .drain()
+            .map(|(uuid, (entity, escalation))| (entity, uuid, escalation))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specs::{Builder, WorldExt};
+
+    #[test]
+    fn escalation() {
+        let mut world = specs::World::new();
+        let entity = world.create_entity().build();
+        let uuid = Uuid::from_u128(1);
+        let settings = AntiCheatSettings {
+            force_server_physics_after: Some(3),
+            kick_after: Some(5),
+            ..AntiCheatSettings::default()
+        };
+        let mut violations = PhysicsViolations::default();
+        let mut record = |n| {
+            for _ in 0..n {
+                violations.record(
+                    entity,
+                    uuid,
+                    "player",
+                    ViolationKind::InsideTerrain,
+                    &settings,
+                );
+            }
+            violations.take_escalations()
+        };
+
+        assert!(record(2).is_empty());
+        assert_eq!(record(1), [(entity, uuid, Escalation::ForceServerPhysics)]);
+        assert_eq!(record(2), [(entity, uuid, Escalation::Kick)]);
+        assert_eq!(violations.get(&uuid).unwrap().total, 5);
+    }
+}
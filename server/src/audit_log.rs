@@ -0,0 +1,225 @@
+//! Append-only log of the commands that need an admin role, see
+//! [`AuditLog`].
+//!
+//! Each command is written as a line of JSON to `audit.log` in the audit
+//! directory. Once the file grows beyond [`MAX_FILE_SIZE`] it is renamed to
+//! `audit.log.1`, shifting older files up to `audit.log.{MAX_ROTATED_FILES}`,
+//! which is removed. Records are never modified or removed otherwise.
+
+use authc::Uuid;
+use chrono::{DateTime, Utc};
+use common::comp::{AdminRole, Content};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+};
+use tracing::{info, warn};
+
+const LOG_FILE: &str = "audit.log";
+/// Size after which the log file is rotated, in bytes.
+const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+/// Number of rotated log files kept next to the current one.
+const MAX_ROTATED_FILES: usize = 5;
+/// Number of records kept in memory for queries.
+const MAX_RECENT: usize = 1000;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub time: DateTime<Utc>,
+    /// UUID of the player who ran the command
+    pub actor: Uuid,
+    /// Alias of the player when they ran the command
+    pub actor_alias: String,
+    pub role: Option<AdminRole>,
+    /// Keyword of the command
+    pub command: String,
+    /// The player or entity the command was run on, if any
+    pub target: Option<String>,
+    pub args: Vec<String>,
+    /// Why the command failed, `None` if it succeeded
+    pub error: Option<String>,
+}
+
+impl AuditRecord {
+    /// Describes the error a command returned, as the plain text or the
+    /// localization key of the message sent to the player.
+    pub fn describe_error(content: &Content) -> String {
+        content
+            .as_plain()
+            .map_or_else(|| format!("{content:?}"), str::to_owned)
+    }
+
+    /// Whether the record was made by the player with the given alias or
+    /// UUID.
+    pub fn is_by(&self, actor: &str) -> bool {
+        self.actor_alias.eq_ignore_ascii_case(actor) || self.actor.to_string() == actor
+    }
+}
+
+/// Resource writing [`AuditRecord`]s to disk and keeping the most recent ones
+/// for queries. Older records are only in the log files.
+pub struct AuditLog {
+    dir: PathBuf,
+    file: Option<File>,
+    size: u64,
+    recent: VecDeque<AuditRecord>,
+}
+
+impl AuditLog {
+    pub fn new(data_dir: &Path) -> Self {
+        let dir = data_dir.join("audit");
+        if let Err(err) = fs::create_dir_all(&dir) {
+            warn!(?err, ?dir, "Failed to create audit log directory");
+        }
+        info!("Using {:?} as the audit log path", dir);
+
+        let mut log = Self {
+            dir,
+            file: None,
+            size: 0,
+            recent: VecDeque::new(),
+        };
+        log.load_recent();
+        log.open();
+        log
+    }
+
+    fn path(&self, rotation: usize) -> PathBuf {
+        if rotation == 0 {
+            self.dir.join(LOG_FILE)
+        } else {
+            self.dir.join(format!("{LOG_FILE}.{rotation}"))
+        }
+    }
+
+    /// Loads the most recent records from the current log file.
+    fn load_recent(&mut self) {
+        let Ok(file) = File::open(self.path(0)) else {
+            return;
+        };
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            match serde_json::from_str(&line) {
+                Ok(record) => self.push_recent(record),
+                Err(err) => warn!(?err, "Skipping unreadable audit log record"),
+            }
+        }
+    }
+
+    fn open(&mut self) {
+        let path = self.path(0);
+        match OpenOptions::new().create(true).append(true).open(&path) {
+            Ok(file) => {
+                self.size = file.metadata().map_or(0, |meta| meta.len());
+                self.file = Some(file);
+            },
+            Err(err) => {
+                warn!(
+                    ?err,
+                    ?path,
+                    "Failed to open audit log, commands will not be recorded"
+                );
+                self.file = None;
+            },
+        }
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file = None;
+        let oldest = self.path(MAX_ROTATED_FILES);
+        if oldest.exists() {
+            fs::remove_file(oldest)?;
+        }
+        for rotation in (0..MAX_ROTATED_FILES).rev() {
+            let path = self.path(rotation);
+            if path.exists() {
+                fs::rename(path, self.path(rotation + 1))?;
+            }
+        }
+        self.open();
+        Ok(())
+    }
+
+    fn push_recent(&mut self, record: AuditRecord) {
+        if self.recent.len() >= MAX_RECENT {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(record);
+    }
+
+    pub fn record(&mut self, record: AuditRecord) {
+        match serde_json::to_string(&record) {
+            Ok(mut line) => {
+                line.push('\n');
+                if self.size + line.len() as u64 > MAX_FILE_SIZE
+                    && let Err(err) = self.rotate()
+                {
+                    warn!(?err, "Failed to rotate audit log");
+                }
+                match self
+                    .file
+                    .as_mut()
+                    .map(|file| file.write_all(line.as_bytes()))
+                {
+                    Some(Ok(())) => self.size += line.len() as u64,
+                    Some(Err(err)) => warn!(?err, ?record, "Failed to write audit log record"),
+                    None => warn!(?record, "Audit log is not open, record is not persisted"),
+                }
+            },
+            Err(err) => warn!(?err, ?record, "Failed to serialize audit log record"),
+        }
+        self.push_recent(record);
+    }
+
+    /// Returns up to `max` of the most recent records, newest first, only
+    /// those by `actor` if given.
+    pub fn query(&self, actor: Option<&str>, max: usize) -> Vec<AuditRecord> {
+        self.recent
+            .iter()
+            .rev()
+            .filter(|record| actor.is_none_or(|actor| record.is_by(actor)))
+            .take(max)
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(alias: &str, command: &str) -> AuditRecord {
+        AuditRecord {
+            time: Utc::now(),
+            actor: Uuid::from_u128(1),
+            actor_alias: alias.to_owned(),
+            role: Some(AdminRole::Admin),
+            command: command.to_owned(),
+            target: None,
+            args: Vec::new(),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn record_and_reload() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let mut log = AuditLog::new(data_dir.path());
+        log.record(record("alice", "kick"));
+        log.record(record("bob", "ban"));
+        log.record(record("alice", "tp"));
+
+        let alice = log.query(Some("Alice"), 10);
+        assert_eq!(
+            alice.iter().map(|r| r.command.as_str()).collect::<Vec<_>>(),
+            ["tp", "kick"]
+        );
+        drop(log);
+
+        let log = AuditLog::new(data_dir.path());
+        assert_eq!(log.query(None, 2).len(), 2);
+        assert_eq!(log.query(None, 10)[2].command, "kick");
+    }
+}
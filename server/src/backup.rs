@@ -0,0 +1,416 @@
+//! Point-in-time snapshots of the persisted server state.
+//!
+//! A snapshot holds the character database, the rtsim data and, when terrain
+//! persistence is enabled, the persisted terrain, all as of the same tick:
+//!
+//! - Online characters are persisted and the database is then copied with
+//!   SQLite's online backup API on the [`CharacterUpdater`] thread. As the
+//!   thread handles requests in order, the copy includes that last batch.
+//! - The rtsim data is cloned during the tick.
+//! - Modified terrain chunks are written back and hard linked into the
+//!   snapshot. Chunk files are always replaced rather than modified, so the
+//!   links keep their contents.
+//!
+//! Each snapshot is a directory named after the time it was taken, and is
+//! complete once its manifest was written. Restoring a snapshot replaces the
+//! current state, so it is only possible while the server isn't running, see
+//! [`restore`].
+//!
+//! [`CharacterUpdater`]: crate::persistence::character_updater::CharacterUpdater
+
+use crate::{
+    persistence::error::PersistenceError,
+    rtsim::RtSim,
+    settings::{BackupRetention, BackupSettings},
+};
+use chrono::{DateTime, Datelike, NaiveDateTime, Utc};
+use crossbeam_channel::{Receiver, Sender};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::Instant,
+};
+use tracing::{info, warn};
+
+const MANIFEST_FILE: &str = "manifest.ron";
+const DATABASE_FILE: &str = "db.sqlite";
+const RTSIM_FILE: &str = "rtsim.dat";
+const TERRAIN_DIR: &str = "terrain";
+
+/// Format of the snapshot directory names, which sort chronologically.
+const NAME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+#[derive(Serialize, Deserialize)]
+pub struct Manifest {
+    pub time: DateTime<Utc>,
+    /// Version of the game the snapshot was taken with.
+    pub game_version: String,
+    pub tick: u64,
+    pub rtsim: bool,
+    pub terrain: bool,
+}
+
+/// Takes snapshots and keeps track of the one being taken, there is at most
+/// one at a time.
+pub struct Backups {
+    settings: BackupSettings,
+    dir: PathBuf,
+    last_snapshot: Instant,
+    in_progress: bool,
+    outcome_tx: Sender<Result<String, String>>,
+    outcome_rx: Receiver<Result<String, String>>,
+}
+
+/// A snapshot being taken, see [`Backups::begin`].
+pub struct Snapshot {
+    time: DateTime<Utc>,
+    dir: PathBuf,
+}
+
+impl Snapshot {
+    /// The file the database has to be copied to.
+    pub fn database_path(&self) -> PathBuf { self.dir.join(DATABASE_FILE) }
+}
+
+impl Backups {
+    pub fn new(settings: BackupSettings, data_dir: &Path) -> Self {
+        let (outcome_tx, outcome_rx) = crossbeam_channel::unbounded();
+        Self {
+            dir: data_dir.join(&settings.directory),
+            settings,
+            last_snapshot: Instant::now(),
+            in_progress: false,
+            outcome_tx,
+            outcome_rx,
+        }
+    }
+
+    /// Whether a scheduled snapshot should be taken now.
+    pub fn is_due(&self) -> bool {
+        self.settings.enabled
+            && !self.in_progress
+            && self.last_snapshot.elapsed() >= self.settings.interval
+    }
+
+    /// Starts taking a snapshot by creating its directory.
+    pub fn begin(&mut self) -> Result<Snapshot, String> {
+        if self.in_progress {
+            return Err("A snapshot is already being taken".to_owned());
+        }
+
+        let time = Utc::now();
+        let dir = self.dir.join(time.format(NAME_FORMAT).to_string());
+        fs::create_dir_all(&dir)
+            .map_err(|err| format!("Failed to create {}: {}", dir.display(), err))?;
+
+        self.in_progress = true;
+        self.last_snapshot = Instant::now();
+        Ok(Snapshot { time, dir })
+    }
+
+    /// Finishes a snapshot in the background once the database was copied.
+    ///
+    /// The terrain is linked into the snapshot right away, so all of its
+    /// modifications have to be written back before calling this.
+    pub fn finish(
+        &self,
+        snapshot: Snapshot,
+        tick: u64,
+        database: Receiver<Result<(), PersistenceError>>,
+        rtsim: Option<rtsim::data::Data>,
+        terrain_dir: Option<&Path>,
+    ) {
+        let terrain = match terrain_dir {
+            Some(terrain_dir) => link_dir(terrain_dir, &snapshot.dir.join(TERRAIN_DIR))
+                .map(|()| true)
+                .map_err(|err| format!("Failed to link terrain: {}", err)),
+            None => Ok(false),
+        };
+
+        let outcome_tx = self.outcome_tx.clone();
+        let backups_dir = self.dir.clone();
+        let retention = self.settings.retention.clone();
+        let spawned = thread::Builder::new()
+            .name("snapshot".into())
+            .spawn(move || {
+                let result =
+                    write_snapshot(&snapshot, tick, database, rtsim, terrain).and_then(|()| {
+                        let removed = apply_retention(&backups_dir, &retention)
+                            .map_err(|err| format!("Failed to apply retention rules: {}", err))?;
+                        Ok(format!(
+                            "Snapshot {} taken, removed {} old snapshots",
+                            snapshot.dir.display(),
+                            removed
+                        ))
+                    });
+                if result.is_err()
+                    && let Err(err) = fs::remove_dir_all(&snapshot.dir)
+                {
+                    warn!(?err, "Failed to remove incomplete snapshot");
+                }
+                let _ = outcome_tx.send(result);
+            });
+        if let Err(err) = spawned {
+            let _ = self
+                .outcome_tx
+                .send(Err(format!("Failed to spawn snapshot thread: {}", err)));
+        }
+    }
+
+    /// Outcomes of the snapshots finished since the last call.
+    pub fn finished(&mut self) -> Vec<Result<String, String>> {
+        let outcomes = self.outcome_rx.try_iter().collect::<Vec<_>>();
+        if !outcomes.is_empty() {
+            self.in_progress = false;
+        }
+        outcomes
+    }
+}
+
+fn write_snapshot(
+    snapshot: &Snapshot,
+    tick: u64,
+    database: Receiver<Result<(), PersistenceError>>,
+    rtsim: Option<rtsim::data::Data>,
+    terrain: Result<bool, String>,
+) -> Result<(), String> {
+    let terrain = terrain?;
+
+    if let Some(rtsim) = &rtsim {
+        let mut writer = fs::File::create(snapshot.dir.join(RTSIM_FILE))
+            .map(io::BufWriter::new)
+            .map_err(|err| format!("Failed to create rtsim data file: {}", err))?;
+        rtsim
+            .write_to(&mut writer)
+            .map_err(|err| format!("Failed to write rtsim data: {:?}", err))?;
+        writer
+            .flush()
+            .map_err(|err| format!("Failed to write rtsim data: {}", err))?;
+    }
+
+    database
+        .recv()
+        .map_err(|_| "The database was not copied".to_owned())?
+        .map_err(|err| format!("Failed to copy the database: {}", err))?;
+
+    let manifest = Manifest {
+        time: snapshot.time,
+        game_version: common::util::DISPLAY_VERSION_LONG.clone(),
+        tick,
+        rtsim: rtsim.is_some(),
+        terrain,
+    };
+    let contents = ron::ser::to_string_pretty(&manifest, ron::ser::PrettyConfig::default())
+        .map_err(|err| format!("Failed to serialize manifest: {}", err))?;
+    fs::write(snapshot.dir.join(MANIFEST_FILE), contents)
+        .map_err(|err| format!("Failed to write manifest: {}", err))
+}
+
+fn read_manifest(snapshot_dir: &Path) -> Result<Manifest, String> {
+    let contents = fs::read_to_string(snapshot_dir.join(MANIFEST_FILE))
+        .map_err(|err| format!("Failed to read manifest: {}", err))?;
+    ron::from_str(&contents).map_err(|err| format!("Invalid manifest: {}", err))
+}
+
+/// Whether a directory name is that of a snapshot, complete or not.
+fn is_snapshot_name(name: &str) -> bool { NaiveDateTime::parse_from_str(name, NAME_FORMAT).is_ok() }
+
+/// Lists the complete snapshots, newest first.
+pub fn list(settings: &BackupSettings, data_dir: &Path) -> io::Result<Vec<(String, Manifest)>> {
+    let dir = data_dir.join(&settings.directory);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut snapshots = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if is_snapshot_name(&name)
+            && let Ok(manifest) = read_manifest(&entry.path())
+        {
+            snapshots.push((name, manifest));
+        }
+    }
+    snapshots.sort_by(|(_, a), (_, b)| b.time.cmp(&a.time));
+    Ok(snapshots)
+}
+
+/// Removes the snapshots that none of the retention rules keep, returning how
+/// many were removed. This also removes incomplete snapshots, as no other
+/// snapshot is taken meanwhile.
+fn apply_retention(dir: &Path, retention: &BackupRetention) -> io::Result<usize> {
+    let mut snapshots = Vec::new();
+    let mut removed = 0;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if !is_snapshot_name(&entry.file_name().to_string_lossy()) {
+            continue;
+        }
+        match read_manifest(&entry.path()) {
+            Ok(manifest) => snapshots.push((manifest.time, entry.path())),
+            Err(_) => {
+                fs::remove_dir_all(entry.path())?;
+                removed += 1;
+            },
+        }
+    }
+    snapshots.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+    let times = snapshots.iter().map(|(time, _)| *time).collect::<Vec<_>>();
+    for ((_, path), keep) in snapshots.iter().zip(retained(&times, retention)) {
+        if !keep {
+            fs::remove_dir_all(path)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Which of the snapshots taken at `times`, newest first, are kept.
+fn retained(times: &[DateTime<Utc>], retention: &BackupRetention) -> Vec<bool> {
+    let mut days = HashSet::new();
+    let mut weeks = HashSet::new();
+    times
+        .iter()
+        .enumerate()
+        .map(|(i, time)| {
+            let week = time.iso_week();
+            // As the snapshots are ordered newest first, the first one of every day and
+            // week is its most recent one
+            let newest_of_day = days.len() < retention.keep_daily && days.insert(time.date_naive());
+            let newest_of_week =
+                weeks.len() < retention.keep_weekly && weeks.insert((week.year(), week.week()));
+            i < retention.keep_last || newest_of_day || newest_of_week
+        })
+        .collect()
+}
+
+/// Links every file in `from` into `to`, copying them where they can't be
+/// linked (for example across file systems).
+fn link_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            let target = to.join(entry.file_name());
+            if fs::hard_link(entry.path(), &target).is_err() {
+                fs::copy(entry.path(), &target)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the persisted state with that of a snapshot. This must only be
+/// done while the server isn't running.
+///
+/// The replaced state is moved to a `replaced_*` directory next to the
+/// snapshots, so that it can be recovered if needed.
+pub fn restore(
+    settings: &BackupSettings,
+    data_dir: &Path,
+    db_dir: &Path,
+    name: &str,
+) -> Result<(), String> {
+    if !is_snapshot_name(name) {
+        return Err(format!("{} is not the name of a snapshot", name));
+    }
+    let backups_dir = data_dir.join(&settings.directory);
+    let snapshot_dir = backups_dir.join(name);
+    let manifest = read_manifest(&snapshot_dir)?;
+
+    let replaced_dir = backups_dir.join(format!("replaced_{}", Utc::now().format(NAME_FORMAT)));
+    fs::create_dir_all(&replaced_dir)
+        .map_err(|err| format!("Failed to create {}: {}", replaced_dir.display(), err))?;
+    let set_aside = |path: &Path| -> Result<(), String> {
+        if !path.exists() {
+            return Ok(());
+        }
+        let target = replaced_dir.join(path.file_name().unwrap_or_default());
+        fs::rename(path, &target).map_err(|err| {
+            format!(
+                "Failed to move {} to {}: {}",
+                path.display(),
+                target.display(),
+                err
+            )
+        })
+    };
+
+    // The write-ahead log belongs to the replaced database, and would corrupt the
+    // restored one if it was left in place
+    for file in ["", "-wal", "-shm"] {
+        set_aside(&db_dir.join(format!("{}{}", DATABASE_FILE, file)))?;
+    }
+    fs::create_dir_all(db_dir).map_err(|err| err.to_string())?;
+    fs::copy(snapshot_dir.join(DATABASE_FILE), db_dir.join(DATABASE_FILE))
+        .map_err(|err| format!("Failed to restore the database: {}", err))?;
+
+    if manifest.rtsim {
+        let rtsim_path = RtSim::get_file_path(data_dir.to_owned());
+        set_aside(&rtsim_path)?;
+        if let Some(dir) = rtsim_path.parent() {
+            fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+        fs::copy(snapshot_dir.join(RTSIM_FILE), &rtsim_path)
+            .map_err(|err| format!("Failed to restore rtsim data: {}", err))?;
+    }
+
+    if manifest.terrain {
+        #[cfg(feature = "persistent_world")]
+        {
+            let terrain_dir =
+                crate::terrain_persistence::TerrainPersistence::dir(data_dir.to_owned());
+            set_aside(&terrain_dir)?;
+            link_dir(&snapshot_dir.join(TERRAIN_DIR), &terrain_dir)
+                .map_err(|err| format!("Failed to restore terrain: {}", err))?;
+        }
+        #[cfg(not(feature = "persistent_world"))]
+        warn!("The snapshot contains terrain, but terrain persistence is not compiled in");
+    }
+
+    info!(
+        "Restored snapshot {} taken at tick {} with {}, the replaced state was moved to {}",
+        name,
+        manifest.tick,
+        manifest.game_version,
+        replaced_dir.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn retention_rules() {
+        let retention = BackupRetention {
+            keep_last: 2,
+            keep_daily: 2,
+            keep_weekly: 2,
+        };
+        // Newest first: two on Wednesday, one on Tuesday, one each in the two weeks
+        // before
+        let times = [
+            (2024, 5, 15, 18),
+            (2024, 5, 15, 12),
+            (2024, 5, 15, 6),
+            (2024, 5, 14, 18),
+            (2024, 5, 14, 6),
+            (2024, 5, 8, 12),
+            (2024, 5, 1, 12),
+        ]
+        .map(|(y, m, d, h)| Utc.with_ymd_and_hms(y, m, d, h, 0, 0).unwrap());
+
+        assert_eq!(retained(&times, &retention), [
+            true, true, false, true, false, true, false
+        ]);
+    }
+}
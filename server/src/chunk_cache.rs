@@ -0,0 +1,172 @@
+//! An on-disk cache of the base terrain of generated chunks, see
+//! [`BaseChunkCache`].
+//!
+//! Entries are only valid for the world they were generated in and for the
+//! code that generated them, so each cache lives in a directory named after the
+//! world seed, a hash of the map and the git hash of the server. Caches with
+//! any other name are removed when the server starts. Within a cache, chunks
+//! generated during calendar events are kept apart from the others, as events
+//! change the terrain.
+
+use atomicwrites::{AtomicFile, OverwriteBehavior};
+use bincode::{
+    config::legacy,
+    serde::{decode_from_slice, encode_to_vec},
+};
+use common::{calendar::Calendar, terrain::TerrainChunk, util::GIT_HASH};
+use common_net::msg::{CompressedData, WorldMapMsg};
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+use tracing::{debug, info, warn};
+use vek::*;
+use world::BaseChunkCache;
+
+/// Deflate level used for cached chunks. They are written once and read many
+/// times, so this favours size over speed more than chunks sent to clients.
+const COMPRESSION_LEVEL: u32 = 5;
+
+pub struct ChunkCache {
+    path: PathBuf,
+}
+
+impl ChunkCache {
+    /// Opens the cache of the given world in `data_dir`, removing the caches of
+    /// other worlds and versions.
+    pub fn new(data_dir: &Path, seed: u32, map: &WorldMapMsg) -> Self {
+        let root = Self::dir(data_dir);
+        let name = Self::cache_name(seed, map);
+
+        if let Ok(entries) = fs::read_dir(&root) {
+            for entry in entries.flatten() {
+                if entry.file_name() != name.as_str() {
+                    info!(path = ?entry.path(), "Removing outdated chunk cache");
+                    if let Err(err) = fs::remove_dir_all(entry.path()) {
+                        warn!(?err, path = ?entry.path(), "Failed to remove outdated chunk cache");
+                    }
+                }
+            }
+        }
+
+        let path = root.join(name);
+        if let Err(err) = fs::create_dir_all(&path) {
+            warn!(?err, ?path, "Failed to create chunk cache directory");
+        }
+        info!("Using {:?} as the chunk cache path", path);
+
+        Self { path }
+    }
+
+    /// The directory chunk caches are stored in for the given data directory.
+    pub fn dir(data_dir: &Path) -> PathBuf { data_dir.join("chunk_cache") }
+
+    fn cache_name(seed: u32, map: &WorldMapMsg) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(map.dimensions_lg.x.to_le_bytes());
+        hasher.update(map.dimensions_lg.y.to_le_bytes());
+        for alt in map.alt.raw() {
+            hasher.update(alt.to_le_bytes());
+        }
+        let map_hash = hex::encode(&hasher.finalize()[..8]);
+        format!("{seed:08x}-{map_hash}-{}", *GIT_HASH)
+    }
+
+    fn path_for(&self, chunk_pos: Vec2<i32>, calendar: Option<&Calendar>) -> PathBuf {
+        let mut events = calendar
+            .into_iter()
+            .flat_map(Calendar::events)
+            .copied()
+            .collect::<Vec<_>>();
+        events.sort_by_key(|event| *event as u16);
+        let events = if events.is_empty() {
+            "default".to_string()
+        } else {
+            events
+                .iter()
+                .map(|event| format!("{event:?}").to_lowercase())
+                .collect::<Vec<_>>()
+                .join("_")
+        };
+        self.path
+            .join(events)
+            .join(format!("chunk_{}_{}.dat", chunk_pos.x, chunk_pos.y))
+    }
+}
+
+impl BaseChunkCache for ChunkCache {
+    fn load(&self, chunk_pos: Vec2<i32>, calendar: Option<&Calendar>) -> Option<TerrainChunk> {
+        let path = self.path_for(chunk_pos, calendar);
+        let bytes = fs::read(&path).ok()?;
+        let chunk = decode_from_slice::<CompressedData<TerrainChunk>, _>(&bytes, legacy())
+            .ok()
+            .and_then(|(data, _)| data.decompress());
+        if chunk.is_none() {
+            // The chunk is generated and stored again
+            debug!(?path, "Failed to decode cached chunk");
+        }
+        chunk
+    }
+
+    fn store(&self, chunk_pos: Vec2<i32>, calendar: Option<&Calendar>, chunk: &TerrainChunk) {
+        let path = self.path_for(chunk_pos, calendar);
+        if let Some(dir) = path.parent()
+            && let Err(err) = fs::create_dir_all(dir)
+        {
+            warn!(?err, ?dir, "Failed to create chunk cache directory");
+            return;
+        }
+
+        let data = CompressedData::compress(chunk, COMPRESSION_LEVEL);
+        let bytes = match encode_to_vec(&data, legacy()) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                warn!(?err, "Failed to encode chunk for the chunk cache");
+                return;
+            },
+        };
+        let atomic_file = AtomicFile::new(&path, OverwriteBehavior::AllowOverwrite);
+        if let Err(err) = atomic_file.write(|file| file.write_all(&bytes)) {
+            warn!(?err, ?path, "Failed to write chunk to the chunk cache");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{
+        calendar::CalendarEvent,
+        terrain::{Block, BlockKind, SpriteKind, TerrainChunkMeta},
+        vol::{ReadVol, WriteVol},
+    };
+
+    #[test]
+    fn round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ChunkCache {
+            path: dir.path().to_owned(),
+        };
+
+        let mut chunk = TerrainChunk::new(
+            10,
+            Block::new(BlockKind::Rock, Rgb::zero()),
+            Block::air(SpriteKind::Empty),
+            TerrainChunkMeta::void(),
+        );
+        let pos = Vec3::new(3, 4, 12);
+        chunk
+            .set(pos, Block::new(BlockKind::Wood, Rgb::new(1, 2, 3)))
+            .unwrap();
+
+        let key = Vec2::new(-2, 5);
+        let christmas = Calendar::from_events(vec![CalendarEvent::Christmas]);
+        cache.store(key, None, &chunk);
+        assert!(cache.load(key, Some(&christmas)).is_none());
+        let loaded = cache.load(key, None).unwrap();
+        assert_eq!(loaded.get(pos).ok(), chunk.get(pos).ok());
+        assert_eq!(loaded.get_min_z(), chunk.get_min_z());
+    }
+}
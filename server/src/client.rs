@@ -124,6 +124,8 @@ impl Client {
                     | ServerGeneral::Knockback(_)
                     | ServerGeneral::UpdatePendingTrade(_, _, _)
                     | ServerGeneral::FinishedTrade(_)
+                    | ServerGeneral::UpdateStorage(_)
+                    | ServerGeneral::UpdateReputation(_)
                     | ServerGeneral::WeatherUpdate(_) => {
                         self.in_game_stream.lock().unwrap().send(g)
                     },
@@ -198,6 +200,8 @@ impl Client {
                     | ServerGeneral::SiteEconomy(_)
                     | ServerGeneral::UpdatePendingTrade(_, _, _)
                     | ServerGeneral::FinishedTrade(_)
+                    | ServerGeneral::UpdateStorage(_)
+                    | ServerGeneral::UpdateReputation(_)
                     | ServerGeneral::MapMarker(_)
                     | ServerGeneral::WeatherUpdate(_)
                     | ServerGeneral::LocalWindUpdate(_)
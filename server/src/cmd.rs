@@ -5,12 +5,15 @@
 use crate::weather::WeatherJob;
 use crate::{
     Server, Settings, StateExt,
+    anti_cheat::PhysicsViolations,
+    audit_log::{AuditLog, AuditRecord},
     client::Client,
+    graveyard::Graveyard,
     location::Locations,
     login_provider::LoginProvider,
     settings::{
-        BanInfo, BanOperation, BanOperationError, EditableSetting, SettingError, WhitelistInfo,
-        WhitelistRecord,
+        BanInfo, BanOperation, BanOperationError, EditableSetting, Mute, MuteAction, MuteInfo,
+        SettingError, WhitelistInfo, WhitelistRecord,
         banlist::{BanAction, NormalizedIpAddr},
         server_description::ServerDescription,
         server_physics::ServerPhysicsForceRecord,
@@ -28,8 +31,9 @@ use common::{
     CachedSpatialGrid, Damage, DamageKind, DamageSource, Explosion, GroupTarget, LoadoutBuilder,
     RadiusEffect, assets,
     calendar::Calendar,
+    character::CharacterId,
     cmd::{
-        AreaKind, BUFF_PACK, BUFF_PARSER, EntityTarget, KIT_MANIFEST_PATH, KitSpec,
+        AreaKind, ArgumentSpec, BUFF_PACK, BUFF_PARSER, EntityTarget, KIT_MANIFEST_PATH, KitSpec,
         PRESET_MANIFEST_PATH, ServerChatCommand,
     },
     combat,
@@ -134,8 +138,9 @@ fn do_command(
     args: Vec<String>,
     cmd: &ServerChatCommand,
 ) -> CmdResult<()> {
-    // Make sure your role is at least high enough to execute this command.
-    if cmd.needs_role() > server.entity_admin_role(client) {
+    // Make sure your role is at least high enough to execute this command, or that
+    // one of your permission groups allows it.
+    if cmd.needs_role() > server.entity_admin_role(client) && !group_allows(server, client, cmd) {
         return Err(Content::localized_with_args("command-no-permission", [(
             "command_name",
             cmd.keyword(),
@@ -149,6 +154,7 @@ fn do_command(
         ServerChatCommand::AreaAdd => handle_area_add,
         ServerChatCommand::AreaList => handle_area_list,
         ServerChatCommand::AreaRemove => handle_area_remove,
+        ServerChatCommand::AuditLog => handle_audit_log,
         ServerChatCommand::Aura => handle_aura,
         ServerChatCommand::Ban => handle_ban,
         ServerChatCommand::BanIp => handle_ban_ip,
@@ -159,6 +165,8 @@ fn do_command(
         ServerChatCommand::Buff => handle_buff,
         ServerChatCommand::Build => handle_build,
         ServerChatCommand::Campfire => handle_spawn_campfire,
+        ServerChatCommand::ChatMute => handle_chat_mute,
+        ServerChatCommand::ChatUnmute => handle_chat_unmute,
         ServerChatCommand::ClearPersistedTerrain => handle_clear_persisted_terrain,
         ServerChatCommand::DeathEffect => handle_death_effect,
         ServerChatCommand::DebugColumn => handle_debug_column,
@@ -167,18 +175,21 @@ fn do_command(
         ServerChatCommand::DropAll => handle_drop_all,
         ServerChatCommand::Dummy => handle_spawn_training_dummy,
         ServerChatCommand::Explosion => handle_explosion,
+        ServerChatCommand::ExportCharacter => handle_export_character,
         ServerChatCommand::Faction => handle_faction,
         ServerChatCommand::GiveItem => handle_give_item,
         ServerChatCommand::Gizmos => handle_gizmos,
         ServerChatCommand::GizmosRange => handle_gizmos_range,
         ServerChatCommand::Goto => handle_goto,
         ServerChatCommand::GotoRand => handle_goto_rand,
+        ServerChatCommand::Graveyard => handle_graveyard,
         ServerChatCommand::Group => handle_group,
         ServerChatCommand::GroupInvite => handle_group_invite,
         ServerChatCommand::GroupKick => handle_group_kick,
         ServerChatCommand::GroupLeave => handle_group_leave,
         ServerChatCommand::GroupPromote => handle_group_promote,
         ServerChatCommand::Health => handle_health,
+        ServerChatCommand::ImportCharacter => handle_import_character,
         ServerChatCommand::IntoNpc => handle_into_npc,
         ServerChatCommand::JoinFaction => handle_join_faction,
         ServerChatCommand::Jump => handle_jump,
@@ -195,6 +206,7 @@ fn do_command(
         ServerChatCommand::Object => handle_object,
         ServerChatCommand::Outcome => handle_outcome,
         ServerChatCommand::PermitBuild => handle_permit_build,
+        ServerChatCommand::PhysicsViolations => handle_physics_violations,
         ServerChatCommand::Players => handle_players,
         ServerChatCommand::Poise => handle_poise,
         ServerChatCommand::Portal => handle_spawn_portal,
@@ -205,6 +217,7 @@ fn do_command(
         ServerChatCommand::Respawn => handle_respawn,
         ServerChatCommand::RevokeBuild => handle_revoke_build,
         ServerChatCommand::RevokeBuildAll => handle_revoke_build_all,
+        ServerChatCommand::Route => handle_route,
         ServerChatCommand::Safezone => handle_safezone,
         ServerChatCommand::Say => handle_say,
         ServerChatCommand::ServerPhysics => handle_server_physics,
@@ -247,7 +260,70 @@ fn do_command(
         ServerChatCommand::Dismount => handle_dismount,
     };
 
-    handler(server, client, target, args, cmd)
+    // Commands that need a role are recorded in the audit log
+    if cmd.needs_role().is_none() {
+        return handler(server, client, target, args, cmd);
+    }
+    let record_args = args.clone();
+    let result = handler(server, client, target, args, cmd);
+    record_command(server, client, target, cmd, record_args, &result);
+    result
+}
+
+fn group_allows(server: &Server, client: EcsEntity, cmd: &ServerChatCommand) -> bool {
+    server
+        .state
+        .read_component_cloned::<comp::Player>(client)
+        .is_some_and(|player| {
+            server
+                .editable_settings()
+                .permission_groups
+                .allows(&player.uuid(), cmd)
+        })
+}
+
+fn record_command(
+    server: &mut Server,
+    client: EcsEntity,
+    target: EcsEntity,
+    cmd: &ServerChatCommand,
+    args: Vec<String>,
+    result: &CmdResult<()>,
+) {
+    let (actor, actor_alias) = server
+        .state
+        .read_component_cloned::<comp::Player>(client)
+        .map(|player| (player.uuid(), player.alias))
+        .unwrap_or_default();
+    let target = if target != client {
+        Some(
+            server
+                .state
+                .read_component_cloned::<comp::Player>(target)
+                .map_or_else(|| format!("{target:?}"), |player| player.alias),
+        )
+    } else if let Some(ArgumentSpec::PlayerName(_) | ArgumentSpec::EntityTarget(_)) =
+        cmd.data().args.first()
+    {
+        args.first().cloned()
+    } else {
+        None
+    };
+    let record = AuditRecord {
+        time: Utc::now(),
+        actor,
+        actor_alias,
+        role: server.entity_admin_role(client),
+        command: cmd.keyword().to_owned(),
+        target,
+        args,
+        error: result.as_ref().err().map(AuditRecord::describe_error),
+    };
+    server
+        .state
+        .ecs()
+        .write_resource::<AuditLog>()
+        .record(record);
 }
 
 // Fallibly get position of entity with the given descriptor (used for error
@@ -1459,6 +1535,104 @@ fn handle_site(
     }
 }
 
+#[cfg(not(feature = "worldgen"))]
+fn handle_route(
+    _server: &mut Server,
+    _client: EcsEntity,
+    _target: EcsEntity,
+    _args: Vec<String>,
+    _action: &ServerChatCommand,
+) -> CmdResult<()> {
+    Err(Content::Plain(
+        "Unsupported without worldgen enabled".into(),
+    ))
+}
+
+/// When each player last searched for a route, see [`handle_route`].
+#[cfg(feature = "worldgen")]
+#[derive(Default)]
+struct RouteSearches(HashMap<Uuid, f64>);
+
+#[cfg(feature = "worldgen")]
+fn handle_route(
+    server: &mut Server,
+    client: EcsEntity,
+    target: EcsEntity,
+    args: Vec<String>,
+    action: &ServerChatCommand,
+) -> CmdResult<()> {
+    use common::{comp::compass::Direction, terrain::TerrainChunkSize, vol::RectVolSize};
+    use world::pathfinding::Searcher;
+
+    /// The direction given is the one towards the route a few chunks ahead, so
+    /// that it isn't thrown off by small turns
+    const HEADING_LOOKAHEAD: usize = 4;
+    /// Seconds a player has to wait between searches, since they are expensive
+    const ROUTE_COOLDOWN: f64 = 10.0;
+
+    let Some(dest_name) = parse_cmd_args!(args, String) else {
+        return Err(action.help_content());
+    };
+
+    let uuid = uuid(server, client, "client")?;
+    let now = server.state.get_program_time();
+    {
+        let mut searches = server
+            .state
+            .ecs_mut()
+            .entry::<RouteSearches>()
+            .or_insert_with(Default::default);
+        if let Some(last) = searches.0.get(&uuid)
+            && now - last < ROUTE_COOLDOWN
+        {
+            return Err(Content::localized_with_args("command-route-cooldown", [(
+                "seconds",
+                LocalizationArg::from(format!("{:.0}", (ROUTE_COOLDOWN - (now - last)).ceil())),
+            )]));
+        }
+        searches.0.insert(uuid, now);
+    }
+    let (site, _) = resolve_site(server, &dest_name)?;
+    let site_pos = server.index.sites.get(site).origin;
+    let pos = position(server, target, "target")?.0.xy().as_::<i32>();
+
+    let route = Searcher::new(server.world.sim())
+        .search(pos.wpos_to_cpos(), site_pos.wpos_to_cpos())
+        .ok_or_else(|| Content::localized("command-route-not-found"))?;
+
+    let distance = route
+        .nodes()
+        .windows(2)
+        .map(|pair| pair[0].as_::<f32>().distance(pair[1].as_()))
+        .sum::<f32>()
+        * TerrainChunkSize::RECT_SIZE.x as f32;
+    let heading = route
+        .nodes()
+        .get(HEADING_LOOKAHEAD)
+        .or(route.end())
+        .map_or(site_pos, |chunk| chunk.cpos_to_wpos_center());
+    let direction = Direction::from_dir((heading - pos).as_());
+
+    server.notify_client(
+        client,
+        ServerGeneral::server_msg(
+            ChatType::CommandInfo,
+            Content::localized_with_args("command-route", [
+                ("site", LocalizationArg::from(dest_name)),
+                (
+                    "distance",
+                    LocalizationArg::from(format!("{:.1}", distance / 1000.0)),
+                ),
+                (
+                    "direction",
+                    LocalizationArg::from(direction.name().to_lowercase()),
+                ),
+            ]),
+        ),
+    );
+    Ok(())
+}
+
 fn handle_respawn(
     server: &mut Server,
     _client: EcsEntity,
@@ -1982,11 +2156,36 @@ fn handle_rtsim_info(
         let _ = writeln!(&mut info, "Pos: {:?}", npc.wpos);
         let _ = writeln!(&mut info, "Role: {:?}", npc.role);
         let _ = writeln!(&mut info, "Home: {:?}", npc.home);
+        let _ = writeln!(&mut info, "House: {:?}", npc.house);
+        if let Some(age) = npc.age(data.time_of_day) {
+            let _ = writeln!(&mut info, "Age: {:.1} days", age / (60.0 * 60.0 * 24.0));
+        }
         let _ = writeln!(&mut info, "Faction: {:?}", npc.faction);
         let _ = writeln!(&mut info, "Personality: {:?}", npc.personality);
+        if let Some(faction) = npc.faction {
+            let _ = writeln!(&mut info, "-- Faction Relations --");
+            for (other, diplomacy) in data.factions.relations_of(faction) {
+                let _ = writeln!(
+                    &mut info,
+                    "{:?}: {:?} (since {:.0}s ago)",
+                    other,
+                    diplomacy.relation,
+                    data.time_of_day.0 - diplomacy.since.0,
+                );
+            }
+        }
         let _ = writeln!(&mut info, "-- Status --");
         let _ = writeln!(&mut info, "Current site: {:?}", npc.current_site);
         let _ = writeln!(&mut info, "Current mode: {:?}", npc.mode);
+        if let Some((activity, wpos)) = rtsim::rule::npc_ai::schedule::whereabouts(
+            &server.world,
+            server.index.as_index_ref(),
+            &data,
+            npc,
+            data.time_of_day,
+        ) {
+            let _ = writeln!(&mut info, "Scheduled: {:?} at {:?}", activity, wpos);
+        }
         let _ = writeln!(
             &mut info,
             "Riding: {:?}",
@@ -2642,6 +2841,36 @@ fn handle_clear_persisted_terrain(
     ))
 }
 
+fn handle_export_character(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    args: Vec<String>,
+    action: &ServerChatCommand,
+) -> CmdResult<()> {
+    let Some(character_id) = parse_cmd_args!(args, i64) else {
+        return Err(action.help_content());
+    };
+    server.export_character(Some(client), CharacterId(character_id));
+    Ok(())
+}
+
+fn handle_import_character(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    args: Vec<String>,
+    action: &ServerChatCommand,
+) -> CmdResult<()> {
+    let (Some(username), Some(file_name), unsigned) = parse_cmd_args!(args, String, String, String)
+    else {
+        return Err(action.help_content());
+    };
+    let allow_unsigned = unsigned.is_some_and(|flag| flag == "--unsigned");
+    server.import_character(Some(client), &username, file_name, allow_unsigned);
+    Ok(())
+}
+
 fn handle_safezone(
     server: &mut Server,
     client: EcsEntity,
@@ -2820,6 +3049,55 @@ fn handle_players(
     Ok(())
 }
 
+fn handle_graveyard(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    _args: Vec<String>,
+    _action: &ServerChatCommand,
+) -> CmdResult<()> {
+    /// Only the top of the leaderboard is listed in chat
+    const MAX_GRAVES: usize = 10;
+
+    let graveyard = server.state.ecs().read_resource::<Graveyard>();
+    let leaderboard = graveyard.leaderboard();
+
+    let mut grave_list = String::new();
+    for (rank, grave) in leaderboard.iter().take(MAX_GRAVES).enumerate() {
+        grave_list.push_str(&format!(
+            "#{} {} (level {}, played {}h{:02}m): {}",
+            rank + 1,
+            grave.alias,
+            grave.level,
+            grave.playtime / 3600,
+            grave.playtime / 60 % 60,
+            grave.cause,
+        ));
+        if let Some(killer) = &grave.killer {
+            grave_list.push_str(&format!(" by {killer}"));
+        }
+        if let Some(location) = &grave.location {
+            grave_list.push_str(&format!(" at {location}"));
+        }
+        grave_list.push('\n');
+    }
+    let count = leaderboard.len();
+    drop(graveyard);
+
+    server.notify_client(
+        client,
+        ServerGeneral::server_msg(
+            ChatType::CommandInfo,
+            Content::localized_with_args("graveyard-list-header", [
+                ("count", LocalizationArg::from(count as u64)),
+                ("grave_list", LocalizationArg::from(grave_list)),
+            ]),
+        ),
+    );
+
+    Ok(())
+}
+
 fn handle_spawn_portal(
     server: &mut Server,
     client: EcsEntity,
@@ -5651,6 +5929,139 @@ fn handle_unban(
     )
 }
 
+fn make_mute_info(
+    server: &mut Server,
+    client: EcsEntity,
+    client_uuid: Uuid,
+) -> CmdResult<MuteInfo> {
+    let client_username = uuid_to_username(server, client, client_uuid)?;
+    let client_role = real_role(server, client_uuid, "client")?;
+    Ok(MuteInfo {
+        performed_by: client_uuid,
+        performed_by_username: client_username,
+        performed_by_role: client_role.into(),
+    })
+}
+
+fn handle_chat_mute(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    args: Vec<String>,
+    action: &ServerChatCommand,
+) -> CmdResult<()> {
+    let (Some(username), Some(duration), shadow, reason_opt) =
+        parse_cmd_args!(args, String, HumanDuration, bool, String)
+    else {
+        return Err(action.help_content());
+    };
+
+    let client_uuid = uuid(server, client, "client")?;
+    let info = make_mute_info(server, client, client_uuid)?;
+    let player_uuid = find_username(server, &username)?;
+
+    // Players can't be muted by someone whose role isn't above theirs
+    if let Ok(player_role) = real_role(server, player_uuid, "player")
+        && player_role >= real_role(server, client_uuid, "client")?
+    {
+        return Err(Content::localized_with_args(
+            "command-chat-mute-role-too-low",
+            [("player", username)],
+        ));
+    }
+
+    let now = Utc::now();
+    let end_date = ban_end_date(now, Some(duration))?.unwrap_or(now);
+    let reason = reason_opt.unwrap_or_default();
+    let mute = Mute {
+        reason: reason.clone(),
+        shadow: shadow.unwrap_or(false),
+        info,
+        end_date,
+    };
+
+    let result =
+        server
+            .editable_settings_mut()
+            .mutelist
+            .edit(server.data_dir().as_ref(), |mutelist| {
+                mutelist
+                    .perform(now, player_uuid, username.clone(), MuteAction::Mute(mute))
+                    .then_some(())
+            });
+    let info = || {
+        Content::localized_with_args("command-chat-mute-added", [
+            ("player", username.clone()),
+            ("reason", reason.clone()),
+        ])
+    };
+    match result {
+        Some(((), Err(setting_error))) => {
+            edit_setting_error_feedback(server, client, setting_error, info)
+        },
+        _ => {
+            server.notify_client(
+                client,
+                ServerGeneral::server_msg(ChatType::CommandInfo, info()),
+            );
+            Ok(())
+        },
+    }
+}
+
+fn handle_chat_unmute(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    args: Vec<String>,
+    action: &ServerChatCommand,
+) -> CmdResult<()> {
+    let Some(username) = parse_cmd_args!(args, String) else {
+        return Err(action.help_content());
+    };
+
+    let client_uuid = uuid(server, client, "client")?;
+    let info = make_mute_info(server, client, client_uuid)?;
+    let player_uuid = find_username(server, &username)?;
+
+    let result =
+        server
+            .editable_settings_mut()
+            .mutelist
+            .edit(server.data_dir().as_ref(), |mutelist| {
+                mutelist
+                    .perform(
+                        Utc::now(),
+                        player_uuid,
+                        username.clone(),
+                        MuteAction::Unmute(info),
+                    )
+                    .then_some(())
+            });
+    let info = || {
+        Content::localized_with_args("command-chat-unmute-successful", [(
+            "player",
+            username.clone(),
+        )])
+    };
+    match result {
+        Some(((), Ok(()))) => {
+            server.notify_client(
+                client,
+                ServerGeneral::server_msg(ChatType::CommandInfo, info()),
+            );
+            Ok(())
+        },
+        Some(((), Err(setting_error))) => {
+            edit_setting_error_feedback(server, client, setting_error, info)
+        },
+        None => Err(Content::localized_with_args(
+            "command-chat-unmute-not-muted",
+            [("player", username.clone())],
+        )),
+    }
+}
+
 fn handle_unban_ip(
     server: &mut Server,
     client: EcsEntity,
@@ -5759,6 +6170,97 @@ fn handle_server_physics(
     }
 }
 
+fn handle_audit_log(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    args: Vec<String>,
+    _action: &ServerChatCommand,
+) -> CmdResult<()> {
+    let (actor, max_entries) = parse_cmd_args!(args, String, i32);
+    let max_entries = max_entries
+        .and_then(|i| usize::try_from(i).ok())
+        .unwrap_or(10);
+
+    let records = server.audit_log(actor.as_deref(), max_entries);
+    if records.is_empty() {
+        return Err(Content::localized("command-audit_log-empty"));
+    }
+    let mut msg = String::new();
+    for record in records.iter().rev() {
+        msg.push_str(&format!(
+            "\n{} {} [{}] ({}): /{} {}",
+            record.time.format("%Y-%m-%d %H:%M:%S"),
+            record.actor_alias,
+            record.actor,
+            record
+                .role
+                .map_or_else(|| "no role".to_owned(), |role| format!("{role:?}")),
+            record.command,
+            record.args.join(" "),
+        ));
+        if let Some(target) = &record.target {
+            msg.push_str(&format!("\n  Target: {target}"));
+        }
+        if let Some(error) = &record.error {
+            msg.push_str(&format!("\n  Failed: {error}"));
+        }
+    }
+    server.notify_client(
+        client,
+        ServerGeneral::server_msg(ChatType::CommandInfo, Content::Plain(msg)),
+    );
+    Ok(())
+}
+
+fn handle_physics_violations(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    args: Vec<String>,
+    action: &ServerChatCommand,
+) -> CmdResult<()> {
+    let Some(username) = parse_cmd_args!(args, String) else {
+        return Err(action.help_content());
+    };
+    let uuid = find_username(server, &username)?;
+    let window = server.settings().anti_cheat.violation_window;
+
+    let msg = match server
+        .state
+        .ecs()
+        .read_resource::<PhysicsViolations>()
+        .get(&uuid)
+    {
+        Some(violations) => {
+            let mut msg = format!(
+                "{} ({}) has {} physics violations, {} within the last {}:",
+                violations.alias,
+                uuid,
+                violations.total,
+                violations.recent(window),
+                humantime::format_duration(window),
+            );
+            for violation in violations.history.iter().rev() {
+                msg.push_str(&format!(
+                    "\n{}: {}",
+                    violation.time.format("%Y-%m-%d %H:%M:%S"),
+                    violation.kind
+                ));
+            }
+            Content::Plain(msg)
+        },
+        None => {
+            Content::localized_with_args("command-physics_violations-none", [("player", username)])
+        },
+    };
+    server.notify_client(
+        client,
+        ServerGeneral::server_msg(ChatType::CommandInfo, msg),
+    );
+    Ok(())
+}
+
 fn handle_buff(
     server: &mut Server,
     _client: EcsEntity,
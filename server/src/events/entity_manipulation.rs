@@ -11,6 +11,7 @@ use crate::{
     },
     error,
     events::entity_creation::handle_create_npc,
+    graveyard::HardcoreDeath,
     persistence::character_updater::CharacterUpdater,
     pet::tame_pet,
     state_ext::StateExt,
@@ -550,6 +551,8 @@ pub struct DestroyEventData<'a> {
     uids: ReadStorage<'a, Uid>,
     positions: ReadStorage<'a, Pos>,
     healths: WriteStorage<'a, Health>,
+    hardcore: ReadStorage<'a, Hardcore>,
+    hardcore_deaths: WriteStorage<'a, HardcoreDeath>,
     bodies: ReadStorage<'a, Body>,
     poises: ReadStorage<'a, Poise>,
     groups: ReadStorage<'a, Group>,
@@ -766,6 +769,37 @@ impl ServerEvent for DestroyEvent {
                     _ => KillSource::Other,
                 };
 
+                if data.hardcore.contains(ev.entity) {
+                    let level = data.skill_sets.get(ev.entity).map_or(0, |skill_set| {
+                        skill_set
+                            .skill_groups()
+                            .map(|group| u32::from(group.earned_sp))
+                            .sum()
+                    });
+                    let location = data.positions.get(ev.entity).map(|pos| {
+                        #[cfg(feature = "worldgen")]
+                        if let Some(name) = data
+                            .world
+                            .get_location_name(data.index.as_index_ref(), pos.0.xy().as_())
+                        {
+                            return name;
+                        }
+                        format!("{:.0}, {:.0}", pos.0.x, pos.0.y)
+                    });
+                    let death = HardcoreDeath::new(
+                        &kill_source,
+                        |uid| {
+                            data.id_maps
+                                .uid_entity(uid)
+                                .and_then(|entity| data.players.get(entity))
+                                .map(|player| player.alias.clone())
+                        },
+                        level,
+                        location,
+                    );
+                    let _ = data.hardcore_deaths.insert(ev.entity, death);
+                }
+
                 chat_emitter.emit(ChatEvent {
                     msg: comp::UnresolvedChatMsg::death(kill_source, *uid),
                     from_client: false,
@@ -11,9 +11,9 @@ pub use common::event::{
     ParryHookEvent, PoiseChangeEvent, PossessEvent, ProcessTradeActionEvent, RegrowHeadEvent,
     RemoveLightEmitterEvent, RequestPluginsEvent, RequestSiteInfoEvent, RespawnEvent,
     SetBattleModeEvent, SetLanternEvent, SetPetStayEvent, ShockwaveEvent, ShootEvent, SoundEvent,
-    StartInteractionEvent, StartTeleportingEvent, SummonBeamPillarsEvent, TamePetEvent,
-    TeleportToEvent, TeleportToPositionEvent, ThrowEvent, ToggleSpriteLightEvent, TransformEvent,
-    UpdateCharacterDataEvent, UpdateMapMarkerEvent,
+    StartInteractionEvent, StartTeleportingEvent, StorageActionEvent, SummonBeamPillarsEvent,
+    TamePetEvent, TeleportToEvent, TeleportToPositionEvent, ThrowEvent, ToggleSpriteLightEvent,
+    TransformEvent, UpdateCharacterDataEvent, UpdateMapMarkerEvent,
 };
 
 /// X-macro that provides list of server events to the macro this is called
@@ -53,6 +53,7 @@ macro_rules! server_events {
             InviteResponseEvent
             InitiateInviteEvent
             ProcessTradeActionEvent
+            StorageActionEvent
             MountEvent
             SetPetStayEvent
             PossessEvent
@@ -18,13 +18,14 @@ use common::{
     consts::MAX_PICKUP_RANGE,
     event::{
         BuffEvent, ChangeBodyEvent, CreateItemDropEvent, CreateObjectEvent, DeleteEvent, EmitExt,
-        HealthChangeEvent, InventoryManipEvent, PoiseChangeEvent, TamePetEvent,
+        HealthChangeEvent, InventoryManipEvent, PoiseChangeEvent, StorageActionEvent, TamePetEvent,
     },
     event_emitters, match_some,
     mounting::VolumePos,
     outcome::Outcome,
     recipe::{self, RecipeBookManifest, default_component_recipe_book, default_repair_recipe_book},
     resources::{ProgramTime, Time},
+    storage::StorageAction,
     terrain::{Block, SpriteKind},
     trade::Trades,
     uid::{IdMaps, Uid},
@@ -68,6 +69,7 @@ event_emitters! {
         poise_change: PoiseChangeEvent,
         buff: BuffEvent,
         change_body: ChangeBodyEvent,
+        storage_action: StorageActionEvent,
         outcome: Outcome,
     }
 }
@@ -661,6 +663,23 @@ impl ServerEvent for InventoryManipEvent {
 
                                         Some(InventoryUpdateEvent::Used)
                                     },
+                                    ItemKind::Utility {
+                                        kind: item::Utility::StorageContainer,
+                                        ..
+                                    } => {
+                                        // Placing the container needs access to the
+                                        // storage resource, the item is only taken once
+                                        // the placement succeeded.
+                                        inventory.insert_or_stack_at(slot, item).expect(
+                                            "slot was just vacated of item, so it definitely fits \
+                                             there.",
+                                        );
+                                        emitters.emit(StorageActionEvent(
+                                            entity,
+                                            StorageAction::Place(slot),
+                                        ));
+                                        None
+                                    },
                                     ItemKind::RecipeGroup { .. } => {
                                         match inventory.push_recipe_group(item) {
                                             Ok(()) => {
@@ -719,8 +738,8 @@ impl ServerEvent for InventoryManipEvent {
                             }
                             Some(InventoryUpdateEvent::Used)
                         },
-                        // Items in overflow slots cannot be used
-                        Slot::Overflow(_) => None,
+                        // Items in overflow or stash slots cannot be used
+                        Slot::Overflow(_) | Slot::Stash(_) => None,
                     };
 
                     if let Some(effects) = maybe_effect {
@@ -858,6 +877,7 @@ impl ServerEvent for InventoryManipEvent {
                         },
                         Slot::Equip(_) => None,
                         Slot::Overflow(_) => None,
+                        Slot::Stash(_) => None,
                     };
 
                     if let Some(item) = item
@@ -878,6 +898,8 @@ impl ServerEvent for InventoryManipEvent {
                         Slot::Inventory(slot) => inventory.remove(slot),
                         Slot::Equip(slot) => inventory.replace_loadout_item(slot, None, *data.time),
                         Slot::Overflow(slot) => inventory.overflow_remove(slot),
+                        // Items must be moved out of the stash before they can be dropped
+                        Slot::Stash(_) => None,
                     };
 
                     // FIXME: We should really require the drop and write to be atomic!
@@ -906,6 +928,7 @@ impl ServerEvent for InventoryManipEvent {
                         Slot::Overflow(o) => {
                             inventory.overflow_take_half(o, &data.ability_map, &data.msm)
                         },
+                        Slot::Stash(_) => None,
                     };
 
                     // FIXME: We should really require the drop and write to be atomic!
@@ -2,6 +2,8 @@ use super::{
     ServerEvent, event_dispatch,
     group_manip::{self, update_map_markers},
 };
+#[cfg(feature = "worldgen")]
+use crate::rtsim::RtSim;
 use crate::{Settings, client::Client};
 use common::{
     comp::{
@@ -234,6 +236,8 @@ pub struct InviteResponseData<'a> {
     trades: Write<'a, Trades>,
     #[cfg(feature = "worldgen")]
     index: ReadExpect<'a, IndexOwned>,
+    #[cfg(feature = "worldgen")]
+    rtsim: ReadExpect<'a, RtSim>,
     id_maps: Read<'a, IdMaps>,
     invites: WriteStorage<'a, Invite>,
     pending_invites: WriteStorage<'a, PendingInvites>,
@@ -243,6 +247,8 @@ pub struct InviteResponseData<'a> {
     clients: ReadStorage<'a, Client>,
     alignments: ReadStorage<'a, comp::Alignment>,
     map_markers: ReadStorage<'a, comp::MapMarker>,
+    #[cfg(feature = "worldgen")]
+    presences: ReadStorage<'a, comp::Presence>,
 }
 
 impl ServerEvent for InviteResponseEvent {
@@ -325,21 +331,20 @@ pub fn handle_invite_accept(data: &mut InviteResponseData, entity: Entity) {
                             .push_back(AgentEvent::TradeAccepted(invitee_uid));
                     }
                     #[cfg(feature = "worldgen")]
-                    let pricing = data
-                        .agents
-                        .get(inviter)
-                        .and_then(|a| {
-                            a.behavior
-                                .trade_site()
-                                .and_then(|id| data.index.get_site_prices(id))
-                        })
-                        .or_else(|| {
-                            data.agents.get(entity).and_then(|a| {
-                                a.behavior
-                                    .trade_site()
-                                    .and_then(|id| data.index.get_site_prices(id))
+                    let pricing = {
+                        // Sites trade at better or worse prices depending on the reputation of
+                        // the player trading with them
+                        let character = [inviter, entity]
+                            .into_iter()
+                            .find_map(|e| data.presences.get(e)?.kind.character_id());
+                        let site_prices = |e| {
+                            data.agents.get(e)?.behavior.trade_site().and_then(|id| {
+                                data.rtsim
+                                    .site_prices(data.index.as_index_ref(), id, character)
                             })
-                        });
+                        };
+                        site_prices(inviter).or_else(|| site_prices(entity))
+                    };
                     #[cfg(not(feature = "worldgen"))]
                     let pricing = None;
 
@@ -27,6 +27,7 @@ use self::{
     player::{
         handle_character_delete, handle_client_disconnect, handle_exit_ingame, handle_possess,
     },
+    storage::handle_storage_action,
     trade::handle_process_trade_action,
 };
 
@@ -40,6 +41,7 @@ mod inventory_manip;
 mod invite;
 mod mounting;
 mod player;
+mod storage;
 mod trade;
 
 pub(crate) use event_types::register_event_busses;
@@ -200,6 +202,7 @@ impl Server {
         self.handle_serial_events(handle_mount);
         self.handle_serial_events(handle_tame_pet);
         self.handle_serial_events(handle_process_trade_action);
+        self.handle_serial_events(handle_storage_action);
         self.handle_serial_events(handle_set_battle_mode);
     }
 
@@ -1,6 +1,6 @@
 use super::Event;
 use crate::{
-    BattleModeBuffer, Server, client::Client, metrics::PlayerMetrics,
+    BattleModeBuffer, Server, client::Client, graveyard::HardcoreDeath, metrics::PlayerMetrics,
     persistence::character_updater::CharacterUpdater, settings::banlist::NormalizedIpAddr,
     state_ext::StateExt,
 };
@@ -358,9 +358,17 @@ pub(super) fn persist_entity(state: &mut State, entity: EcsEntity) -> EcsEntity
                         .get(entity)
                         .is_some_and(|health| health.is_dead)
                 {
-                    // Delete dead hardcore characters instead of persisting
-                    character_updater
-                        .queue_character_deletion(player_info.uuid().to_string(), char_id);
+                    // Archive dead hardcore characters instead of persisting
+                    let death = state
+                        .ecs()
+                        .write_storage::<HardcoreDeath>()
+                        .remove(entity)
+                        .unwrap_or_else(HardcoreDeath::unknown);
+                    character_updater.queue_character_archival(
+                        player_info.uuid().to_string(),
+                        char_id,
+                        death,
+                    );
                 } else {
                     let waypoint = state
                         .ecs()
@@ -399,15 +407,18 @@ pub(super) fn persist_entity(state: &mut State, entity: EcsEntity) -> EcsEntity
                         })
                         .collect();
 
-                    character_updater.add_pending_logout_update((
-                        char_id,
-                        skill_set.clone(),
-                        inventory.clone(),
-                        pets,
-                        waypoint,
-                        active_abilities.clone(),
-                        map_marker,
-                    ));
+                    character_updater.add_pending_logout_update(
+                        player_info.uuid().to_string(),
+                        (
+                            char_id,
+                            skill_set.clone(),
+                            inventory.clone(),
+                            pets,
+                            waypoint,
+                            active_abilities.clone(),
+                            map_marker,
+                        ),
+                    );
                 }
             },
             PresenceKind::Spectator => { /* Do nothing, spectators do not need persisting */ },
@@ -0,0 +1,374 @@
+#[cfg(feature = "persistent_world")]
+use crate::TerrainPersistence;
+use crate::{
+    Server,
+    client::Client,
+    storage_container::{StorageContainer, StorageContainers},
+};
+use common::{
+    comp::{
+        self, CanBuild, ChatType, Content, Group, Inventory, InventoryUpdate, Player, Pos,
+        inventory::InventoryUpdateEvent,
+        item::{ItemKind, MaterialStatManifest, Utility, tool::AbilityMap},
+    },
+    consts::MAX_INTERACT_RANGE,
+    event::StorageActionEvent,
+    storage::{OpenStorage, StorageAction},
+    terrain::{Block, SpriteKind, TerrainGrid},
+    uuid::Uuid,
+    vol::ReadVol,
+};
+use common_net::msg::ServerGeneral;
+use common_state::{AreasContainer, BlockChange, BuildArea};
+use specs::{Entity as EcsEntity, Join, WorldExt};
+use std::f32::consts::FRAC_PI_4;
+use vek::*;
+
+const STORAGE_CHEST_ITEM: &str = "common.items.utility.storage_chest";
+
+/// Handles everything to do with player-placed storage containers: placing
+/// them, opening them and moving items in and out of them.
+pub(super) fn handle_storage_action(
+    server: &mut Server,
+    StorageActionEvent(entity, action): StorageActionEvent,
+) {
+    let ecs = server.state.ecs();
+    // Storage containers are only available with terrain persistence, without
+    // it their sprites would be lost on the next chunk unload.
+    let Some(mut storage_containers) = ecs.try_fetch_mut::<StorageContainers>() else {
+        if matches!(action, StorageAction::Place(_)) {
+            notify(ecs, entity, "hud-storage-unavailable");
+        }
+        return;
+    };
+
+    match action {
+        StorageAction::Place(inv_slot) => {
+            let Some(owner) = ecs.read_storage::<Player>().get(entity).map(Player::uuid) else {
+                return;
+            };
+            let (Some(pos), Some(ori)) = (
+                ecs.read_storage::<Pos>().get(entity).copied(),
+                ecs.read_storage::<comp::Ori>().get(entity).copied(),
+            ) else {
+                return;
+            };
+
+            let mut inventories = ecs.write_storage::<Inventory>();
+            let Some(inventory) = inventories.get_mut(entity) else {
+                return;
+            };
+            if !inventory.get(inv_slot).is_some_and(|item| {
+                matches!(&*item.kind(), ItemKind::Utility {
+                    kind: Utility::StorageContainer
+                })
+            }) {
+                return;
+            }
+
+            // Place the container on the ground right in front of the player, facing them
+            let dir = ori
+                .look_dir()
+                .to_horizontal()
+                .map_or(Vec3::unit_y(), |dir| *dir);
+            let target = (pos.0 + dir * 1.5).map(|e| e.floor() as i32);
+            let block = {
+                let terrain = ecs.read_resource::<TerrainGrid>();
+                terrain
+                    .get(target)
+                    .ok()
+                    .filter(|block| block.get_sprite() == Some(SpriteKind::Empty))
+                    .filter(|_| {
+                        terrain
+                            .get(target - Vec3::unit_z())
+                            .is_ok_and(|below| below.is_filled())
+                    })
+                    .and_then(|block| block.try_with_sprite(SpriteKind::StorageChest).ok())
+                    .and_then(|block| block.with_ori(sprite_ori(-dir)))
+            };
+
+            let Some(block) = block.filter(|_| {
+                may_build_at(ecs, entity, target) && storage_containers.get(target).is_none()
+            }) else {
+                notify(ecs, entity, "hud-storage-place_failed");
+                return;
+            };
+
+            if ecs
+                .write_resource::<BlockChange>()
+                .try_set(target, block)
+                .is_none()
+            {
+                notify(ecs, entity, "hud-storage-place_failed");
+                return;
+            }
+            #[cfg(feature = "persistent_world")]
+            if let Some(mut terrain_persistence) = ecs.try_fetch_mut::<TerrainPersistence>() {
+                terrain_persistence.set_block(target, block);
+            }
+
+            inventory.take(
+                inv_slot,
+                &ecs.read_resource::<AbilityMap>(),
+                &ecs.read_resource::<MaterialStatManifest>(),
+            );
+            storage_containers.insert(target, StorageContainer::new(owner));
+            push_inventory_event(ecs, entity, InventoryUpdateEvent::Used);
+        },
+        StorageAction::Open(pos) => {
+            if let Some(container) = storage_containers.get(pos)
+                && in_range(ecs, entity, pos)
+            {
+                if can_access(ecs, entity, pos, container.owner) {
+                    let open_storage = to_open_storage(ecs, entity, pos, container);
+                    storage_containers.open(entity, pos);
+                    send(
+                        ecs,
+                        entity,
+                        ServerGeneral::UpdateStorage(Some(open_storage)),
+                    );
+                } else {
+                    notify(ecs, entity, "hud-storage-denied");
+                }
+            }
+        },
+        StorageAction::Close => {
+            storage_containers.close(entity);
+        },
+        StorageAction::Swap {
+            inv_slot,
+            storage_slot,
+        } => {
+            let Some(pos) = still_accessible(ecs, entity, &mut storage_containers) else {
+                return;
+            };
+            let mut inventories = ecs.write_storage::<Inventory>();
+            if let Some(container) = storage_containers.get_mut(pos)
+                && let Some(stored) = container.slots.get_mut(storage_slot)
+                && let Some(slot) = inventories
+                    .get_mut(entity)
+                    .and_then(|inventory| inventory.slot_mut(inv_slot))
+            {
+                core::mem::swap(slot, stored);
+                drop(inventories);
+                push_inventory_event(ecs, entity, InventoryUpdateEvent::Swapped);
+                update_viewers(ecs, &mut storage_containers, pos);
+            }
+        },
+        StorageAction::Move(a, b) => {
+            let Some(pos) = still_accessible(ecs, entity, &mut storage_containers) else {
+                return;
+            };
+            if let Some(container) = storage_containers.get_mut(pos)
+                && a < container.slots.len()
+                && b < container.slots.len()
+            {
+                container.slots.swap(a, b);
+                update_viewers(ecs, &mut storage_containers, pos);
+            }
+        },
+        StorageAction::PickUp => {
+            let Some(pos) = still_accessible(ecs, entity, &mut storage_containers) else {
+                return;
+            };
+            let is_owner = storage_containers.get(pos).is_some_and(|container| {
+                ecs.read_storage::<Player>()
+                    .get(entity)
+                    .is_some_and(|player| player.uuid() == container.owner)
+            });
+            if !is_owner {
+                return;
+            }
+            if !storage_containers
+                .get(pos)
+                .is_some_and(StorageContainer::is_empty)
+            {
+                notify(ecs, entity, "hud-storage-not_empty");
+                return;
+            }
+
+            let mut inventories = ecs.write_storage::<Inventory>();
+            let Some(inventory) = inventories.get_mut(entity) else {
+                return;
+            };
+            let item = comp::Item::new_from_asset_expect(STORAGE_CHEST_ITEM);
+            let item_msg = item.frontend_item(
+                &ecs.read_resource::<AbilityMap>(),
+                &ecs.read_resource::<MaterialStatManifest>(),
+            );
+            if inventory.push(item).is_err() {
+                notify(ecs, entity, "hud-storage-inventory_full");
+                return;
+            }
+            drop(inventories);
+
+            let viewers = storage_containers.viewers_of(pos).collect::<Vec<_>>();
+            storage_containers.remove(pos);
+            for viewer in viewers {
+                send(ecs, viewer, ServerGeneral::UpdateStorage(None));
+            }
+
+            let block = ecs
+                .read_resource::<TerrainGrid>()
+                .get(pos)
+                .map(|block| block.into_vacant())
+                .unwrap_or_else(|_| Block::empty());
+            ecs.write_resource::<BlockChange>().set(pos, block);
+            #[cfg(feature = "persistent_world")]
+            if let Some(mut terrain_persistence) = ecs.try_fetch_mut::<TerrainPersistence>() {
+                terrain_persistence.set_block(pos, block);
+            }
+            push_inventory_event(ecs, entity, InventoryUpdateEvent::Collected(item_msg));
+        },
+    }
+}
+
+/// The sprite orientation that makes a sprite face in the given direction.
+///
+/// Sprite orientations are steps of 45° counter-clockwise from the y axis.
+fn sprite_ori(dir: Vec3<f32>) -> u8 {
+    ((-dir.x).atan2(dir.y) / FRAC_PI_4).round().rem_euclid(8.0) as u8
+}
+
+fn in_range(ecs: &specs::World, entity: EcsEntity, pos: Vec3<i32>) -> bool {
+    ecs.read_storage::<Pos>()
+        .get(entity)
+        .is_some_and(|entity_pos| {
+            entity_pos
+                .0
+                .distance_squared(pos.as_() + Vec3::broadcast(0.5))
+                <= MAX_INTERACT_RANGE.powi(2)
+        })
+}
+
+/// Build areas are the only places where not everybody is allowed to build, so
+/// placing containers inside of them needs the permission to build there.
+fn may_build_at(ecs: &specs::World, entity: EcsEntity, pos: Vec3<i32>) -> bool {
+    let build_areas = ecs.read_resource::<AreasContainer<BuildArea>>();
+    let can_build = ecs.read_storage::<CanBuild>();
+    let can_build = can_build.get(entity);
+    build_areas
+        .areas()
+        .iter()
+        .filter(|(_, aabb)| aabb.contains_point(pos))
+        .all(|(id, _)| can_build.is_some_and(|can_build| can_build.build_areas.contains(&id)))
+}
+
+/// Owners can always access their containers. Inside of build areas, the
+/// containers are shared by everybody allowed to build there, elsewhere with
+/// the owner's group.
+fn can_access(ecs: &specs::World, entity: EcsEntity, pos: Vec3<i32>, owner: Uuid) -> bool {
+    let players = ecs.read_storage::<Player>();
+    if players
+        .get(entity)
+        .is_some_and(|player| player.uuid() == owner)
+    {
+        return true;
+    }
+
+    let build_areas = ecs.read_resource::<AreasContainer<BuildArea>>();
+    let mut areas = build_areas
+        .areas()
+        .iter()
+        .filter(|(_, aabb)| aabb.contains_point(pos))
+        .map(|(id, _)| id)
+        .peekable();
+    if areas.peek().is_some() {
+        let can_build = ecs.read_storage::<CanBuild>();
+        let can_build = can_build.get(entity);
+        return areas
+            .any(|id| can_build.is_some_and(|can_build| can_build.build_areas.contains(&id)));
+    }
+
+    let groups = ecs.read_storage::<Group>();
+    groups.get(entity).is_some_and(|group| {
+        (&ecs.entities(), &players, &groups)
+            .join()
+            .any(|(_, player, owner_group)| player.uuid() == owner && owner_group == group)
+    })
+}
+
+/// Checks that the container the entity has open is still there and that the
+/// entity is still allowed to use it, closing it otherwise.
+fn still_accessible(
+    ecs: &specs::World,
+    entity: EcsEntity,
+    storage_containers: &mut StorageContainers,
+) -> Option<Vec3<i32>> {
+    let pos = storage_containers.opened_by(entity)?;
+    let accessible = storage_containers.get(pos).is_some_and(|container| {
+        in_range(ecs, entity, pos) && can_access(ecs, entity, pos, container.owner)
+    });
+    if accessible {
+        Some(pos)
+    } else {
+        storage_containers.close(entity);
+        send(ecs, entity, ServerGeneral::UpdateStorage(None));
+        None
+    }
+}
+
+fn to_open_storage(
+    ecs: &specs::World,
+    viewer: EcsEntity,
+    pos: Vec3<i32>,
+    container: &StorageContainer,
+) -> OpenStorage {
+    OpenStorage {
+        pos,
+        // Clients need their own copy of the contents to display them
+        slots: container
+            .slots
+            .iter()
+            .map(|slot| {
+                slot.as_ref().map(|item| {
+                    item.duplicate(
+                        &ecs.read_resource::<AbilityMap>(),
+                        &ecs.read_resource::<MaterialStatManifest>(),
+                    )
+                })
+            })
+            .collect(),
+        is_owner: ecs
+            .read_storage::<Player>()
+            .get(viewer)
+            .is_some_and(|player| player.uuid() == container.owner),
+    }
+}
+
+/// Send the new contents of a container to everyone that has it open.
+fn update_viewers(ecs: &specs::World, storage_containers: &mut StorageContainers, pos: Vec3<i32>) {
+    let viewers = storage_containers.viewers_of(pos).collect::<Vec<_>>();
+    for viewer in viewers {
+        if !ecs.is_alive(viewer) {
+            storage_containers.close(viewer);
+        } else if let Some(container) = storage_containers.get(pos) {
+            let open_storage = to_open_storage(ecs, viewer, pos, container);
+            send(
+                ecs,
+                viewer,
+                ServerGeneral::UpdateStorage(Some(open_storage)),
+            );
+        }
+    }
+}
+
+fn push_inventory_event(ecs: &specs::World, entity: EcsEntity, event: InventoryUpdateEvent) {
+    if let Ok(entry) = ecs.write_storage::<InventoryUpdate>().entry(entity) {
+        entry.or_insert_with(InventoryUpdate::default).push(event);
+    }
+}
+
+fn send(ecs: &specs::World, entity: EcsEntity, msg: ServerGeneral) {
+    if let Some(client) = ecs.read_storage::<Client>().get(entity) {
+        client.send_fallible(msg);
+    }
+}
+
+fn notify(ecs: &specs::World, entity: EcsEntity, key: &str) {
+    send(
+        ecs,
+        entity,
+        ServerGeneral::server_msg(ChatType::Meta, Content::localized(key)),
+    );
+}
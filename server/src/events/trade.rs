@@ -1,4 +1,8 @@
 use crate::Server;
+#[cfg(feature = "worldgen")]
+use crate::rtsim::RtSim;
+#[cfg(feature = "worldgen")]
+use common::{character::CharacterId, comp::Presence};
 use common::{
     comp::{
         CharacterState, Health,
@@ -36,6 +40,8 @@ pub fn notify_agent_simple(
 fn notify_agent_prices(
     mut agents: specs::WriteStorage<Agent>,
     index: &IndexOwned,
+    rtsim: &RtSim,
+    character: Option<CharacterId>,
     entity: EcsEntity,
     event: AgentEvent,
 ) {
@@ -45,7 +51,7 @@ fn notify_agent_prices(
         // Prefer using this Agent's price data, but use the counterparty's price
         // data if we don't have price data
         let prices = site_id
-            .and_then(|site_id| index.get_site_prices(site_id))
+            .and_then(|site_id| rtsim.site_prices(index.as_index_ref(), site_id, character))
             .unwrap_or(boxval.2);
         // Box<(tid, pend, _, inventories)>) = event {
         agent
@@ -131,6 +137,18 @@ pub(super) fn handle_process_trade_action(
                     #[cfg(not(feature = "worldgen"))]
                     let prices = None;
                     let agents = server.state.ecs().read_storage::<Agent>();
+                    // Sites trade at better or worse prices depending on the reputation of
+                    // the player trading with them
+                    #[cfg(feature = "worldgen")]
+                    let rtsim = server.state.ecs().read_resource::<RtSim>();
+                    #[cfg(feature = "worldgen")]
+                    let character = {
+                        let presences = server.state.ecs().read_storage::<Presence>();
+                        parties.iter().find_map(|party| {
+                            let entity = server.state.ecs().entity_from_uid(*party)?;
+                            presences.get(entity)?.kind.character_id()
+                        })
+                    };
                     // sadly there is no map and collect on arrays
                     for i in 0..2 {
                         // parties.len()) {
@@ -150,7 +168,13 @@ pub(super) fn handle_process_trade_action(
                                     agents
                                         .get(e)
                                         .and_then(|a| a.behavior.trade_site())
-                                        .and_then(|id| server.index.get_site_prices(id))
+                                        .and_then(|id| {
+                                            rtsim.site_prices(
+                                                server.index.as_index_ref(),
+                                                id,
+                                                character,
+                                            )
+                                        })
                                 });
                             }
                         }
@@ -170,6 +194,8 @@ pub(super) fn handle_process_trade_action(
                             notify_agent_prices(
                                 server.state.ecs().write_storage::<Agent>(),
                                 &server.index,
+                                &rtsim,
+                                character,
                                 e,
                                 AgentEvent::UpdatePendingTrade(Box::new((
                                     trade_id,
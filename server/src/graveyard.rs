@@ -0,0 +1,199 @@
+//! Memorial of dead hardcore characters.
+//!
+//! Hardcore characters can't respawn, so once they die they are removed from
+//! their player's character list. Instead of erasing every trace of them, the
+//! character is archived as a [`Grave`] recording how far it got and how it
+//! met its end. The graves are listed on a leaderboard available both in game
+//! and through the server's web API.
+
+use common::{
+    comp::{
+        Content,
+        chat::{KillSource, KillType},
+    },
+    uid::Uid,
+};
+use serde::Serialize;
+use specs::{Component, DenseVecStorage};
+use std::cmp::Ordering;
+
+/// Circumstances of the death of a hardcore character, captured when it dies
+/// and archived along with the character once it leaves the game.
+#[derive(Clone, Debug)]
+pub struct HardcoreDeath {
+    pub cause: String,
+    pub killer: Option<String>,
+    pub level: u32,
+    pub location: Option<String>,
+}
+
+impl Component for HardcoreDeath {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl HardcoreDeath {
+    /// `player_alias` looks up the alias of the player who landed the killing
+    /// blow, if any.
+    pub fn new(
+        kill_source: &KillSource,
+        player_alias: impl FnOnce(Uid) -> Option<String>,
+        level: u32,
+        location: Option<String>,
+    ) -> Self {
+        let (cause, killer) = match kill_source {
+            KillSource::Player(uid, kill_type) => (kill_type_name(kill_type), player_alias(*uid)),
+            KillSource::NonPlayer(name, kill_type) => {
+                (kill_type_name(kill_type), Some(content_text(name)))
+            },
+            KillSource::NonExistent(kill_type) => (kill_type_name(kill_type), None),
+            KillSource::FallDamage => ("fall".to_string(), None),
+            KillSource::Suicide => ("suicide".to_string(), None),
+            KillSource::Other => ("other".to_string(), None),
+        };
+
+        Self {
+            cause,
+            killer,
+            level,
+            location,
+        }
+    }
+
+    /// Used for characters that died without their death being recorded, e.g.
+    /// if the server was restarted in between.
+    pub fn unknown() -> Self {
+        Self {
+            cause: "other".to_string(),
+            killer: None,
+            level: 0,
+            location: None,
+        }
+    }
+}
+
+fn kill_type_name(kill_type: &KillType) -> String {
+    match kill_type {
+        KillType::Buff(buff_kind) => format!("buff:{buff_kind:?}").to_lowercase(),
+        KillType::Melee => "melee".to_string(),
+        KillType::Projectile => "projectile".to_string(),
+        KillType::Explosion => "explosion".to_string(),
+        KillType::Energy => "energy".to_string(),
+        KillType::Other => "other".to_string(),
+    }
+}
+
+/// The server can't localize names, so localized ones are recorded by their
+/// i18n key.
+fn content_text(content: &Content) -> String {
+    match content {
+        Content::Plain(text) => text.clone(),
+        Content::Key(key) | Content::Localized { key, .. } => key.clone(),
+        Content::Attr(key, attr) => format!("{key}.{attr}"),
+    }
+}
+
+/// An archived hardcore character.
+#[derive(Clone, Debug, Serialize)]
+pub struct Grave {
+    pub alias: String,
+    pub cause: String,
+    pub killer: Option<String>,
+    /// Total time the character was played, in seconds
+    pub playtime: u64,
+    pub level: u32,
+    pub location: Option<String>,
+    /// Unix timestamp of the time the character was archived
+    pub died_at: i64,
+}
+
+impl Grave {
+    /// Graves are ranked by level first, ties are broken by playtime and then
+    /// by who died first.
+    fn rank(&self, other: &Self) -> Ordering {
+        other
+            .level
+            .cmp(&self.level)
+            .then(other.playtime.cmp(&self.playtime))
+            .then(self.died_at.cmp(&other.died_at))
+    }
+}
+
+/// Resource holding every grave, ordered by rank.
+#[derive(Default)]
+pub struct Graveyard {
+    graves: Vec<Grave>,
+}
+
+impl Graveyard {
+    pub fn new(graves: Vec<Grave>) -> Self {
+        let mut graveyard = Self::default();
+        graveyard.bury(graves);
+        graveyard
+    }
+
+    pub fn bury(&mut self, graves: impl IntoIterator<Item = Grave>) {
+        self.graves.extend(graves);
+        self.graves.sort_by(Grave::rank);
+    }
+
+    pub fn leaderboard(&self) -> &[Grave] { &self.graves }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grave(alias: &str, level: u32, playtime: u64, died_at: i64) -> Grave {
+        Grave {
+            alias: alias.to_string(),
+            cause: "melee".to_string(),
+            killer: None,
+            playtime,
+            level,
+            location: None,
+            died_at,
+        }
+    }
+
+    #[test]
+    fn leaderboard_ranking() {
+        let mut graveyard = Graveyard::new(vec![
+            grave("a", 3, 100, 0),
+            grave("b", 5, 10, 1),
+            grave("c", 3, 200, 2),
+        ]);
+        graveyard.bury([grave("d", 3, 200, 1)]);
+
+        let aliases = graveyard
+            .leaderboard()
+            .iter()
+            .map(|grave| grave.alias.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(aliases, ["b", "d", "c", "a"]);
+    }
+
+    #[test]
+    fn death_causes() {
+        let death = HardcoreDeath::new(
+            &KillSource::NonPlayer(Content::Plain("Cyclops".to_string()), KillType::Melee),
+            |_| None,
+            7,
+            None,
+        );
+        assert_eq!(death.cause, "melee");
+        assert_eq!(death.killer.as_deref(), Some("Cyclops"));
+
+        let death = HardcoreDeath::new(
+            &KillSource::Player(Uid(1), KillType::Projectile),
+            |_| Some("Archer".to_string()),
+            0,
+            None,
+        );
+        assert_eq!(death.cause, "projectile");
+        assert_eq!(death.killer.as_deref(), Some("Archer"));
+
+        let death = HardcoreDeath::new(&KillSource::FallDamage, |_| None, 0, None);
+        assert_eq!(death.cause, "fall");
+        assert_eq!(death.killer, None);
+    }
+}
@@ -6,9 +6,13 @@
 #![deny(clippy::clone_on_ref_ptr)]
 #![feature(box_patterns, option_zip, const_type_name, slice_partition_dedup)]
 
+pub mod anti_cheat;
+pub mod audit_log;
 pub mod automod;
+pub mod backup;
 mod character_creator;
 pub mod chat;
+pub mod chunk_cache;
 pub mod chunk_generator;
 mod chunk_serialize;
 pub mod client;
@@ -17,6 +21,7 @@ pub mod connection_handler;
 mod data_dir;
 pub mod error;
 pub mod events;
+pub mod graveyard;
 pub mod input;
 pub mod location;
 pub mod lod;
@@ -24,10 +29,12 @@ pub mod login_provider;
 pub mod metrics;
 pub mod persistence;
 mod pet;
+pub mod pregen;
 pub mod presence;
 pub mod rtsim;
 pub mod settings;
 pub mod state_ext;
+pub mod storage_container;
 pub mod sys;
 #[cfg(feature = "persistent_world")]
 pub mod terrain_persistence;
@@ -46,10 +53,15 @@ pub use crate::{
     settings::{CalendarMode, EditableSettings, Settings},
 };
 
+#[cfg(feature = "worldgen")]
+use crate::chunk_cache::ChunkCache;
 #[cfg(feature = "persistent_world")]
 use crate::terrain_persistence::TerrainPersistence;
 use crate::{
+    anti_cheat::{Escalation, PhysicsViolations},
+    audit_log::{AuditLog, AuditRecord},
     automod::AutoMod,
+    backup::Backups,
     chunk_generator::ChunkGenerator,
     client::Client,
     cmd::ChatCommandExt,
@@ -58,8 +70,11 @@ use crate::{
     location::Locations,
     login_provider::LoginProvider,
     persistence::PersistedComponents,
+    pregen::{Pregen, PregenProgress},
     presence::{RegionSubscription, RepositionOnChunkLoad},
+    settings::server_physics::ServerPhysicsForceRecord,
     state_ext::StateExt,
+    storage_container::StorageContainers,
     sys::sentinel::DeletedEntities,
 };
 use authc::Uuid;
@@ -238,6 +253,34 @@ pub enum ServerInitStage {
     StartingSystems,
 }
 
+/// Generate the world described by the server settings.
+#[cfg(feature = "worldgen")]
+pub(crate) fn generate_world(
+    settings: &Settings,
+    threadpool: &rayon::ThreadPool,
+    report_stage: &(dyn Fn(WorldGenerateStage) + Send + Sync),
+) -> (World, IndexOwned) {
+    World::generate(
+        settings.world_seed,
+        WorldOpts {
+            seed_elements: true,
+            world_file: if let Some(ref opts) = settings.map_file {
+                opts.clone()
+            } else {
+                // Load default map from assets.
+                FileOpts::LoadAsset(DEFAULT_WORLD_MAP.into())
+            },
+            calendar: Some(
+                settings
+                    .calendar_mode
+                    .calendar_now(&settings.calendar_events),
+            ),
+        },
+        threadpool,
+        report_stage,
+    )
+}
+
 pub struct Server {
     state: State,
     world: Arc<World>,
@@ -251,6 +294,7 @@ pub struct Server {
     chat_cache: ChatCache,
     database_settings: Arc<RwLock<DatabaseSettings>>,
     disconnect_all_clients_requested: bool,
+    pregen: Option<Pregen>,
 
     event_dispatcher: SendDispatcher<'static>,
 }
@@ -304,23 +348,9 @@ impl Server {
 
         debug!("Generating world, seed: {}", settings.world_seed);
         #[cfg(feature = "worldgen")]
-        let (world, index) = World::generate(
-            settings.world_seed,
-            WorldOpts {
-                seed_elements: true,
-                world_file: if let Some(ref opts) = settings.map_file {
-                    opts.clone()
-                } else {
-                    // Load default map from assets.
-                    FileOpts::LoadAsset(DEFAULT_WORLD_MAP.into())
-                },
-                calendar: Some(settings.calendar_mode.calendar_now()),
-            },
-            &pools,
-            &|stage| {
-                report_stage(ServerInitStage::WorldGen(stage));
-            },
-        );
+        let (world, index) = generate_world(&settings, &pools, &|stage| {
+            report_stage(ServerInitStage::WorldGen(stage));
+        });
         #[cfg(not(feature = "worldgen"))]
         let (world, index) = World::generate(settings.world_seed);
 
@@ -339,6 +369,13 @@ impl Server {
             default_chunk: Arc::new(world.generate_oob_chunk()),
         };
 
+        #[cfg(feature = "worldgen")]
+        let world = if settings.generated_chunk_cache {
+            world.with_base_chunk_cache(ChunkCache::new(data_dir, settings.world_seed, &map))
+        } else {
+            world
+        };
+
         #[cfg(feature = "worldgen")]
         let map_size_lg = world.sim().map_size_lg();
         #[cfg(not(feature = "worldgen"))]
@@ -379,6 +416,7 @@ impl Server {
             .ecs_mut()
             .insert(EventBus::<chunk_serialize::ChunkSendEntry>::default());
         state.ecs_mut().insert(Locations::default());
+        state.ecs_mut().insert(PhysicsViolations::default());
         state.ecs_mut().insert(LoginProvider::new(
             settings.auth_server_address.clone(),
             Arc::clone(&runtime),
@@ -412,6 +450,10 @@ impl Server {
                 state
                     .ecs_mut()
                     .insert(TerrainPersistence::new(data_dir.to_owned()));
+                // Storage containers are only placeable when their sprites persist
+                state.ecs_mut().insert(StorageContainers::new(
+                    persistence::load_storage_containers(&database_settings.read().unwrap()),
+                ));
             }
             #[cfg(not(feature = "persistent_world"))]
             error!(
@@ -419,13 +461,20 @@ impl Server {
                  compiled with the feature. Terrain modifications will *not* be persisted."
             );
         }
+        state
+            .ecs_mut()
+            .insert(Backups::new(settings.backup.clone(), data_dir));
+        state.ecs_mut().insert(AuditLog::new(data_dir));
         {
             let pool = state.ecs_mut().write_resource::<SlowJobPool>();
             pool.configure("CHUNK_DROP", |_n| 1);
             pool.configure("CHUNK_GENERATOR", |n| n / 2 + n / 4);
+            pool.configure("CHUNK_PREGEN", |n| (n / 4).max(1));
             pool.configure("CHUNK_SERIALIZER", |n| n / 2);
             pool.configure("RTSIM_SAVE", |_| 1);
+            pool.configure("RTSIM_CONSTRUCTION", |_| 1);
             pool.configure("WEATHER", |_| 1);
+            pool.configure("NAV_GRAPH", |_| 1);
         }
         state
             .ecs_mut()
@@ -456,6 +505,12 @@ impl Server {
             Arc::<RwLock<DatabaseSettings>>::clone(&database_settings),
         )?);
 
+        state
+            .ecs_mut()
+            .insert(graveyard::Graveyard::new(persistence::load_graveyard(
+                &database_settings.read().unwrap(),
+            )));
+
         // System schedulers to control execution of systems
         state
             .ecs_mut()
@@ -475,6 +530,7 @@ impl Server {
         state.ecs_mut().register::<login_provider::PendingLogin>();
         state.ecs_mut().register::<RepositionOnChunkLoad>();
         state.ecs_mut().register::<RtSimEntity>();
+        state.ecs_mut().register::<graveyard::HardcoreDeath>();
 
         // Load banned words list
         let banned_words = settings.moderation.load_banned_words(data_dir);
@@ -666,11 +722,16 @@ impl Server {
         // Init rtsim, loading it from disk if possible
         #[cfg(feature = "worldgen")]
         {
+            #[cfg(feature = "persistent_world")]
+            let terrain_persistence = state.ecs().try_fetch::<TerrainPersistence>().is_some();
+            #[cfg(not(feature = "persistent_world"))]
+            let terrain_persistence = false;
             match rtsim::RtSim::new(
                 &settings.world,
                 index.as_index_ref(),
                 &world,
                 data_dir.to_owned(),
+                terrain_persistence,
             ) {
                 Ok(rtsim) => {
                     state.ecs_mut().insert(rtsim.state().data().time_of_day);
@@ -695,6 +756,7 @@ impl Server {
             chat_cache,
             database_settings,
             disconnect_all_clients_requested: false,
+            pregen: None,
 
             event_dispatcher: Self::create_event_dispatcher(pools),
         };
@@ -789,12 +851,12 @@ impl Server {
         // Update calendar events as time changes
         // TODO: If a lot of calendar events get added, this might become expensive.
         // Maybe don't do this every tick?
-        let new_calendar = self
-            .state
-            .ecs()
-            .read_resource::<Settings>()
-            .calendar_mode
-            .calendar_now();
+        let new_calendar = {
+            let settings = self.state.ecs().read_resource::<Settings>();
+            settings
+                .calendar_mode
+                .calendar_now(&settings.calendar_events)
+        };
         *self.state.ecs_mut().write_resource::<Calendar>() = new_calendar;
 
         // This tick function is the centre of the Veloren universe. Most server-side
@@ -1043,6 +1105,15 @@ impl Server {
                 CharacterUpdaterMessage::DatabaseBatchCompletion(batch_id) => {
                     character_updater.process_batch_completion(batch_id);
                 },
+                CharacterUpdaterMessage::CharacterTransfer { requester, result } => {
+                    self.report_character_transfer(requester, result);
+                },
+                CharacterUpdaterMessage::Graves(graves) => {
+                    self.state
+                        .ecs()
+                        .write_resource::<graveyard::Graveyard>()
+                        .bury(graves);
+                },
                 CharacterUpdaterMessage::CharacterScreenResponse(response) => {
                     match response.response_kind {
                         CharacterScreenResponseKind::CharacterList(result) => match result {
@@ -1150,6 +1221,10 @@ impl Server {
         drop(character_loader);
         drop(character_updater);
 
+        self.maintain_backups();
+        self.maintain_pregen();
+        self.apply_physics_escalations();
+
         {
             // Check for new chunks; cancel and regenerate all chunks if the asset has been
             // reloaded. Note that all of these assignments are no-ops, so the
@@ -1466,6 +1541,304 @@ impl Server {
         self.state.ecs().read_storage::<Client>().join().count() as i64
     }
 
+    /// Returns up to `max` of the most recent commands in the audit log, only
+    /// those run by the player with the given alias or UUID if given.
+    pub fn audit_log(&self, actor: Option<&str>, max: usize) -> Vec<AuditRecord> {
+        self.state
+            .ecs()
+            .read_resource::<AuditLog>()
+            .query(actor, max)
+    }
+
+    /// Takes a snapshot of the character database, rtsim data and persisted
+    /// terrain as of this tick, see [`backup`].
+    pub fn take_snapshot(&mut self) {
+        let snapshot = match self.state.ecs().write_resource::<Backups>().begin() {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                warn!("Could not take snapshot: {}", err);
+                return;
+            },
+        };
+
+        // Persist online characters first, so that the database matches the rest of
+        // the snapshot
+        self.state
+            .ecs()
+            .write_resource::<sys::PersistenceScheduler>()
+            .run_next();
+        run_now::<sys::persistence::Sys>(self.state.ecs());
+        let database = self
+            .state
+            .ecs()
+            .write_resource::<CharacterUpdater>()
+            .backup_database(snapshot.database_path());
+
+        #[cfg(feature = "worldgen")]
+        let rtsim = Some(self.state.ecs().read_resource::<rtsim::RtSim>().data());
+        #[cfg(not(feature = "worldgen"))]
+        let rtsim = None;
+
+        #[cfg(feature = "persistent_world")]
+        let terrain_dir = self.state.ecs().try_fetch_mut::<TerrainPersistence>().map(
+            |mut terrain_persistence| {
+                terrain_persistence.flush();
+                terrain_persistence.path()
+            },
+        );
+        #[cfg(not(feature = "persistent_world"))]
+        let terrain_dir: Option<std::path::PathBuf> = None;
+
+        let tick = self.state.ecs().read_resource::<Tick>().0;
+        self.state.ecs().read_resource::<Backups>().finish(
+            snapshot,
+            tick,
+            database,
+            rtsim,
+            terrain_dir.as_deref(),
+        );
+    }
+
+    /// Reports finished snapshots and takes scheduled ones.
+    fn maintain_backups(&mut self) {
+        for outcome in self.state.ecs().write_resource::<Backups>().finished() {
+            match outcome {
+                Ok(msg) => info!("{}", msg),
+                Err(err) => error!("Snapshot failed: {}", err),
+            }
+        }
+
+        let is_due = self.state.ecs().read_resource::<Backups>().is_due();
+        if is_due {
+            self.take_snapshot();
+        }
+    }
+
+    /// Starts pre-generating the chunks in `area`, given in chunk coordinates
+    /// and excluding its maximum, or in the whole world. Any ongoing
+    /// pre-generation is cancelled.
+    pub fn start_pregen(&mut self, area: Option<Aabr<i32>>) {
+        #[cfg(feature = "worldgen")]
+        let size = self.world.sim().get_size().as_::<i32>();
+        #[cfg(not(feature = "worldgen"))]
+        let size = self.world.map_size_lg().chunks().as_::<i32>();
+        let world_area = Aabr {
+            min: Vec2::zero(),
+            max: size,
+        };
+        let area = area.map_or(world_area, |area| area.intersection(world_area));
+        if area.size().product() <= 0 {
+            warn!(
+                ?area,
+                "The area to pre-generate does not overlap with the world"
+            );
+            return;
+        }
+        if !self
+            .state
+            .ecs()
+            .read_resource::<Settings>()
+            .generated_chunk_cache
+        {
+            warn!(
+                "The chunk cache is disabled, so pre-generated chunks will not be kept. Enable \
+                 `generated_chunk_cache` in the server settings to keep them."
+            );
+        }
+        info!(?area, "Pre-generating chunks");
+        self.pregen = Some(Pregen::new(area));
+    }
+
+    pub fn cancel_pregen(&mut self) {
+        if let Some(pregen) = self.pregen.take() {
+            info!("Cancelled pre-generation. {}", pregen.progress());
+        }
+    }
+
+    pub fn pregen_progress(&self) -> Option<PregenProgress> {
+        self.pregen.as_ref().map(Pregen::progress)
+    }
+
+    /// Requests chunks to be pre-generated while no chunks are being generated
+    /// for players, and reports progress.
+    fn maintain_pregen(&mut self) {
+        let Some(pregen) = &mut self.pregen else {
+            return;
+        };
+        if pregen.is_finished() {
+            info!("Pre-generation finished. {}", pregen.progress());
+            self.pregen = None;
+            return;
+        }
+        if pregen.should_report() {
+            info!("{}", pregen.progress());
+        }
+
+        let ecs = self.state.ecs();
+        if ecs
+            .read_resource::<ChunkGenerator>()
+            .pending_chunks()
+            .next()
+            .is_none()
+        {
+            pregen.request_chunks(
+                &ecs.read_resource::<SlowJobPool>(),
+                &self.world,
+                &self.index,
+                (
+                    *ecs.read_resource::<TimeOfDay>(),
+                    (*ecs.read_resource::<Calendar>()).clone(),
+                ),
+            );
+        }
+    }
+
+    /// Applies the escalations of repeated physics violations decided this
+    /// tick, see [`anti_cheat`].
+    fn apply_physics_escalations(&mut self) {
+        let escalations = self
+            .state
+            .ecs()
+            .write_resource::<PhysicsViolations>()
+            .take_escalations();
+        for (entity, uuid, escalation) in escalations {
+            match escalation {
+                Escalation::ForceServerPhysics => {
+                    if self
+                        .editable_settings()
+                        .server_physics_force_list
+                        .contains_key(&uuid)
+                    {
+                        continue;
+                    }
+                    warn!(
+                        ?uuid,
+                        "Forcing server physics after repeated physics violations"
+                    );
+                    let data_dir = self.data_dir();
+                    let _ = self.editable_settings_mut().server_physics_force_list.edit(
+                        data_dir.as_ref(),
+                        |list| {
+                            list.insert(uuid, ServerPhysicsForceRecord {
+                                by: None,
+                                reason: Some("Repeated physics violations".to_owned()),
+                            });
+                            Some(())
+                        },
+                    );
+                },
+                Escalation::Kick => {
+                    warn!(?uuid, "Kicking player after repeated physics violations");
+                    self.notify_client(
+                        entity,
+                        ServerGeneral::Disconnect(DisconnectReason::Kicked(
+                            "Repeated physics violations".to_owned(),
+                        )),
+                    );
+                    self.state.emit_event_now(ClientDisconnectEvent(
+                        entity,
+                        comp::DisconnectReason::Kicked,
+                    ));
+                },
+            }
+        }
+    }
+
+    /// Exports a character to `character_<id>.ron` in the character transfer
+    /// directory, so that it can be imported on another server. The outcome is
+    /// reported to `requester`, if any.
+    ///
+    /// Characters that are in game can't be exported, since the bundle would
+    /// miss their latest changes and they could be played on after the export.
+    pub fn export_character(&mut self, requester: Option<EcsEntity>, character_id: CharacterId) {
+        let mut character_updater = self.state.ecs().write_resource::<CharacterUpdater>();
+        if character_updater.is_in_use(character_id) {
+            drop(character_updater);
+            self.report_character_transfer(
+                requester,
+                Err(persistence::error::PersistenceError::OtherError(format!(
+                    "Character {} is in game or hasn't been saved yet, try again after the player \
+                     logged out",
+                    character_id.0
+                ))),
+            );
+            return;
+        }
+
+        let data_dir = self.data_dir().path.clone();
+        character_updater.export_character(
+            requester,
+            character_id,
+            data_dir,
+            format!("character_{}.ron", character_id.0),
+        );
+    }
+
+    /// Imports a character from a file in the character transfer directory
+    /// for the player with the given username. The outcome is reported to
+    /// `requester`, if any.
+    pub fn import_character(
+        &mut self,
+        requester: Option<EcsEntity>,
+        username: &str,
+        file_name: String,
+        allow_unsigned: bool,
+    ) {
+        let uuid = self
+            .state
+            .ecs()
+            .fetch::<LoginProvider>()
+            .username_to_uuid(username);
+        match uuid {
+            Ok(uuid) => {
+                let data_dir = self.data_dir().path.clone();
+                self.state
+                    .ecs()
+                    .write_resource::<CharacterUpdater>()
+                    .import_character(
+                        requester,
+                        uuid.to_string(),
+                        data_dir,
+                        file_name,
+                        allow_unsigned,
+                    );
+            },
+            Err(err) => self.report_character_transfer(
+                requester,
+                Err(persistence::error::PersistenceError::OtherError(format!(
+                    "Could not find uuid for {}: {:?}",
+                    username, err
+                ))),
+            ),
+        }
+    }
+
+    fn report_character_transfer(
+        &self,
+        requester: Option<EcsEntity>,
+        result: Result<String, persistence::error::PersistenceError>,
+    ) {
+        let (chat_type, msg) = match result {
+            Ok(msg) => {
+                info!("{}", msg);
+                (ChatType::CommandInfo, msg)
+            },
+            Err(e) => {
+                warn!(?e, "Character transfer failed");
+                (
+                    ChatType::CommandError,
+                    format!("Character transfer failed: {}", e),
+                )
+            },
+        };
+        if let Some(requester) = requester {
+            self.notify_client(
+                requester,
+                ServerGeneral::server_msg(chat_type, Content::Plain(msg)),
+            );
+        }
+    }
+
     /// NOTE: Do *not* allow this to be called from any command that doesn't go
     /// through the CLI!
     pub fn add_admin(&mut self, username: &str, role: comp::AdminRole) {
@@ -1527,6 +1900,36 @@ impl Server {
         };
     }
 
+    /// NOTE: Do *not* allow this to be called from any command that doesn't go
+    /// through the CLI!
+    pub fn add_to_group(&self, username: &str, group: &str) {
+        let mut editable_settings = self.editable_settings_mut();
+        let login_provider = self.state.ecs().fetch::<LoginProvider>();
+        let data_dir = self.data_dir();
+        let _ = add_to_group(
+            username,
+            group,
+            &login_provider,
+            &mut editable_settings,
+            &data_dir.path,
+        );
+    }
+
+    /// NOTE: Do *not* allow this to be called from any command that doesn't go
+    /// through the CLI!
+    pub fn remove_from_group(&self, username: &str, group: &str) {
+        let mut editable_settings = self.editable_settings_mut();
+        let login_provider = self.state.ecs().fetch::<LoginProvider>();
+        let data_dir = self.data_dir();
+        let _ = remove_from_group(
+            username,
+            group,
+            &login_provider,
+            &mut editable_settings,
+            &data_dir.path,
+        );
+    }
+
     /// Useful for testing without a client
     /// view_distance: distance in chunks that are persisted, this acts like the
     /// player view distance so it is actually a bit farther due to a buffer
@@ -1773,6 +2176,16 @@ impl Drop for Server {
         self.state
             .notify_players(ServerGeneral::Disconnect(DisconnectReason::Shutdown));
 
+        // Persist online characters along with the storage containers modified since
+        // the last batch, so that items moved between them aren't lost. The batch is
+        // written before the character updater shuts down.
+        info!("Saving characters and storage containers...");
+        self.state
+            .ecs()
+            .write_resource::<sys::PersistenceScheduler>()
+            .run_next();
+        run_now::<sys::persistence::Sys>(self.state.ecs());
+
         #[cfg(feature = "persistent_world")]
         self.state
             .ecs()
@@ -1904,3 +2317,115 @@ pub fn remove_admin(
         },
     }
 }
+
+/// If successful returns the Some(uuid) of the player added to the permission
+/// group
+///
+/// NOTE: Do *not* allow this to be called from any command that doesn't go
+/// through the CLI!
+#[must_use]
+pub fn add_to_group(
+    username: &str,
+    group: &str,
+    login_provider: &LoginProvider,
+    editable_settings: &mut EditableSettings,
+    data_dir: &std::path::Path,
+) -> Option<common::uuid::Uuid> {
+    use crate::settings::EditableSetting;
+    if !editable_settings
+        .permission_groups
+        .groups
+        .contains_key(group)
+    {
+        error!(
+            "There is no permission group named {}, groups have to be defined in the permission \
+             groups settings file first.",
+            group
+        );
+        return None;
+    }
+    match login_provider.username_to_uuid(username) {
+        Ok(uuid) => handle_edit(
+            uuid,
+            editable_settings
+                .permission_groups
+                .edit(data_dir, |permission_groups| {
+                    let member = permission_groups.members.entry(uuid).or_insert_with(|| {
+                        settings::GroupMember {
+                            username_when_added: username.into(),
+                            date: chrono::Utc::now(),
+                            groups: Default::default(),
+                        }
+                    });
+                    if member.groups.insert(group.to_owned()) {
+                        member.username_when_added = username.into();
+                        member.date = chrono::Utc::now();
+                        Some(format!(
+                            "Successfully added {} ({}) to the {} group!",
+                            username, uuid, group
+                        ))
+                    } else {
+                        info!("{} ({}) is already in the {} group!", username, uuid, group);
+                        None
+                    }
+                }),
+        ),
+        Err(err) => {
+            error!(
+                ?err,
+                "Could not find uuid for this name; either the user does not exist or there was \
+                 an error communicating with the auth server."
+            );
+            None
+        },
+    }
+}
+
+/// If successful returns the Some(uuid) of the player removed from the
+/// permission group
+///
+/// NOTE: Do *not* allow this to be called from any command that doesn't go
+/// through the CLI!
+#[must_use]
+pub fn remove_from_group(
+    username: &str,
+    group: &str,
+    login_provider: &LoginProvider,
+    editable_settings: &mut EditableSettings,
+    data_dir: &std::path::Path,
+) -> Option<common::uuid::Uuid> {
+    use crate::settings::EditableSetting;
+    match login_provider.username_to_uuid(username) {
+        Ok(uuid) => handle_edit(
+            uuid,
+            editable_settings
+                .permission_groups
+                .edit(data_dir, |permission_groups| {
+                    let members = &mut permission_groups.members;
+                    let Some(member) = members
+                        .get_mut(&uuid)
+                        .filter(|member| member.groups.contains(group))
+                    else {
+                        info!("{} ({}) is not in the {} group!", username, uuid, group);
+                        return None;
+                    };
+                    member.groups.remove(group);
+                    if member.groups.is_empty() {
+                        members.remove(&uuid);
+                    }
+                    Some(format!(
+                        "Successfully removed {} ({}) from the {} group",
+                        username, uuid, group
+                    ))
+                }),
+        ),
+        Err(err) => {
+            error!(
+                ?err,
+                "Could not find uuid for this name; either the user does not exist or there was \
+                 an error communicating with the auth server."
+            );
+            None
+        },
+    }
+}
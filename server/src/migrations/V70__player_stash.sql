@@ -0,0 +1,8 @@
+-- Creates new stash table, linking each player to the pseudo-container holding
+-- the items of their account-wide stash
+CREATE TABLE "stash" (
+      "player_uuid" TEXT NOT NULL,
+      "stash_container_id" INT NOT NULL,
+      PRIMARY KEY("player_uuid"),
+      FOREIGN KEY("stash_container_id") REFERENCES item(item_id)
+);
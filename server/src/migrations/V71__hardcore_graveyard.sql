@@ -0,0 +1,18 @@
+-- Tracks how long each character has been played, in seconds
+ALTER TABLE "character" ADD COLUMN "playtime" INT NOT NULL DEFAULT 0;
+
+-- Creates the graveyard, which keeps a record of every hardcore character that
+-- died after the character itself has been deleted
+CREATE TABLE "hardcore_grave" (
+      "grave_id" INTEGER NOT NULL,
+      "player_uuid" TEXT NOT NULL,
+      "character_id" INT NOT NULL,
+      "alias" TEXT NOT NULL,
+      "cause" TEXT NOT NULL,
+      "killer" TEXT,
+      "playtime" INT NOT NULL,
+      "level" INT NOT NULL,
+      "location" TEXT,
+      "died_at" INT NOT NULL,
+      PRIMARY KEY("grave_id" AUTOINCREMENT)
+);
@@ -0,0 +1,11 @@
+-- Creates the storage container table, linking the position of each
+-- player-placed storage container to the pseudo-container holding its items
+CREATE TABLE "storage_container" (
+      "pos_x" INT NOT NULL,
+      "pos_y" INT NOT NULL,
+      "pos_z" INT NOT NULL,
+      "owner_uuid" TEXT NOT NULL,
+      "container_id" INT NOT NULL,
+      PRIMARY KEY("pos_x", "pos_y", "pos_z"),
+      FOREIGN KEY("container_id") REFERENCES item(item_id)
+);
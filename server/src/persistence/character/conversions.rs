@@ -13,6 +13,7 @@ use common::{
         ActiveAbilities, Body as CompBody, Content, Hardcore, Inventory, MapMarker, Stats,
         Waypoint, body,
         inventory::{
+            InvSlot, STASH_SLOTS,
             item::{Item as VelorenItem, MaterialStatManifest, tool::AbilityMap},
             loadout::{Loadout, LoadoutError},
             loadout_builder::LoadoutBuilder,
@@ -23,6 +24,7 @@ use common::{
         skillset::{self, SkillGroupKind, SkillSet, skills::Skill},
     },
     resources::Time,
+    storage::STORAGE_SLOTS,
 };
 use core::{convert::TryFrom, num::NonZeroU64};
 use hashbrown::HashMap;
@@ -59,6 +61,7 @@ pub fn convert_items_to_database_items(
     inventory_container_id: EntityId,
     overflow_items_container_id: EntityId,
     recipe_book_container_id: EntityId,
+    stash_container_id: Option<EntityId>,
     next_id: &mut i64,
 ) -> Vec<ItemModelPair> {
     let loadout = inventory
@@ -83,6 +86,19 @@ pub fn convert_items_to_database_items(
                 recipe_book_container_id,
             )
         });
+    // Stash slots, only persisted if the stash was loaded for this character
+    let stash = stash_container_id
+        .zip(inventory.stash())
+        .into_iter()
+        .flat_map(|(stash_container_id, stash)| {
+            stash.iter().enumerate().map(move |(i, item)| {
+                (
+                    serde_json::to_string(&i).expect("failed to serialize index of stash slot"),
+                    item.as_ref(),
+                    stash_container_id,
+                )
+            })
+        });
     // Inventory slots.
     let inventory = inventory.slots_with_id().map(|(pos, item)| {
         (
@@ -92,19 +108,65 @@ pub fn convert_items_to_database_items(
         )
     });
 
+    let mut containers = vec![
+        inventory_container_id,
+        loadout_container_id,
+        overflow_items_container_id,
+        recipe_book_container_id,
+    ];
+    containers.extend(stash_container_id);
+
+    convert_queued_items_to_database_items(
+        inventory
+            .chain(loadout)
+            .chain(overflow_items)
+            .chain(recipe_book)
+            .chain(stash)
+            .collect(),
+        &containers,
+        next_id,
+    )
+}
+
+/// Returns all item rows to upsert for the slots of a player-placed storage
+/// container, see [`convert_items_to_database_items`].
+pub fn convert_storage_container_to_database_items(
+    container_id: EntityId,
+    slots: &[InvSlot],
+    next_id: &mut i64,
+) -> Vec<ItemModelPair> {
+    convert_queued_items_to_database_items(
+        slots
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                (
+                    serde_json::to_string(&i)
+                        .expect("failed to serialize index of storage container slot"),
+                    item.as_ref(),
+                    container_id,
+                )
+            })
+            .collect(),
+        &[container_id],
+        next_id,
+    )
+}
+
+/// Converts the items queued with their position and parent container into item
+/// rows, along with all of their components.
+fn convert_queued_items_to_database_items(
     // Use Breadth-first search to recurse into containers/modular weapons to store
     // their parts
-    let mut bfs_queue: VecDeque<_> = inventory
-        .chain(loadout)
-        .chain(overflow_items)
-        .chain(recipe_book)
-        .collect();
+    mut bfs_queue: VecDeque<(String, Option<&VelorenItem>, EntityId)>,
+    pseudo_containers: &[EntityId],
+    next_id: &mut i64,
+) -> Vec<ItemModelPair> {
     let mut upserts = Vec::new();
-    let mut depth = HashMap::new();
-    depth.insert(inventory_container_id, 0);
-    depth.insert(loadout_container_id, 0);
-    depth.insert(overflow_items_container_id, 0);
-    depth.insert(recipe_book_container_id, 0);
+    let mut depth = pseudo_containers
+        .iter()
+        .map(|container_id| (*container_id, 0))
+        .collect::<HashMap<_, _>>();
     while let Some((position, item, parent_container_item_id)) = bfs_queue.pop_front() {
         // Construct new items.
         if let Some(item) = item {
@@ -585,7 +647,93 @@ pub fn convert_overflow_items_from_database_items(
     overflow_items_container_id: i64,
     database_items: &[Item],
 ) -> Result<Vec<VelorenItem>, PersistenceError> {
-    let mut overflow_items_with_database_position = HashMap::new();
+    let overflow_items = convert_positioned_items_from_database_items(
+        overflow_items_container_id,
+        database_items,
+        "overflow",
+    )?
+    .into_values()
+    .collect::<Vec<_>>();
+
+    Ok(overflow_items)
+}
+
+/// Loads the items of the account-wide stash into their slots. Items that
+/// don't fit into the stash (for example due to a reduction in the number of
+/// stash slots) are returned separately so that they can be pushed to the
+/// character's inventory instead.
+pub fn convert_stash_from_database_items(
+    stash_container_id: i64,
+    database_items: &[Item],
+) -> Result<(Vec<InvSlot>, Vec<VelorenItem>), PersistenceError> {
+    let mut stash = vec![None; STASH_SLOTS];
+    let mut leftover_items = Vec::new();
+
+    for (position, mut item) in
+        convert_positioned_items_from_database_items(stash_container_id, database_items, "stash")?
+    {
+        // Some items may have had components added, so update the item config to
+        // ensure that it correctly accounts for them
+        item.update_item_state(&ABILITY_MAP, &MATERIAL_STATS_MANIFEST);
+
+        match serde_json::from_str::<usize>(&position)
+            .ok()
+            .and_then(|idx| stash.get_mut(idx))
+        {
+            Some(slot) => *slot = Some(item),
+            None => leftover_items.push(item),
+        }
+    }
+
+    Ok((stash, leftover_items))
+}
+
+/// Loads the items of a player-placed storage container into their slots.
+/// Items that don't fit into their slot (for example due to a reduction in the
+/// number of container slots) are moved to a free slot, or to additional slots
+/// if the container is full.
+pub fn convert_storage_container_from_database_items(
+    container_id: i64,
+    database_items: &[Item],
+) -> Result<Vec<InvSlot>, PersistenceError> {
+    let mut slots = vec![None; STORAGE_SLOTS];
+    let mut leftover_items = Vec::new();
+
+    for (position, mut item) in convert_positioned_items_from_database_items(
+        container_id,
+        database_items,
+        "storage container",
+    )? {
+        item.update_item_state(&ABILITY_MAP, &MATERIAL_STATS_MANIFEST);
+
+        match serde_json::from_str::<usize>(&position)
+            .ok()
+            .and_then(|idx| slots.get_mut(idx))
+            .filter(|slot| slot.is_none())
+        {
+            Some(slot) => *slot = Some(item),
+            None => leftover_items.push(item),
+        }
+    }
+
+    for item in leftover_items {
+        match slots.iter_mut().find(|slot| slot.is_none()) {
+            Some(slot) => *slot = Some(item),
+            None => slots.push(Some(item)),
+        }
+    }
+
+    Ok(slots)
+}
+
+/// Loads items stored directly in a pseudo-container, keyed by their position
+/// within that container
+fn convert_positioned_items_from_database_items(
+    container_id: i64,
+    database_items: &[Item],
+    container_name: &str,
+) -> Result<HashMap<String, VelorenItem>, PersistenceError> {
+    let mut items_with_database_position = HashMap::new();
     let mut item_indices = HashMap::new();
 
     // In order to items with components to properly load, it is important that this
@@ -624,17 +772,17 @@ pub fn convert_overflow_items_from_database_items(
             })?;
         }
 
-        if db_item.parent_container_item_id == overflow_items_container_id {
-            match overflow_items_with_database_position.insert(db_item.position.clone(), item) {
+        if db_item.parent_container_item_id == container_id {
+            match items_with_database_position.insert(db_item.position.clone(), item) {
                 None => {
                     // Insert successful
                 },
                 Some(_item) => {
                     // If insert returns a value, database had two items stored with the same
                     // position which is an error.
-                    return Err(PersistenceError::ConversionError(
-                        "Inserted an item into the same overflow slot twice".to_string(),
-                    ));
+                    return Err(PersistenceError::ConversionError(format!(
+                        "Inserted an item into the same {container_name} slot twice"
+                    )));
                 },
             }
         } else if let Some(&j) = item_indices.get(&db_item.parent_container_item_id) {
@@ -642,23 +790,19 @@ pub fn convert_overflow_items_from_database_items(
                 j,
                 database_items,
                 &item_indices,
-                &mut overflow_items_with_database_position,
+                &mut items_with_database_position,
                 &|o_i, s| o_i.get_mut(s),
             )?
             .persistence_access_add_component(item);
         } else {
             return Err(PersistenceError::ConversionError(format!(
-                "Couldn't find parent item {} before item {} in overflow items",
+                "Couldn't find parent item {} before item {} in {container_name} items",
                 db_item.parent_container_item_id, db_item.item_id
             )));
         }
     }
 
-    let overflow_items = overflow_items_with_database_position
-        .into_values()
-        .collect::<Vec<_>>();
-
-    Ok(overflow_items)
+    Ok(items_with_database_position)
 }
 
 fn get_item_from_asset(item_definition_id: &str) -> Result<common::comp::Item, PersistenceError> {
@@ -9,6 +9,7 @@ extern crate rusqlite;
 use super::{error::PersistenceError, models::*};
 use crate::{
     comp::{self, Inventory},
+    graveyard::{Grave, HardcoreDeath},
     persistence::{
         EditableComponents, PersistedComponents,
         character::conversions::{
@@ -18,24 +19,39 @@ use crate::{
             convert_hardcore_to_database, convert_inventory_from_database_items,
             convert_items_to_database_items, convert_loadout_from_database_items,
             convert_recipe_book_from_database_items, convert_skill_groups_to_database,
-            convert_skill_set_from_database, convert_stats_from_database,
-            convert_waypoint_from_database_json, convert_waypoint_to_database_json,
+            convert_skill_set_from_database, convert_stash_from_database_items,
+            convert_stats_from_database, convert_storage_container_from_database_items,
+            convert_storage_container_to_database_items, convert_waypoint_from_database_json,
+            convert_waypoint_to_database_json,
         },
         character_loader::{CharacterCreationResult, CharacterDataResult, CharacterListResult},
-        character_updater::PetPersistenceData,
+        character_transfer::{
+            BundleBody, BundleContainers, BundleItem, BundleSkillGroup, CharacterBundle,
+        },
+        character_updater::{PetPersistenceData, StorageContainerUpdateData},
         error::PersistenceError::DatabaseError,
+        json_models::DatabaseAbilitySet,
     },
+    storage_container::StorageContainer,
 };
+use chrono::Utc;
 use common::{
     character::{CharacterId, CharacterItem, MAX_CHARACTERS_PER_PLAYER},
     comp::Content,
     event::{PermanentChange, UpdateCharacterMetadata},
     npc::NPC_NAMES,
+    uuid::Uuid,
 };
 use core::ops::Range;
-use rusqlite::{Connection, ToSql, Transaction, types::Value};
-use std::{num::NonZeroU64, rc::Rc};
+use rusqlite::{Connection, OptionalExtension, ToSql, Transaction, types::Value};
+use std::{
+    collections::{BTreeSet, HashMap},
+    num::NonZeroU64,
+    rc::Rc,
+    time::Duration,
+};
 use tracing::{debug, error, trace, warn};
+use vek::Vec3;
 
 /// Private module for very tightly coupled database conversion methods.  In
 /// general, these have many invariants that need to be maintained when they're
@@ -52,6 +68,8 @@ const LOADOUT_PSEUDO_CONTAINER_DEF_ID: &str = "veloren.core.pseudo_containers.lo
 const OVERFLOW_ITEMS_PSEUDO_CONTAINER_DEF_ID: &str =
     "veloren.core.pseudo_containers.overflow_items";
 const RECIPE_BOOK_PSEUDO_CONTAINER_DEF_ID: &str = "veloren.core.pseudo_containers.recipe_book";
+const STASH_PSEUDO_CONTAINER_DEF_ID: &str = "veloren.core.pseudo_containers.stash";
+const STORAGE_PSEUDO_CONTAINER_DEF_ID: &str = "veloren.core.pseudo_containers.storage";
 const INVENTORY_PSEUDO_CONTAINER_POSITION: &str = "inventory";
 const LOADOUT_PSEUDO_CONTAINER_POSITION: &str = "loadout";
 const OVERFLOW_ITEMS_PSEUDO_CONTAINER_POSITION: &str = "overflow_items";
@@ -140,6 +158,8 @@ pub fn load_character_data(
         load_items(connection, character_containers.overflow_items_container_id)?;
     let recipe_book_items = load_items(connection, character_containers.recipe_book_container_id)?;
 
+    let stash_container_id = get_stash_container_id(connection, &requesting_player_uuid)?;
+
     let mut stmt = connection.prepare_cached(
         "
         SELECT  c.character_id,
@@ -289,21 +309,40 @@ pub fn load_character_data(
         convert_skill_set_from_database(&skill_group_data);
     let body = convert_body_from_database(&body_data.variant, &body_data.body_data)?;
     let hardcore = convert_hardcore_from_database(character_data.hardcore)?;
+    let mut inventory = convert_inventory_from_database_items(
+        character_containers.inventory_container_id,
+        &inventory_items,
+        character_containers.loadout_container_id,
+        &loadout_items,
+        character_containers.overflow_items_container_id,
+        &overflow_items_items,
+        &recipe_book_items,
+    )?;
+
+    // Hardcore characters have no access to the stash shared by the player's other
+    // characters
+    if hardcore.is_none() {
+        let (stash, leftover_items) = match stash_container_id {
+            Some(stash_container_id) => convert_stash_from_database_items(
+                stash_container_id,
+                &load_items(connection, stash_container_id)?,
+            )?,
+            None => (Vec::new(), Vec::new()),
+        };
+
+        inventory = inventory.with_stash(stash);
+        if let Err(inv_error) = inventory.push_all(leftover_items.into_iter()) {
+            inventory.persistence_push_overflow_items(inv_error.returned_items());
+        }
+    }
+
     Ok((
         PersistedComponents {
             body,
             hardcore,
             stats: convert_stats_from_database(character_data.alias, body),
             skill_set,
-            inventory: convert_inventory_from_database_items(
-                character_containers.inventory_container_id,
-                &inventory_items,
-                character_containers.loadout_container_id,
-                &loadout_items,
-                character_containers.overflow_items_container_id,
-                &overflow_items_items,
-                &recipe_book_items,
-            )?,
+            inventory,
             waypoint: char_waypoint,
             pets,
             active_abilities: convert_active_abilities_from_database(&ability_set_data),
@@ -591,6 +630,7 @@ pub fn create_character(
             inventory_container_id,
             overflow_items_container_id,
             recipe_book_container_id,
+            None,
             &mut next_id,
         );
         inserts = inserts_;
@@ -792,6 +832,576 @@ pub fn delete_character(
     Ok(())
 }
 
+/// Records a dead hardcore character in the graveyard before deleting it
+pub fn archive_character(
+    requesting_player_uuid: &str,
+    char_id: CharacterId,
+    death: &HardcoreDeath,
+    playtime: Duration,
+    transaction: &mut Transaction,
+) -> Result<Option<Grave>, PersistenceError> {
+    debug!(?requesting_player_uuid, ?char_id, "Archiving character");
+
+    let mut stmt = transaction.prepare_cached(
+        "
+        SELECT  alias,
+                playtime
+        FROM    character
+        WHERE   character_id = ?1
+        AND     player_uuid = ?2",
+    )?;
+
+    let character = stmt
+        .query_row([&char_id.0 as &dyn ToSql, &requesting_player_uuid], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })
+        .optional()?;
+    drop(stmt);
+
+    let Some((alias, stored_playtime)) = character else {
+        // Same as for deletions, the character does not exist or does not belong to
+        // the requesting player so silently drop the request.
+        return Ok(None);
+    };
+
+    let grave = Grave {
+        alias,
+        cause: death.cause.clone(),
+        killer: death.killer.clone(),
+        playtime: stored_playtime as u64 + playtime.as_secs(),
+        level: death.level,
+        location: death.location.clone(),
+        died_at: Utc::now().timestamp(),
+    };
+
+    let mut stmt = transaction.prepare_cached(
+        "
+        INSERT
+        INTO    hardcore_grave (player_uuid,
+                                character_id,
+                                alias,
+                                cause,
+                                killer,
+                                playtime,
+                                level,
+                                location,
+                                died_at)
+        VALUES  (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+    )?;
+
+    stmt.execute([
+        &requesting_player_uuid as &dyn ToSql,
+        &char_id.0,
+        &grave.alias,
+        &grave.cause,
+        &grave.killer,
+        &(grave.playtime as i64),
+        &grave.level,
+        &grave.location,
+        &grave.died_at,
+    ])?;
+    drop(stmt);
+
+    delete_character(requesting_player_uuid, char_id, transaction)?;
+
+    Ok(Some(grave))
+}
+
+/// Loads every grave of the hardcore graveyard
+pub fn load_graves(connection: &Connection) -> Result<Vec<Grave>, PersistenceError> {
+    let mut stmt = connection.prepare_cached(
+        "
+        SELECT  alias,
+                cause,
+                killer,
+                playtime,
+                level,
+                location,
+                died_at
+        FROM    hardcore_grave",
+    )?;
+
+    let graves = stmt
+        .query_map([], |row| {
+            Ok(Grave {
+                alias: row.get(0)?,
+                cause: row.get(1)?,
+                killer: row.get(2)?,
+                playtime: row.get::<_, i64>(3)? as u64,
+                level: row.get(4)?,
+                location: row.get(5)?,
+                died_at: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(graves)
+}
+
+/// Loads every player-placed storage container along with its items.
+/// Containers whose items fail to load are skipped.
+pub fn load_storage_containers(
+    connection: &Connection,
+) -> Result<Vec<(Vec3<i32>, StorageContainer)>, PersistenceError> {
+    let mut stmt = connection.prepare_cached(
+        "
+        SELECT  pos_x,
+                pos_y,
+                pos_z,
+                owner_uuid,
+                container_id
+        FROM    storage_container",
+    )?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                Vec3::new(row.get(0)?, row.get(1)?, row.get(2)?),
+                row.get::<_, String>(3)?,
+                row.get::<_, EntityId>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    drop(stmt);
+
+    let mut containers = Vec::with_capacity(rows.len());
+    for (pos, owner, container_id) in rows {
+        let slots = load_items(connection, container_id)
+            .and_then(|items| convert_storage_container_from_database_items(container_id, &items));
+        match (Uuid::parse_str(&owner), slots) {
+            (Ok(owner), Ok(slots)) => containers.push((pos, StorageContainer { owner, slots })),
+            (Err(e), _) => error!(?e, ?pos, "Invalid owner of storage container"),
+            (_, Err(e)) => error!(?e, ?pos, "Failed to load items of storage container"),
+        }
+    }
+
+    Ok(containers)
+}
+
+/// Fetches the ID of the pseudo-container holding the items of the storage
+/// container at the given position, if there is one
+fn get_storage_container_id(
+    connection: &Connection,
+    pos: Vec3<i32>,
+) -> Result<Option<EntityId>, PersistenceError> {
+    let mut stmt = connection.prepare_cached(
+        "
+        SELECT  container_id
+        FROM    storage_container
+        WHERE   pos_x = ?1
+        AND     pos_y = ?2
+        AND     pos_z = ?3",
+    )?;
+
+    #[expect(clippy::needless_question_mark)]
+    let res = stmt.query_row([pos.x, pos.y, pos.z], |row| Ok(row.get(0)?));
+
+    match res {
+        Ok(id) => Ok(Some(id)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(DatabaseError(e)),
+    }
+}
+
+/// Stores the contents of modified storage containers, and deletes removed
+/// containers along with their items.
+pub fn update_storage_containers(
+    updates: Vec<StorageContainerUpdateData>,
+    transaction: &mut Transaction,
+) -> Result<(), PersistenceError> {
+    for (pos, container) in updates {
+        let existing_container_id = get_storage_container_id(transaction, pos)?;
+
+        let Some(container) = container else {
+            let Some(container_id) = existing_container_id else {
+                continue;
+            };
+            trace!(?pos, "Deleting storage container {}", container_id);
+
+            // Delete the pseudo-container and all items within it
+            let mut stmt = transaction.prepare_cached(
+                "
+                WITH RECURSIVE
+                parents AS (
+                    SELECT  item_id
+                    FROM    item
+                    WHERE   item.item_id = ?1
+                    UNION ALL
+                    SELECT  item.item_id
+                    FROM    item,
+                            parents
+                    WHERE   item.parent_container_item_id = parents.item_id
+                )
+                DELETE
+                FROM    item
+                WHERE   EXISTS (SELECT 1 FROM parents WHERE parents.item_id = item.item_id)",
+            )?;
+            stmt.execute([container_id])?;
+            drop(stmt);
+
+            let mut stmt = transaction.prepare_cached(
+                "
+                DELETE
+                FROM    storage_container
+                WHERE   container_id = ?1",
+            )?;
+            stmt.execute([container_id])?;
+            continue;
+        };
+
+        let container_id = match existing_container_id {
+            Some(container_id) => container_id,
+            None => create_storage_container(pos, container.owner, transaction)?,
+        };
+
+        let mut upserts = Vec::new();
+        get_new_entity_ids(transaction, |mut next_id| {
+            upserts = convert_storage_container_to_database_items(
+                container_id,
+                &container.slots,
+                &mut next_id,
+            );
+            next_id
+        })?;
+
+        // Delete any items that were taken out of the container
+        let existing_item_ids = std::iter::once(Value::from(container_id))
+            .chain(
+                load_items(transaction, container_id)?
+                    .into_iter()
+                    .map(|item| Value::from(item.item_id)),
+            )
+            .collect::<Vec<_>>();
+        let upserted_item_ids = upserts
+            .iter()
+            .map(|item_pair| Value::from(item_pair.model.item_id))
+            .collect::<Vec<_>>();
+
+        let mut stmt = transaction.prepare_cached(
+            "
+            DELETE
+            FROM    item
+            WHERE   parent_container_item_id
+            IN      rarray(?1)
+            AND     item_id NOT IN rarray(?2)",
+        )?;
+        let delete_count =
+            stmt.execute([Rc::new(existing_item_ids), Rc::new(upserted_item_ids)])?;
+        drop(stmt);
+        trace!(?pos, "Deleted {} storage container items", delete_count);
+
+        if upserts.is_empty() {
+            continue;
+        }
+
+        // Items may have moved here from a character's inventory in the same batch,
+        // see the comment in `update`
+        transaction.pragma_update(None, "defer_foreign_keys", "ON")?;
+
+        let mut stmt = transaction.prepare_cached(
+            "
+            REPLACE
+            INTO    item (item_id,
+                          parent_container_item_id,
+                          item_definition_id,
+                          stack_size,
+                          position,
+                          properties)
+            VALUES  (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?;
+
+        for item in upserts.iter().map(|item_pair| &item_pair.model) {
+            stmt.execute([
+                &item.item_id as &dyn ToSql,
+                &item.parent_container_item_id,
+                &item.item_definition_id,
+                &item.stack_size,
+                &item.position,
+                &item.properties,
+            ])?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates the pseudo-container holding the items of a new storage container
+fn create_storage_container(
+    pos: Vec3<i32>,
+    owner: Uuid,
+    transaction: &mut Transaction,
+) -> Result<EntityId, PersistenceError> {
+    let container_id = get_new_entity_ids(transaction, |next_id| next_id + 1)?.start;
+
+    let mut stmt = transaction.prepare_cached(
+        "
+        INSERT INTO item (item_id,
+                          parent_container_item_id,
+                          item_definition_id,
+                          stack_size,
+                          position,
+                          properties)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
+
+    stmt.execute([
+        &container_id as &dyn ToSql,
+        &WORLD_PSEUDO_CONTAINER_ID,
+        &STORAGE_PSEUDO_CONTAINER_DEF_ID,
+        &1,
+        &container_id.to_string(),
+        &String::new(),
+    ])?;
+    drop(stmt);
+
+    let mut stmt = transaction.prepare_cached(
+        "
+        INSERT INTO storage_container (pos_x,
+                                       pos_y,
+                                       pos_z,
+                                       owner_uuid,
+                                       container_id)
+        VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+
+    stmt.execute([
+        &pos.x as &dyn ToSql,
+        &pos.y,
+        &pos.z,
+        &owner.to_string(),
+        &container_id,
+    ])?;
+    drop(stmt);
+
+    debug!(?pos, "Created storage container {}", container_id);
+    Ok(container_id)
+}
+
+/// Collects everything stored about a character so that it can be moved to
+/// another server, see
+/// [`character_transfer`](crate::persistence::character_transfer).
+pub fn export_character(
+    char_id: CharacterId,
+    connection: &Connection,
+) -> Result<CharacterBundle, PersistenceError> {
+    let character_containers = get_pseudo_containers(connection, char_id)?;
+    let load_bundle_items = |container_id| -> Result<Vec<BundleItem>, PersistenceError> {
+        Ok(load_items(connection, container_id)?
+            .into_iter()
+            .map(BundleItem::from)
+            .collect())
+    };
+
+    let mut stmt = connection.prepare_cached(
+        "
+        SELECT  c.alias,
+                c.hardcore,
+                b.variant,
+                b.body_data
+        FROM    character c
+        JOIN    body b ON (c.character_id = b.body_id)
+        WHERE   c.character_id = ?1",
+    )?;
+
+    let (alias, hardcore, body): (String, i64, BundleBody) =
+        stmt.query_row([char_id.0], |row| {
+            Ok((row.get(0)?, row.get(1)?, BundleBody {
+                variant: row.get(2)?,
+                body_data: row.get(3)?,
+            }))
+        })?;
+    drop(stmt);
+
+    let mut stmt = connection.prepare_cached(
+        "
+        SELECT  skill_group_kind,
+                earned_exp,
+                spent_exp,
+                skills,
+                hash_val
+        FROM    skill_group
+        WHERE   entity_id = ?1",
+    )?;
+
+    let skill_groups = stmt
+        .query_map([char_id.0], |row| {
+            Ok(BundleSkillGroup {
+                skill_group_kind: row.get(0)?,
+                earned_exp: row.get(1)?,
+                spent_exp: row.get(2)?,
+                skills: row.get(3)?,
+                hash_val: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    drop(stmt);
+
+    let mut stmt = connection.prepare_cached(
+        "
+        SELECT  b.variant,
+                b.body_data
+        FROM    pet p
+        JOIN    body b ON (p.pet_id = b.body_id)
+        WHERE   p.character_id = ?1",
+    )?;
+
+    let pets = stmt
+        .query_map([char_id.0], |row| {
+            Ok(BundleBody {
+                variant: row.get(0)?,
+                body_data: row.get(1)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    drop(stmt);
+
+    let mut stmt = connection.prepare_cached(
+        "
+        SELECT  ability_sets
+        FROM    ability_set
+        WHERE   entity_id = ?1",
+    )?;
+
+    #[expect(clippy::needless_question_mark)]
+    let ability_sets = stmt.query_row([char_id.0], |row| Ok(row.get(0)?))?;
+    drop(stmt);
+
+    Ok(CharacterBundle {
+        schema_version: super::schema_version(),
+        alias,
+        hardcore,
+        body,
+        skill_groups,
+        ability_sets,
+        pets,
+        containers: BundleContainers {
+            inventory: character_containers.inventory_container_id,
+            loadout: character_containers.loadout_container_id,
+            overflow_items: character_containers.overflow_items_container_id,
+            recipe_book: character_containers.recipe_book_container_id,
+        },
+        inventory: load_bundle_items(character_containers.inventory_container_id)?,
+        loadout: load_bundle_items(character_containers.loadout_container_id)?,
+        overflow_items: load_bundle_items(character_containers.overflow_items_container_id)?,
+        recipe_book: load_bundle_items(character_containers.recipe_book_container_id)?,
+    })
+}
+
+/// Creates a new character for a player from a bundle exported by
+/// [`export_character`], possibly on another server.
+///
+/// The bundle goes through the same conversions as a character loaded from
+/// the database, so anything that wouldn't load is rejected before the
+/// character is created.
+pub fn import_character(
+    uuid: &str,
+    mut bundle: CharacterBundle,
+    transaction: &mut Transaction,
+) -> Result<CharacterId, PersistenceError> {
+    bundle.migrate_items(super::item_migrations_since(bundle.schema_version));
+
+    // Report every item that doesn't exist on this server at once, rather than
+    // having the admin find them one by one
+    let unknown_items = bundle
+        .items()
+        .map(|item| item.item_definition_id.as_str())
+        .filter(|item_definition_id| comp::Item::new_from_asset(item_definition_id).is_err())
+        .collect::<BTreeSet<_>>();
+    if !unknown_items.is_empty() {
+        return Err(PersistenceError::AssetError(format!(
+            "Unknown item definitions: {}",
+            unknown_items.into_iter().collect::<Vec<_>>().join(", ")
+        )));
+    }
+    // Invalid ability sets aren't reported as an error when converting them
+    serde_json::from_str::<Vec<DatabaseAbilitySet>>(&bundle.ability_sets)?;
+
+    // The IDs in the bundle are only meaningful within it, so every item and
+    // container gets a new ID to not collide with the items on this server
+    let item_count = bundle.items().count() as i64;
+    let mut new_entity_ids = get_new_entity_ids(transaction, |next_id| next_id + item_count + 4)?;
+    let mut new_ids = HashMap::new();
+    let mut remap = |id: EntityId| -> Result<EntityId, PersistenceError> {
+        if let Some(new_id) = new_ids.get(&id) {
+            return Ok(*new_id);
+        }
+        let new_id = new_entity_ids.next().ok_or_else(|| {
+            PersistenceError::ConversionError(
+                "Items of the bundle are stored in unknown containers".to_owned(),
+            )
+        })?;
+        new_ids.insert(id, new_id);
+        Ok(new_id)
+    };
+    let containers = bundle.containers;
+    let inventory_container_id = remap(containers.inventory)?;
+    let loadout_container_id = remap(containers.loadout)?;
+    let overflow_items_container_id = remap(containers.overflow_items)?;
+    let recipe_book_container_id = remap(containers.recipe_book)?;
+    for item in bundle.items_mut() {
+        item.item_id = remap(item.item_id)?;
+        item.parent_container_item_id = remap(item.parent_container_item_id)?;
+    }
+
+    let to_items = |items: &[BundleItem]| items.iter().map(Item::from).collect::<Vec<_>>();
+    let inventory = convert_inventory_from_database_items(
+        inventory_container_id,
+        &to_items(&bundle.inventory),
+        loadout_container_id,
+        &to_items(&bundle.loadout),
+        overflow_items_container_id,
+        &to_items(&bundle.overflow_items),
+        &to_items(&bundle.recipe_book),
+    )?;
+
+    let body = convert_body_from_database(&bundle.body.variant, &bundle.body.body_data)?;
+    let hardcore = convert_hardcore_from_database(bundle.hardcore)?;
+    let skill_groups = bundle
+        .skill_groups
+        .into_iter()
+        .map(|skill_group| SkillGroup {
+            // The skill groups are only converted here, not stored
+            entity_id: 0,
+            skill_group_kind: skill_group.skill_group_kind,
+            earned_exp: skill_group.earned_exp,
+            spent_exp: skill_group.spent_exp,
+            skills: skill_group.skills,
+            hash_val: skill_group.hash_val,
+        })
+        .collect::<Vec<_>>();
+    let (skill_set, _) = convert_skill_set_from_database(&skill_groups);
+    let active_abilities = convert_active_abilities_from_database(&AbilitySets {
+        entity_id: 0,
+        ability_sets: bundle.ability_sets,
+    });
+    let pets = bundle
+        .pets
+        .iter()
+        .map(|pet| {
+            let body = convert_body_from_database(&pet.variant, &pet.body_data)?;
+            Ok((comp::Pet::default(), body, comp::Stats::empty(body)))
+        })
+        .collect::<Result<Vec<_>, PersistenceError>>()?;
+
+    let persisted_components = PersistedComponents {
+        body,
+        hardcore,
+        stats: convert_stats_from_database(bundle.alias.clone(), body),
+        skill_set,
+        inventory,
+        waypoint: None,
+        pets: Vec::new(),
+        active_abilities,
+        map_marker: None,
+    };
+
+    let (character_id, _) =
+        create_character(uuid, &bundle.alias, persisted_components, transaction)?;
+    update_pets(character_id, pets, transaction)?;
+
+    Ok(character_id)
+}
+
 /// Before creating a character, we ensure that the limit on the number of
 /// characters has not been exceeded
 pub fn check_character_limit(
@@ -929,6 +1539,90 @@ fn get_pseudo_container_id(
     }
 }
 
+/// Fetches the ID of the pseudo-container holding the items of a player's
+/// account-wide stash, if one has been created yet
+fn get_stash_container_id(
+    connection: &Connection,
+    player_uuid: &str,
+) -> Result<Option<EntityId>, PersistenceError> {
+    let mut stmt = connection.prepare_cached(
+        "
+        SELECT  stash_container_id
+        FROM    stash
+        WHERE   player_uuid = ?1",
+    )?;
+
+    #[expect(clippy::needless_question_mark)]
+    let res = stmt.query_row([player_uuid], |row| Ok(row.get(0)?));
+
+    match res {
+        Ok(id) => Ok(Some(id)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(DatabaseError(e)),
+    }
+}
+
+/// Fetches the ID of the stash pseudo-container of the player owning a
+/// character, creating the stash if the player doesn't have one yet
+fn get_or_create_stash_container(
+    char_id: CharacterId,
+    transaction: &mut Transaction,
+) -> Result<EntityId, PersistenceError> {
+    let mut stmt = transaction.prepare_cached(
+        "
+        SELECT  player_uuid
+        FROM    character
+        WHERE   character_id = ?1",
+    )?;
+
+    #[expect(clippy::needless_question_mark)]
+    let player_uuid: String = stmt.query_row([char_id.0], |row| Ok(row.get(0)?))?;
+    drop(stmt);
+
+    if let Some(stash_container_id) = get_stash_container_id(transaction, &player_uuid)? {
+        return Ok(stash_container_id);
+    }
+
+    let stash_container_id = get_new_entity_ids(transaction, |next_id| next_id + 1)?.start;
+
+    let mut stmt = transaction.prepare_cached(
+        "
+        INSERT INTO item (item_id,
+                          parent_container_item_id,
+                          item_definition_id,
+                          stack_size,
+                          position,
+                          properties)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
+
+    stmt.execute([
+        &stash_container_id as &dyn ToSql,
+        &WORLD_PSEUDO_CONTAINER_ID,
+        &STASH_PSEUDO_CONTAINER_DEF_ID,
+        &1,
+        &stash_container_id.to_string(),
+        &String::new(),
+    ])?;
+    drop(stmt);
+
+    let mut stmt = transaction.prepare_cached(
+        "
+        INSERT INTO stash (player_uuid,
+                           stash_container_id)
+        VALUES (?1, ?2)",
+    )?;
+
+    stmt.execute([&player_uuid as &dyn ToSql, &stash_container_id])?;
+    drop(stmt);
+
+    debug!(
+        "Created stash container {} for player {}",
+        stash_container_id, player_uuid
+    );
+    Ok(stash_container_id)
+}
+
 /// Stores new pets in the database, and removes pets from the database that the
 /// player no longer has. Currently there are no actual updates to pet data
 /// since we don't store any updatable data about pets in the database.
@@ -1069,12 +1763,20 @@ pub fn update(
     char_waypoint: Option<comp::Waypoint>,
     active_abilities: comp::ability::ActiveAbilities,
     map_marker: Option<comp::MapMarker>,
+    playtime: Duration,
     transaction: &mut Transaction,
 ) -> Result<(), PersistenceError> {
     // Run pet persistence
     update_pets(char_id, pets, transaction)?;
 
     let pseudo_containers = get_pseudo_containers(transaction, char_id)?;
+    // The stash is only present in the inventory if it was loaded for this
+    // character, otherwise it must be left untouched
+    let stash_container_id = if inventory.stash().is_some() {
+        Some(get_or_create_stash_container(char_id, transaction)?)
+    } else {
+        None
+    };
     let mut upserts = Vec::new();
     // First, get all the entity IDs for any new items, and identify which
     // slots to upsert and which ones to delete.
@@ -1085,6 +1787,7 @@ pub fn update(
             pseudo_containers.inventory_container_id,
             pseudo_containers.overflow_items_container_id,
             pseudo_containers.recipe_book_container_id,
+            stash_container_id,
             &mut next_id,
         );
         upserts = upserts_;
@@ -1111,6 +1814,12 @@ pub fn update(
     for it in load_items(transaction, pseudo_containers.recipe_book_container_id)? {
         existing_item_ids.push(Value::from(it.item_id));
     }
+    if let Some(stash_container_id) = stash_container_id {
+        existing_item_ids.push(Value::from(stash_container_id));
+        for it in load_items(transaction, stash_container_id)? {
+            existing_item_ids.push(Value::from(it.item_id));
+        }
+    }
 
     let non_upserted_items = upserts
         .iter()
@@ -1209,12 +1918,17 @@ pub fn update(
     let mut stmt = transaction.prepare_cached(
         "
         UPDATE  character
-        SET     waypoint = ?1
-        WHERE   character_id = ?2
+        SET     waypoint = ?1,
+                playtime = playtime + ?2
+        WHERE   character_id = ?3
     ",
     )?;
 
-    let waypoint_count = stmt.execute([&db_waypoint as &dyn ToSql, &char_id.0])?;
+    let waypoint_count = stmt.execute([
+        &db_waypoint as &dyn ToSql,
+        &(playtime.as_secs() as i64),
+        &char_id.0,
+    ])?;
 
     if waypoint_count != 1 {
         return Err(PersistenceError::OtherError(format!(
@@ -1,8 +1,11 @@
-use crate::persistence::{
-    ConnectionMode, DatabaseSettings, PersistedComponents,
-    character::{load_character_data, load_character_list},
-    error::PersistenceError,
-    establish_connection,
+use crate::{
+    graveyard::Grave,
+    persistence::{
+        ConnectionMode, DatabaseSettings, PersistedComponents,
+        character::{load_character_data, load_character_list},
+        error::PersistenceError,
+        establish_connection,
+    },
 };
 use common::{
     character::{CharacterId, CharacterItem},
@@ -36,6 +39,14 @@ enum CharacterLoaderRequestKind {
 pub enum CharacterUpdaterMessage {
     CharacterScreenResponse(CharacterScreenResponse),
     DatabaseBatchCompletion(u64),
+    /// Outcome of a character export or import, with a message describing
+    /// what was done on success
+    CharacterTransfer {
+        requester: Option<specs::Entity>,
+        result: Result<String, PersistenceError>,
+    },
+    /// Graves of the hardcore characters archived by a batch update
+    Graves(Vec<Grave>),
 }
 
 /// An event emitted from CharacterUpdater in response to a request made from
@@ -0,0 +1,398 @@
+//! Moving characters between servers.
+//!
+//! A character is exported to a [`CharacterBundle`], which holds the same rows
+//! that are stored in the database for it (see [`models`]). The bundle is
+//! written to a RON file in the [`TRANSFER_DIR`] of the server data directory,
+//! signed with a key kept in the same directory. Servers that share this key
+//! accept each other's bundles without further confirmation; unsigned or
+//! foreign bundles have to be imported explicitly.
+//!
+//! Importing validates the bundle the same way characters are validated when
+//! they are loaded from the database, in particular every item definition has
+//! to exist in the assets of the importing server. Items renamed or removed by
+//! database migrations since the bundle was exported are migrated first.
+
+use super::{ItemMigration, error::PersistenceError, models};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tracing::info;
+
+/// Directory within the server data directory that bundles are written to and
+/// read from.
+pub const TRANSFER_DIR: &str = "character_transfer";
+
+const KEY_FILE: &str = "signing.key";
+
+/// Version of the bundle format, bump this when changing [`CharacterBundle`].
+const BUNDLE_VERSION: u32 = 1;
+
+/// The file format of an exported character.
+#[derive(Serialize, Deserialize)]
+struct SignedCharacterBundle {
+    version: u32,
+    /// Version of the game that the character was exported from, only used
+    /// for diagnostics.
+    game_version: String,
+    /// Hex encoded HMAC-SHA256 of `payload`.
+    signature: String,
+    /// The [`CharacterBundle`], serialized to RON. The signature is over this
+    /// string so that it doesn't depend on how the bundle gets serialized.
+    payload: String,
+}
+
+/// Everything stored about a character, as it is stored in the database.
+///
+/// Neither the account-wide stash nor the waypoint are included: the stash
+/// doesn't belong to the character and the waypoint only makes sense in the
+/// world it was set in.
+#[derive(Serialize, Deserialize)]
+pub struct CharacterBundle {
+    /// Version of the last database migration of the exporting server, see
+    /// [`item_migrations_since`](super::item_migrations_since).
+    pub schema_version: i64,
+    pub alias: String,
+    pub hardcore: i64,
+    pub body: BundleBody,
+    pub skill_groups: Vec<BundleSkillGroup>,
+    pub ability_sets: String,
+    pub pets: Vec<BundleBody>,
+    /// IDs of the pseudo-containers that the items below are stored in. These
+    /// and the item IDs are only meaningful within the bundle.
+    pub containers: BundleContainers,
+    pub inventory: Vec<BundleItem>,
+    pub loadout: Vec<BundleItem>,
+    pub overflow_items: Vec<BundleItem>,
+    pub recipe_book: Vec<BundleItem>,
+}
+
+impl CharacterBundle {
+    pub fn items(&self) -> impl Iterator<Item = &BundleItem> {
+        self.inventory
+            .iter()
+            .chain(&self.loadout)
+            .chain(&self.overflow_items)
+            .chain(&self.recipe_book)
+    }
+
+    pub fn items_mut(&mut self) -> impl Iterator<Item = &mut BundleItem> {
+        self.inventory
+            .iter_mut()
+            .chain(&mut self.loadout)
+            .chain(&mut self.overflow_items)
+            .chain(&mut self.recipe_book)
+    }
+
+    /// Applies the item renames and removals of database migrations to the
+    /// items of the bundle, the same way they were applied to the items stored
+    /// in the database.
+    pub(crate) fn migrate_items<'a>(
+        &mut self,
+        migrations: impl IntoIterator<Item = &'a ItemMigration>,
+    ) {
+        for migration in migrations {
+            for items in [
+                &mut self.inventory,
+                &mut self.loadout,
+                &mut self.overflow_items,
+                &mut self.recipe_book,
+            ] {
+                items.retain_mut(|item| match migration {
+                    ItemMigration::Rename(from, to) => {
+                        if item.item_definition_id == *from {
+                            item.item_definition_id = (*to).to_owned();
+                        }
+                        true
+                    },
+                    ItemMigration::Remove(item_definition_id) => {
+                        item.item_definition_id != *item_definition_id
+                    },
+                });
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BundleBody {
+    pub variant: String,
+    pub body_data: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BundleSkillGroup {
+    pub skill_group_kind: String,
+    pub earned_exp: i64,
+    pub spent_exp: i64,
+    pub skills: String,
+    pub hash_val: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct BundleContainers {
+    pub inventory: i64,
+    pub loadout: i64,
+    pub overflow_items: i64,
+    pub recipe_book: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BundleItem {
+    pub item_id: i64,
+    pub parent_container_item_id: i64,
+    pub item_definition_id: String,
+    pub stack_size: i64,
+    pub position: String,
+    pub properties: String,
+}
+
+impl From<models::Item> for BundleItem {
+    fn from(item: models::Item) -> Self {
+        Self {
+            item_id: item.item_id,
+            parent_container_item_id: item.parent_container_item_id,
+            item_definition_id: item.item_definition_id,
+            stack_size: item.stack_size,
+            position: item.position,
+            properties: item.properties,
+        }
+    }
+}
+
+impl From<&BundleItem> for models::Item {
+    fn from(item: &BundleItem) -> Self {
+        Self {
+            item_id: item.item_id,
+            parent_container_item_id: item.parent_container_item_id,
+            item_definition_id: item.item_definition_id.clone(),
+            stack_size: item.stack_size,
+            position: item.position.clone(),
+            properties: item.properties.clone(),
+        }
+    }
+}
+
+/// The key bundles are signed with.
+pub struct TransferKey(Vec<u8>);
+
+impl TransferKey {
+    /// Loads the key of this server, generating a new one if there is none
+    /// yet.
+    pub fn load_or_create(data_dir: &Path) -> Result<Self, PersistenceError> {
+        let dir = data_dir.join(TRANSFER_DIR);
+        let path = dir.join(KEY_FILE);
+        match fs::read_to_string(&path) {
+            Ok(key) => hex::decode(key.trim()).map(Self).map_err(|err| {
+                PersistenceError::OtherError(format!("Invalid key in {}: {}", path.display(), err))
+            }),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                let key = rand::random::<[u8; 32]>();
+                fs::create_dir_all(&dir).map_err(io_error)?;
+                fs::write(&path, hex::encode(key)).map_err(io_error)?;
+                info!("Generated new character transfer key at {}", path.display());
+                Ok(Self(key.to_vec()))
+            },
+            Err(err) => Err(io_error(err)),
+        }
+    }
+
+    fn mac(&self, message: &[u8]) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.0).expect("HMAC accepts keys of any length");
+        mac.update(message);
+        mac
+    }
+
+    /// HMAC-SHA256 of the message.
+    fn sign(&self, message: &[u8]) -> [u8; 32] { self.mac(message).finalize().into_bytes().into() }
+
+    fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        // Compares in constant time
+        self.mac(message).verify_slice(signature).is_ok()
+    }
+}
+
+/// Resolves the name of a bundle file within the transfer directory. Only plain
+/// file names are accepted, so that commands can't be used to access arbitrary
+/// files.
+pub fn bundle_path(data_dir: &Path, file_name: &str) -> Result<PathBuf, PersistenceError> {
+    let is_plain_name = Path::new(file_name)
+        .file_name()
+        .is_some_and(|name| name == file_name)
+        && !file_name.starts_with('.');
+    if is_plain_name {
+        Ok(data_dir.join(TRANSFER_DIR).join(file_name))
+    } else {
+        Err(PersistenceError::OtherError(format!(
+            "Invalid bundle file name: {}",
+            file_name
+        )))
+    }
+}
+
+pub fn write_bundle(
+    path: &Path,
+    bundle: &CharacterBundle,
+    key: &TransferKey,
+) -> Result<(), PersistenceError> {
+    let payload =
+        ron::to_string(bundle).map_err(|err| PersistenceError::ConversionError(err.to_string()))?;
+    let signed = SignedCharacterBundle {
+        version: BUNDLE_VERSION,
+        game_version: common::util::DISPLAY_VERSION_LONG.clone(),
+        signature: hex::encode(key.sign(payload.as_bytes())),
+        payload,
+    };
+    let contents = ron::ser::to_string_pretty(&signed, ron::ser::PrettyConfig::default())
+        .map_err(|err| PersistenceError::ConversionError(err.to_string()))?;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    fs::write(path, contents).map_err(io_error)
+}
+
+/// Reads a bundle, checking that it was signed with the given key unless
+/// `allow_unsigned` is set.
+pub fn read_bundle(
+    path: &Path,
+    key: &TransferKey,
+    allow_unsigned: bool,
+) -> Result<CharacterBundle, PersistenceError> {
+    let contents = fs::read_to_string(path).map_err(io_error)?;
+    let signed = ron::from_str::<SignedCharacterBundle>(&contents)
+        .map_err(|err| PersistenceError::ConversionError(err.to_string()))?;
+
+    if signed.version != BUNDLE_VERSION {
+        return Err(PersistenceError::ConversionError(format!(
+            "Unsupported bundle version {}, exported from {}",
+            signed.version, signed.game_version
+        )));
+    }
+
+    let is_signed = hex::decode(&signed.signature)
+        .is_ok_and(|signature| key.verify(signed.payload.as_bytes(), &signature));
+    if !is_signed && !allow_unsigned {
+        return Err(PersistenceError::OtherError(
+            "The bundle was not signed by a trusted server, it has to be imported as unsigned"
+                .to_owned(),
+        ));
+    }
+
+    ron::from_str(&signed.payload).map_err(|err| PersistenceError::ConversionError(err.to_string()))
+}
+
+fn io_error(err: std::io::Error) -> PersistenceError {
+    PersistenceError::OtherError(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hmac_sha256_test_vector() {
+        // Test case 2 of RFC 4231
+        let key = TransferKey(b"Jefe".to_vec());
+        assert_eq!(
+            hex::encode(key.sign(b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    fn bundle(schema_version: i64, items: &[&str]) -> CharacterBundle {
+        let body = || BundleBody {
+            variant: "humanoid".to_owned(),
+            body_data: String::new(),
+        };
+        CharacterBundle {
+            schema_version,
+            alias: "Bundled".to_owned(),
+            hardcore: 0,
+            body: body(),
+            skill_groups: Vec::new(),
+            ability_sets: String::new(),
+            pets: vec![body()],
+            containers: BundleContainers {
+                inventory: 1,
+                loadout: 2,
+                overflow_items: 3,
+                recipe_book: 4,
+            },
+            inventory: items
+                .iter()
+                .enumerate()
+                .map(|(i, item_definition_id)| BundleItem {
+                    item_id: 5 + i as i64,
+                    parent_container_item_id: 1,
+                    item_definition_id: (*item_definition_id).to_owned(),
+                    stack_size: 1,
+                    position: String::new(),
+                    properties: String::new(),
+                })
+                .collect(),
+            loadout: Vec::new(),
+            overflow_items: Vec::new(),
+            recipe_book: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn migrates_renamed_items() {
+        let mut bundle = bundle(72, &["common.items.a", "common.items.b", "common.items.c"]);
+        bundle.migrate_items(&[
+            ItemMigration::Rename("common.items.a", "common.items.d"),
+            ItemMigration::Remove("common.items.b"),
+            ItemMigration::Rename("common.items.d", "common.items.e"),
+        ]);
+        assert_eq!(
+            bundle
+                .items()
+                .map(|item| item.item_definition_id.as_str())
+                .collect::<Vec<_>>(),
+            ["common.items.e", "common.items.c"]
+        );
+    }
+
+    #[test]
+    fn item_migrations_are_listed() {
+        use crate::persistence::{FIRST_EXPORT_SCHEMA_VERSION, ITEM_MIGRATIONS, embedded};
+
+        // Every migration since characters can be exported that changes items
+        // needs to say how
+        for migration in embedded::migrations::runner().get_migrations() {
+            let version = i64::from(migration.version());
+            let sql = migration.sql().unwrap_or_default().to_ascii_uppercase();
+            if version > FIRST_EXPORT_SCHEMA_VERSION
+                && (sql.contains("UPDATE ITEM") || sql.contains("DELETE FROM ITEM"))
+            {
+                assert!(
+                    ITEM_MIGRATIONS.iter().any(|(listed, _)| *listed == version),
+                    "Migration {version} changes items but isn't listed in ITEM_MIGRATIONS"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn requires_schema_version() {
+        let payload = ron::to_string(&bundle(72, &["common.items.a"])).unwrap();
+        assert!(ron::from_str::<CharacterBundle>(&payload).is_ok());
+        let payload = payload.replacen("schema_version:72,", "", 1);
+        assert!(ron::from_str::<CharacterBundle>(&payload).is_err());
+    }
+
+    #[test]
+    fn rejects_paths() {
+        let data_dir = Path::new("data");
+        assert!(bundle_path(data_dir, "character_1.ron").is_ok());
+        assert!(bundle_path(data_dir, "../settings.ron").is_err());
+        assert!(bundle_path(data_dir, "/etc/passwd").is_err());
+        assert!(bundle_path(data_dir, ".hidden").is_err());
+        assert!(bundle_path(data_dir, "").is_err());
+    }
+}
@@ -1,4 +1,8 @@
-use crate::comp;
+use crate::{
+    comp,
+    graveyard::{Grave, HardcoreDeath},
+    storage_container::StorageContainer,
+};
 use common::{character::CharacterId, event::PermanentChange};
 
 use crate::persistence::{
@@ -6,6 +10,7 @@ use crate::persistence::{
     character_loader::{
         CharacterScreenResponse, CharacterScreenResponseKind, CharacterUpdaterMessage,
     },
+    character_transfer,
     error::PersistenceError,
     establish_connection,
 };
@@ -14,12 +19,15 @@ use rusqlite::DropBehavior;
 use specs::Entity;
 use std::{
     collections::HashMap,
+    path::{Path, PathBuf},
     sync::{
         Arc, RwLock,
         atomic::{AtomicBool, Ordering},
     },
+    time::{Duration, Instant},
 };
 use tracing::{debug, error, info, trace, warn};
+use vek::Vec3;
 
 pub type CharacterUpdateData = (
     CharacterId,
@@ -33,6 +41,10 @@ pub type CharacterUpdateData = (
 
 pub type PetPersistenceData = (comp::Pet, comp::Body, comp::Stats);
 
+/// The contents of a modified storage container, or `None` if the container
+/// was removed
+pub type StorageContainerUpdateData = (Vec3<i32>, Option<StorageContainer>);
+
 #[expect(clippy::large_enum_variant)]
 enum CharacterUpdaterAction {
     BatchUpdate {
@@ -53,6 +65,23 @@ enum CharacterUpdaterAction {
         editable_components: EditableComponents,
         trusted_change: Option<PermanentChange>,
     },
+    ExportCharacter {
+        requester: Option<Entity>,
+        character_id: CharacterId,
+        data_dir: PathBuf,
+        file_name: String,
+    },
+    ImportCharacter {
+        requester: Option<Entity>,
+        player_uuid: String,
+        data_dir: PathBuf,
+        file_name: String,
+        allow_unsigned: bool,
+    },
+    BackupDatabase {
+        path: PathBuf,
+        done: crossbeam_channel::Sender<Result<(), PersistenceError>>,
+    },
     DisconnectedSuccess,
 }
 
@@ -76,11 +105,19 @@ impl DatabaseAction {
 
 #[derive(Clone)]
 enum DatabaseActionKind {
-    UpdateCharacter(Box<CharacterUpdateData>),
+    /// Along with the time played since the last update
+    UpdateCharacter(Box<CharacterUpdateData>, Duration),
     DeleteCharacter {
         requesting_player_uuid: String,
         character_id: CharacterId,
     },
+    ArchiveCharacter {
+        requesting_player_uuid: String,
+        character_id: CharacterId,
+        death: Box<HardcoreDeath>,
+        playtime: Duration,
+    },
+    UpdateStorageContainers(Vec<StorageContainerUpdateData>),
 }
 
 /// A unidirectional messaging resource for saving characters in a
@@ -95,6 +132,12 @@ pub struct CharacterUpdater {
     /// Pending actions to be performed during the next persistence batch, such
     /// as updates for recently logged out players and character deletions
     pending_database_actions: HashMap<CharacterId, DatabaseAction>,
+    /// UUIDs of players whose account-wide stash is included in a pending
+    /// database action, keyed by the character the stash belongs to
+    pending_stash_owners: HashMap<CharacterId, String>,
+    /// For each character in game, the time from which its playtime hasn't
+    /// been persisted yet
+    sessions: HashMap<CharacterId, Instant>,
     /// Will disconnect all characters (without persistence) on the next tick if
     /// set to true
     disconnect_all_clients_requested: Arc<AtomicBool>,
@@ -128,15 +171,25 @@ impl CharacterUpdater {
                             }
                             conn.update_log_mode(&settings);
 
-                            if let Err(e) = execute_batch_update(updates.into_iter(), &mut conn) {
-                                error!(
-                                    ?e,
-                                    "Error during character batch update, disconnecting all \
-                                     clients to avoid loss of data integrity."
-                                );
-                                disconnect_all_clients_requested_clone
-                                    .store(true, Ordering::Relaxed);
-                            };
+                            match execute_batch_update(updates.into_iter(), &mut conn) {
+                                Ok(graves) if !graves.is_empty() => {
+                                    if let Err(e) =
+                                        response_tx.send(CharacterUpdaterMessage::Graves(graves))
+                                    {
+                                        error!(?e, "Could not send Graves message");
+                                    }
+                                },
+                                Ok(_) => {},
+                                Err(e) => {
+                                    error!(
+                                        ?e,
+                                        "Error during character batch update, disconnecting all \
+                                         clients to avoid loss of data integrity."
+                                    );
+                                    disconnect_all_clients_requested_clone
+                                        .store(true, Ordering::Relaxed);
+                                },
+                            }
 
                             if let Err(e) = response_tx
                                 .send(CharacterUpdaterMessage::DatabaseBatchCompletion(batch_id))
@@ -211,6 +264,56 @@ impl CharacterUpdater {
                                 ),
                             }
                         },
+                        CharacterUpdaterAction::ExportCharacter {
+                            requester,
+                            character_id,
+                            data_dir,
+                            file_name,
+                        } => {
+                            let result = execute_character_export(
+                                character_id,
+                                &data_dir,
+                                &file_name,
+                                &mut conn,
+                            );
+                            if let Err(e) =
+                                response_tx.send(CharacterUpdaterMessage::CharacterTransfer {
+                                    requester,
+                                    result,
+                                })
+                            {
+                                error!(?e, "Could not send character export response");
+                            }
+                        },
+                        CharacterUpdaterAction::ImportCharacter {
+                            requester,
+                            player_uuid,
+                            data_dir,
+                            file_name,
+                            allow_unsigned,
+                        } => {
+                            let result = execute_character_import(
+                                &player_uuid,
+                                &data_dir,
+                                &file_name,
+                                allow_unsigned,
+                                &mut conn,
+                            );
+                            if let Err(e) =
+                                response_tx.send(CharacterUpdaterMessage::CharacterTransfer {
+                                    requester,
+                                    result,
+                                })
+                            {
+                                error!(?e, "Could not send character import response");
+                            }
+                        },
+                        CharacterUpdaterAction::BackupDatabase { path, done } => {
+                            let result = execute_database_backup(&path, &conn);
+                            if let Err(e) = done.send(result) {
+                                error!(?e, "Could not send database backup result");
+                            }
+                        },
                         CharacterUpdaterAction::DisconnectedSuccess => {
                             info!(
                                 "CharacterUpdater received DisconnectedSuccess event, resuming \
@@ -230,6 +333,8 @@ impl CharacterUpdater {
             response_rx,
             handle: Some(handle),
             pending_database_actions: HashMap::new(),
+            pending_stash_owners: HashMap::new(),
+            sessions: HashMap::new(),
             disconnect_all_clients_requested,
             last_pending_database_event_id: 0,
         })
@@ -237,7 +342,11 @@ impl CharacterUpdater {
 
     /// Adds a character to the list of characters that have recently logged out
     /// and will be persisted in the next batch update.
-    pub fn add_pending_logout_update(&mut self, update_data: CharacterUpdateData) {
+    pub fn add_pending_logout_update(
+        &mut self,
+        player_uuid: String,
+        update_data: CharacterUpdateData,
+    ) {
         if self
             .disconnect_all_clients_requested
             .load(Ordering::Relaxed)
@@ -259,22 +368,70 @@ impl CharacterUpdater {
             return;
         }
 
+        if update_data.2.stash().is_some() {
+            self.pending_stash_owners.insert(update_data.0, player_uuid);
+        }
+
+        let playtime = self.end_session(update_data.0);
         self.pending_database_actions.insert(
             update_data.0, // CharacterId
-            DatabaseAction::New(DatabaseActionKind::UpdateCharacter(Box::new(update_data))),
+            DatabaseAction::New(DatabaseActionKind::UpdateCharacter(
+                Box::new(update_data),
+                playtime,
+            )),
         );
     }
 
+    /// Starts counting the playtime of a character that entered the game.
+    pub fn start_session(&mut self, character_id: CharacterId) {
+        self.sessions.insert(character_id, Instant::now());
+    }
+
+    /// Returns the playtime of a character since it was last persisted, in
+    /// whole seconds so that no time is lost to rounding.
+    fn take_playtime(&mut self, character_id: CharacterId) -> Duration {
+        self.sessions
+            .get_mut(&character_id)
+            .map_or(Duration::ZERO, |since| {
+                let playtime = Duration::from_secs(since.elapsed().as_secs());
+                *since += playtime;
+                playtime
+            })
+    }
+
+    fn end_session(&mut self, character_id: CharacterId) -> Duration {
+        let playtime = self.take_playtime(character_id);
+        self.sessions.remove(&character_id);
+        playtime
+    }
+
     pub fn has_pending_database_action(&self, character_id: CharacterId) -> bool {
         self.pending_database_actions.contains_key(&character_id)
     }
 
+    /// Returns whether a character is in game, or was recently and hasn't been
+    /// persisted since. The database doesn't hold its latest state until then.
+    pub fn is_in_use(&self, character_id: CharacterId) -> bool {
+        self.sessions.contains_key(&character_id) || self.has_pending_database_action(character_id)
+    }
+
+    /// Returns whether the stash of a player is part of a pending update, in
+    /// which case none of their characters may load it yet.
+    pub fn has_pending_stash_update(&self, player_uuid: &str) -> bool {
+        self.pending_stash_owners
+            .values()
+            .any(|stash_owner| stash_owner == player_uuid)
+    }
+
     pub fn process_batch_completion(&mut self, completed_batch_id: u64) {
         self.pending_database_actions.retain(|_, event| {
             !matches!(event, DatabaseAction::Submitted {
                     batch_id,
             } if completed_batch_id == *batch_id)
         });
+        let pending_database_actions = &self.pending_database_actions;
+        self.pending_stash_owners
+            .retain(|character_id, _| pending_database_actions.contains_key(character_id));
         debug!(
             "Processed database batch completion - Batch ID: {}",
             completed_batch_id
@@ -336,6 +493,74 @@ impl CharacterUpdater {
         }
     }
 
+    /// Writes a character to a bundle file in the transfer directory of
+    /// `data_dir`, see [`character_transfer`](super::character_transfer).
+    pub fn export_character(
+        &mut self,
+        requester: Option<Entity>,
+        character_id: CharacterId,
+        data_dir: PathBuf,
+        file_name: String,
+    ) {
+        if let Err(e) =
+            self.update_tx
+                .as_ref()
+                .unwrap()
+                .send(CharacterUpdaterAction::ExportCharacter {
+                    requester,
+                    character_id,
+                    data_dir,
+                    file_name,
+                })
+        {
+            error!(?e, "Could not send character export request");
+        }
+    }
+
+    /// Creates a character for a player from a bundle file in the transfer
+    /// directory of `data_dir`.
+    pub fn import_character(
+        &mut self,
+        requester: Option<Entity>,
+        player_uuid: String,
+        data_dir: PathBuf,
+        file_name: String,
+        allow_unsigned: bool,
+    ) {
+        if let Err(e) =
+            self.update_tx
+                .as_ref()
+                .unwrap()
+                .send(CharacterUpdaterAction::ImportCharacter {
+                    requester,
+                    player_uuid,
+                    data_dir,
+                    file_name,
+                    allow_unsigned,
+                })
+        {
+            error!(?e, "Could not send character import request");
+        }
+    }
+
+    /// Copies the database to `path`. The copy includes every batch update
+    /// submitted before, the returned channel receives the outcome.
+    pub fn backup_database(
+        &mut self,
+        path: PathBuf,
+    ) -> crossbeam_channel::Receiver<Result<(), PersistenceError>> {
+        let (done, result) = crossbeam_channel::bounded(1);
+        if let Err(e) = self
+            .update_tx
+            .as_ref()
+            .unwrap()
+            .send(CharacterUpdaterAction::BackupDatabase { path, done })
+        {
+            error!(?e, "Could not send database backup request");
+        }
+        result
+    }
+
     fn next_pending_database_event_id(&mut self) -> u64 {
         self.last_pending_database_event_id += 1;
         self.last_pending_database_event_id
@@ -358,8 +583,35 @@ impl CharacterUpdater {
         );
     }
 
-    /// Updates a collection of characters based on their id and components
-    pub fn batch_update(&mut self, updates: impl Iterator<Item = CharacterUpdateData>) {
+    /// Moves a dead hardcore character that left the game to the graveyard.
+    pub fn queue_character_archival(
+        &mut self,
+        requesting_player_uuid: String,
+        character_id: CharacterId,
+        death: HardcoreDeath,
+    ) {
+        let playtime = self.end_session(character_id);
+        self.pending_database_actions.insert(
+            character_id,
+            DatabaseAction::New(DatabaseActionKind::ArchiveCharacter {
+                requesting_player_uuid,
+                character_id,
+                death: Box::new(death),
+                playtime,
+            }),
+        );
+    }
+
+    /// Updates a collection of characters based on their id and components,
+    /// along with the storage containers modified since the last batch.
+    ///
+    /// Storage containers are saved in the same transaction as characters, so
+    /// that items moved between the two are never duplicated or lost.
+    pub fn batch_update(
+        &mut self,
+        updates: impl Iterator<Item = CharacterUpdateData>,
+        storage_containers: Vec<StorageContainerUpdateData>,
+    ) {
         let batch_id = self.next_pending_database_event_id();
 
         // Collect any new updates, ignoring updates from a previous update that are
@@ -367,12 +619,19 @@ impl CharacterUpdater {
         let existing_pending_actions = self
             .pending_database_actions
             .iter_mut()
-            .filter_map(|(_, event)| event.take_new(batch_id));
+            .filter_map(|(_, event)| event.take_new(batch_id))
+            .collect::<Vec<_>>();
 
         // Combine the pending actions with the updates for logged in characters
         let pending_actions = existing_pending_actions
             .into_iter()
-            .chain(updates.map(|update| DatabaseActionKind::UpdateCharacter(Box::new(update))))
+            .chain(updates.map(|update| {
+                let playtime = self.take_playtime(update.0);
+                DatabaseActionKind::UpdateCharacter(Box::new(update), playtime)
+            }))
+            .chain((!storage_containers.is_empty()).then_some(
+                DatabaseActionKind::UpdateStorageContainers(storage_containers),
+            ))
             .collect::<Vec<DatabaseActionKind>>();
 
         if !pending_actions.is_empty() {
@@ -414,23 +673,20 @@ impl CharacterUpdater {
     pub fn messages(&self) -> TryIter<'_, CharacterUpdaterMessage> { self.response_rx.try_iter() }
 }
 
+/// Returns the graves of the characters archived by this batch
 fn execute_batch_update(
     updates: impl Iterator<Item = DatabaseActionKind>,
     connection: &mut VelorenConnection,
-) -> Result<(), PersistenceError> {
+) -> Result<Vec<Grave>, PersistenceError> {
     let mut transaction = connection.connection.transaction()?;
     transaction.set_drop_behavior(DropBehavior::Rollback);
     trace!("Transaction started for character batch update");
+    let mut graves = Vec::new();
     updates.into_iter().try_for_each(|event| match event {
-        DatabaseActionKind::UpdateCharacter(box (
-            character_id,
-            stats,
-            inventory,
-            pets,
-            waypoint,
-            active_abilities,
-            map_marker,
-        )) => super::character::update(
+        DatabaseActionKind::UpdateCharacter(
+            box (character_id, stats, inventory, pets, waypoint, active_abilities, map_marker),
+            playtime,
+        ) => super::character::update(
             character_id,
             stats,
             inventory,
@@ -438,6 +694,7 @@ fn execute_batch_update(
             waypoint,
             active_abilities,
             map_marker,
+            playtime,
             &mut transaction,
         ),
         DatabaseActionKind::DeleteCharacter {
@@ -448,12 +705,28 @@ fn execute_batch_update(
             character_id,
             &mut transaction,
         ),
+        DatabaseActionKind::ArchiveCharacter {
+            requesting_player_uuid,
+            character_id,
+            death,
+            playtime,
+        } => super::character::archive_character(
+            &requesting_player_uuid,
+            character_id,
+            &death,
+            playtime,
+            &mut transaction,
+        )
+        .map(|grave| graves.extend(grave)),
+        DatabaseActionKind::UpdateStorageContainers(containers) => {
+            super::character::update_storage_containers(containers, &mut transaction)
+        },
     })?;
 
     transaction.commit()?;
 
     trace!("Commit for character batch update completed");
-    Ok(())
+    Ok(graves)
 }
 
 fn execute_character_create(
@@ -484,6 +757,62 @@ fn execute_character_create(
     Ok(CharacterUpdaterMessage::CharacterScreenResponse(response))
 }
 
+/// Copies the database with SQLite's online backup API.
+fn execute_database_backup(
+    path: &Path,
+    connection: &VelorenConnection,
+) -> Result<(), PersistenceError> {
+    let mut destination = rusqlite::Connection::open(path)?;
+    let backup = rusqlite::backup::Backup::new(&connection.connection, &mut destination)?;
+    // Nothing else writes to the database while the updater thread is busy, so
+    // all pages can be copied in a single step
+    backup.run_to_completion(-1, Duration::ZERO, None)?;
+    Ok(())
+}
+
+fn execute_character_export(
+    character_id: CharacterId,
+    data_dir: &Path,
+    file_name: &str,
+    connection: &mut VelorenConnection,
+) -> Result<String, PersistenceError> {
+    let path = character_transfer::bundle_path(data_dir, file_name)?;
+    let key = character_transfer::TransferKey::load_or_create(data_dir)?;
+    let bundle = super::character::export_character(character_id, &connection.connection)?;
+    character_transfer::write_bundle(&path, &bundle, &key)?;
+
+    Ok(format!(
+        "Exported character {} ({}) to {}",
+        character_id.0,
+        bundle.alias,
+        path.display()
+    ))
+}
+
+fn execute_character_import(
+    player_uuid: &str,
+    data_dir: &Path,
+    file_name: &str,
+    allow_unsigned: bool,
+    connection: &mut VelorenConnection,
+) -> Result<String, PersistenceError> {
+    let path = character_transfer::bundle_path(data_dir, file_name)?;
+    let key = character_transfer::TransferKey::load_or_create(data_dir)?;
+    let bundle = character_transfer::read_bundle(&path, &key, allow_unsigned)?;
+    let alias = bundle.alias.clone();
+
+    let mut transaction = connection.connection.transaction()?;
+    let character_id = super::character::import_character(player_uuid, bundle, &mut transaction)?;
+    transaction.commit()?;
+
+    Ok(format!(
+        "Imported {} as character {} ({})",
+        path.display(),
+        character_id.0,
+        alias
+    ))
+}
+
 fn execute_character_edit(
     entity: Entity,
     character_id: CharacterId,
@@ -6,13 +6,17 @@
 
 pub(in crate::persistence) mod character;
 pub mod character_loader;
+pub mod character_transfer;
 pub mod character_updater;
 mod diesel_to_rusqlite;
 pub mod error;
 mod json_models;
 mod models;
 
-use crate::persistence::character_updater::PetPersistenceData;
+use crate::{
+    graveyard::Grave, persistence::character_updater::PetPersistenceData,
+    storage_container::StorageContainer,
+};
 use common::comp;
 use refinery::Report;
 use rusqlite::{Connection, OpenFlags};
@@ -23,7 +27,8 @@ use std::{
     sync::{Arc, RwLock},
     time::Duration,
 };
-use tracing::info;
+use tracing::{error, info};
+use vek::Vec3;
 
 // re-export waypoint parser for use to look up location names in character list
 pub(crate) use character::parse_waypoint;
@@ -160,6 +165,45 @@ impl ToString for SqlLogMode {
     }
 }
 
+/// The version of the latest database migration
+pub(crate) fn schema_version() -> i64 {
+    embedded::migrations::runner()
+        .get_migrations()
+        .iter()
+        .map(|migration| i64::from(migration.version()))
+        .max()
+        .unwrap_or(0)
+}
+
+/// The database schema version at which characters could first be exported.
+const FIRST_EXPORT_SCHEMA_VERSION: i64 = 72;
+
+/// A change to item definitions made by a database migration.
+pub(crate) enum ItemMigration {
+    /// Items of the first definition were renamed to the second.
+    Rename(&'static str, &'static str),
+    /// Items of the definition were deleted.
+    Remove(&'static str),
+}
+
+/// The changes to item definitions made by each database migration, by the
+/// version of the migration. Migrations that rename or delete items need an
+/// entry here, so that the items of characters exported before them can be
+/// migrated the same way (see [`character_transfer`]).
+///
+/// Migrations older than [`FIRST_EXPORT_SCHEMA_VERSION`] don't need to be
+/// listed.
+const ITEM_MIGRATIONS: &[(i64, &[ItemMigration])] = &[];
+
+/// The changes to item definitions made by the database migrations newer than
+/// the given version, in the order they have to be applied.
+pub(crate) fn item_migrations_since(version: i64) -> impl Iterator<Item = &'static ItemMigration> {
+    ITEM_MIGRATIONS
+        .iter()
+        .filter(move |(migration, _)| *migration > version)
+        .flat_map(|(_, changes)| changes.iter())
+}
+
 /// Runs any pending database migrations. This is executed during server startup
 pub fn run_migrations(settings: &DatabaseSettings) {
     let mut conn = establish_connection(settings, ConnectionMode::ReadWrite);
@@ -189,6 +233,28 @@ pub fn vacuum_database(settings: &DatabaseSettings) {
     info!("Database vacuumed");
 }
 
+/// Loads the graves of every archived hardcore character. This is executed
+/// during server startup
+pub fn load_graveyard(settings: &DatabaseSettings) -> Vec<Grave> {
+    let conn = establish_connection(settings, ConnectionMode::ReadOnly);
+
+    character::load_graves(&conn).unwrap_or_else(|error| {
+        error!(?error, "Failed to load the hardcore graveyard");
+        Vec::new()
+    })
+}
+
+/// Loads every player-placed storage container. This is executed during server
+/// startup
+pub fn load_storage_containers(settings: &DatabaseSettings) -> Vec<(Vec3<i32>, StorageContainer)> {
+    let conn = establish_connection(settings, ConnectionMode::ReadOnly);
+
+    character::load_storage_containers(&conn).unwrap_or_else(|error| {
+        error!(?error, "Failed to load storage containers");
+        Vec::new()
+    })
+}
+
 // These callbacks use info logging because they are never enabled by default,
 // only when explicitly turned on via CLI arguments or interactive CLI commands.
 // Setting them to anything other than info would remove the ability to get SQL
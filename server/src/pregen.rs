@@ -0,0 +1,149 @@
+//! Background generation of the chunks in an area of the world, so that their
+//! base terrain is in the [`chunk_cache`](crate::chunk_cache) before players
+//! get there.
+//!
+//! Chunks are generated by their own slow job, which only gets a few threads,
+//! and no new chunks are requested while chunks are being generated for
+//! players. Generated chunks are discarded rather than loaded, so nothing is
+//! spawned for them.
+
+#[cfg(not(feature = "worldgen"))]
+use crate::test_world::{IndexOwned, World};
+use common::{calendar::Calendar, resources::TimeOfDay, slowjob::SlowJobPool};
+use serde::Serialize;
+use std::{
+    fmt,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use vek::*;
+#[cfg(feature = "worldgen")]
+use world::{IndexOwned, World};
+
+/// Maximum number of chunks requested but not generated yet.
+const MAX_IN_FLIGHT: usize = 16;
+/// How often progress is logged.
+const REPORT_INTERVAL: Duration = Duration::from_secs(30);
+
+pub struct Pregen {
+    /// Chunk keys to generate, the maximum is exclusive
+    area: Aabr<i32>,
+    requested: usize,
+    generated: Arc<AtomicUsize>,
+    cancel: Arc<AtomicBool>,
+    started: Instant,
+    last_report: Instant,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PregenProgress {
+    pub generated: usize,
+    pub total: usize,
+    pub elapsed: Duration,
+}
+
+impl Pregen {
+    /// Pre-generates the chunks in `area`, which is given in chunk coordinates
+    /// and excludes its maximum.
+    pub fn new(area: Aabr<i32>) -> Self {
+        let now = Instant::now();
+        Self {
+            area,
+            requested: 0,
+            generated: Arc::new(AtomicUsize::new(0)),
+            cancel: Arc::new(AtomicBool::new(false)),
+            started: now,
+            last_report: now,
+        }
+    }
+
+    pub fn area(&self) -> Aabr<i32> { self.area }
+
+    fn total(&self) -> usize { self.area.size().product().max(0) as usize }
+
+    pub fn progress(&self) -> PregenProgress {
+        PregenProgress {
+            generated: self.generated.load(Ordering::Relaxed),
+            total: self.total(),
+            elapsed: self.started.elapsed(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool { self.generated.load(Ordering::Relaxed) >= self.total() }
+
+    /// Whether progress should be reported, at most every
+    /// [`REPORT_INTERVAL`].
+    pub fn should_report(&mut self) -> bool {
+        let due = self.last_report.elapsed() >= REPORT_INTERVAL;
+        if due {
+            self.last_report = Instant::now();
+        }
+        due
+    }
+
+    /// Requests more chunks to be generated, if few enough are being generated
+    /// already.
+    pub fn request_chunks(
+        &mut self,
+        slowjob_pool: &SlowJobPool,
+        world: &Arc<World>,
+        index: &IndexOwned,
+        time: (TimeOfDay, Calendar),
+    ) {
+        let width = self.area.size().w;
+        while self.requested < self.total()
+            && self.requested - self.generated.load(Ordering::Relaxed) < MAX_IN_FLIGHT
+        {
+            let i = self.requested as i32;
+            let key = self.area.min + Vec2::new(i % width, i / width);
+            self.requested += 1;
+
+            let world = Arc::clone(world);
+            let index = index.clone();
+            let time = time.clone();
+            let generated = Arc::clone(&self.generated);
+            let cancel = Arc::clone(&self.cancel);
+            slowjob_pool.spawn("CHUNK_PREGEN", move || {
+                let _ = world.generate_chunk(
+                    index.as_index_ref(),
+                    key,
+                    None,
+                    || cancel.load(Ordering::Relaxed),
+                    Some(time),
+                );
+                generated.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+    }
+}
+
+impl Drop for Pregen {
+    fn drop(&mut self) { self.cancel.store(true, Ordering::Relaxed); }
+}
+
+impl fmt::Display for PregenProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let elapsed = Duration::from_secs(self.elapsed.as_secs());
+        write!(
+            f,
+            "Pre-generated {}/{} chunks ({:.1}%) in {}",
+            self.generated,
+            self.total,
+            100.0 * self.generated as f32 / self.total.max(1) as f32,
+            humantime::format_duration(elapsed),
+        )?;
+        if self.generated > 0 && self.generated < self.total {
+            let remaining = self.elapsed.as_secs_f64() / self.generated as f64
+                * (self.total - self.generated) as f64;
+            write!(
+                f,
+                ", about {} left",
+                humantime::format_duration(Duration::from_secs(remaining as u64))
+            )?;
+        }
+        Ok(())
+    }
+}
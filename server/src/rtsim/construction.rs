@@ -0,0 +1,176 @@
+//! Applies the changes that the rtsim architect makes to the layout of sites
+//! (see [`rtsim::data::Construction`]) to the terrain.
+//!
+//! Rendering a plot means generating every chunk that it overlaps, so this is
+//! done by a slow job, one construction at a time. The resulting blocks are
+//! then applied like any other block change and recorded by terrain
+//! persistence, so that players see them even when the chunks are generated
+//! again.
+
+use super::*;
+use crate::sys::terrain::TerrainPersistenceData;
+use common::{
+    calendar::Calendar,
+    resources::TimeOfDay,
+    rtsim::SiteId,
+    slowjob::SlowJobPool,
+    terrain::{Block, SpriteKind},
+};
+use common_ecs::{Job, Origin, Phase, System};
+use common_state::BlockChange;
+use rand::Rng;
+use rtsim::data::ConstructionKind;
+use specs::{Read, ReadExpect, Write, WriteExpect};
+use std::sync::Arc;
+use world::{IndexOwned, Land};
+
+type ConstructionResult = (SiteId, usize, Vec<(Vec3<i32>, Block)>);
+
+/// The construction being rendered by a slow job, if any.
+pub struct Constructions {
+    tx: Sender<ConstructionResult>,
+    rx: Receiver<ConstructionResult>,
+    pending: Option<(SiteId, usize)>,
+}
+
+impl Default for Constructions {
+    fn default() -> Self {
+        let (tx, rx) = unbounded();
+        Self {
+            tx,
+            rx,
+            pending: None,
+        }
+    }
+}
+
+/// Remove the blocks of a plot that collapse as it falls into ruin, the
+/// higher up a block is the more likely it is to collapse.
+fn ruin(blocks: Vec<(Vec3<i32>, Block)>) -> Vec<(Vec3<i32>, Block)> {
+    let mut rng = rand::rng();
+    let (min_z, max_z) = blocks
+        .iter()
+        .filter(|(_, block)| block.is_filled())
+        .fold((i32::MAX, i32::MIN), |(min, max), (pos, _)| {
+            (min.min(pos.z), max.max(pos.z))
+        });
+    blocks
+        .into_iter()
+        .filter(|(_, block)| block.is_filled())
+        .filter_map(|(pos, _)| {
+            let height = (pos.z - min_z) as f64 / (max_z - min_z).max(1) as f64;
+            rng.random_bool(0.2 + height * 0.6)
+                .then_some((pos, Block::air(SpriteKind::Empty)))
+        })
+        .collect()
+}
+
+#[derive(Default)]
+pub struct Sys;
+impl<'a> System<'a> for Sys {
+    type SystemData = (
+        Read<'a, TimeOfDay>,
+        ReadExpect<'a, Calendar>,
+        WriteExpect<'a, RtSim>,
+        ReadExpect<'a, Arc<World>>,
+        ReadExpect<'a, IndexOwned>,
+        ReadExpect<'a, SlowJobPool>,
+        Write<'a, BlockChange>,
+        TerrainPersistenceData<'a>,
+    );
+
+    const NAME: &'static str = "rtsim::construction";
+    const ORIGIN: Origin = Origin::Server;
+    const PHASE: Phase = Phase::Create;
+
+    fn run(
+        _job: &mut Job<Self>,
+        (
+            time_of_day,
+            calendar,
+            mut rtsim,
+            world,
+            index,
+            slow_jobs,
+            mut block_change,
+            _terrain_persistence,
+        ): Self::SystemData,
+    ) {
+        let rtsim = &mut *rtsim;
+        #[cfg(feature = "persistent_world")]
+        let mut terrain_persistence = _terrain_persistence;
+
+        // Apply the construction that has finished rendering
+        while let Ok((site_id, idx, blocks)) = rtsim.constructions.rx.try_recv() {
+            debug!(
+                ?site_id,
+                blocks = blocks.len(),
+                "Applying rtsim construction"
+            );
+            for (pos, block) in blocks {
+                block_change.set(pos, block);
+                #[cfg(feature = "persistent_world")]
+                if let Some(terrain_persistence) = terrain_persistence.as_mut() {
+                    terrain_persistence.set_block(pos, block);
+                }
+            }
+            if let Some(construction) = rtsim
+                .state
+                .data_mut()
+                .sites
+                .get_mut(site_id)
+                .and_then(|site| site.constructions.get_mut(idx))
+            {
+                construction.applied = true;
+            }
+            rtsim.constructions.pending = None;
+        }
+
+        if rtsim.constructions.pending.is_some() {
+            return;
+        }
+
+        // Start rendering the next construction
+        let Some((site_id, idx, world_site, kind)) =
+            rtsim.state.data().sites.iter().find_map(|(site_id, site)| {
+                let idx = site.constructions.iter().position(|c| !c.applied)?;
+                Some((
+                    site_id,
+                    idx,
+                    site.world_site?,
+                    site.constructions[idx].kind.clone(),
+                ))
+            })
+        else {
+            return;
+        };
+        rtsim.constructions.pending = Some((site_id, idx));
+
+        let tx = rtsim.constructions.tx.clone();
+        let world = Arc::clone(&world);
+        let index = index.clone();
+        let time = (*time_of_day, (*calendar).clone());
+        slow_jobs.spawn("RTSIM_CONSTRUCTION", move || {
+            let index = index.as_index_ref();
+            let site = &index.sites[world_site];
+            let land = Land::from_sim(world.sim());
+            let blocks = match kind {
+                ConstructionKind::Build(plan) => {
+                    let plot = site.build_plan(&land, &plan, Some(&time.1));
+                    world.render_plot(index, site, &plot, Some(time))
+                },
+                ConstructionKind::Ruin(plot_id) => site
+                    .plots
+                    .iter()
+                    .find(|(id, _)| id.id() == plot_id)
+                    .map(|(_, plot)| ruin(world.plot_blocks(index, site, plot, Some(time))))
+                    .unwrap_or_default(),
+                ConstructionKind::RuinBuilt(plan) => {
+                    let plot = site.build_plan(&land, &plan, Some(&time.1));
+                    ruin(world.plot_blocks(index, site, &plot, Some(time)))
+                },
+            };
+            let _ = tx.send((site_id, idx, blocks));
+        });
+    }
+}
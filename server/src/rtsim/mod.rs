@@ -1,13 +1,17 @@
+pub mod construction;
 pub mod event;
 pub mod rule;
+#[cfg(feature = "worldgen")] pub mod simulate;
 pub mod tick;
 
 use atomicwrites::{AtomicFile, OverwriteBehavior};
 use common::{
+    character::CharacterId,
     grid::Grid,
     mounting::VolumePos,
     rtsim::{Actor, NpcId, RtSimEntity, TerrainResource, WorldSettings},
     terrain::{CoordinateConversions, SpriteKind},
+    trade::{self, SitePrices},
 };
 use common_ecs::{System, dispatch};
 use common_state::BlockDiff;
@@ -35,6 +39,7 @@ pub struct RtSim {
     last_saved: Option<Instant>,
     state: RtState,
     save_thread: Option<(Sender<Data>, JoinHandle<()>)>,
+    constructions: construction::Constructions,
 }
 
 impl RtSim {
@@ -43,6 +48,7 @@ impl RtSim {
         index: IndexRef,
         world: &World,
         data_dir: PathBuf,
+        terrain_persistence: bool,
     ) -> Result<Self, ron::Error> {
         let file_path = Self::get_file_path(data_dir);
 
@@ -122,12 +128,17 @@ impl RtSim {
 
         let mut this = Self {
             last_saved: None,
-            state: RtState::new(data).with_resource(ChunkStates(Grid::populate_from(
-                world.sim().get_size().as_(),
-                |_| None,
-            ))),
+            state: RtState::new(data)
+                .with_resource(ChunkStates(Grid::populate_from(
+                    world.sim().get_size().as_(),
+                    |_| None,
+                )))
+                // Changes to the layout of sites are applied to the terrain, so they're
+                // only kept if the terrain is
+                .with_resource(rtsim::rule::architect::SiteGrowth(terrain_persistence)),
             file_path,
             save_thread: None,
+            constructions: Default::default(),
         };
 
         rule::start_rules(&mut this.state);
@@ -137,7 +148,8 @@ impl RtSim {
         Ok(this)
     }
 
-    fn get_file_path(mut data_dir: PathBuf) -> PathBuf {
+    /// The file rtsim data is stored in for the given data directory.
+    pub fn get_file_path(mut data_dir: PathBuf) -> PathBuf {
         let mut path = std::env::var("VELOREN_RTSIM")
             .map(PathBuf::from)
             .unwrap_or_else(|_| {
@@ -349,8 +361,32 @@ impl RtSim {
             .unwrap_or_default()
     }
 
+    /// The prices that a site trades at, adjusted to the reputation of the
+    /// character trading with it if there is one.
+    pub fn site_prices(
+        &self,
+        index: IndexRef,
+        site: trade::SiteId,
+        character: Option<CharacterId>,
+    ) -> Option<SitePrices> {
+        let mut prices = index.get_site_prices(site)?;
+        let data = self.state.data();
+        if let Some(character) = character
+            && let Some(site) = index
+                .sites
+                .recreate_id(site)
+                .and_then(|site| data.sites.world_site_map.get(&site))
+        {
+            prices.goodwill = data.reputations.goodwill(*site, character);
+        }
+        Some(prices)
+    }
+
     pub fn state(&self) -> &RtState { &self.state }
 
+    /// A copy of the current rtsim data, as it would be saved.
+    pub fn data(&self) -> Data { self.state.data().clone() }
+
     pub fn set_should_purge(&mut self, should_purge: bool) {
         self.state.data_mut().should_purge = should_purge;
     }
@@ -380,4 +416,5 @@ pub struct LoadedChunkState {
 
 pub fn add_server_systems(dispatch_builder: &mut DispatcherBuilder) {
     dispatch::<tick::Sys>(dispatch_builder, &[&common_systems::phys::Sys::sys_name()]);
+    dispatch::<construction::Sys>(dispatch_builder, &[]);
 }
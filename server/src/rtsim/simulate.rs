@@ -0,0 +1,326 @@
+//! Runs rtsim without a game server, as fast as possible.
+//!
+//! Only the world and rtsim are set up: there are no clients and no loaded
+//! chunks, so every NPC is simulated by rtsim rules alone. This makes it
+//! possible to see how populations, the architect, quests and resources evolve
+//! over many in-game days within minutes, which is useful when balancing rtsim.
+//!
+//! Note that, since nothing is ever loaded, NPCs can only die in ways that
+//! rtsim simulates itself.
+
+use super::*;
+use crate::{Error, Settings, generate_world};
+use common::{
+    assets::AssetExt,
+    comp,
+    resources::{Time, TimeOfDay},
+    rtsim::{QuestId, ReportId, SiteId},
+    shared_server_config::ServerConstants,
+    uid::IdMaps,
+    weather::{CHUNKS_PER_CELL, WeatherGrid},
+};
+use common_state::{GameMode, State};
+use hashbrown::{HashMap, HashSet};
+use rtsim::{
+    ai::NpcSystemData,
+    data::{ReportKind, architect::TrackedPopulation},
+};
+use specs::WorldExt;
+use std::{path::Path, sync::Mutex};
+
+const DAY: f64 = 60.0 * 60.0 * 24.0;
+
+/// Options of a headless rtsim simulation, see [`simulate`].
+#[derive(Clone, Debug)]
+pub struct SimulateOpts {
+    /// The number of in-game days to simulate.
+    pub days: f64,
+    /// How often, in in-game days, statistics are reported.
+    pub report_every: f64,
+    /// The length of each tick, in seconds. Longer ticks make the simulation
+    /// faster but coarser.
+    pub dt: f32,
+    /// Where to save the rtsim data once the simulation is over. The data is
+    /// discarded if this is `None`, so that the server's data is left as it
+    /// was.
+    pub save_to: Option<PathBuf>,
+}
+
+/// Load the world and rtsim data of the server and run rtsim rules without
+/// clients, reporting statistics at regular intervals.
+pub fn simulate(settings: &Settings, data_dir: &Path, opts: SimulateOpts) -> Result<(), Error> {
+    info!("Generating world, seed: {}", settings.world_seed);
+    let pools = State::pools(GameMode::Server);
+    let (world, index) = generate_world(settings, &pools, &|_| {});
+
+    // The resources that rtsim rules expect the server to provide
+    let mut ecs = specs::World::new();
+    ecs.register::<comp::Pos>();
+    ecs.register::<comp::Inventory>();
+    ecs.insert(IdMaps::default());
+    ecs.insert(ServerConstants {
+        day_cycle_coefficient: settings.day_cycle_coefficient(),
+    });
+    ecs.insert(WeatherGrid::new(world.sim().get_size() / CHUNKS_PER_CELL));
+    ecs.insert(comp::gizmos::RtsimGizmos::default());
+    ecs.insert(
+        comp::item::tool::AbilityMap::<comp::AbilityItem>::load_expect_cloned(
+            "common.abilities.ability_set_manifest",
+        ),
+    );
+    ecs.insert(comp::inventory::item::MaterialStatManifest::load().cloned());
+
+    let mut rtsim = RtSim::new(
+        &settings.world,
+        index.as_index_ref(),
+        &world,
+        data_dir.to_owned(),
+        // No terrain is kept while simulating, so sites don't grow
+        false,
+    )
+    .map_err(Error::RtsimError)?;
+
+    let mut system_data = NpcSystemData {
+        positions: ecs.system_data(),
+        id_maps: ecs.system_data(),
+        server_constants: ecs.system_data(),
+        weather_grid: ecs.system_data(),
+        rtsim_gizmos: ecs.system_data(),
+        ability_map: ecs.system_data(),
+        msm: ecs.system_data(),
+        inventories: Mutex::new(ecs.system_data()),
+    };
+
+    let start = rtsim.state.data().time_of_day;
+    let end = start.0 + opts.days * DAY;
+    let mut time_of_day = start;
+    let mut time = Time(0.0);
+    let mut next_report = start.0 + opts.report_every * DAY;
+    let mut stats = Stats::new(&rtsim.state.data());
+    let started_at = Instant::now();
+
+    info!(
+        days = opts.days,
+        dt = opts.dt,
+        "Starting headless rtsim simulation"
+    );
+    while time_of_day.0 < end {
+        time.0 += opts.dt as f64;
+        time_of_day.0 += opts.dt as f64 * settings.day_cycle_coefficient();
+
+        rtsim.state.tick(
+            &mut system_data,
+            &world,
+            index.as_index_ref(),
+            time_of_day,
+            time,
+            opts.dt,
+        );
+
+        if time_of_day.0 >= next_report || time_of_day.0 >= end {
+            let elapsed = started_at.elapsed().as_secs_f64();
+            info!(
+                "-- Day {:.1} (tick {}, {:.0}x real speed) --",
+                (time_of_day.0 - start.0) / DAY,
+                rtsim.state.data().tick,
+                time.0 / elapsed.max(f64::EPSILON),
+            );
+            stats.report(&rtsim.state.data(), index.as_index_ref());
+            next_report += opts.report_every * DAY;
+        }
+    }
+    info!(
+        "Simulated {:.1} days in {:.1}s",
+        opts.days,
+        started_at.elapsed().as_secs_f64()
+    );
+
+    if let Some(path) = opts.save_to {
+        info!("Saving rtsim data to {}", path.display());
+        rtsim.file_path = path;
+        rtsim.save(true);
+    }
+
+    Ok(())
+}
+
+/// The state of the simulation at the last report, used to report what
+/// changed since then.
+struct Stats {
+    site_population: HashMap<SiteId, usize>,
+    seen_reports: HashSet<ReportId>,
+    last_death: TimeOfDay,
+    next_quest: u64,
+    resolved_quests: usize,
+}
+
+impl Stats {
+    fn new(data: &Data) -> Self {
+        Self {
+            site_population: site_population(data),
+            seen_reports: data.reports.keys().collect(),
+            last_death: data.time_of_day,
+            next_quest: next_quest(data),
+            resolved_quests: resolved_quests(data),
+        }
+    }
+
+    fn report(&mut self, data: &Data, index: IndexRef) {
+        // Population per site
+        let site_population = site_population(data);
+        info!(
+            "NPCs: {} alive",
+            data.npcs.values().filter(|npc| !npc.is_dead()).count()
+        );
+        for (site_id, site) in data.sites.iter() {
+            let population = site_population.get(&site_id).copied().unwrap_or(0);
+            let previous = self.site_population.get(&site_id).copied().unwrap_or(0);
+            if population == 0 && previous == 0 {
+                continue;
+            }
+            let name = site
+                .world_site
+                .and_then(|ws| index.sites.get(ws).name())
+                .unwrap_or("<unnamed>");
+            info!(
+                "  Site {} ({}): {} residents ({:+}), prosperity {:.2}, {} constructions",
+                name,
+                site.uid,
+                population,
+                population as i64 - previous as i64,
+                site.prosperity,
+                site.constructions.len(),
+            );
+        }
+        self.site_population = site_population;
+
+        // Deaths, by population and by cause
+        let mut deaths = HashMap::<String, usize>::new();
+        for death in data
+            .architect
+            .deaths
+            .iter()
+            .filter(|death| death.time.0 > self.last_death.0)
+        {
+            *deaths
+                .entry(format!(
+                    "{:?}",
+                    TrackedPopulation::from_body_and_role(&death.body, &death.role)
+                ))
+                .or_default() += 1;
+        }
+        let births = data
+            .npcs
+            .values()
+            .filter(|npc| npc.born.is_some_and(|born| born.0 > self.last_death.0))
+            .count();
+        self.last_death = data.time_of_day;
+        let mut causes = HashMap::<String, usize>::new();
+        for (report_id, report) in data.reports.iter() {
+            if !self.seen_reports.insert(report_id) {
+                continue;
+            }
+            if let ReportKind::Death { killer, .. } = report.kind {
+                let cause = match killer {
+                    Some(Actor::Npc(npc)) => data
+                        .npcs
+                        .get(npc)
+                        .and_then(|npc| npc.profession())
+                        .map_or_else(|| "npc".to_string(), |p| format!("{p:?}")),
+                    Some(Actor::Character(_)) => "player".to_string(),
+                    None => "other".to_string(),
+                };
+                *causes.entry(cause).or_default() += 1;
+            }
+        }
+        self.seen_reports
+            .retain(|report_id| data.reports.contains_key(*report_id));
+        info!("Births: {}", births);
+        info!(
+            "Deaths: {} by population, {} by cause",
+            format_counts(deaths),
+            format_counts(causes)
+        );
+
+        // Quests
+        let next_quest = next_quest(data);
+        let resolved_quests = resolved_quests(data);
+        info!(
+            "Quests: {} created, {} resolved, {} unresolved",
+            next_quest.saturating_sub(self.next_quest),
+            resolved_quests.saturating_sub(self.resolved_quests),
+            data.quests
+                .iter()
+                .filter(|(_, quest)| quest.resolution().is_none())
+                .count(),
+        );
+        self.next_quest = next_quest;
+        self.resolved_quests = resolved_quests;
+
+        // Resource depletion
+        let chunks = data.nature.chunks.raw();
+        let mut total = EnumMap::<TerrainResource, f32>::default();
+        let mut depleted = EnumMap::<TerrainResource, usize>::default();
+        for chunk in chunks {
+            for (res, amount) in chunk.res.iter() {
+                total[res] += amount;
+                if *amount < 0.5 {
+                    depleted[res] += 1;
+                }
+            }
+        }
+        info!(
+            "Resources (average, chunks below half): {}",
+            total
+                .iter()
+                .map(|(res, total)| format!(
+                    "{:?} {:.1}% {}",
+                    res,
+                    total / chunks.len().max(1) as f32 * 100.0,
+                    depleted[res]
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+}
+
+fn site_population(data: &Data) -> HashMap<SiteId, usize> {
+    let mut population = HashMap::new();
+    for npc in data.npcs.values().filter(|npc| !npc.is_dead()) {
+        if let Some(home) = npc.home {
+            *population.entry(home).or_default() += 1;
+        }
+    }
+    population
+}
+
+/// Quest ids are allocated in increasing order, so every quest with an id of
+/// at least this value was created since.
+fn next_quest(data: &Data) -> u64 {
+    data.quests
+        .iter()
+        .map(|(QuestId(id), _)| id + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+fn resolved_quests(data: &Data) -> usize {
+    data.quests
+        .iter()
+        .filter(|(_, quest)| quest.resolution().is_some())
+        .count()
+}
+
+fn format_counts(counts: HashMap<String, usize>) -> String {
+    if counts.is_empty() {
+        return "none".to_string();
+    }
+    let mut counts = counts.into_iter().collect::<Vec<_>>();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+        .into_iter()
+        .map(|(name, count)| format!("{name} {count}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
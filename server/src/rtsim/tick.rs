@@ -1,5 +1,5 @@
 use super::*;
-use crate::{ServerConstants, sys::terrain::SpawnEntityData};
+use crate::{ServerConstants, client::Client, sys::terrain::SpawnEntityData};
 use common::{
     LoadoutBuilder,
     calendar::Calendar,
@@ -19,11 +19,12 @@ use common::{
     weather::WeatherGrid,
 };
 use common_ecs::{Job, Origin, Phase, System};
+use common_net::msg::ServerGeneral;
 use rand::Rng;
 use rtsim::{
     ai::NpcSystemData,
     data::{
-        Npc, Sites,
+        Npc, Reputations, Site, Sites,
         npc::{Profession, SimulationMode},
     },
 };
@@ -302,8 +303,12 @@ fn get_npc_entity_info(
     let mut rng = npc.rng(Npc::PERM_ENTITY_CONFIG);
     if let Some(profession) = npc.profession() {
         let economy = npc.home.and_then(|home| {
-            let site = sites.get(home)?.world_site?;
-            index.sites.get(site).trade_information(site)
+            let site = sites.get(home)?;
+            let world_site = site.world_site?;
+            let mut info = index.sites.get(world_site).trade_information(world_site)?;
+            // Include the goods brought or taken away by caravans
+            site.adjust_stock(&mut info.unconsumed_stock);
+            Some(info)
         });
 
         let config_asset = humanoid_config(&profession);
@@ -366,6 +371,12 @@ fn get_npc_entity_info(
                 },
                 species => unimplemented!("rtsim spawning for {:?}", species),
             },
+            // Pack animals of caravans
+            Body::QuadrupedMedium(body) => match body.species {
+                comp::quadruped_medium::Species::Donkey => "common.entity.wild.peaceful.donkey",
+                comp::quadruped_medium::Species::Camel => "common.entity.wild.peaceful.camel",
+                species => unimplemented!("rtsim spawning for {:?}", species),
+            },
             body => unimplemented!("rtsim spawning for {:?}", body),
         };
         let entity_config = EntityConfig::from_asset_expect_owned(config_asset)
@@ -375,6 +386,11 @@ fn get_npc_entity_info(
     }
 }
 
+/// How many ticks to skip between telling players their reputation.
+const REPUTATION_SYNC_TICKS: u64 = 150;
+/// How close players need to be to a site to be told their reputation with it.
+const REPUTATION_SITE_RANGE: f32 = 320.0;
+
 #[derive(Default)]
 pub struct Sys;
 impl<'a> System<'a> for Sys {
@@ -402,6 +418,7 @@ impl<'a> System<'a> for Sys {
         WriteExpect<'a, comp::gizmos::RtsimGizmos>,
         ReadExpect<'a, comp::tool::AbilityMap>,
         ReadExpect<'a, comp::item::MaterialStatManifest>,
+        ReadStorage<'a, Client>,
     );
 
     const NAME: &'static str = "rtsim::tick";
@@ -434,6 +451,7 @@ impl<'a> System<'a> for Sys {
             rtsim_gizmos,
             ability_map,
             msm,
+            clients,
         ): Self::SystemData,
     ) {
         let mut create_ship_emitter = create_ship_events.emitter();
@@ -640,5 +658,26 @@ impl<'a> System<'a> for Sys {
                 }
             }
         }
+
+        // Let players know how the site they're at regards them
+        if data.tick.is_multiple_of(REPUTATION_SYNC_TICKS) {
+            for (client, presence, pos) in (&clients, &presences, &positions).join() {
+                let Some(character) = presence.kind.character_id() else {
+                    continue;
+                };
+                let dist_sqr = |site: &Site| site.wpos.as_::<f32>().distance_squared(pos.0.xy());
+                let reputation = data
+                    .sites
+                    .iter()
+                    .filter(|(_, site)| dist_sqr(site) < REPUTATION_SITE_RANGE.powi(2))
+                    .min_by(|(_, a), (_, b)| dist_sqr(a).total_cmp(&dist_sqr(b)))
+                    .and_then(|(site_id, site)| {
+                        let name = index.sites.get(site.world_site?).name()?.to_string();
+                        let reputation = data.reputations.site(site_id, character);
+                        Some((name, Reputations::standing(reputation)))
+                    });
+                client.send_fallible(ServerGeneral::UpdateReputation(reputation));
+            }
+        }
     }
 }
@@ -1,6 +1,8 @@
 pub mod admin;
 pub mod banlist;
 mod editable;
+pub mod mutelist;
+pub mod permission_groups;
 pub mod server_description;
 pub mod server_physics;
 pub mod whitelist;
@@ -12,12 +14,14 @@ pub use banlist::{
     Ban, BanEntry, BanError, BanErrorKind, BanInfo, BanKind, BanOperation, BanOperationError,
     BanRecord, Banlist,
 };
+pub use mutelist::{Mute, MuteAction, MuteInfo, Mutelist};
+pub use permission_groups::{GroupMember, PermissionGroup, PermissionGroups};
 pub use server_description::ServerDescriptions;
 pub use whitelist::{Whitelist, WhitelistInfo, WhitelistRecord};
 
 use chrono::Utc;
 use common::{
-    calendar::{Calendar, CalendarEvent},
+    calendar::{Calendar, CalendarEvent, CustomCalendarEvent},
     consts::DAY_LENGTH_DEFAULT,
     resources::BattleMode,
     rtsim::WorldSettings,
@@ -46,6 +50,8 @@ const BANLIST_FILENAME: &str = "banlist.ron";
 const SERVER_DESCRIPTION_FILENAME: &str = "description.ron";
 const ADMINS_FILENAME: &str = "admins.ron";
 const SERVER_PHYSICS_FORCE_FILENAME: &str = "server_physics_force.ron";
+const PERMISSION_GROUPS_FILENAME: &str = "permission_groups.ron";
+const MUTELIST_FILENAME: &str = "mutelist.ron";
 
 pub const SINGLEPLAYER_SERVER_NAME: &str = "Singleplayer";
 
@@ -119,6 +125,53 @@ impl Default for GameplaySettings {
     }
 }
 
+/// Limits on the physics state sent by clients using client-authoritative
+/// physics, and what happens to players that repeatedly exceed them, see
+/// [`anti_cheat`](crate::anti_cheat).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AntiCheatSettings {
+    /// Maximum horizontal speed, in blocks per second.
+    pub max_h_velocity: f32,
+    /// Maximum upwards speed, in blocks per second.
+    pub max_up_velocity: f32,
+    /// Maximum downwards speed, in blocks per second.
+    pub max_down_velocity: f32,
+    /// How far players may stray from the position expected from their
+    /// velocity, e.g. due to latency.
+    pub position_threshold: f32,
+    /// Factor the speed limits are multiplied by while gliding.
+    pub glide_factor: f32,
+    /// How quickly, per second, players lose the speed beyond the limits that
+    /// the server gave them, e.g. when dismounting or being knocked back.
+    pub velocity_allowance_decay: f32,
+    /// Number of violations within `violation_window` after which a player is
+    /// added to the server physics force list.
+    pub force_server_physics_after: Option<usize>,
+    /// Number of violations within `violation_window` after which a player is
+    /// kicked. Players using server physics can't cause violations, so this
+    /// only applies before that if it is lower than
+    /// `force_server_physics_after`.
+    pub kick_after: Option<usize>,
+    pub violation_window: Duration,
+}
+
+impl Default for AntiCheatSettings {
+    fn default() -> Self {
+        Self {
+            max_h_velocity: 75.0,
+            max_up_velocity: 80.0,
+            max_down_velocity: 100.0,
+            position_threshold: 16.0,
+            glide_factor: 1.5,
+            velocity_allowance_decay: 1.0,
+            force_server_physics_after: Some(20),
+            kick_after: None,
+            violation_window: Duration::from_secs(60),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ModerationSettings {
     #[serde(default)]
@@ -157,6 +210,54 @@ impl Default for ModerationSettings {
     }
 }
 
+/// Scheduled snapshots of the persisted server state, see
+/// [`backup`](crate::backup).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupSettings {
+    /// Whether snapshots are taken every `interval`. Snapshots can always be
+    /// taken manually.
+    pub enabled: bool,
+    pub interval: Duration,
+    /// Directory snapshots are stored in, relative to the data directory.
+    pub directory: PathBuf,
+    pub retention: BackupRetention,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: Duration::from_secs(6 * 60 * 60),
+            directory: PathBuf::from("backups"),
+            retention: BackupRetention::default(),
+        }
+    }
+}
+
+/// Which snapshots are kept when a new one was taken, every snapshot matching
+/// any of the rules is kept.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupRetention {
+    /// Number of most recent snapshots to keep.
+    pub keep_last: usize,
+    /// Number of days to keep the most recent snapshot of.
+    pub keep_daily: usize,
+    /// Number of weeks to keep the most recent snapshot of.
+    pub keep_weekly: usize,
+}
+
+impl Default for BackupRetention {
+    fn default() -> Self {
+        Self {
+            keep_last: 4,
+            keep_daily: 7,
+            keep_weekly: 4,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
 pub enum CalendarMode {
     None,
@@ -167,11 +268,13 @@ pub enum CalendarMode {
 }
 
 impl CalendarMode {
-    pub fn calendar_now(&self) -> Calendar {
+    /// The events taking place now, including the custom events unless the
+    /// events are fixed.
+    pub fn calendar_now(&self, custom_events: &[CustomCalendarEvent]) -> Calendar {
         match self {
             CalendarMode::None => Calendar::default(),
-            CalendarMode::Auto => Calendar::from_tz(None),
-            CalendarMode::Timezone(tz) => Calendar::from_tz(Some(*tz)),
+            CalendarMode::Auto => Calendar::from_tz(None, custom_events),
+            CalendarMode::Timezone(tz) => Calendar::from_tz(Some(*tz), custom_events),
             CalendarMode::Events(events) => Calendar::from_events(events.clone()),
         }
     }
@@ -196,19 +299,32 @@ pub struct Settings {
     pub client_timeout: Duration,
     pub max_player_for_kill_broadcast: Option<usize>,
     pub calendar_mode: CalendarMode,
+    /// Events defined by the server, in addition to the built-in ones
+    pub calendar_events: Vec<CustomCalendarEvent>,
 
     /// Experimental feature. No guaranteed forwards-compatibility, may be
     /// removed at *any time* with no migration.
     #[serde(default, skip_serializing)]
     pub experimental_terrain_persistence: bool,
 
+    /// Whether the base terrain of generated chunks is cached on disk, which
+    /// saves CPU time when chunks are loaded again at the cost of disk space.
+    /// See [`chunk_cache`](crate::chunk_cache).
+    #[serde(default)]
+    pub generated_chunk_cache: bool,
+
     #[serde(default)]
     pub gameplay: GameplaySettings,
     #[serde(default)]
     pub moderation: ModerationSettings,
+    #[serde(default)]
+    pub anti_cheat: AntiCheatSettings,
 
     #[serde(default)]
     pub world: WorldSettings,
+
+    #[serde(default)]
+    pub backup: BackupSettings,
 }
 
 impl Default for Settings {
@@ -232,12 +348,16 @@ impl Default for Settings {
             max_view_distance: Some(65),
             max_player_group_size: 6,
             calendar_mode: CalendarMode::Auto,
+            calendar_events: Vec::new(),
             client_timeout: Duration::from_secs(40),
             max_player_for_kill_broadcast: None,
             experimental_terrain_persistence: false,
+            generated_chunk_cache: false,
             gameplay: GameplaySettings::default(),
             moderation: ModerationSettings::default(),
+            anti_cheat: AntiCheatSettings::default(),
             world: WorldSettings::default(),
+            backup: BackupSettings::default(),
         }
     }
 }
@@ -339,6 +459,19 @@ impl Settings {
             );
             self.day_length = default_values.day_length;
         }
+
+        self.calendar_events.retain(|event| {
+            let valid = event.has_valid_dates();
+            if !valid {
+                warn!(
+                    "{} Setting: calendar_events, Event: {}, Start: {:?}, End: {:?}. Ignoring the \
+                     event. Help: dates are (month, day), months go from 1 to 12 and days must \
+                     exist in their month.",
+                    INVALID_SETTING_MSG, event.name, event.start, event.end
+                );
+            }
+            valid
+        });
     }
 
     /// Derive a coefficient that is the relatively speed of the in-game
@@ -385,6 +518,8 @@ pub struct EditableSettings {
     pub server_description: ServerDescriptions,
     pub admins: Admins,
     pub server_physics_force_list: ServerPhysicsForceList,
+    pub permission_groups: PermissionGroups,
+    pub mutelist: Mutelist,
 }
 
 impl EditableSettings {
@@ -395,6 +530,8 @@ impl EditableSettings {
             server_description: ServerDescriptions::load(data_dir),
             admins: Admins::load(data_dir),
             server_physics_force_list: ServerPhysicsForceList::load(data_dir),
+            permission_groups: PermissionGroups::load(data_dir),
+            mutelist: Mutelist::load(data_dir),
         }
     }
 
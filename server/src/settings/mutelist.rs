@@ -0,0 +1,216 @@
+//! Players muted by moderators, with a log of the mutes and unmutes of each
+//! player.
+//!
+//! A muted player can't send chat messages until their mute ends. A shadow
+//! muted player still sees their own messages, but nobody else does.
+
+use super::{EditableSetting, MUTELIST_FILENAME as FILENAME, editable::Version};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+pub use v0::*;
+
+#[derive(Deserialize, Serialize)]
+pub enum MutelistRaw {
+    V0(Mutelist),
+}
+
+#[expect(clippy::infallible_try_from)] // TODO: evaluate
+impl TryFrom<MutelistRaw> for (Version, Mutelist) {
+    type Error = <Mutelist as EditableSetting>::Error;
+
+    fn try_from(value: MutelistRaw) -> Result<Self, Self::Error> {
+        use MutelistRaw::*;
+        Ok(match value {
+            V0(mut value) => (value.validate()?, value),
+        })
+    }
+}
+
+impl From<Mutelist> for MutelistRaw {
+    fn from(value: Mutelist) -> Self { Self::V0(value) }
+}
+
+impl EditableSetting for Mutelist {
+    type Error = Infallible;
+    type Legacy = Mutelist;
+    type Setting = MutelistRaw;
+
+    const FILENAME: &'static str = FILENAME;
+}
+
+type Latest = Mutelist;
+
+mod v0 {
+    use super::Latest;
+    use authc::Uuid;
+    use chrono::{DateTime, Utc};
+    use common::comp::AdminRole;
+    use serde::{Deserialize, Serialize};
+    use std::{
+        collections::HashMap,
+        ops::{Deref, DerefMut},
+    };
+
+    use crate::settings::{EditableSetting, editable::Version};
+
+    /// NOTE: This must stay a separate copy of the role, see
+    /// [`crate::settings::admin::Role`].
+    #[derive(Clone, Copy, Deserialize, Serialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum Role {
+        Moderator = 0,
+        Admin = 1,
+    }
+
+    impl From<AdminRole> for Role {
+        fn from(value: AdminRole) -> Self {
+            match value {
+                AdminRole::Moderator => Self::Moderator,
+                AdminRole::Admin => Self::Admin,
+            }
+        }
+    }
+
+    #[derive(Clone, Deserialize, Serialize, Debug)]
+    pub struct MuteInfo {
+        pub performed_by: Uuid,
+        pub performed_by_username: String,
+        pub performed_by_role: Role,
+    }
+
+    #[derive(Clone, Deserialize, Serialize, Debug)]
+    pub struct Mute {
+        pub reason: String,
+        /// Whether the player still sees their own messages
+        pub shadow: bool,
+        pub info: MuteInfo,
+        pub end_date: DateTime<Utc>,
+    }
+
+    #[derive(Clone, Deserialize, Serialize, Debug)]
+    pub enum MuteAction {
+        Mute(Mute),
+        Unmute(MuteInfo),
+    }
+
+    #[derive(Clone, Deserialize, Serialize, Debug)]
+    pub struct MuteRecord {
+        pub username_when_performed: String,
+        pub action: MuteAction,
+        /// Date when the action was performed
+        pub date: DateTime<Utc>,
+    }
+
+    #[derive(Clone, Deserialize, Serialize, Debug)]
+    pub struct MuteEntry {
+        pub current: MuteRecord,
+        /// Previous actions, oldest first
+        pub history: Vec<MuteRecord>,
+    }
+
+    impl MuteEntry {
+        /// The mute of the player, if it hasn't ended at `now`.
+        pub fn active_mute(&self, now: DateTime<Utc>) -> Option<&Mute> {
+            match &self.current.action {
+                MuteAction::Mute(mute) if mute.end_date > now => Some(mute),
+                _ => None,
+            }
+        }
+    }
+
+    #[derive(Clone, Deserialize, Serialize, Default)]
+    pub struct Mutelist(HashMap<Uuid, MuteEntry>);
+
+    impl Deref for Mutelist {
+        type Target = HashMap<Uuid, MuteEntry>;
+
+        fn deref(&self) -> &Self::Target { &self.0 }
+    }
+
+    impl DerefMut for Mutelist {
+        fn deref_mut(&mut self) -> &mut Self::Target { &mut self.0 }
+    }
+
+    impl Mutelist {
+        /// The mute of the given player, if they are muted at `now`.
+        pub fn active_mute(&self, uuid: &Uuid, now: DateTime<Utc>) -> Option<&Mute> {
+            self.0.get(uuid).and_then(|entry| entry.active_mute(now))
+        }
+
+        /// Records the action for the given player, keeping their previous
+        /// action in the history. Returns false without recording anything
+        /// when unmuting a player who isn't muted.
+        pub fn perform(
+            &mut self,
+            now: DateTime<Utc>,
+            uuid: Uuid,
+            username: String,
+            action: MuteAction,
+        ) -> bool {
+            if matches!(action, MuteAction::Unmute(_)) && self.active_mute(&uuid, now).is_none() {
+                return false;
+            }
+            let record = MuteRecord {
+                username_when_performed: username,
+                action,
+                date: now,
+            };
+            match self.0.get_mut(&uuid) {
+                Some(entry) => {
+                    let previous = std::mem::replace(&mut entry.current, record);
+                    entry.history.push(previous);
+                },
+                None => {
+                    self.0.insert(uuid, MuteEntry {
+                        current: record,
+                        history: Vec::new(),
+                    });
+                },
+            }
+            true
+        }
+
+        pub(super) fn validate(&mut self) -> Result<Version, <Latest as EditableSetting>::Error> {
+            Ok(Version::Latest)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use authc::Uuid;
+    use chrono::{Duration, Utc};
+
+    #[test]
+    fn mute_and_unmute() {
+        let now = Utc::now();
+        let uuid = Uuid::from_u128(1);
+        let info = MuteInfo {
+            performed_by: Uuid::from_u128(2),
+            performed_by_username: "moderator".to_string(),
+            performed_by_role: Role::Moderator,
+        };
+        let mut mutelist = Mutelist::default();
+
+        let unmute = MuteAction::Unmute(info.clone());
+        assert!(!mutelist.perform(now, uuid, "player".to_string(), unmute.clone()));
+
+        let mute = MuteAction::Mute(Mute {
+            reason: "spam".to_string(),
+            shadow: false,
+            info,
+            end_date: now + Duration::minutes(10),
+        });
+        assert!(mutelist.perform(now, uuid, "player".to_string(), mute));
+        assert!(mutelist.active_mute(&uuid, now).is_some());
+        assert!(
+            mutelist
+                .active_mute(&uuid, now + Duration::minutes(11))
+                .is_none()
+        );
+
+        assert!(mutelist.perform(now, uuid, "player".to_string(), unmute));
+        assert!(mutelist.active_mute(&uuid, now).is_none());
+        assert_eq!(mutelist[&uuid].history.len(), 1);
+    }
+}
@@ -0,0 +1,146 @@
+//! Groups of commands which can be granted to players regardless of their
+//! admin role, for example to let builders edit terrain without letting them
+//! ban players.
+
+use super::{EditableSetting, PERMISSION_GROUPS_FILENAME as FILENAME, editable::Version};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+pub use v0::*;
+
+#[derive(Deserialize, Serialize)]
+pub enum PermissionGroupsRaw {
+    V0(PermissionGroups),
+}
+
+#[expect(clippy::infallible_try_from)] // TODO: evaluate
+impl TryFrom<PermissionGroupsRaw> for (Version, PermissionGroups) {
+    type Error = <PermissionGroups as EditableSetting>::Error;
+
+    fn try_from(value: PermissionGroupsRaw) -> Result<Self, Self::Error> {
+        use PermissionGroupsRaw::*;
+        Ok(match value {
+            V0(mut value) => (value.validate()?, value),
+        })
+    }
+}
+
+impl From<PermissionGroups> for PermissionGroupsRaw {
+    fn from(value: PermissionGroups) -> Self { Self::V0(value) }
+}
+
+impl EditableSetting for PermissionGroups {
+    type Error = Infallible;
+    type Legacy = PermissionGroups;
+    type Setting = PermissionGroupsRaw;
+
+    const FILENAME: &'static str = FILENAME;
+}
+
+type Latest = PermissionGroups;
+
+mod v0 {
+    use super::Latest;
+    use authc::Uuid;
+    use chrono::{DateTime, Utc};
+    use common::cmd::ServerChatCommand;
+    use serde::{Deserialize, Serialize};
+    use std::collections::{BTreeSet, HashMap};
+    use tracing::warn;
+
+    use crate::settings::{EditableSetting, editable::Version};
+
+    #[derive(Clone, Deserialize, Serialize, Debug, Default)]
+    pub struct PermissionGroup {
+        /// Keywords of the commands members of the group may use
+        pub commands: BTreeSet<String>,
+    }
+
+    #[derive(Clone, Deserialize, Serialize, Debug)]
+    pub struct GroupMember {
+        pub username_when_added: String,
+        /// Date that the player was last added to a group.
+        pub date: DateTime<Utc>,
+        pub groups: BTreeSet<String>,
+    }
+
+    /// NOTE: Like admins, members are only added or removed through the
+    /// command line, while groups are defined by editing the file.
+    #[derive(Clone, Deserialize, Serialize)]
+    pub struct PermissionGroups {
+        pub groups: HashMap<String, PermissionGroup>,
+        pub members: HashMap<Uuid, GroupMember>,
+    }
+
+    impl Default for PermissionGroups {
+        fn default() -> Self {
+            let builder = PermissionGroup {
+                commands: ["build", "make_block", "make_sprite"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+            };
+            Self {
+                groups: HashMap::from([("builder".to_string(), builder)]),
+                members: HashMap::new(),
+            }
+        }
+    }
+
+    impl PermissionGroups {
+        /// Whether any of the groups of the player allows them to use `cmd`.
+        pub fn allows(&self, uuid: &Uuid, cmd: &ServerChatCommand) -> bool {
+            self.members.get(uuid).is_some_and(|member| {
+                member.groups.iter().any(|group| {
+                    self.groups
+                        .get(group)
+                        .is_some_and(|group| group.commands.contains(cmd.keyword()))
+                })
+            })
+        }
+
+        pub(super) fn validate(&mut self) -> Result<Version, <Latest as EditableSetting>::Error> {
+            for (name, group) in &self.groups {
+                for command in &group.commands {
+                    if command.parse::<ServerChatCommand>().is_err() {
+                        warn!(
+                            ?name,
+                            ?command,
+                            "Permission group contains an unknown command"
+                        );
+                    }
+                }
+            }
+            for (uuid, member) in &self.members {
+                for group in &member.groups {
+                    if !self.groups.contains_key(group) {
+                        warn!(%uuid, ?group, "Player is a member of an undefined permission group");
+                    }
+                }
+            }
+            Ok(Version::Latest)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use authc::Uuid;
+    use chrono::Utc;
+    use common::cmd::ServerChatCommand;
+
+    #[test]
+    fn builder_group() {
+        let mut permission_groups = PermissionGroups::default();
+        let builder = Uuid::from_u128(1);
+        permission_groups.members.insert(builder, GroupMember {
+            username_when_added: "builder".to_string(),
+            date: Utc::now(),
+            groups: ["builder".to_string()].into(),
+        });
+
+        assert!(permission_groups.allows(&builder, &ServerChatCommand::MakeBlock));
+        assert!(!permission_groups.allows(&builder, &ServerChatCommand::Ban));
+        assert!(!permission_groups.allows(&Uuid::from_u128(2), &ServerChatCommand::MakeBlock));
+    }
+}
@@ -51,7 +51,8 @@ mod v0 {
     #[derive(Clone, Deserialize, Serialize, Debug)]
     pub struct ServerPhysicsForceRecord {
         /// Moderator/Admin who forced the player to server authoritative
-        /// physics, none if applied via the server (currently not possible)
+        /// physics, none if applied by the server after repeated physics
+        /// violations
         pub by: Option<(Uuid, String)>,
         pub reason: Option<String>,
     }
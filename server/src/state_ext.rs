@@ -6,13 +6,14 @@ use crate::{
     chat::ChatExporter,
     client::Client,
     events::{self, shared::update_map_markers},
-    persistence::PersistedComponents,
+    persistence::{PersistedComponents, character_updater::CharacterUpdater},
     pet::restore_pet,
     presence::RepositionOnChunkLoad,
-    settings::Settings,
+    settings::{EditableSettings, Settings},
     sys::sentinel::DeletedEntities,
     wiring,
 };
+use chrono::Utc;
 use common::{
     LoadoutBuilder, ViewDistances,
     character::CharacterId,
@@ -613,6 +614,9 @@ impl StateExt for State {
                         self.ecs()
                             .write_resource::<IdMaps>()
                             .add_character(id, entity);
+                        self.ecs()
+                            .write_resource::<CharacterUpdater>()
+                            .start_session(id);
                         Ok(())
                     } else {
                         Err("PresenceKind is not LoadingCharacter")
@@ -824,10 +828,6 @@ impl StateExt for State {
 
         let group_info = msg.get_group().and_then(|g| group_manager.group_info(*g));
 
-        if let Some(exported_message) = ChatExporter::generate(&msg, ecs) {
-            chat_exporter.send(exported_message);
-        }
-
         let resolved_msg = msg
             .clone()
             .map_group(|_| group_info.map_or_else(|| "???".to_string(), |i| i.name.clone()));
@@ -835,6 +835,17 @@ impl StateExt for State {
         let id_maps = ecs.read_resource::<IdMaps>();
         let entity_from_uid = |uid| id_maps.uid_entity(uid);
 
+        // Messages of muted players are neither exported nor sent to other players
+        if let Some(sender) = msg.chat_type.uid().and_then(entity_from_uid)
+            && notify_if_muted(ecs, sender, &resolved_msg)
+        {
+            return;
+        }
+
+        if let Some(exported_message) = ChatExporter::generate(&msg, ecs) {
+            chat_exporter.send(exported_message);
+        }
+
         if msg.chat_type.uid().is_none_or(|sender| {
             entity_from_uid(sender).is_some_and(|e| {
                 self.validate_chat_msg(e, &msg.chat_type, msg.content(), from_client)
@@ -1249,6 +1260,35 @@ pub fn position_mut<T>(
     res
 }
 
+/// Returns whether the sender of the message is muted, in which case they are
+/// told so, or only sent their own message back if they are shadow muted.
+fn notify_if_muted(ecs: &specs::World, sender: EcsEntity, msg: &comp::ChatMsg) -> bool {
+    let Some(uuid) = ecs.read_storage::<Player>().get(sender).map(Player::uuid) else {
+        return false;
+    };
+    let editable_settings = ecs.read_resource::<EditableSettings>();
+    let Some(mute) = editable_settings.mutelist.active_mute(&uuid, Utc::now()) else {
+        return false;
+    };
+    if let Some(client) = ecs.read_storage::<Client>().get(sender) {
+        client.send_fallible(if mute.shadow {
+            ServerGeneral::ChatMsg(msg.clone())
+        } else {
+            ServerGeneral::server_msg(
+                ChatType::CommandError,
+                Content::localized_with_args("command-chat-mute-muted", [
+                    ("reason", mute.reason.clone()),
+                    (
+                        "until",
+                        mute.end_date.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+                    ),
+                ]),
+            )
+        });
+    }
+    true
+}
+
 fn send_to_group(g: &Group, ecs: &specs::World, msg: &comp::ChatMsg) {
     for (client, group) in (&ecs.read_storage::<Client>(), &ecs.read_storage::<Group>()).join() {
         if g == group {
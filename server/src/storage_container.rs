@@ -0,0 +1,118 @@
+//! Contents of player-placed storage containers, see [`common::storage`].
+//!
+//! Containers are keyed by the position of their sprite. Since the sprite
+//! itself is only kept across restarts by terrain persistence, containers only
+//! exist when it is enabled. Their contents are stored in the character
+//! database alongside character inventories, see
+//! [`CharacterUpdater::batch_update`](crate::persistence::character_updater::CharacterUpdater::batch_update).
+
+use crate::persistence::character_updater::StorageContainerUpdateData;
+use common::{comp::inventory::InvSlot, storage::STORAGE_SLOTS, uuid::Uuid};
+use hashbrown::{HashMap, HashSet};
+use specs::Entity as EcsEntity;
+use tracing::{info, warn};
+use vek::*;
+
+#[derive(Clone)]
+pub struct StorageContainer {
+    /// UUID of the player who placed the container.
+    pub owner: Uuid,
+    pub slots: Vec<InvSlot>,
+}
+
+impl StorageContainer {
+    pub fn new(owner: Uuid) -> Self {
+        Self {
+            owner,
+            slots: std::iter::repeat_with(|| None)
+                .take(STORAGE_SLOTS)
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool { self.slots.iter().all(Option::is_none) }
+}
+
+pub struct StorageContainers {
+    containers: HashMap<Vec3<i32>, StorageContainer>,
+    /// The container each player currently has open.
+    viewers: HashMap<EcsEntity, Vec3<i32>>,
+    /// Positions of the containers modified since the last persistence batch.
+    modified: HashSet<Vec3<i32>>,
+}
+
+impl StorageContainers {
+    /// Create the resource from the containers loaded from the database, see
+    /// [`crate::persistence::load_storage_containers`].
+    pub fn new(containers: Vec<(Vec3<i32>, StorageContainer)>) -> Self {
+        info!("Loaded {} storage containers", containers.len());
+
+        Self {
+            containers: containers.into_iter().collect(),
+            viewers: HashMap::new(),
+            modified: HashSet::new(),
+        }
+    }
+
+    pub fn get(&self, pos: Vec3<i32>) -> Option<&StorageContainer> { self.containers.get(&pos) }
+
+    pub fn get_mut(&mut self, pos: Vec3<i32>) -> Option<&mut StorageContainer> {
+        let container = self.containers.get_mut(&pos);
+        if container.is_some() {
+            self.modified.insert(pos);
+        }
+        container
+    }
+
+    /// Add a new container at the given position. Returns `false` if there
+    /// already is a container there.
+    pub fn insert(&mut self, pos: Vec3<i32>, container: StorageContainer) -> bool {
+        if self.containers.contains_key(&pos) {
+            warn!(
+                ?pos,
+                "Tried to place a storage container on top of another one"
+            );
+            false
+        } else {
+            self.containers.insert(pos, container);
+            self.modified.insert(pos);
+            true
+        }
+    }
+
+    /// Remove the container at the given position, closing it for everyone
+    /// that had it open.
+    pub fn remove(&mut self, pos: Vec3<i32>) -> Option<StorageContainer> {
+        let container = self.containers.remove(&pos)?;
+        self.viewers.retain(|_, open| *open != pos);
+        self.modified.insert(pos);
+        Some(container)
+    }
+
+    pub fn open(&mut self, viewer: EcsEntity, pos: Vec3<i32>) { self.viewers.insert(viewer, pos); }
+
+    pub fn close(&mut self, viewer: EcsEntity) -> Option<Vec3<i32>> { self.viewers.remove(&viewer) }
+
+    /// The position of the container the given entity currently has open.
+    pub fn opened_by(&self, viewer: EcsEntity) -> Option<Vec3<i32>> {
+        self.viewers.get(&viewer).copied()
+    }
+
+    /// All entities that currently have the container at the given position
+    /// open.
+    pub fn viewers_of(&self, pos: Vec3<i32>) -> impl Iterator<Item = EcsEntity> + '_ {
+        self.viewers
+            .iter()
+            .filter(move |(_, open)| **open == pos)
+            .map(|(viewer, _)| *viewer)
+    }
+
+    /// Take the contents of every container modified since the last call, to
+    /// be persisted. Removed containers have no contents.
+    pub fn take_modified(&mut self) -> Vec<StorageContainerUpdateData> {
+        self.modified
+            .drain()
+            .map(|pos| (pos, self.containers.get(&pos).cloned()))
+            .collect()
+    }
+}
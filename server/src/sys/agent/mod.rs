@@ -196,6 +196,7 @@ impl<'a> System<'a> for Sys {
                         can_fly: moving_body.is_some_and(|b| b.fly_thrust().is_some()),
                         vectored_propulsion: moving_body.is_some_and(|b| b.vectored_propulsion()),
                         is_target_loaded: true,
+                        nav_graph: Some(&*read_data.nav_graph),
                     };
                     let health_fraction = health.map_or(1.0, Health::fraction);
 
@@ -7,6 +7,7 @@ pub mod item;
 pub mod loot;
 pub mod metrics;
 pub mod msg;
+pub mod nav;
 pub mod object;
 pub mod persistence;
 pub mod pets;
@@ -57,12 +58,16 @@ pub fn run_sync_systems(ecs: &mut specs::World) {
     // Sync
     run_now::<terrain_sync::Sys>(ecs);
     run_now::<entity_sync::Sys>(ecs);
+
+    // Runs after terrain changes have been applied so it sees modified blocks
+    run_now::<nav::Sys>(ecs);
 }
 
 /// Used to schedule systems to run at an interval
 pub struct SysScheduler<S> {
     interval: Duration,
     last_run: Instant,
+    forced: bool,
     _phantom: PhantomData<S>,
 }
 
@@ -71,19 +76,25 @@ impl<S> SysScheduler<S> {
         Self {
             interval,
             last_run: Instant::now(),
+            forced: false,
             _phantom: PhantomData,
         }
     }
 
     pub fn should_run(&mut self) -> bool {
-        if self.last_run.elapsed() > self.interval {
+        if self.forced || self.last_run.elapsed() > self.interval {
             self.last_run = Instant::now();
+            self.forced = false;
 
             true
         } else {
             false
         }
     }
+
+    /// Makes the system run the next time it is dispatched, regardless of the
+    /// interval.
+    pub fn run_next(&mut self) { self.forced = true; }
 }
 
 impl<S> Default for SysScheduler<S> {
@@ -91,6 +102,7 @@ impl<S> Default for SysScheduler<S> {
         Self {
             interval: Duration::from_secs(30),
             last_run: Instant::now(),
+            forced: false,
             _phantom: PhantomData,
         }
     }
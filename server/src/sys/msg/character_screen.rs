@@ -120,7 +120,9 @@ impl Sys {
                     // this.
                     if presences.contains(entity) {
                         debug!("player already ingame, aborting");
-                    } else if character_updater.has_pending_database_action(character_id) {
+                    } else if character_updater.has_pending_database_action(character_id)
+                        || character_updater.has_pending_stash_update(&player.uuid().to_string())
+                    {
                         debug!("player recently logged out pending persistence, aborting");
                         client.send(ServerGeneral::CharacterDataLoadResult(Err(
                             "You have recently logged out, please wait a few seconds and try again"
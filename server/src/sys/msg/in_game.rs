@@ -1,10 +1,15 @@
 #[cfg(feature = "persistent_world")]
 use crate::TerrainPersistence;
-use crate::{EditableSettings, Settings, client::Client};
+use crate::{
+    EditableSettings, Settings,
+    anti_cheat::{PhysicsViolations, ViolationKind},
+    client::Client,
+};
 use common::{
     comp::{
-        Admin, AdminRole, Body, CanBuild, ControlEvent, Controller, ForceUpdate, Health, Ori,
-        Player, Pos, Presence, PresenceKind, Scale, SkillSet, SpectatingEntity, Vel,
+        Admin, AdminRole, Body, CanBuild, CharacterState, ControlEvent, Controller, ForceUpdate,
+        Health, Ori, Player, Pos, Presence, PresenceKind, Scale, SkillSet, SpectatingEntity, Stats,
+        Vel,
     },
     event::{self, EmitExt},
     event_emitters,
@@ -12,7 +17,7 @@ use common::{
     mounting::{Rider, VolumeRider},
     resources::{DeltaTime, PlayerPhysicsSetting, PlayerPhysicsSettings},
     slowjob::SlowJobPool,
-    terrain::TerrainGrid,
+    terrain::{Block, TerrainGrid},
     uid::IdMaps,
     vol::ReadVol,
 };
@@ -54,6 +59,12 @@ event_emitters! {
     }
 }
 
+fn is_storage_container(block: &Block) -> bool {
+    block
+        .get_sprite()
+        .is_some_and(|sprite| sprite.is_storage_container())
+}
+
 impl Sys {
     #[expect(clippy::too_many_arguments)]
     fn handle_client_in_game_msg(
@@ -170,6 +181,9 @@ impl Sys {
                                 // Vek defaults to inclusive which is not optimal
                                 .filter(|aabb| aabb.contains_point(pos))
                                 .and_then(|_| terrain.get(pos).ok())
+                                // Storage containers have to be picked up so their contents
+                                // aren't lost
+                                .filter(|block| !is_storage_container(block))
                         {
                             let new_block = old_block.into_vacant();
                             // Take the rare writes lock as briefly as possible.
@@ -198,6 +212,7 @@ impl Sys {
                                 // Vek defaults to inclusive which is not optimal
                                 .filter(|aabb| aabb.contains_point(pos))
                                 .is_some()
+                            && !terrain.get(pos).is_ok_and(is_storage_container)
                         {
                             // Take the rare writes lock as briefly as possible.
                             let mut guard = rare_writes.lock();
@@ -323,6 +338,11 @@ impl<'a> System<'a> for Sys {
         TerrainPersistenceData<'a>,
         ReadStorage<'a, Player>,
         ReadStorage<'a, Admin>,
+        (
+            ReadStorage<'a, CharacterState>,
+            ReadStorage<'a, Stats>,
+            Write<'a, PhysicsViolations>,
+        ),
     );
 
     const NAME: &'static str = "msg::in_game";
@@ -356,6 +376,7 @@ impl<'a> System<'a> for Sys {
             mut terrain_persistence,
             players,
             admins,
+            (character_states, stats, mut physics_violations),
         ): Self::SystemData,
     ) {
         let time_for_vd_changes = Instant::now();
@@ -414,6 +435,7 @@ impl<'a> System<'a> for Sys {
                     let mut skill_set = skill_set.map(Cow::Borrowed);
                     let mut player_physics = None;
                     let mut spectating_entity = None;
+                    let mut violation = None;
                     let _ = super::try_recv_all(client, 2, |client, msg| {
                         Self::handle_client_in_game_msg(
                             emitters,
@@ -447,31 +469,39 @@ impl<'a> System<'a> for Sys {
                         && let Some(old_vel) = vel.as_deref_mut()
                         && let Some(old_ori) = ori.as_deref_mut()
                     {
-                        enum Rejection {
-                            TooFar { old: Vec3<f32>, new: Vec3<f32> },
-                            TooFast { vel: Vec3<f32> },
-                            InsideTerrain,
-                        }
-
                         let rejection = if maybe_admin.is_some() {
                             None
                         } else {
-                            // Reminder: review these frequently to ensure they're reasonable
-                            const MAX_H_VELOCITY: f32 = 75.0;
-                            const MAX_V_VELOCITY: std::ops::Range<f32> = -100.0..80.0;
+                            let limits = &settings.anti_cheat;
+                            // Riders move as fast as their mount does
+                            let limited = is_rider
+                                .get(entity)
+                                .and_then(|is_rider| id_maps.uid_entity(is_rider.mount))
+                                .unwrap_or(entity);
+                            // Speed buffs and gliding let players move faster
+                            let speed_factor = stats.get(limited).map_or(1.0, |stats| stats.move_speed_modifier.max(1.0))
+                                * if matches!(character_states.get(limited), Some(CharacterState::Glide(_))) {
+                                    limits.glide_factor
+                                } else {
+                                    1.0
+                                };
 
                             'rejection: {
-                                let is_velocity_ok = new_vel.0.xy().magnitude_squared() < MAX_H_VELOCITY.powi(2)
-                                    && MAX_V_VELOCITY.contains(&new_vel.0.z);
+                                // Players may exceed the limits by the speed the server last gave them beyond
+                                // them (e.g. when dismounting or after being knocked back), but that allowance
+                                // decays so that it can't be kept forever
+                                let decay = (-limits.velocity_allowance_decay * dt.0).exp();
+                                let with_allowance = |limit: f32, speed: f32| limit + (speed - limit).max(0.0) * decay;
+                                let max_h_velocity = with_allowance(limits.max_h_velocity * speed_factor, old_vel.0.xy().magnitude());
+                                let max_up_velocity = with_allowance(limits.max_up_velocity * speed_factor, old_vel.0.z);
+                                let max_down_velocity = with_allowance(limits.max_down_velocity * speed_factor, -old_vel.0.z);
+                                let is_velocity_ok = new_vel.0.xy().magnitude_squared() <= max_h_velocity.powi(2)
+                                    && (-max_down_velocity..=max_up_velocity).contains(&new_vel.0.z);
 
                                 if !is_velocity_ok {
-                                    break 'rejection Some(Rejection::TooFast { vel: new_vel.0 });
+                                    break 'rejection Some(ViolationKind::TooFast { vel: new_vel.0 });
                                 }
 
-                                // How far the player is permitted to stray from the correct position (perhaps due to
-                                // latency problems).
-                                const POSITION_THRESHOLD: f32 = 16.0;
-
                                 // The position can either be sensible with respect to either the old or the new
                                 // velocity such that we don't punish for edge cases after a sudden change
                                 let is_position_ok = [old_vel.0, new_vel.0]
@@ -487,11 +517,11 @@ impl<'a> System<'a> for Sys {
                                             .projected_point(rpos)
                                             // + 1.5 accounts for minor changes in position without corresponding
                                             // velocity like block hopping/snapping
-                                            .distance_squared(rpos) < (rpos.magnitude() * 0.5 + 1.5 + POSITION_THRESHOLD).powi(2)
+                                            .distance_squared(rpos) < (rpos.magnitude() * 0.5 + 1.5 + limits.position_threshold).powi(2)
                                     });
 
                                 if !is_position_ok {
-                                    break 'rejection Some(Rejection::TooFar { old: old_pos.0, new: new_pos.0 });
+                                    break 'rejection Some(ViolationKind::TooFar { old: old_pos.0, new: new_pos.0 });
                                 }
 
                                 // Checks that are only relevant if the position changed
@@ -509,7 +539,7 @@ impl<'a> System<'a> for Sys {
                                             .get(pos)
                                             .is_ok_and(|block| block.is_fluid())
                                     }) {
-                                        break 'rejection Some(Rejection::InsideTerrain);
+                                        break 'rejection Some(ViolationKind::InsideTerrain);
                                     }
                                 }
 
@@ -518,21 +548,9 @@ impl<'a> System<'a> for Sys {
                         };
 
                         if let Some(rejection) = rejection {
-                            // TODO: Log when false positives aren't generated often
                             let alias = maybe_player.map(|p| &p.alias);
-                            match rejection {
-                                Rejection::TooFar { old, new } => warn!("Rejected physics for player {alias:?} (new position {new:?} is too far from old position {old:?})"),
-                                Rejection::TooFast { vel } => warn!("Rejected physics for player {alias:?} (new velocity {vel:?} is too fast)"),
-                                Rejection::InsideTerrain => warn!("Rejected physics for player {alias:?}: Inside terrain."),
-                            }
-
-                            /*
-                            // Perhaps this is overzealous?
-                            if let Some(mut setting) = new_player_physics_setting.as_mut() {
-                                setting.server_force = true;
-                                warn!("Switching player {alias:?} to server-side physics");
-                            }
-                            */
+                            warn!("Rejected physics for player {alias:?}: {rejection}");
+                            violation = maybe_player.map(|p| (entity, p.uuid(), p.alias.clone(), rejection));
 
                             // Reject the change and force the server's view of the physics state
                             force_update.as_mut().map(|fu| fu.update());
@@ -564,18 +582,18 @@ impl<'a> System<'a> for Sys {
                         .zip(new_player_physics_setting
                              .filter(|_| old_player_physics_setting != new_player_physics_setting));
                      let spectating_entity_update = spectating_entity.map(|e| (entity, e));
-                    (skill_set_update, spectating_entity_update, physics_update)
+                    (skill_set_update, spectating_entity_update, physics_update, violation)
                 },
             )
             // NOTE: Would be nice to combine this with the map_init somehow, but I'm not sure if
             // that's possible.
-            .filter(|(x, y, z)| x.is_some() || y.is_some() || z.is_some())
+            .filter(|(x, y, z, w)| x.is_some() || y.is_some() || z.is_some() || w.is_some())
             // NOTE: I feel like we shouldn't actually need to allocate here, but hopefully this
             // doesn't turn out to be important as there shouldn't be that many connected clients.
             // The reason we can't just use unzip is that the two sides might be different lengths.
             .collect::<Vec<_>>();
         let player_physics_settings = &mut *player_physics_settings_;
-        // Deferred updates to skillsets and player physics.
+        // Deferred updates to skillsets and player physics, and physics violations.
         //
         // NOTE: It is an invariant that there is at most one client entry per player
         // uuid; since we joined on clients, it follows that there's just one update
@@ -583,7 +601,7 @@ impl<'a> System<'a> for Sys {
         // order, even though we're not updating directly by entity or uid (note that
         // for a given entity, we process messages serially).
         deferred_updates.iter_mut().for_each(
-            |(skill_set_update, spectating_entity_update, physics_update)| {
+            |(skill_set_update, spectating_entity_update, physics_update, violation)| {
                 if let Some((entity, new_skill_set)) = skill_set_update {
                     // We know this exists, because we already iterated over it with the skillset
                     // lock taken, so we can ignore the error.
@@ -613,6 +631,9 @@ impl<'a> System<'a> for Sys {
                         .settings
                         .insert(uuid, player_physics_setting);
                 }
+                if let Some((entity, uuid, alias, kind)) = violation.take() {
+                    physics_violations.record(entity, uuid, &alias, kind, &settings.anti_cheat);
+                }
             },
         );
         // Finally, drop the deferred updates in another thread.
@@ -0,0 +1,85 @@
+use common::{
+    nav::{ChunkNav, NavGraph},
+    slowjob::SlowJobPool,
+    terrain::{CoordinateConversions, TerrainGrid},
+};
+use common_ecs::{Job, Origin, Phase, System};
+use common_state::TerrainChanges;
+use specs::{Read, ReadExpect, Write};
+use std::sync::Arc;
+use vek::*;
+
+/// Maximum number of chunks whose navigation data is being rebuilt at once.
+const MAX_PENDING_BUILDS: usize = 16;
+
+/// Chunk navigation data being built in the background.
+pub struct NavBuilds {
+    tx: crossbeam_channel::Sender<(Vec2<i32>, ChunkNav)>,
+    rx: crossbeam_channel::Receiver<(Vec2<i32>, ChunkNav)>,
+    pending: usize,
+}
+
+impl Default for NavBuilds {
+    fn default() -> Self {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        Self { tx, rx, pending: 0 }
+    }
+}
+
+/// This system keeps the navigation graph used by NPC pathfinding up to date
+/// with the loaded terrain
+#[derive(Default)]
+pub struct Sys;
+impl<'a> System<'a> for Sys {
+    type SystemData = (
+        Read<'a, TerrainChanges>,
+        ReadExpect<'a, TerrainGrid>,
+        ReadExpect<'a, SlowJobPool>,
+        Write<'a, NavBuilds>,
+        Write<'a, NavGraph>,
+    );
+
+    const NAME: &'static str = "nav";
+    const ORIGIN: Origin = Origin::Server;
+    const PHASE: Phase = Phase::Create;
+
+    fn run(
+        _job: &mut Job<Self>,
+        (terrain_changes, terrain, slow_jobs, mut builds, mut nav_graph): Self::SystemData,
+    ) {
+        // Builds finish in the order they were started, so chunks that changed
+        // again in the meantime are still dirty and get rebuilt later
+        while let Ok((key, nav)) = builds.rx.try_recv() {
+            builds.pending = builds.pending.saturating_sub(1);
+            if terrain.get_key(key).is_some() {
+                nav_graph.insert(key, nav);
+            }
+        }
+
+        for key in &terrain_changes.removed_chunks {
+            nav_graph.remove(*key);
+        }
+        for key in terrain_changes
+            .new_chunks
+            .iter()
+            .chain(&terrain_changes.modified_chunks)
+        {
+            nav_graph.invalidate(*key);
+        }
+        for wpos in terrain_changes.modified_blocks.keys() {
+            nav_graph.invalidate(wpos.xy().wpos_to_cpos());
+        }
+
+        for key in nav_graph.take_dirty(MAX_PENDING_BUILDS.saturating_sub(builds.pending)) {
+            if let Some(chunk) = terrain.get_key_arc(key) {
+                let chunk = Arc::clone(chunk);
+                let tx = builds.tx.clone();
+                builds.pending += 1;
+                slow_jobs.spawn("NAV_GRAPH", move || {
+                    let nav = ChunkNav::build(&*chunk, chunk.get_min_z()..chunk.get_max_z() + 1);
+                    let _ = tx.send((key, nav));
+                });
+            }
+        }
+    }
+}
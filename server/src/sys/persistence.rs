@@ -1,4 +1,6 @@
-use crate::{persistence::character_updater, sys::SysScheduler};
+use crate::{
+    persistence::character_updater, storage_container::StorageContainers, sys::SysScheduler,
+};
 use common::{
     comp::{
         ActiveAbilities, Alignment, Body, Inventory, MapMarker, Presence, PresenceKind, SkillSet,
@@ -28,6 +30,7 @@ impl<'a> System<'a> for Sys {
         ReadStorage<'a, Stats>,
         ReadStorage<'a, ActiveAbilities>,
         WriteExpect<'a, character_updater::CharacterUpdater>,
+        Option<Write<'a, StorageContainers>>,
         Write<'a, SysScheduler<Self>>,
     );
 
@@ -50,6 +53,7 @@ impl<'a> System<'a> for Sys {
             stats,
             active_abilities,
             mut updater,
+            storage_containers,
             mut scheduler,
         ): Self::SystemData,
     ) {
@@ -112,6 +116,9 @@ impl<'a> System<'a> for Sys {
                             PresenceKind::Spectator | PresenceKind::Possessor => None,
                         },
                     ),
+                storage_containers
+                    .map(|mut storage_containers| storage_containers.take_modified())
+                    .unwrap_or_default(),
             );
         }
     }
@@ -415,6 +415,7 @@ impl SpawnEntityData {
             no_flee,
             idle_wander_factor,
             aggro_range_multiplier,
+            tactic,
             // stats
             body,
             name,
@@ -514,6 +515,7 @@ impl SpawnEntityData {
                 .with_no_flee_if(matches!(agent_mark, Some(agent::Mark::Guard)) || no_flee)
                 .with_idle_wander_factor(idle_wander_factor)
                 .with_aggro_range_multiplier(aggro_range_multiplier)
+                .with_tactic(tactic)
         });
 
         let agent = if matches!(alignment, comp::Alignment::Enemy)
@@ -41,13 +41,8 @@ impl TerrainPersistence {
     ///
     /// If the `VELOREN_TERRAIN` environment variable is set, this will be used
     /// as the persistence directory instead.
-    pub fn new(mut data_dir: PathBuf) -> Self {
-        let path = std::env::var("VELOREN_TERRAIN")
-            .map(PathBuf::from)
-            .unwrap_or_else(|_| {
-                data_dir.push("terrain");
-                data_dir
-            });
+    pub fn new(data_dir: PathBuf) -> Self {
+        let path = Self::dir(data_dir);
 
         std::fs::create_dir_all(&path).expect("Failed to create terrain persistence directory");
 
@@ -60,6 +55,20 @@ impl TerrainPersistence {
         }
     }
 
+    /// The directory terrain modifications are persisted to.
+    pub fn path(&self) -> PathBuf { self.path.clone() }
+
+    /// The directory terrain modifications are persisted to for the given data
+    /// directory, see [`TerrainPersistence::new`].
+    pub fn dir(mut data_dir: PathBuf) -> PathBuf {
+        std::env::var("VELOREN_TERRAIN")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                data_dir.push("terrain");
+                data_dir
+            })
+    }
+
     /// Apply persistence changes to a newly generated chunk.
     pub fn apply_changes(&mut self, key: Vec2<i32>, terrain_chunk: &mut TerrainChunk) {
         let loaded_chunk = self.load_chunk(key);
@@ -167,35 +176,56 @@ impl TerrainPersistence {
             }
 
             // Prevent any uneccesarry IO when nothing in this chunk has changed
-            if !modified {
-                return;
+            if modified {
+                self.write_chunk(key, chunk);
             }
+        }
+    }
 
-            if chunk.blocks.is_empty() {
-                let path = self.path_for(key);
+    /// Write all modified chunks back to the filesystem without unloading
+    /// them.
+    pub fn flush(&mut self) {
+        let mut written = Vec::new();
+        for (key, loaded_chunk) in &self.chunks {
+            if loaded_chunk.modified && self.write_chunk(*key, loaded_chunk.chunk.clone()) {
+                written.push(*key);
+            }
+        }
+        for key in written {
+            if let Some(loaded_chunk) = self.chunks.get_mut(&key) {
+                loaded_chunk.modified = false;
+            }
+        }
+    }
 
-                if path.is_file()
-                    && let Err(error) = std::fs::remove_file(&path)
-                {
-                    error!(?error, ?path, "Failed to remove file for empty chunk");
-                }
-            } else {
-                let bytes =
-                    match encode_to_vec::<version::Current, _>(chunk.prepare_raw(), legacy()) {
-                        Err(err) => {
-                            error!("Failed to serialize chunk data: {:?}", err);
-                            return;
-                        },
-                        Ok(bytes) => bytes,
-                    };
+    /// Returns whether the chunk was written successfully.
+    fn write_chunk(&self, key: Vec2<i32>, chunk: Chunk) -> bool {
+        if chunk.blocks.is_empty() {
+            let path = self.path_for(key);
 
-                let atomic_file =
-                    AtomicFile::new(self.path_for(key), OverwriteBehavior::AllowOverwrite);
-                if let Err(err) = atomic_file.write(|file| file.write_all(&bytes)) {
-                    error!("Failed to write chunk data to file: {:?}", err);
-                }
+            if path.is_file()
+                && let Err(error) = std::fs::remove_file(&path)
+            {
+                error!(?error, ?path, "Failed to remove file for empty chunk");
+                return false;
+            }
+        } else {
+            let bytes = match encode_to_vec::<version::Current, _>(chunk.prepare_raw(), legacy()) {
+                Err(err) => {
+                    error!("Failed to serialize chunk data: {:?}", err);
+                    return false;
+                },
+                Ok(bytes) => bytes,
+            };
+
+            let atomic_file =
+                AtomicFile::new(self.path_for(key), OverwriteBehavior::AllowOverwrite);
+            if let Err(err) = atomic_file.write(|file| file.write_all(&bytes)) {
+                error!("Failed to write chunk data to file: {:?}", err);
+                return false;
             }
         }
+        true
     }
 
     pub fn clear_chunk(&mut self, chunk: Vec2<i32>) {
@@ -305,28 +305,33 @@ impl<'a> InventoryScroller<'a> {
             .scroll_kids_vertically()
             .set(state.ids.inv_alignment, ui);
 
+        // The stash is only shown for our own inventory
+        let stash = self.inventory.stash().filter(|_| self.is_us);
+
         // Bag Slots
         // Create available inventory slot widgets
-        if state.ids.inv_slots.len() < self.inventory.capacity() {
+        let slot_count = self.inventory.capacity()
+            + self.inventory.overflow_items().count()
+            + stash.map_or(0, |stash| stash.len());
+        if state.ids.inv_slots.len() < slot_count {
             state.update(|s| {
-                s.ids.inv_slots.resize(
-                    self.inventory.capacity() + self.inventory.overflow_items().count(),
-                    &mut ui.widget_id_generator(),
-                );
+                s.ids
+                    .inv_slots
+                    .resize(slot_count, &mut ui.widget_id_generator());
             });
         }
-        if state.ids.inv_slot_names.len() < self.inventory.capacity() {
+        if state.ids.inv_slot_names.len() < slot_count {
             state.update(|s| {
                 s.ids
                     .inv_slot_names
-                    .resize(self.inventory.capacity(), &mut ui.widget_id_generator());
+                    .resize(slot_count, &mut ui.widget_id_generator());
             });
         }
-        if state.ids.inv_slot_amounts.len() < self.inventory.capacity() {
+        if state.ids.inv_slot_amounts.len() < slot_count {
             state.update(|s| {
                 s.ids
                     .inv_slot_amounts
-                    .resize(self.inventory.capacity(), &mut ui.widget_id_generator());
+                    .resize(slot_count, &mut ui.widget_id_generator());
             });
         }
         // Determine the range of inventory slots that are provided by the loadout item
@@ -375,6 +380,13 @@ impl<'a> InventoryScroller<'a> {
                     .enumerate()
                     .map(|(i, item)| (Slot::Overflow(i), Some(item))),
             )
+            .chain(
+                stash
+                    .into_iter()
+                    .flatten()
+                    .enumerate()
+                    .map(|(i, item)| (Slot::Stash(i), item.as_ref())),
+            )
             .collect::<Vec<_>>();
         if self.details_mode && !self.is_us {
             items.sort_by_cached_key(|(_, item)| {
@@ -436,6 +448,11 @@ impl<'a> InventoryScroller<'a> {
                 slot_widget = slot_widget.with_background_color(Color::Rgba(1.0, 0.0, 0.0, 1.0));
             }
 
+            // Highlight in blue slots that are part of the stash
+            if matches!(pos, Slot::Stash(_)) {
+                slot_widget = slot_widget.with_background_color(Color::Rgba(0.3, 0.5, 1.0, 1.0));
+            }
+
             if let Some(item) = item {
                 let quality_col_img = match item.quality() {
                     Quality::Low => self.imgs.inv_slot_grey,
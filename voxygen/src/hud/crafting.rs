@@ -1787,6 +1787,7 @@ impl Widget for Crafting<'_> {
                                     Slot::Inventory(slot) => Some(slot),
                                     Slot::Equip(_) => None,
                                     Slot::Overflow(_) => None,
+                                    Slot::Stash(_) => None,
                                 }),
                             });
                         }
@@ -1946,6 +1947,7 @@ impl Widget for Crafting<'_> {
                             Slot::Inventory(slot) => self.inventory.get(slot),
                             Slot::Equip(_) => None,
                             Slot::Overflow(_) => None,
+                            Slot::Stash(_) => None,
                         })
                         .and_then(|item| item.item_definition_id().itemdef_id().map(String::from))
                     {
@@ -1957,6 +1959,7 @@ impl Widget for Crafting<'_> {
                                     Slot::Inventory(slot) => self.inventory.get(slot),
                                     Slot::Equip(_) => None,
                                     Slot::Overflow(_) => None,
+                                    Slot::Stash(_) => None,
                                 })
                                 .and_then(|item| {
                                     item.item_definition_id().itemdef_id().map(String::from)
@@ -1980,7 +1983,7 @@ impl Widget for Crafting<'_> {
                     if let Some(item) = match craft_slot_1 {
                         Some(Slot::Inventory(slot)) => self.inventory.get(slot),
                         Some(Slot::Equip(slot)) => self.inventory.equipped(slot),
-                        Some(Slot::Overflow(_)) => None,
+                        Some(Slot::Overflow(_) | Slot::Stash(_)) => None,
                         None => None,
                     } {
                         if let Some(recipe) = self.client.repair_recipe_book().repair_recipe(item) {
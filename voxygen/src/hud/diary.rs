@@ -1242,6 +1242,21 @@ impl Widget for Diary<'_> {
                             .as_ref()
                             .cloned()
                             .unwrap_or_else(|| "Unknown".to_string()),
+                        CharacterStat::Reputation => match self.client.reputation() {
+                            Some((site, standing)) => self
+                                .localized_strings
+                                .get_msg_ctx("hud-reputation-standing", &i18n::fluent_args! {
+                                    "standing" => self
+                                        .localized_strings
+                                        .get_msg(standing.localization_key()),
+                                    "site" => site.as_str(),
+                                })
+                                .into_owned(),
+                            None => self
+                                .localized_strings
+                                .get_msg("hud-reputation-unknown")
+                                .into_owned(),
+                        },
                         CharacterStat::Hitpoints => format!("{}", self.health.base_max() as u32),
                         CharacterStat::Energy => format!("{}", self.energy.base_max() as u32),
                         CharacterStat::Poise => format!("{}", self.poise.base_max() as u32),
@@ -3321,13 +3336,14 @@ impl<'a> SkillStrings<'a> {
 }
 
 /// The number of variants of the [`CharacterStat`] enum.
-const STAT_COUNT: usize = 15;
+const STAT_COUNT: usize = 16;
 
 #[derive(EnumIter)]
 enum CharacterStat {
     Name,
     BattleMode,
     Waypoint,
+    Reputation,
     Hitpoints,
     Energy,
     Poise,
@@ -3350,6 +3366,7 @@ impl CharacterStat {
             Name => i18n.get_msg("character_window-character_name"),
             BattleMode => i18n.get_msg("hud-battle-mode"),
             Waypoint => i18n.get_msg("hud-waypoint"),
+            Reputation => i18n.get_msg("hud-reputation"),
             Hitpoints => i18n.get_msg("hud-bag-health"),
             Energy => i18n.get_msg("hud-bag-energy"),
             CombatRating => i18n.get_msg("hud-bag-combat_rating"),
@@ -22,6 +22,7 @@ mod settings_window;
 mod skillbar;
 mod slots;
 mod social;
+mod storage;
 mod subtitles;
 mod trade;
 
@@ -59,6 +60,7 @@ use serde::{Deserialize, Serialize};
 use settings_window::{SettingsTab, SettingsWindow};
 use skillbar::Skillbar;
 use social::Social;
+use storage::Storage;
 use subtitles::Subtitles;
 use trade::Trade;
 
@@ -116,6 +118,7 @@ use common::{
     resources::{BattleMode, Secs, Time},
     rtsim,
     slowjob::SlowJobPool,
+    storage::StorageAction,
     terrain::{Block, SpriteKind, TerrainChunk, UnlockKind},
     trade::{ReducedInventory, TradeAction},
     uid::Uid,
@@ -326,6 +329,7 @@ widget_ids! {
         prompt_dialog,
         bag,
         trade,
+        storage,
         social,
         quest,
         diary,
@@ -715,6 +719,7 @@ pub enum Event {
     SortInventory(InventorySortOrder),
     ChangeHotbarState(Box<HotbarState>),
     TradeAction(TradeAction),
+    StorageAction(StorageAction),
     Ability {
         idx: usize,
         state: bool,
@@ -932,6 +937,7 @@ pub struct Show {
     bag_details: bool,
     trade: bool,
     trade_details: bool,
+    storage: bool,
     social: bool,
     diary: bool,
     group: bool,
@@ -979,6 +985,15 @@ impl Show {
         }
     }
 
+    fn storage(&mut self, open: bool) {
+        if !self.esc_menu {
+            self.bag = open;
+            self.storage = open;
+            self.map = false;
+            self.want_grab = !self.any_window_requires_cursor();
+        }
+    }
+
     fn map(&mut self, open: bool) {
         if !self.esc_menu {
             self.map = open;
@@ -1079,6 +1094,8 @@ impl Show {
 
     fn toggle_trade(&mut self) { self.trade(!self.trade); }
 
+    fn toggle_storage(&mut self) { self.storage(!self.storage); }
+
     fn toggle_map(&mut self) { self.map(!self.map) }
 
     fn toggle_social(&mut self) { self.social(!self.social); }
@@ -1113,6 +1130,7 @@ impl Show {
     fn any_window_requires_cursor(&self) -> bool {
         self.bag
             || self.trade
+            || self.storage
             || self.esc_menu
             || self.map
             || self.social
@@ -1127,6 +1145,7 @@ impl Show {
         if self.any_window_requires_cursor() {
             self.bag = false;
             self.trade = false;
+            self.storage = false;
             self.esc_menu = false;
             self.intro = false;
             self.map = false;
@@ -1416,6 +1435,7 @@ impl Hud {
                 bag_details: false,
                 trade: false,
                 trade_details: false,
+                storage: false,
                 esc_menu: false,
                 open_windows: Windows::None,
                 map: false,
@@ -1595,6 +1615,10 @@ impl Hud {
                 self.show.toggle_trade();
             }
 
+            if client.storage().is_some() != self.show.storage {
+                self.show.toggle_storage();
+            }
+
             //self.input = client.read_storage::<comp::ControllerInputs>();
             if let Some(health) = healths.get(me) {
                 // Hurt Frame
@@ -2222,6 +2246,11 @@ impl Hud {
                         i18n.get_msg("hud-read").to_string(),
                         overitem::TEXT_COLOR,
                     ),
+                    BlockInteraction::Storage => (
+                        Some(GameInput::Interact),
+                        i18n.get_msg("hud-open").to_string(),
+                        overitem::TEXT_COLOR,
+                    ),
                     // TODO: change to turn on/turn off?
                     BlockInteraction::LightToggle(enable) => (
                         Some(GameInput::Interact),
@@ -3390,6 +3419,31 @@ impl Hud {
             }
         }
 
+        // Storage container window
+        if self.show.storage
+            && let Some(action) = Storage::new(
+                client,
+                &info,
+                &self.imgs,
+                &self.item_imgs,
+                &self.fonts,
+                &self.rot_imgs,
+                item_tooltip_manager,
+                &mut self.slot_manager,
+                i18n,
+                &self.item_i18n,
+                &msm,
+                &rbm,
+                self.pulse,
+            )
+            .set(self.ids.storage, ui_widgets)
+        {
+            if let StorageAction::Close = action {
+                self.show.storage(false);
+            }
+            events.push(Event::StorageAction(action));
+        }
+
         // Buffs
         if let (Some(player_buffs), Some(health), Some(energy)) = (
             buffs.get(info.viewpoint_entity),
@@ -4050,7 +4104,7 @@ impl Hud {
             let to_slot = |slot_kind| match slot_kind {
                 Inventory(
                     i @ InventorySlot {
-                        slot: Slot::Inventory(_) | Slot::Overflow(_),
+                        slot: Slot::Inventory(_) | Slot::Overflow(_) | Slot::Stash(_),
                         ours: true,
                         ..
                     },
@@ -4066,6 +4120,7 @@ impl Hud {
                 Trade(_) => None,
                 Ability(_) => None,
                 Crafting(_) => None,
+                Storage(_) => None,
             };
             match event {
                 slot::Event::Dragged(a, b) => {
@@ -4116,6 +4171,29 @@ impl Hud {
                                 ours: t.ours,
                             }));
                         }
+                    } else if let (
+                        Inventory(InventorySlot {
+                            slot: Slot::Inventory(inv_slot),
+                            ours: true,
+                            ..
+                        }),
+                        Storage(s),
+                    )
+                    | (
+                        Storage(s),
+                        Inventory(InventorySlot {
+                            slot: Slot::Inventory(inv_slot),
+                            ours: true,
+                            ..
+                        }),
+                    ) = (a, b)
+                    {
+                        events.push(Event::StorageAction(StorageAction::Swap {
+                            inv_slot,
+                            storage_slot: s.index,
+                        }));
+                    } else if let (Storage(a), Storage(b)) = (a, b) {
+                        events.push(Event::StorageAction(StorageAction::Move(a.index, b.index)));
                     } else if let (Ability(a), Ability(b)) = (a, b) {
                         match (a, b) {
                             (AbilitySlot::Ability(ability), AbilitySlot::Slot(index)) => {
@@ -4394,7 +4472,7 @@ impl Hud {
                                         .map(|e| {
                                             prices.values.get(&e.1).cloned().unwrap_or_default()
                                                 * e.0
-                                                * (if ours { e.1.trade_margin() } else { 1.0 })
+                                                * (if ours { prices.margin(e.1) } else { 1.0 })
                                         })
                                         .sum();
 
@@ -4879,6 +4957,9 @@ impl Hud {
                     self.force_chat = false;
                 } else if self.show.trade {
                     self.events.push(Event::TradeAction(TradeAction::Decline));
+                } else if self.show.storage {
+                    self.show.storage(false);
+                    self.events.push(Event::StorageAction(StorageAction::Close));
                 } else {
                     // Close windows on esc
                     if self.show.bag {
@@ -14,6 +14,7 @@ use common::{
         slot::{InvSlotId, Slot},
     },
     recipe::ComponentRecipeBook,
+    storage::OpenStorage,
 };
 use conrod_core::{Color, image};
 use specs::Entity as EcsEntity;
@@ -29,6 +30,7 @@ pub enum SlotKind {
     Trade(TradeSlot),
     Ability(AbilitySlot),
     Crafting(CraftSlot),
+    Storage(StorageSlot),
     /* Spellbook(SpellbookSlot), TODO */
 }
 
@@ -116,6 +118,30 @@ impl SlotKey<Inventory, ItemImgs> for TradeSlot {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StorageSlot {
+    pub index: usize,
+}
+
+impl SlotKey<OpenStorage, ItemImgs> for StorageSlot {
+    type ImageKey = ItemKey;
+
+    fn image_key(&self, source: &OpenStorage) -> Option<(Self::ImageKey, Option<Color>)> {
+        source.get(self.index).map(|i| (i.into(), None))
+    }
+
+    fn amount(&self, source: &OpenStorage) -> Option<u32> {
+        source
+            .get(self.index)
+            .map(|item| item.amount())
+            .filter(|amount| *amount > 1)
+    }
+
+    fn image_ids(key: &Self::ImageKey, source: &ItemImgs) -> Vec<image::Id> {
+        source.img_ids_or_not_found_img(key.clone())
+    }
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub enum HotbarImage {
     Item(ItemKey),
@@ -300,7 +326,7 @@ impl CraftSlot {
         match self.slot {
             Some(Slot::Inventory(slot)) => inv.get(slot),
             Some(Slot::Equip(slot)) => inv.equipped(slot),
-            Some(Slot::Overflow(_)) => None,
+            Some(Slot::Overflow(_) | Slot::Stash(_)) => None,
             None => None,
         }
     }
@@ -367,6 +393,10 @@ impl From<CraftSlot> for SlotKind {
     fn from(craft: CraftSlot) -> Self { Self::Crafting(craft) }
 }
 
+impl From<StorageSlot> for SlotKind {
+    fn from(storage: StorageSlot) -> Self { Self::Storage(storage) }
+}
+
 impl SumSlot for SlotKind {
     fn drag_size(&self) -> Option<[f64; 2]> {
         Some(match self {
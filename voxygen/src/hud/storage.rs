@@ -0,0 +1,335 @@
+use conrod_core::{
+    Color, Colorable, Labelable, Positionable, Sizeable, UiCell, Widget, WidgetCommon, color,
+    position::Relative,
+    widget::{self, Button, Image, Rectangle, State as ConrodState, Text},
+    widget_ids,
+};
+use vek::*;
+
+use client::Client;
+use common::{
+    comp::inventory::item::{ItemDesc, ItemI18n, MaterialStatManifest, Quality},
+    recipe::RecipeBookManifest,
+    storage::{OpenStorage, STORAGE_SLOTS, StorageAction},
+};
+use i18n::Localization;
+
+use crate::ui::{
+    ImageFrame, ItemTooltip, ItemTooltipManager, ItemTooltipable,
+    fonts::Fonts,
+    slot::{ContentSize, SlotMaker},
+};
+
+use super::{
+    HudInfo, TEXT_COLOR, UI_HIGHLIGHT_0, UI_MAIN,
+    img_ids::{Imgs, ImgsRot},
+    item_imgs::ItemImgs,
+    slots::{SlotManager, StorageSlot},
+};
+
+/// Number of slots per row of the storage grid.
+const SLOTS_PER_ROW: usize = 9;
+const SLOT_SIZE: f64 = 40.0;
+
+pub struct State {
+    ids: Ids,
+}
+
+widget_ids! {
+    pub struct Ids {
+        storage_close,
+        bg,
+        bg_frame,
+        storage_title_bg,
+        storage_title,
+        slot_alignment,
+        slots[],
+        pick_up_button,
+    }
+}
+
+#[derive(WidgetCommon)]
+pub struct Storage<'a> {
+    client: &'a Client,
+    info: &'a HudInfo<'a>,
+    imgs: &'a Imgs,
+    item_imgs: &'a ItemImgs,
+    fonts: &'a Fonts,
+    rot_imgs: &'a ImgsRot,
+    item_tooltip_manager: &'a mut ItemTooltipManager,
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+    slot_manager: &'a mut SlotManager,
+    localized_strings: &'a Localization,
+    item_i18n: &'a ItemI18n,
+    msm: &'a MaterialStatManifest,
+    rbm: &'a RecipeBookManifest,
+    pulse: f32,
+}
+
+impl<'a> Storage<'a> {
+    pub fn new(
+        client: &'a Client,
+        info: &'a HudInfo,
+        imgs: &'a Imgs,
+        item_imgs: &'a ItemImgs,
+        fonts: &'a Fonts,
+        rot_imgs: &'a ImgsRot,
+        item_tooltip_manager: &'a mut ItemTooltipManager,
+        slot_manager: &'a mut SlotManager,
+        localized_strings: &'a Localization,
+        item_i18n: &'a ItemI18n,
+        msm: &'a MaterialStatManifest,
+        rbm: &'a RecipeBookManifest,
+        pulse: f32,
+    ) -> Self {
+        Self {
+            client,
+            info,
+            imgs,
+            item_imgs,
+            fonts,
+            rot_imgs,
+            item_tooltip_manager,
+            common: widget::CommonBuilder::default(),
+            slot_manager,
+            localized_strings,
+            item_i18n,
+            msm,
+            rbm,
+            pulse,
+        }
+    }
+}
+
+impl<'a> Storage<'a> {
+    fn background(&mut self, state: &mut ConrodState<'_, State>, ui: &mut UiCell<'_>) {
+        Image::new(self.imgs.inv_middle_bg_bag)
+            .w_h(424.0, 250.0)
+            .color(Some(UI_MAIN))
+            .mid_bottom_with_margin_on(ui.window, 295.0)
+            .set(state.ids.bg, ui);
+        Image::new(self.imgs.inv_middle_frame)
+            .w_h(424.0, 250.0)
+            .middle_of(state.ids.bg)
+            .color(Some(UI_HIGHLIGHT_0))
+            .set(state.ids.bg_frame, ui);
+    }
+
+    fn title(&mut self, state: &mut ConrodState<'_, State>, ui: &mut UiCell<'_>) {
+        Text::new(&self.localized_strings.get_msg("hud-storage-title"))
+            .mid_top_with_margin_on(state.ids.bg_frame, 9.0)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(20))
+            .color(Color::Rgba(0.0, 0.0, 0.0, 1.0))
+            .set(state.ids.storage_title_bg, ui);
+        Text::new(&self.localized_strings.get_msg("hud-storage-title"))
+            .top_left_with_margins_on(state.ids.storage_title_bg, 2.0, 2.0)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(20))
+            .color(TEXT_COLOR)
+            .set(state.ids.storage_title, ui);
+    }
+
+    fn slots(
+        &mut self,
+        state: &mut ConrodState<'_, State>,
+        ui: &mut UiCell<'_>,
+        storage: &OpenStorage,
+    ) {
+        let inventories = self.client.inventories();
+        // Our inventory is needed to know what recipes are known
+        let our_inventory = inventories.get(self.client.entity());
+
+        let item_tooltip = ItemTooltip::new(
+            {
+                // Edge images [t, b, r, l]
+                // Corner images [tr, tl, br, bl]
+                let edge = &self.rot_imgs.tt_side;
+                let corner = &self.rot_imgs.tt_corner;
+                ImageFrame::new(
+                    [edge.cw180, edge.none, edge.cw270, edge.cw90],
+                    [corner.none, corner.cw270, corner.cw90, corner.cw180],
+                    Color::Rgba(0.08, 0.07, 0.04, 1.0),
+                    5.0,
+                )
+            },
+            self.client,
+            self.info,
+            self.imgs,
+            self.item_imgs,
+            self.pulse,
+            self.msm,
+            self.rbm,
+            our_inventory,
+            self.localized_strings,
+            self.item_i18n,
+        )
+        .title_font_size(self.fonts.cyri.scale(20))
+        .parent(ui.window)
+        .desc_font_size(self.fonts.cyri.scale(12))
+        .font_id(self.fonts.cyri.conrod_id)
+        .desc_text_color(TEXT_COLOR);
+
+        // Alignment for Grid
+        Rectangle::fill_with(
+            [
+                SLOTS_PER_ROW as f64 * SLOT_SIZE,
+                STORAGE_SLOTS.div_ceil(SLOTS_PER_ROW) as f64 * SLOT_SIZE,
+            ],
+            color::TRANSPARENT,
+        )
+        .mid_top_with_margin_on(state.ids.bg, 60.0)
+        .set(state.ids.slot_alignment, ui);
+
+        let mut slot_maker = SlotMaker {
+            empty_slot: self.imgs.inv_slot,
+            filled_slot: self.imgs.inv_slot,
+            selected_slot: self.imgs.inv_slot_sel,
+            background_color: Some(UI_MAIN),
+            content_size: ContentSize {
+                width_height_ratio: 1.0,
+                max_fraction: 0.75,
+            },
+            selected_content_scale: 1.067,
+            amount_font: self.fonts.cyri.conrod_id,
+            amount_margins: Vec2::new(-4.0, 0.0),
+            amount_font_size: self.fonts.cyri.scale(12),
+            amount_text_color: TEXT_COLOR,
+            content_source: storage,
+            image_source: self.item_imgs,
+            slot_manager: Some(self.slot_manager),
+            pulse: self.pulse,
+        };
+
+        if state.ids.slots.len() < storage.slots.len() {
+            state.update(|s| {
+                s.ids
+                    .slots
+                    .resize(storage.slots.len(), &mut ui.widget_id_generator());
+            });
+        }
+
+        for index in 0..storage.slots.len() {
+            let x = index % SLOTS_PER_ROW;
+            let y = index / SLOTS_PER_ROW;
+
+            let slot_widget = slot_maker
+                .fabricate(StorageSlot { index }, [SLOT_SIZE; 2])
+                .top_left_with_margins_on(
+                    state.ids.slot_alignment,
+                    y as f64 * SLOT_SIZE,
+                    x as f64 * SLOT_SIZE,
+                );
+            let slot_id = state.ids.slots[index];
+            if let Some(item) = storage.get(index) {
+                let quality_col_img = match item.quality() {
+                    Quality::Low => self.imgs.inv_slot_grey,
+                    Quality::Common => self.imgs.inv_slot_common,
+                    Quality::Moderate => self.imgs.inv_slot_green,
+                    Quality::High => self.imgs.inv_slot_blue,
+                    Quality::Epic => self.imgs.inv_slot_purple,
+                    Quality::Legendary => self.imgs.inv_slot_gold,
+                    Quality::Artifact => self.imgs.inv_slot_orange,
+                    _ => self.imgs.inv_slot_red,
+                };
+
+                slot_widget
+                    .filled_slot(quality_col_img)
+                    .with_item_tooltip(
+                        self.item_tooltip_manager,
+                        core::iter::once(item as &dyn ItemDesc),
+                        &None,
+                        &item_tooltip,
+                    )
+                    .set(slot_id, ui);
+            } else {
+                slot_widget.set(slot_id, ui);
+            }
+        }
+    }
+
+    fn pick_up_button(
+        &mut self,
+        state: &mut ConrodState<'_, State>,
+        ui: &mut UiCell<'_>,
+        storage: &OpenStorage,
+    ) -> Option<StorageAction> {
+        // Only empty containers can be picked up, so that their contents can't be lost
+        if !storage.is_owner {
+            return None;
+        }
+        let (hover_img, press_img, luminance) = if storage.is_empty() {
+            (
+                self.imgs.button_hover,
+                self.imgs.button_press,
+                Color::Rgba(1.0, 1.0, 1.0, 1.0),
+            )
+        } else {
+            (
+                self.imgs.button,
+                self.imgs.button,
+                Color::Rgba(0.6, 0.6, 0.6, 1.0),
+            )
+        };
+        (Button::image(self.imgs.button)
+            .w_h(31.0 * 5.0, 12.0 * 2.0)
+            .hover_image(hover_img)
+            .press_image(press_img)
+            .image_color(luminance)
+            .mid_bottom_with_margin_on(state.ids.bg, 30.0)
+            .label(&self.localized_strings.get_msg("hud-storage-pick_up"))
+            .label_font_size(self.fonts.cyri.scale(14))
+            .label_color(TEXT_COLOR)
+            .label_font_id(self.fonts.cyri.conrod_id)
+            .label_y(Relative::Scalar(2.0))
+            .set(state.ids.pick_up_button, ui)
+            .was_clicked()
+            && storage.is_empty())
+        .then_some(StorageAction::PickUp)
+    }
+
+    fn close_button(
+        &mut self,
+        state: &mut ConrodState<'_, State>,
+        ui: &mut UiCell<'_>,
+    ) -> Option<StorageAction> {
+        Button::image(self.imgs.close_btn)
+            .w_h(24.0, 25.0)
+            .hover_image(self.imgs.close_btn_hover)
+            .press_image(self.imgs.close_btn_press)
+            .top_right_with_margins_on(state.ids.bg, 0.0, 0.0)
+            .set(state.ids.storage_close, ui)
+            .was_clicked()
+            .then_some(StorageAction::Close)
+    }
+}
+
+impl Widget for Storage<'_> {
+    type Event = Option<StorageAction>;
+    type State = State;
+    type Style = ();
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
+        State {
+            ids: Ids::new(id_gen),
+        }
+    }
+
+    fn style(&self) -> Self::Style {}
+
+    fn update(mut self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        common_base::prof_span!("Storage::update");
+        let widget::UpdateArgs { state, ui, .. } = args;
+
+        let Some(storage) = self.client.storage() else {
+            return Some(StorageAction::Close);
+        };
+
+        self.background(state, ui);
+        self.title(state, ui);
+        self.slots(state, ui, storage);
+        let event = self.pick_up_button(state, ui, storage);
+        self.close_button(state, ui).or(event)
+    }
+}
@@ -25,13 +25,15 @@ pub fn price_desc<'a>(
     let prices = prices.as_ref()?;
     let materials = TradePricing::get_materials(&item_definition_id)?;
     let coinprice = prices.values.get(&Good::Coin).cloned().unwrap_or(1.0);
+    // Goodwill makes the coins paid worth more
     let buyprice: f32 = materials
         .iter()
         .map(|e| prices.values.get(&e.1).cloned().unwrap_or_default() * e.0)
-        .sum();
+        .sum::<f32>()
+        / prices.margin(Good::Coin);
     let sellprice: f32 = materials
         .iter()
-        .map(|e| prices.values.get(&e.1).cloned().unwrap_or_default() * e.0 * e.1.trade_margin())
+        .map(|e| prices.values.get(&e.1).cloned().unwrap_or_default() * e.0 * prices.margin(e.1))
         .sum();
 
     let deal_goodness: f32 = materials
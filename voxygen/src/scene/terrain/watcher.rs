@@ -17,6 +17,7 @@ pub enum Interaction {
     Mount,
     Read,
     LightToggle(bool),
+    Storage,
 }
 
 #[derive(Copy, Clone)]
@@ -272,6 +273,9 @@ impl BlocksOfInterest {
                             SpriteKind::Sign | SpriteKind::HangingSign => {
                                 interactables.push((pos, Interaction::Read))
                             },
+                            SpriteKind::StorageChest => {
+                                interactables.push((pos, Interaction::Storage))
+                            },
                             SpriteKind::MycelBlue => spores.push(pos),
                             SpriteKind::Mold => spores.push(pos),
                             _ => {},
@@ -48,6 +48,7 @@ pub enum BlockInteraction {
     Mount,
     Read(Content),
     LightToggle(bool),
+    Storage,
 }
 
 #[derive(Debug, Clone)]
@@ -127,6 +128,10 @@ impl BlockInteraction {
             Interaction::Craft(tab) => BlockInteraction::Craft(tab),
             Interaction::Mount => BlockInteraction::Mount,
             Interaction::LightToggle(enable) => BlockInteraction::LightToggle(enable),
+            Interaction::Storage => match volume_pos.kind {
+                common::mounting::Volume::Terrain => BlockInteraction::Storage,
+                common::mounting::Volume::Entity(_) => return None,
+            },
         };
         Some((block, block_interaction))
     }
@@ -137,6 +142,7 @@ impl BlockInteraction {
             | BlockInteraction::Read(_)
             | BlockInteraction::LightToggle(_)
             | BlockInteraction::Craft(_)
+            | BlockInteraction::Storage
             | BlockInteraction::Unlock { .. } => GameInput::Interact,
             BlockInteraction::Mine(_) => GameInput::Primary,
             BlockInteraction::Mount => GameInput::Mount,
@@ -151,9 +157,9 @@ impl BlockInteraction {
             | BlockInteraction::Mine(_)
             | BlockInteraction::Craft(_) => consts::MAX_PICKUP_RANGE,
             BlockInteraction::Mount => consts::MAX_SPRITE_MOUNT_RANGE,
-            BlockInteraction::LightToggle(_) | BlockInteraction::Read(_) => {
-                consts::MAX_INTERACT_RANGE
-            },
+            BlockInteraction::LightToggle(_)
+            | BlockInteraction::Read(_)
+            | BlockInteraction::Storage => consts::MAX_INTERACT_RANGE,
         }
     }
 
@@ -522,6 +528,7 @@ impl Interactable {
             Self::Entity { interaction: EntityInteraction::ActivatePortal, .. }  => 4,
             Self::Entity { interaction: EntityInteraction::PickupItem, .. }      => 3,
             Self::Block  { interaction: BlockInteraction::Craft(_), .. }         => 3,
+            Self::Block  { interaction: BlockInteraction::Storage, .. }          => 3,
             Self::Block  { interaction: BlockInteraction::Collect { .. }, .. }   => 3,
             Self::Entity { interaction: EntityInteraction::HelpDowned, .. }      => 2,
             Self::Block  { interaction: BlockInteraction::Unlock { .. }, .. }    => 1,
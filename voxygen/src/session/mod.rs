@@ -28,6 +28,7 @@ use common::{
     mounting::{Mount, VolumePos},
     outcome::Outcome,
     recipe::{self, RecipeBookManifest},
+    storage::StorageAction,
     terrain::{Block, BlockKind},
     trade::TradeResult,
     util::{Dir, Plane},
@@ -1156,6 +1157,15 @@ impl PlayState for SessionState {
                                                             *enable,
                                                         );
                                                     },
+                                                    BlockInteraction::Storage => {
+                                                        if let common::mounting::Volume::Terrain =
+                                                            volume_pos.kind
+                                                        {
+                                                            client.perform_storage_action(
+                                                                StorageAction::Open(volume_pos.pos),
+                                                            );
+                                                        }
+                                                    },
                                                 }
                                             },
                                             Interactable::Entity {
@@ -1898,7 +1908,7 @@ impl PlayState for SessionState {
                                         move_allowed = false;
                                     }
                                 },
-                                Slot::Overflow(_) => {},
+                                Slot::Overflow(_) | Slot::Stash(_) => {},
                             }
                         };
 
@@ -2063,6 +2073,9 @@ impl PlayState for SessionState {
                     HudEvent::TradeAction(action) => {
                         self.client.borrow_mut().perform_trade_action(action);
                     },
+                    HudEvent::StorageAction(action) => {
+                        self.client.borrow_mut().perform_storage_action(action);
+                    },
                     HudEvent::Ability { idx, state } => {
                         self.client.borrow_mut().handle_input(
                             InputKind::Ability(idx),
@@ -2178,7 +2191,7 @@ impl PlayState for SessionState {
                                     let item = match item {
                                         Slot::Equip(slot) => inventory.equipped(slot),
                                         Slot::Inventory(slot) => inventory.get(slot),
-                                        Slot::Overflow(_) => None,
+                                        Slot::Overflow(_) | Slot::Stash(_) => None,
                                     }?;
                                     let repair_recipe =
                                         client.repair_recipe_book().repair_recipe(item)?;
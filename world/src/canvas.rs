@@ -15,14 +15,15 @@ use common::{
 };
 use rand::SeedableRng;
 use rand_chacha::ChaChaRng;
-use std::{borrow::Cow, ops::Deref};
+use std::{borrow::Cow, cell::OnceCell, ops::Deref};
 use vek::*;
 
 #[derive(Copy, Clone)]
 pub struct CanvasInfo<'a> {
     pub(crate) chunk_pos: Vec2<i32>,
     pub(crate) wpos: Vec2<i32>,
-    pub(crate) column_grid: &'a Grid<Option<ZCache<'a>>>,
+    /// Columns that weren't generated up front are generated when first needed
+    pub(crate) column_grid: &'a Grid<OnceCell<Option<ZCache<'a>>>>,
     pub(crate) column_grid_border: i32,
     pub(crate) chunks: &'a WorldSim,
     pub(crate) index: IndexRef<'a>,
@@ -43,10 +44,18 @@ impl<'a> CanvasInfo<'a> {
         .into()
     }
 
-    pub fn col(&self, wpos: Vec2<i32>) -> Option<&'a ColumnSample<'_>> {
+    pub fn col(&self, wpos: Vec2<i32>) -> Option<&'a ColumnSample<'a>> {
         self.column_grid
-            .get(self.column_grid_border + wpos - self.wpos())
-            .and_then(Option::as_ref)
+            .get(self.column_grid_border + wpos - self.wpos())?
+            .get_or_init(|| {
+                ColumnGen::new(self.chunks)
+                    .get((wpos, self.index, self.calendar))
+                    .map(|sample| ZCache {
+                        sample,
+                        calendar: self.calendar,
+                    })
+            })
+            .as_ref()
             .map(|zc| &zc.sample)
     }
 
@@ -96,7 +105,7 @@ impl<'a> CanvasInfo<'a> {
         sim: &'a WorldSim,
         f: F,
     ) -> A {
-        let zcache_grid = Grid::populate_from(Vec2::broadcast(1), |_| None);
+        let zcache_grid = Grid::populate_from(Vec2::broadcast(1), |_| OnceCell::from(None));
         let sim_chunk = SimChunk {
             chaos: 0.0,
             alt: 0.0,
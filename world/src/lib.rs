@@ -68,7 +68,7 @@ use enum_map::EnumMap;
 use rand::{Rng, prelude::*};
 use rand_chacha::ChaCha8Rng;
 use serde::Deserialize;
-use std::{borrow::Cow, time::Duration};
+use std::{borrow::Cow, cell::OnceCell, time::Duration};
 use vek::*;
 
 #[cfg(all(feature = "be-dyn-lib", feature = "use-dyn-lib"))]
@@ -102,6 +102,17 @@ pub enum WorldGenerateStage {
 pub struct World {
     sim: sim::WorldSim,
     civs: civ::Civs,
+    base_chunk_cache: Option<Box<dyn BaseChunkCache>>,
+}
+
+/// Storage for the base terrain of chunks, as sampled from the world
+/// simulation before layers and sites are applied. Unlike the rest of a chunk,
+/// it only depends on the world and the calendar, so it can be reused whenever
+/// a chunk is generated again.
+pub trait BaseChunkCache: Send + Sync {
+    fn load(&self, chunk_pos: Vec2<i32>, calendar: Option<&Calendar>) -> Option<TerrainChunk>;
+
+    fn store(&self, chunk_pos: Vec2<i32>, calendar: Option<&Calendar>, chunk: &TerrainChunk);
 }
 
 #[derive(Deserialize)]
@@ -158,7 +169,14 @@ impl World {
             report_stage(WorldGenerateStage::SpotGeneration);
             Spot::generate(&mut sim);
 
-            (Self { sim, civs }, IndexOwned::new(index))
+            (
+                Self {
+                    sim,
+                    civs,
+                    base_chunk_cache: None,
+                },
+                IndexOwned::new(index),
+            )
         })
     }
 
@@ -166,6 +184,14 @@ impl World {
 
     pub fn civs(&self) -> &civ::Civs { &self.civs }
 
+    /// Use `cache` to avoid sampling the base terrain of chunks that were
+    /// generated before.
+    #[must_use]
+    pub fn with_base_chunk_cache(mut self, cache: impl BaseChunkCache + 'static) -> Self {
+        self.base_chunk_cache = Some(Box::new(cache));
+        self
+    }
+
     pub fn tick(&self, _dt: Duration) {
         // TODO
     }
@@ -352,20 +378,6 @@ impl World {
         let chunk_wpos2d = chunk_pos * TerrainChunkSize::RECT_SIZE.map(|e| e as i32);
         let chunk_center_wpos2d = chunk_wpos2d + TerrainChunkSize::RECT_SIZE.map(|e| e as i32 / 2);
         let grid_border = 4;
-        let zcache_grid = Grid::populate_from(
-            TerrainChunkSize::RECT_SIZE.map(|e| e as i32) + grid_border * 2,
-            |offs| sampler.get_z_cache(chunk_wpos2d - grid_border + offs, index, calendar),
-        );
-
-        let air = Block::air(SpriteKind::Empty);
-        let stone = Block::new(
-            BlockKind::Rock,
-            zcache_grid
-                .get(grid_border + TerrainChunkSize::RECT_SIZE.map(|e| e as i32) / 2)
-                .and_then(|zcache| zcache.as_ref())
-                .map(|zcache| zcache.sample.stone_col)
-                .unwrap_or_else(|| index.colors.deep_stone_color.into()),
-        );
 
         let (base_z, sim_chunk) = match self
             .sim
@@ -416,60 +428,95 @@ impl World {
             sim_chunk.cliff_height,
         );
 
-        let mut chunk = TerrainChunk::new(base_z, stone, air, meta);
+        let cached = self
+            .base_chunk_cache
+            .as_ref()
+            .and_then(|cache| cache.load(chunk_pos, calendar));
+        // Cached chunks already have their base terrain, so their columns are only
+        // generated when later stages need them
+        let zcache_grid = Grid::populate_from(
+            TerrainChunkSize::RECT_SIZE.map(|e| e as i32) + grid_border * 2,
+            |offs| {
+                if cached.is_some() {
+                    OnceCell::new()
+                } else {
+                    OnceCell::from(sampler.get_z_cache(
+                        chunk_wpos2d - grid_border + offs,
+                        index,
+                        calendar,
+                    ))
+                }
+            },
+        );
+        let info = CanvasInfo {
+            chunk_pos,
+            wpos: chunk_wpos2d,
+            column_grid: &zcache_grid,
+            column_grid_border: grid_border,
+            chunks: &self.sim,
+            index,
+            chunk: sim_chunk,
+            calendar,
+        };
+
+        let mut chunk = if let Some(mut chunk) = cached {
+            *chunk.meta_mut() = meta;
+            chunk
+        } else {
+            let air = Block::air(SpriteKind::Empty);
+            let stone = Block::new(
+                BlockKind::Rock,
+                info.col(chunk_center_wpos2d)
+                    .map(|col| col.stone_col)
+                    .unwrap_or_else(|| index.colors.deep_stone_color.into()),
+            );
+            let mut chunk = TerrainChunk::new(base_z, stone, air, meta);
 
-        for y in 0..TerrainChunkSize::RECT_SIZE.y as i32 {
-            for x in 0..TerrainChunkSize::RECT_SIZE.x as i32 {
-                if should_continue() {
-                    return Err(());
-                };
+            for y in 0..TerrainChunkSize::RECT_SIZE.y as i32 {
+                for x in 0..TerrainChunkSize::RECT_SIZE.x as i32 {
+                    if should_continue() {
+                        return Err(());
+                    };
 
-                let offs = Vec2::new(x, y);
+                    let offs = Vec2::new(x, y);
 
-                let z_cache = match zcache_grid.get(grid_border + offs) {
-                    Some(Some(z_cache)) => z_cache,
-                    _ => continue,
-                };
+                    let z_cache = match zcache_grid.get(grid_border + offs).and_then(OnceCell::get)
+                    {
+                        Some(Some(z_cache)) => z_cache,
+                        _ => continue,
+                    };
 
-                let (min_z, max_z) = z_cache.get_z_limits();
+                    let (min_z, max_z) = z_cache.get_z_limits();
 
-                (base_z..min_z as i32).for_each(|z| {
-                    let _ = chunk.set(Vec3::new(x, y, z), stone);
-                });
+                    (base_z..min_z as i32).for_each(|z| {
+                        let _ = chunk.set(Vec3::new(x, y, z), stone);
+                    });
 
-                (min_z as i32..max_z as i32).for_each(|z| {
-                    let lpos = Vec3::new(x, y, z);
-                    let wpos = Vec3::from(chunk_wpos2d) + lpos;
+                    (min_z as i32..max_z as i32).for_each(|z| {
+                        let lpos = Vec3::new(x, y, z);
+                        let wpos = Vec3::from(chunk_wpos2d) + lpos;
 
-                    if let Some(block) = sampler.get_with_z_cache(wpos, Some(z_cache)) {
-                        let _ = chunk.set(lpos, block);
-                    }
-                });
+                        if let Some(block) = sampler.get_with_z_cache(wpos, Some(z_cache)) {
+                            let _ = chunk.set(lpos, block);
+                        }
+                    });
+                }
             }
-        }
 
-        let sample_get = |offs| {
-            zcache_grid
-                .get(grid_border + offs)
-                .and_then(Option::as_ref)
-                .map(|zc| &zc.sample)
+            if let Some(cache) = &self.base_chunk_cache {
+                cache.store(chunk_pos, calendar, &chunk);
+            }
+            chunk
         };
 
+        let sample_get = |offs| info.col(chunk_wpos2d + offs);
+
         // Only use for rng affecting dynamic elements like chests and entities!
         let mut dynamic_rng = ChaCha8Rng::from_seed(rand::rng().random());
 
         // Apply layers (paths, caves, etc.)
         let mut canvas = Canvas {
-            info: CanvasInfo {
-                chunk_pos,
-                wpos: chunk_pos * TerrainChunkSize::RECT_SIZE.map(|e| e as i32),
-                column_grid: &zcache_grid,
-                column_grid_border: grid_border,
-                chunks: &self.sim,
-                index,
-                chunk: sim_chunk,
-                calendar,
-            },
+            info,
             chunk: &mut chunk,
             entities: Vec::new(),
             rtsim_resource_blocks: Vec::new(),
@@ -610,6 +657,130 @@ impl World {
         Ok((chunk, supplement))
     }
 
+    /// Render a plot of a site on top of the generated terrain, returning the
+    /// blocks that differ from it.
+    ///
+    /// This is used to apply plots built after world generation (see
+    /// [`site::PlotPlan`]) to terrain that players may already have seen, and
+    /// is expensive since every chunk that the plot overlaps gets generated.
+    pub fn render_plot(
+        &self,
+        index: IndexRef,
+        site: &site::Site,
+        plot: &site::Plot,
+        time: Option<(TimeOfDay, Calendar)>,
+    ) -> Vec<(Vec3<i32>, Block)> {
+        self.render_plot_over(index, site, plot, time.clone(), |chunk_pos| {
+            self.generate_chunk(index, chunk_pos, None, || false, time.clone())
+                .ok()
+                .map(|(chunk, _)| chunk)
+        })
+    }
+
+    /// The blocks that a plot of a site is made of, without the terrain around
+    /// it.
+    pub fn plot_blocks(
+        &self,
+        index: IndexRef,
+        site: &site::Site,
+        plot: &site::Plot,
+        time: Option<(TimeOfDay, Calendar)>,
+    ) -> Vec<(Vec3<i32>, Block)> {
+        let air = Block::air(SpriteKind::Empty);
+        self.render_plot_over(index, site, plot, time, |chunk_pos| {
+            let base_z = self.sim.get_base_z(chunk_pos)?;
+            Some(TerrainChunk::new(
+                base_z as i32,
+                air,
+                air,
+                TerrainChunkMeta::void(),
+            ))
+        })
+    }
+
+    /// Render a plot on top of the chunks that it overlaps, as given by `base`,
+    /// returning the blocks that differ from them.
+    fn render_plot_over(
+        &self,
+        index: IndexRef,
+        site: &site::Site,
+        plot: &site::Plot,
+        time: Option<(TimeOfDay, Calendar)>,
+        mut base: impl FnMut(Vec2<i32>) -> Option<TerrainChunk>,
+    ) -> Vec<(Vec3<i32>, Block)> {
+        // Structures may overhang their tiles a little, e.g. roofs
+        const MARGIN: i32 = 8;
+
+        let calendar = time.as_ref().map(|(_, cal)| cal);
+        let chunk_size = TerrainChunkSize::RECT_SIZE.map(|e| e as i32);
+        let tile_bounds = plot.find_bounds();
+        let wpos_bounds = Aabr {
+            min: site.tile_wpos(tile_bounds.min) - MARGIN,
+            max: site.tile_wpos(tile_bounds.max + 1) + MARGIN,
+        };
+        let cpos_bounds = Aabr {
+            min: wpos_bounds.min.wpos_to_cpos(),
+            max: wpos_bounds.max.wpos_to_cpos(),
+        };
+
+        let mut dynamic_rng = ChaCha8Rng::from_seed(rand::rng().random());
+        let mut sampler = self.sample_blocks();
+        let mut blocks = Vec::new();
+        for chunk_pos in (cpos_bounds.min.x..=cpos_bounds.max.x)
+            .flat_map(|x| (cpos_bounds.min.y..=cpos_bounds.max.y).map(move |y| Vec2::new(x, y)))
+        {
+            let Some(sim_chunk) = self.sim.get(chunk_pos) else {
+                continue;
+            };
+            let Some(mut chunk) = base(chunk_pos) else {
+                continue;
+            };
+            let unchanged = chunk.clone();
+
+            let chunk_wpos2d = chunk_pos * chunk_size;
+            let grid_border = 4;
+            let zcache_grid = Grid::populate_from(chunk_size + grid_border * 2, |offs| {
+                OnceCell::from(sampler.get_z_cache(
+                    chunk_wpos2d - grid_border + offs,
+                    index,
+                    calendar,
+                ))
+            });
+            let mut canvas = Canvas {
+                info: CanvasInfo {
+                    chunk_pos,
+                    wpos: chunk_wpos2d,
+                    column_grid: &zcache_grid,
+                    column_grid_border: grid_border,
+                    chunks: &self.sim,
+                    index,
+                    chunk: sim_chunk,
+                    calendar,
+                },
+                chunk: &mut chunk,
+                entities: Vec::new(),
+                rtsim_resource_blocks: Vec::new(),
+            };
+            site.render_plot(&mut canvas, plot, &mut dynamic_rng);
+            drop(canvas);
+
+            for y in 0..chunk_size.y {
+                for x in 0..chunk_size.x {
+                    for z in chunk.get_min_z()..chunk.get_max_z() {
+                        let lpos = Vec3::new(x, y, z);
+                        if let Ok(block) = chunk.get(lpos)
+                            && unchanged.get(lpos).is_ok_and(|old| old != block)
+                        {
+                            blocks.push((chunk_wpos2d.with_z(0) + lpos, *block));
+                        }
+                    }
+                }
+            }
+        }
+
+        blocks
+    }
+
     // Zone coordinates
     pub fn get_lod_zone(&self, pos: Vec2<i32>, index: IndexRef) -> lod::Zone {
         let min_wpos = pos.map(lod::to_wpos);
@@ -1,36 +1,218 @@
-use crate::sim::WorldSim;
-use common::path::Path;
-//use hashbrown::hash_map::DefaultHashBuilder;
+use crate::{sim::WorldSim, util::NEIGHBORS};
+use common::{astar::Astar, path::Path, terrain::TerrainChunkSize, vol::RectVolSize};
+use core::hash::BuildHasherDefault;
+use fxhash::FxHasher64;
 use vek::*;
 
-#[expect(dead_code)]
+/// Maximum number of chunks visited by a search before giving up.
+const MAX_SEARCH_ITERS: usize = 50_000;
+/// Extra cost, in chunks, of fording a river where there is no path across it.
+const RIVER_CROSSING_COST: f32 = 8.0;
+
+#[derive(Copy, Clone, Debug)]
 pub struct SearchCfg {
     // 0.0 = no discount, 1.0 = free travel
-    path_discount: f32,
+    pub path_discount: f32,
     // Cost per metre altitude change per metre horizontal
     // 0.0 = no cost, 1.0 = same cost vertical as horizontal
-    gradient_aversion: f32,
+    pub gradient_aversion: f32,
+}
+
+impl Default for SearchCfg {
+    fn default() -> Self {
+        Self {
+            path_discount: 0.5,
+            gradient_aversion: 2.0,
+        }
+    }
 }
 
-#[expect(dead_code)]
+/// Searches for routes across the world at the scale of chunks, preferring
+/// existing paths and avoiding steep slopes. Oceans and lakes can't be crossed
+/// unless a path goes over them.
 pub struct Searcher<'a> {
     land: &'a WorldSim,
     pub cfg: SearchCfg,
 }
 
-impl Searcher<'_> {
+impl<'a> Searcher<'a> {
+    pub fn new(land: &'a WorldSim) -> Self {
+        Self {
+            land,
+            cfg: SearchCfg::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_cfg(mut self, cfg: SearchCfg) -> Self {
+        self.cfg = cfg;
+        self
+    }
+
     /// Attempt to find a path between two chunks on the map.
-    pub fn search(self, _a: Vec2<i32>, _b: Vec2<i32>) -> Option<Path<i32>> {
-        // TODO: implement this function
-
-        //let heuristic = |pos: &Vec2<i32>| (pos - b).map(|e| e as f32).magnitude();
-        // Astar::new(
-        //     100_000,
-        //     a,
-        //     heuristc,
-        //     DefaultHashBuilder::default(),
-        // );
-
-        None
+    pub fn search(self, a: Vec2<i32>, b: Vec2<i32>) -> Option<Path<Vec2<i32>>> {
+        if self.land.get(b).is_none_or(is_open_water) {
+            return None;
+        }
+
+        let path_discount = self.cfg.path_discount.clamp(0.0, 1.0);
+        // Travelling along paths is the cheapest way to cover any distance, so
+        // this never overestimates the cost.
+        let heuristic =
+            |pos: &Vec2<i32>| (pos.distance_squared(b) as f32).sqrt() * (1.0 - path_discount);
+        let this = &self;
+        let neighbors = |pos: &Vec2<i32>| {
+            let pos = *pos;
+            (0..NEIGHBORS.len()).filter_map(move |i| this.transition(pos, i, path_discount))
+        };
+        // We use this hasher (FxHasher64) because
+        // (1) we don't care about DDOS attacks (ruling out SipHash);
+        // (2) we care about determinism across computers (ruling out AAHash);
+        // (3) we have 8-byte keys (for which FxHash is fastest).
+        Astar::new(
+            MAX_SEARCH_ITERS,
+            a,
+            BuildHasherDefault::<FxHasher64>::default(),
+        )
+        .poll(MAX_SEARCH_ITERS, heuristic, neighbors, |pos| *pos == b)
+        .into_path()
+        .map(|(path, _cost)| path)
+    }
+
+    /// The chunk reached by moving from `a` in the direction of the
+    /// `dir_idx`th neighbor, and the cost of doing so. `None` if that chunk
+    /// can't be entered.
+    fn transition(
+        &self,
+        a: Vec2<i32>,
+        dir_idx: usize,
+        path_discount: f32,
+    ) -> Option<(Vec2<i32>, f32)> {
+        let dir = NEIGHBORS[dir_idx];
+        let b = a + dir;
+        let a_chunk = self.land.get(a)?;
+        let b_chunk = self.land.get(b)?;
+
+        let on_path = a_chunk.path.0.neighbors & (1 << dir_idx) != 0;
+        if !on_path && is_open_water(b_chunk) {
+            return None;
+        }
+
+        let horizontal = dir.as_::<f32>().magnitude();
+        let gradient =
+            (b_chunk.alt - a_chunk.alt).abs() / (horizontal * TerrainChunkSize::RECT_SIZE.x as f32);
+        let cost = horizontal * (1.0 + self.cfg.gradient_aversion * gradient);
+
+        Some((
+            b,
+            if on_path {
+                cost * (1.0 - path_discount)
+            } else if b_chunk.river.is_river() {
+                cost + RIVER_CROSSING_COST
+            } else {
+                cost
+            },
+        ))
+    }
+}
+
+fn is_open_water(chunk: &crate::sim::SimChunk) -> bool {
+    chunk.river.is_ocean() || chunk.river.is_lake()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::{FileOpts, GenOpts, RiverData, RiverKind, WorldOpts};
+
+    /// A small generated world, flattened so that the tests don't depend on
+    /// its terrain.
+    fn flat_world() -> WorldSim {
+        let threadpool = rayon::ThreadPoolBuilder::new().build().unwrap();
+        let opts = WorldOpts {
+            seed_elements: false,
+            world_file: FileOpts::Generate(GenOpts {
+                x_lg: 5,
+                y_lg: 5,
+                erosion_quality: 0.1,
+                ..GenOpts::default()
+            }),
+            calendar: None,
+        };
+        let mut sim = WorldSim::generate(0, opts, &threadpool, &|_| {});
+        for chunk in sim.chunks.iter_mut() {
+            chunk.alt = 100.0;
+            chunk.water_alt = 0.0;
+            chunk.river = RiverData::default();
+            chunk.path = Default::default();
+        }
+        sim
+    }
+
+    fn add_path(sim: &mut WorldSim, nodes: &[Vec2<i32>]) {
+        for locs in nodes.windows(2) {
+            let i = NEIGHBORS
+                .iter()
+                .position(|dir| *dir == locs[1] - locs[0])
+                .expect("Path nodes must be neighbors");
+            sim.get_mut(locs[0]).unwrap().path.0.neighbors |= 1 << i;
+            sim.get_mut(locs[1]).unwrap().path.0.neighbors |= 1 << ((i + 4) % 8);
+        }
+    }
+
+    #[test]
+    fn routes_follow_paths() {
+        let mut sim = flat_world();
+        // A detour around the direct route between both ends
+        let path = (16..22)
+            .map(|y| Vec2::new(2, y))
+            .chain((2..29).map(|x| Vec2::new(x, 22)))
+            .chain((16..=22).rev().map(|y| Vec2::new(29, y)))
+            .collect::<Vec<_>>();
+        add_path(&mut sim, &path);
+
+        let (start, end) = (Vec2::new(2, 16), Vec2::new(29, 16));
+        let route = Searcher::new(&sim).search(start, end).unwrap();
+        assert_eq!(route.start(), Some(&start));
+        assert_eq!(route.end(), Some(&end));
+        assert!(route.iter().all(|pos| path.contains(pos)));
+
+        // Without a discount the direct route is shorter
+        let route = Searcher::new(&sim)
+            .with_cfg(SearchCfg {
+                path_discount: 0.0,
+                ..SearchCfg::default()
+            })
+            .search(start, end)
+            .unwrap();
+        assert_eq!(route.len(), 28);
+    }
+
+    #[test]
+    fn routes_avoid_oceans() {
+        let mut sim = flat_world();
+        for x in 10..=20 {
+            for y in 0..24 {
+                sim.get_mut(Vec2::new(x, y)).unwrap().river.river_kind = Some(RiverKind::Ocean);
+            }
+        }
+
+        let (start, end) = (Vec2::new(2, 10), Vec2::new(29, 10));
+        let route = Searcher::new(&sim).search(start, end).unwrap();
+        assert_eq!(route.end(), Some(&end));
+        assert!(
+            route
+                .iter()
+                .all(|pos| !sim.get(*pos).unwrap().river.is_ocean())
+        );
+        assert!(route.iter().any(|pos| pos.y >= 24));
+
+        // Cut the map in two
+        for x in 10..=20 {
+            for y in 24..32 {
+                sim.get_mut(Vec2::new(x, y)).unwrap().river.river_kind = Some(RiverKind::Ocean);
+            }
+        }
+        assert!(Searcher::new(&sim).search(start, end).is_none());
     }
 }
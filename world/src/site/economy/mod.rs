@@ -328,6 +328,7 @@ impl Economy {
                 });
                 prices.iter().map(|(g, v)| (Good::from(g), *v)).collect()
             },
+            goodwill: 0.0,
         }
     }
 
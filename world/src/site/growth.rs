@@ -0,0 +1,138 @@
+//! Growth of settlements after world generation.
+//!
+//! Site layouts are decided during world generation, but settlements can keep
+//! growing while the game runs. New plots are planned on free tiles next to the
+//! site's roads, and a [`PlotPlan`] is small enough to be persisted by its
+//! caller, e.g. rtsim, so that the same plot can be generated again later with
+//! [`Site::build_plan`].
+//!
+//! Plans are never added to the site itself (the site layout is shared and
+//! immutable while the game runs), so callers are responsible for passing the
+//! plans made so far when planning new plots.
+
+use super::{Site, SiteKind, aabr_tiles, plot};
+use crate::{Land, util::attempt};
+use common::calendar::Calendar;
+use rand::prelude::*;
+use rand_chacha::ChaChaRng;
+use serde::{Deserialize, Serialize};
+use vek::*;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GrowthKind {
+    House,
+    Workshop,
+}
+
+/// Where and how to build a plot that wasn't part of the generated site.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlotPlan {
+    pub kind: GrowthKind,
+    pub tile_aabr: Aabr<i32>,
+    pub door_tile: Vec2<i32>,
+    pub door_dir: Vec2<i32>,
+    pub alt: Option<i32>,
+    pub seed: u64,
+}
+
+impl PlotPlan {
+    /// The area covered by the plot, in world coordinates.
+    pub fn wpos_bounds(&self, site: &Site) -> Aabr<i32> {
+        Aabr {
+            min: site.tile_wpos(self.tile_aabr.min),
+            max: site.tile_wpos(self.tile_aabr.max),
+        }
+    }
+}
+
+impl Site {
+    /// Whether new plots can be built in this site while the game runs.
+    pub fn can_grow(&self) -> bool {
+        matches!(
+            self.kind,
+            Some(SiteKind::Refactor | SiteKind::CoastalTown | SiteKind::SavannahTown)
+        )
+    }
+
+    /// Find free tiles next to a road for a new plot of the given kind,
+    /// avoiding the tiles of plots planned earlier.
+    pub fn plan_growth(
+        &self,
+        rng: &mut impl Rng,
+        kind: GrowthKind,
+        planned: &[PlotPlan],
+    ) -> Option<PlotPlan> {
+        if !self.can_grow() {
+            return None;
+        }
+        // Match the size of the plots placed during world generation
+        let base_size = match (self.kind, kind) {
+            (Some(SiteKind::CoastalTown), _) => 7.0,
+            (Some(SiteKind::SavannahTown), _) => 4.0,
+            (_, GrowthKind::House) => 1.5,
+            (_, GrowthKind::Workshop) => 3.0,
+        };
+        let size = (base_size + rng.random::<f32>().powf(5.0) * 1.5).round() as u32;
+
+        let (tile_aabr, door_tile, door_dir, alt) = attempt(32, || {
+            self.find_roadside_aabr(rng, 4..(size + 1).pow(2), Extent2::broadcast(size))
+                .filter(|(aabr, _, _, _)| {
+                    planned
+                        .iter()
+                        .all(|plan| !plan.tile_aabr.collides_with_aabr(*aabr))
+                })
+        })?;
+
+        Some(PlotPlan {
+            kind,
+            tile_aabr,
+            door_tile,
+            door_dir,
+            alt,
+            seed: rng.random(),
+        })
+    }
+
+    /// Generate the plot described by a plan. The same plan always results in
+    /// the same plot.
+    pub fn build_plan(
+        &self,
+        land: &Land,
+        plan: &PlotPlan,
+        calendar: Option<&Calendar>,
+    ) -> plot::Plot {
+        let mut rng = ChaChaRng::seed_from_u64(plan.seed);
+        let (door_tile, door_dir, aabr, alt) =
+            (plan.door_tile, plan.door_dir, plan.tile_aabr, plan.alt);
+        let kind = match (self.kind, plan.kind) {
+            (Some(SiteKind::CoastalTown), GrowthKind::House) => plot::PlotKind::CoastalHouse(
+                plot::CoastalHouse::generate(land, &mut rng, self, door_tile, door_dir, aabr, alt),
+            ),
+            (Some(SiteKind::CoastalTown), GrowthKind::Workshop) => {
+                plot::PlotKind::CoastalWorkshop(plot::CoastalWorkshop::generate(
+                    land, &mut rng, self, door_tile, door_dir, aabr, alt,
+                ))
+            },
+            (Some(SiteKind::SavannahTown), GrowthKind::House) => plot::PlotKind::SavannahHut(
+                plot::SavannahHut::generate(land, &mut rng, self, door_tile, door_dir, aabr, alt),
+            ),
+            (Some(SiteKind::SavannahTown), GrowthKind::Workshop) => {
+                plot::PlotKind::SavannahWorkshop(plot::SavannahWorkshop::generate(
+                    land, &mut rng, self, door_tile, door_dir, aabr, alt,
+                ))
+            },
+            (_, GrowthKind::House) => plot::PlotKind::House(plot::House::generate(
+                land, &mut rng, self, door_tile, door_dir, aabr, calendar, alt,
+            )),
+            (_, GrowthKind::Workshop) => plot::PlotKind::Workshop(plot::Workshop::generate(
+                land, &mut rng, self, door_tile, door_dir, aabr, alt,
+            )),
+        };
+
+        plot::Plot {
+            kind,
+            root_tile: aabr.center(),
+            tiles: aabr_tiles(aabr).collect(),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 pub mod economy;
 mod generation;
 pub mod genstat;
+mod growth;
 pub mod namegen;
 pub mod plot;
 mod tile;
@@ -11,6 +12,7 @@ pub use self::{
     economy::Economy,
     generation::{Fill, Painter, Primitive, PrimitiveRef, Structure, aabr_with_z},
     genstat::{GenStatPlotKind, GenStatSiteKind, SitesGenMeta},
+    growth::{GrowthKind, PlotPlan},
     plot::{Plot, PlotKind, foreach_plot},
     tile::TileKind,
     util::Dir,
@@ -407,7 +409,7 @@ impl Site {
     }
 
     pub fn find_aabr(
-        &mut self,
+        &self,
         search_pos: Vec2<i32>,
         area_range: Range<u32>,
         min_dims: Extent2<u32>,
@@ -433,7 +435,7 @@ impl Site {
     }
 
     pub fn find_roadside_aabr(
-        &mut self,
+        &self,
         rng: &mut impl Rng,
         area_range: Range<u32>,
         min_dims: Extent2<u32>,
@@ -3119,6 +3121,17 @@ impl Site {
         plots_to_render
             .sort_unstable_by_key(|plot| (self.plots[*plot].kind.render_ordering(), *plot));
 
+        for plot in plots_to_render {
+            self.render_plot(canvas, &self.plots[plot], dynamic_rng);
+        }
+    }
+
+    /// Render the structure of a single plot into the canvas.
+    ///
+    /// The plot doesn't need to belong to the site, which allows rendering
+    /// plots that are constructed after world generation on top of terrain
+    /// that has already been generated.
+    pub fn render_plot(&self, canvas: &mut Canvas, plot: &Plot, dynamic_rng: &mut impl Rng) {
         let wpos2d = canvas.info().wpos();
         let chunk_aabr = Aabr {
             min: wpos2d,
@@ -3127,53 +3140,53 @@ impl Site {
 
         let info = canvas.info();
 
-        for plot in plots_to_render {
-            let (prim_tree, fills, mut entities) =
-                foreach_plot!(&self.plots[plot].kind, plot => plot.render_collect(self, canvas));
-
-            let mut spawn = |pos, last_block| {
-                if let Some(entity) = match &self.plots[plot].kind {
-                    PlotKind::GiantTree(tree) => tree.entity_at(pos, &last_block, dynamic_rng),
-                    _ => None,
-                } {
-                    entities.push(entity);
-                }
-            };
+        let (prim_tree, fills, mut entities) =
+            foreach_plot!(&plot.kind, plot => plot.render_collect(self, canvas));
 
-            let mut entities_from_structure_blocks = Vec::<EntityInfo>::new();
-
-            for (prim, fill) in fills {
-                for mut aabb in Fill::get_bounds_disjoint(&prim_tree, prim) {
-                    aabb.min = Vec2::max(aabb.min.xy(), chunk_aabr.min).with_z(aabb.min.z);
-                    aabb.max = Vec2::min(aabb.max.xy(), chunk_aabr.max).with_z(aabb.max.z);
-
-                    for x in aabb.min.x..aabb.max.x {
-                        for y in aabb.min.y..aabb.max.y {
-                            let wpos = Vec2::new(x, y);
-                            let col_tile = self.wpos_tile(wpos);
-                            if
-                            /* col_tile.is_building() && */
-                            col_tile
-                                .plot
-                                .and_then(|p| self.plots[p].z_range())
-                                .zip(self.plots[plot].z_range())
-                                .is_some_and(|(a, b)| a.end > b.end)
-                            {
-                                continue;
-                            }
-                            let mut last_block = None;
+        let mut spawn = |pos, last_block| {
+            if let Some(entity) = match &plot.kind {
+                PlotKind::GiantTree(tree) => tree.entity_at(pos, &last_block, dynamic_rng),
+                _ => None,
+            } {
+                entities.push(entity);
+            }
+        };
 
-                            let col = canvas
-                                .col(wpos)
-                                .map(|col| col.get_info())
-                                .unwrap_or_default();
+        let mut entities_from_structure_blocks = Vec::<EntityInfo>::new();
+
+        for (prim, fill) in fills {
+            for mut aabb in Fill::get_bounds_disjoint(&prim_tree, prim) {
+                aabb.min = Vec2::max(aabb.min.xy(), chunk_aabr.min).with_z(aabb.min.z);
+                aabb.max = Vec2::min(aabb.max.xy(), chunk_aabr.max).with_z(aabb.max.z);
+
+                for x in aabb.min.x..aabb.max.x {
+                    for y in aabb.min.y..aabb.max.y {
+                        let wpos = Vec2::new(x, y);
+                        let col_tile = self.wpos_tile(wpos);
+                        if
+                        /* col_tile.is_building() && */
+                        col_tile
+                            .plot
+                            .and_then(|p| self.plots[p].z_range())
+                            .zip(plot.z_range())
+                            .is_some_and(|(a, b)| a.end > b.end)
+                        {
+                            continue;
+                        }
+                        let mut last_block = None;
 
-                            for z in aabb.min.z..aabb.max.z {
-                                let pos = Vec3::new(x, y, z);
+                        let col = canvas
+                            .col(wpos)
+                            .map(|col| col.get_info())
+                            .unwrap_or_default();
 
-                                let mut sprite_cfg = None;
+                        for z in aabb.min.z..aabb.max.z {
+                            let pos = Vec3::new(x, y, z);
 
-                                let map = |block| {
+                            let mut sprite_cfg = None;
+
+                            let map =
+                                |block| {
                                     let (current_block, _sb, entity_path) = fill.sample_at(
                                         &prim_tree,
                                         prim,
@@ -3199,32 +3212,31 @@ impl Site {
                                     current_block.unwrap_or(block)
                                 };
 
-                                match fill {
-                                    Fill::ResourceSprite { .. } | Fill::Prefab(..) => {
-                                        canvas.map_resource(pos, map)
-                                    },
-                                    _ => canvas.map(pos, map),
-                                };
+                            match fill {
+                                Fill::ResourceSprite { .. } | Fill::Prefab(..) => {
+                                    canvas.map_resource(pos, map)
+                                },
+                                _ => canvas.map(pos, map),
+                            };
 
-                                if let Some(sprite_cfg) = sprite_cfg {
-                                    canvas.set_sprite_cfg(pos, sprite_cfg);
-                                }
-                            }
-                            if let Some(block) = last_block {
-                                spawn(Vec3::new(x, y, aabb.max.z), block);
+                            if let Some(sprite_cfg) = sprite_cfg {
+                                canvas.set_sprite_cfg(pos, sprite_cfg);
                             }
                         }
+                        if let Some(block) = last_block {
+                            spawn(Vec3::new(x, y, aabb.max.z), block);
+                        }
                     }
                 }
             }
+        }
 
-            for entity in entities {
-                canvas.spawn(entity);
-            }
+        for entity in entities {
+            canvas.spawn(entity);
+        }
 
-            for entity in entities_from_structure_blocks {
-                canvas.spawn(entity);
-            }
+        for entity in entities_from_structure_blocks {
+            canvas.spawn(entity);
         }
     }
 